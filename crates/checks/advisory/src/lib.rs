@@ -1,10 +1,13 @@
 use async_trait::async_trait;
 use safe_pkgs_core::{
     Check, CheckExecutionContext, CheckFinding, CheckId, PackageAdvisory, RegistryError, Severity,
+    VersionScheme,
 };
-use semver::Version;
+use std::cmp::Ordering;
 
 const CHECK_ID: CheckId = "advisory";
+/// CVSS base scores at or above this are critical.
+const CRITICAL_CVSS: f64 = 9.0;
 
 pub fn create_check() -> Box<dyn Check> {
     Box::new(AdvisoryCheck)
@@ -42,6 +45,8 @@ impl Check for AdvisoryCheck {
             &resolved_version.version,
             &package.latest,
             context.advisories,
+            context.registry_client.ecosystem().version_scheme(),
+            context.policy.advisory.min_cvss,
         )
         .into_iter()
         .collect())
@@ -53,10 +58,18 @@ fn run(
     requested_version: &str,
     latest_version: &str,
     advisories: &[PackageAdvisory],
+    scheme: VersionScheme,
+    min_cvss: Option<f64>,
 ) -> Option<CheckFinding> {
-    if advisories.is_empty() {
-        return None;
-    }
+    let severity = advisories
+        .iter()
+        .map(|advisory| advisory_severity(advisory, min_cvss))
+        .max()?;
+    let max_cvss = advisories
+        .iter()
+        .filter_map(|advisory| advisory.cvss_score)
+        .reduce(f64::max);
+    let cvss_vector = highest_cvss_vector(advisories);
 
     let identifiers = advisories
         .iter()
@@ -72,23 +85,44 @@ fn run(
     let fixed_versions = advisories
         .iter()
         .flat_map(|advisory| advisory.fixed_versions.iter())
-        .filter(|fixed| is_version_newer(fixed, requested_version))
+        .filter(|fixed| is_version_newer(fixed, requested_version, scheme))
         .cloned()
         .collect::<Vec<_>>();
 
-    let reason = if let Some(fixed) = best_fixed_version(&fixed_versions) {
+    let best_fixed = best_fixed_version(&fixed_versions, scheme);
+    let git_range_only =
+        best_fixed.is_none() && advisories.iter().any(|advisory| advisory.git_range_only);
+    let mut reason = if let Some(fixed) = best_fixed {
         format!(
             "{package_name}@{requested_version} is affected by {identifiers}; known CVEs are fixed in newer version {fixed} (latest is {latest_version})"
         )
+    } else if git_range_only {
+        format!(
+            "{package_name}@{requested_version} is affected by {identifiers}; advisory applies to git range; no fixed semver"
+        )
     } else {
         format!("{package_name}@{requested_version} is affected by {identifiers}")
     };
+    // The vector lets a reader assess the advisory without a second lookup.
+    match (cvss_vector, max_cvss) {
+        (Some(vector), Some(score)) => reason.push_str(&format!(" ({vector} score {score:.1})")),
+        (Some(vector), None) => reason.push_str(&format!(" ({vector})")),
+        (None, _) => {}
+    }
+    if severity == Severity::Low
+        && let (Some(score), Some(min_cvss)) = (max_cvss, min_cvss)
+    {
+        reason.push_str(&format!(
+            " (CVSS {score:.1} is below advisory.min_cvss {min_cvss:.1})"
+        ));
+    }
 
     let advisory_ids = advisories
         .iter()
         .map(|advisory| advisory.id.clone())
         .collect::<Vec<_>>();
-    let mut finding = CheckFinding::new(Severity::High, reason, "known_advisory")
+    let mut finding = CheckFinding::new(severity, reason, "known_advisory")
+        .with_code("ADV001")
         .with_fact("package_name", package_name)
         .with_fact("requested_version", requested_version)
         .with_fact("latest_version", latest_version)
@@ -101,13 +135,68 @@ fn run(
                 .collect::<Vec<_>>(),
         );
 
-    if let Some(fixed) = best_fixed_version(&fixed_versions) {
-        finding = finding.with_fact("recommended_fixed_version", fixed);
+    if let Some(fixed) = best_fixed {
+        finding = finding
+            .with_fact("recommended_fixed_version", fixed)
+            .with_remediation(format!("upgrade to {package_name}@{fixed} or later"));
+    }
+    if git_range_only {
+        finding = finding.with_fact("git_range_only", true);
+    }
+    if let Some(score) = max_cvss {
+        finding = finding.with_fact("max_cvss", format!("{score:.1}"));
+    }
+    if let Some(vector) = cvss_vector {
+        finding = finding.with_fact("cvss_vector", vector);
     }
 
     Some(finding)
 }
 
+/// CVSS vector of the highest-scored advisory, preferring the v3 vector its score is
+/// computed from; when no advisory is scored, the first vector of any version.
+fn highest_cvss_vector(advisories: &[PackageAdvisory]) -> Option<&str> {
+    let highest_scored = advisories
+        .iter()
+        .filter(|advisory| advisory.cvss_score.is_some())
+        .max_by(|left, right| {
+            left.cvss_score
+                .partial_cmp(&right.cvss_score)
+                .unwrap_or(Ordering::Equal)
+        });
+    match highest_scored {
+        Some(advisory) => cvss_vector(advisory),
+        None => advisories.iter().find_map(cvss_vector),
+    }
+}
+
+fn cvss_vector(advisory: &PackageAdvisory) -> Option<&str> {
+    let vectors = advisory
+        .severity
+        .iter()
+        .map(|severity| severity.score.as_str())
+        .filter(|score| score.starts_with("CVSS:"));
+    vectors
+        .clone()
+        .find(|score| score.starts_with("CVSS:3"))
+        .or_else(|| vectors.clone().next())
+}
+
+/// Maps an advisory's CVSS band to a severity once `min_cvss` opts into CVSS scoring;
+/// otherwise, and for unscored advisories, every advisory stays high.
+fn advisory_severity(advisory: &PackageAdvisory, min_cvss: Option<f64>) -> Severity {
+    let (Some(score), Some(min_cvss)) = (advisory.cvss_score, min_cvss) else {
+        return Severity::High;
+    };
+    if score < min_cvss {
+        Severity::Low
+    } else if score >= CRITICAL_CVSS {
+        Severity::Critical
+    } else {
+        Severity::High
+    }
+}
+
 fn advisory_identifiers(advisory: &PackageAdvisory) -> Vec<String> {
     let aliases = advisory
         .aliases
@@ -122,35 +211,69 @@ fn advisory_identifiers(advisory: &PackageAdvisory) -> Vec<String> {
     }
 }
 
-fn is_version_newer(candidate: &str, baseline: &str) -> bool {
-    match (Version::parse(candidate), Version::parse(baseline)) {
-        (Ok(lhs), Ok(rhs)) => lhs > rhs,
-        _ => candidate > baseline,
-    }
+fn is_version_newer(candidate: &str, baseline: &str, scheme: VersionScheme) -> bool {
+    compare_versions(candidate, baseline, scheme) == Ordering::Greater
 }
 
-fn best_fixed_version(candidates: &[String]) -> Option<&str> {
+fn best_fixed_version(candidates: &[String], scheme: VersionScheme) -> Option<&str> {
     candidates
         .iter()
-        .min_by(|left, right| {
-            match (
-                Version::parse(left.as_str()),
-                Version::parse(right.as_str()),
-            ) {
-                (Ok(lhs), Ok(rhs)) => lhs.cmp(&rhs),
-                _ => left.cmp(right),
-            }
-        })
+        .min_by(|left, right| compare_versions(left, right, scheme))
         .map(String::as_str)
 }
 
+/// Orders versions by the ecosystem scheme, falling back to string order when unparseable.
+fn compare_versions(left: &str, right: &str, scheme: VersionScheme) -> Ordering {
+    scheme
+        .compare(left, right)
+        .unwrap_or_else(|| left.cmp(right))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use safe_pkgs_core::AdvisorySeverity;
+
+    fn owned(versions: &[&str]) -> Vec<String> {
+        versions.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn best_fixed_version_orders_prereleases_before_release() {
+        let candidates = owned(&[
+            "1.0.0+build.5",
+            "1.0.0-beta",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha",
+        ]);
+        assert_eq!(
+            best_fixed_version(&candidates, VersionScheme::SemVer),
+            Some("1.0.0-alpha")
+        );
+    }
+
+    #[test]
+    fn best_fixed_version_ignores_build_metadata() {
+        let candidates = owned(&["1.2.0+build.1", "1.1.0+build.9", "1.1.0"]);
+        assert_eq!(
+            best_fixed_version(&candidates, VersionScheme::SemVer),
+            Some("1.1.0+build.9")
+        );
+        assert!(!is_version_newer(
+            "1.0.0+build.9",
+            "1.0.0+build.1",
+            VersionScheme::SemVer
+        ));
+        assert!(is_version_newer(
+            "1.0.1+build.1",
+            "1.0.0+build.9",
+            VersionScheme::SemVer
+        ));
+    }
 
     #[test]
     fn empty_advisories_has_no_finding() {
-        let finding = run("demo", "1.0.0", "1.2.0", &[]);
+        let finding = run("demo", "1.0.0", "1.2.0", &[], VersionScheme::SemVer, None);
         assert!(finding.is_none());
     }
 
@@ -160,12 +283,28 @@ mod tests {
             id: "OSV-123".to_string(),
             aliases: vec!["CVE-2025-1234".to_string()],
             fixed_versions: vec!["1.1.0".to_string(), "2.0.0".to_string()],
+            git_range_only: false,
+            cvss_score: None,
+            severity: Vec::new(),
         }];
 
-        let finding = run("demo", "1.0.0", "2.0.0", &advisories).expect("finding");
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "2.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            None,
+        )
+        .expect("finding");
         assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.code, "ADV001");
         assert!(finding.reason.contains("CVE-2025-1234"));
         assert!(finding.reason.contains("newer version 1.1.0"));
+        assert_eq!(
+            finding.remediation.as_deref(),
+            Some("upgrade to demo@1.1.0 or later")
+        );
     }
 
     #[test]
@@ -174,9 +313,231 @@ mod tests {
             id: "OSV-999".to_string(),
             aliases: Vec::new(),
             fixed_versions: Vec::new(),
+            git_range_only: false,
+            cvss_score: None,
+            severity: Vec::new(),
         }];
 
-        let finding = run("demo", "1.0.0", "1.0.0", &advisories).expect("finding");
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            None,
+        )
+        .expect("finding");
         assert!(finding.reason.contains("OSV-999"));
+        assert!(finding.remediation.is_none());
+    }
+
+    #[test]
+    fn git_only_advisory_explains_missing_fixed_version() {
+        let advisories = vec![PackageAdvisory {
+            id: "OSV-2024-456".to_string(),
+            aliases: Vec::new(),
+            fixed_versions: Vec::new(),
+            git_range_only: true,
+            cvss_score: None,
+            severity: Vec::new(),
+        }];
+
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            None,
+        )
+        .expect("finding");
+        assert_eq!(finding.severity, Severity::High);
+        assert!(
+            finding
+                .reason
+                .ends_with("advisory applies to git range; no fixed semver")
+        );
+        assert_eq!(
+            finding.facts["git_range_only"],
+            safe_pkgs_core::FindingValue::Bool(true)
+        );
+    }
+
+    fn scored_advisory(id: &str, cvss_score: f64) -> PackageAdvisory {
+        PackageAdvisory {
+            id: id.to_string(),
+            aliases: Vec::new(),
+            fixed_versions: Vec::new(),
+            git_range_only: false,
+            cvss_score: Some(cvss_score),
+            severity: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn critical_cvss_advisory_is_critical_risk() {
+        let advisories = vec![scored_advisory("OSV-CRITICAL", 9.8)];
+
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            Some(7.0),
+        )
+        .expect("finding");
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(
+            finding.facts["max_cvss"],
+            safe_pkgs_core::FindingValue::String("9.8".to_string())
+        );
+
+        // Without `min_cvss` the CVSS bands are not applied.
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            None,
+        )
+        .expect("finding");
+        assert_eq!(finding.severity, Severity::High);
+    }
+
+    #[test]
+    fn advisory_below_min_cvss_is_low_risk() {
+        let advisories = vec![scored_advisory("OSV-LOW", 3.1)];
+
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            Some(7.0),
+        )
+        .expect("finding");
+        assert_eq!(finding.severity, Severity::Low);
+        assert!(
+            finding
+                .reason
+                .contains("CVSS 3.1 is below advisory.min_cvss 7.0")
+        );
+
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            None,
+        )
+        .expect("finding");
+        assert_eq!(finding.severity, Severity::High);
+    }
+
+    #[test]
+    fn unscored_advisory_keeps_overall_risk_high() {
+        let mut advisories = vec![scored_advisory("OSV-LOW", 3.1)];
+        advisories.push(PackageAdvisory {
+            cvss_score: None,
+            ..scored_advisory("OSV-UNSCORED", 0.0)
+        });
+
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            Some(7.0),
+        )
+        .expect("finding");
+        assert_eq!(finding.severity, Severity::High);
+        assert!(!finding.reason.contains("min_cvss"));
+    }
+
+    #[test]
+    fn pep440_fixed_version_after_release_candidate_is_recommended() {
+        let advisories = vec![PackageAdvisory {
+            id: "PYSEC-2025-1".to_string(),
+            aliases: Vec::new(),
+            fixed_versions: vec!["1.0.0rc1".to_string(), "1.0.0".to_string()],
+            git_range_only: false,
+            cvss_score: None,
+            severity: Vec::new(),
+        }];
+
+        let finding = run(
+            "demo",
+            "1.0.0rc1",
+            "1.0.0",
+            &advisories,
+            VersionScheme::Pep440,
+            None,
+        )
+        .expect("finding");
+        assert!(finding.reason.contains("newer version 1.0.0 "));
+    }
+
+    #[test]
+    fn cvss_vector_and_score_are_included_in_reason() {
+        let advisories = vec![PackageAdvisory {
+            severity: vec![
+                AdvisorySeverity {
+                    kind: "CVSS_V4".to_string(),
+                    score: "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"
+                        .to_string(),
+                },
+                AdvisorySeverity {
+                    kind: "CVSS_V3".to_string(),
+                    score: "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".to_string(),
+                },
+            ],
+            ..scored_advisory("OSV-CRITICAL", 9.8)
+        }];
+
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            None,
+        )
+        .expect("finding");
+        assert!(
+            finding
+                .reason
+                .ends_with("(CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H score 9.8)")
+        );
+        assert_eq!(
+            finding.facts["cvss_vector"],
+            safe_pkgs_core::FindingValue::String(
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn missing_cvss_data_leaves_reason_unchanged() {
+        let advisories = vec![PackageAdvisory {
+            cvss_score: None,
+            ..scored_advisory("OSV-UNSCORED", 0.0)
+        }];
+
+        let finding = run(
+            "demo",
+            "1.0.0",
+            "1.0.0",
+            &advisories,
+            VersionScheme::SemVer,
+            None,
+        )
+        .expect("finding");
+        assert_eq!(finding.reason, "demo@1.0.0 is affected by OSV-UNSCORED");
+        assert!(!finding.facts.contains_key("cvss_vector"));
     }
 }
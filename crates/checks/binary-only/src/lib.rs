@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use safe_pkgs_core::{
+    Check, CheckExecutionContext, CheckFinding, CheckId, PackageVersion, RegistryError, Severity,
+};
+
+const CHECK_ID: CheckId = "binary_only";
+/// Extensions of interpreted entry points whose source ships in the tarball.
+const SCRIPT_EXTENSIONS: [&str; 8] = ["js", "cjs", "mjs", "ts", "sh", "py", "rb", "pl"];
+/// Files a binary-only tarball carries besides its executables
+/// (`package.json`, README, LICENSE).
+const METADATA_FILE_ALLOWANCE: u64 = 3;
+
+pub fn create_check() -> Box<dyn Check> {
+    Box::new(BinaryOnlyCheck)
+}
+
+pub struct BinaryOnlyCheck;
+
+#[async_trait]
+impl Check for BinaryOnlyCheck {
+    fn id(&self) -> CheckId {
+        CHECK_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags versions that appear to ship only pre-built executables and no source."
+    }
+
+    async fn run(
+        &self,
+        context: &CheckExecutionContext<'_>,
+    ) -> Result<Vec<CheckFinding>, RegistryError> {
+        let Some(resolved_version) = context.resolved_version else {
+            return Ok(Vec::new());
+        };
+
+        Ok(run(context.package_name, resolved_version)
+            .await
+            .into_iter()
+            .collect())
+    }
+}
+
+async fn run(package_name: &str, version: &PackageVersion) -> Option<CheckFinding> {
+    if version.bin_targets.is_empty() {
+        return None;
+    }
+    // A script entry point means readable source is in the tarball.
+    if version.bin_targets.iter().any(|target| is_script(target)) {
+        return None;
+    }
+    // When the registry reports a file count, a tarball with more than the executables
+    // plus metadata files most likely carries sources next to them.
+    let bin_count = u64::try_from(version.bin_targets.len()).unwrap_or(u64::MAX);
+    if let Some(file_count) = version.file_count
+        && file_count > bin_count.saturating_add(METADATA_FILE_ALLOWANCE)
+    {
+        return None;
+    }
+
+    let targets = version.bin_targets.join(", ");
+    let mut finding = CheckFinding::new(
+        Severity::Low,
+        format!(
+            "{package_name}@{} ships pre-built executables ({targets}) without visible source",
+            version.version
+        ),
+        "prebuilt_binary",
+    )
+    .with_code("BIN001")
+    .with_fact("package_name", package_name)
+    .with_fact("resolved_version", version.version.as_str())
+    .with_fact("bin_targets", version.bin_targets.clone());
+    if let Some(file_count) = version.file_count {
+        finding = finding.with_fact("file_count", file_count);
+    }
+    Some(finding)
+}
+
+fn is_script(target: &str) -> bool {
+    let file_name = target.rsplit('/').next().unwrap_or(target);
+    file_name.rsplit_once('.').is_some_and(|(_, extension)| {
+        SCRIPT_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(bin_targets: &[&str], file_count: Option<u64>) -> PackageVersion {
+        PackageVersion {
+            version: "1.0.0".to_string(),
+            published: None,
+            deprecated: false,
+            install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count,
+            maintainer_emails: Vec::new(),
+            bin_targets: bin_targets.iter().map(ToString::to_string).collect(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn native_bin_in_small_tarball_is_low_risk() {
+        let finding = run("demo", &version(&["bin/demo"], Some(3)))
+            .await
+            .expect("finding");
+        assert_eq!(finding.severity, Severity::Low);
+        assert_eq!(finding.reason_code, "prebuilt_binary");
+        assert_eq!(finding.code, "BIN001");
+        assert!(finding.reason.contains("bin/demo"));
+    }
+
+    #[tokio::test]
+    async fn script_bin_or_large_tarball_has_no_finding() {
+        assert!(
+            run("demo", &version(&["./cli.js"], Some(2)))
+                .await
+                .is_none()
+        );
+        assert!(
+            run("demo", &version(&["bin/demo"], Some(40)))
+                .await
+                .is_none()
+        );
+        assert!(run("demo", &version(&[], Some(1))).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn unknown_file_count_relies_on_bin_targets() {
+        assert!(
+            run("demo", &version(&["bin/demo.exe"], None))
+                .await
+                .is_some()
+        );
+        assert!(
+            run("demo", &version(&["bin/demo.mjs"], None))
+                .await
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use safe_pkgs_core::{
+    Check, CheckExecutionContext, CheckFinding, CheckId, RegistryError, Severity,
+};
+
+const CHECK_ID: CheckId = "bus_factor";
+
+pub fn create_check() -> Box<dyn Check> {
+    Box::new(BusFactorCheck)
+}
+
+pub struct BusFactorCheck;
+
+#[async_trait]
+impl Check for BusFactorCheck {
+    fn id(&self) -> CheckId {
+        CHECK_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags widely used packages that depend on a single maintainer."
+    }
+
+    fn needs_weekly_downloads(&self) -> bool {
+        true
+    }
+
+    fn needs_full_metadata(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        context: &CheckExecutionContext<'_>,
+    ) -> Result<Vec<CheckFinding>, RegistryError> {
+        let Some(package) = context.package else {
+            return Ok(Vec::new());
+        };
+
+        Ok(run(
+            context.package_name,
+            &package.publishers,
+            context.weekly_downloads,
+            context.policy.bus_factor.high_download_threshold,
+        )
+        .await
+        .into_iter()
+        .collect())
+    }
+}
+
+async fn run(
+    package_name: &str,
+    publishers: &[String],
+    weekly_downloads: Option<u64>,
+    high_download_threshold: u64,
+) -> Option<CheckFinding> {
+    // Obscure single-maintainer packages are covered by popularity/typosquat.
+    let weekly_downloads = weekly_downloads?;
+    if weekly_downloads <= high_download_threshold {
+        return None;
+    }
+    let [maintainer] = publishers else {
+        return None;
+    };
+
+    Some(
+        CheckFinding::new(
+            Severity::Low,
+            format!(
+                "{package_name} has a single maintainer ({maintainer}) but {weekly_downloads} weekly downloads"
+            ),
+            "single_maintainer",
+        )
+        .with_code("BUS001")
+        .with_fact("package_name", package_name)
+        .with_fact("maintainer", maintainer.as_str())
+        .with_fact("weekly_downloads", weekly_downloads)
+        .with_fact("high_download_threshold", high_download_threshold),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: u64 = 1_000_000;
+
+    #[tokio::test]
+    async fn single_maintainer_high_download_package_is_low_risk() {
+        let finding = run("demo", &["alice".to_string()], Some(5_000_000), THRESHOLD)
+            .await
+            .expect("finding");
+        assert_eq!(finding.severity, Severity::Low);
+        assert_eq!(finding.code, "BUS001");
+        assert!(finding.reason.contains("alice"));
+    }
+
+    #[tokio::test]
+    async fn multi_maintainer_package_has_no_finding() {
+        let finding = run(
+            "demo",
+            &["alice".to_string(), "bob".to_string()],
+            Some(5_000_000),
+            THRESHOLD,
+        )
+        .await;
+        assert!(finding.is_none());
+    }
+
+    #[tokio::test]
+    async fn obscure_single_maintainer_package_has_no_finding() {
+        let finding = run("demo", &["alice".to_string()], Some(120), THRESHOLD).await;
+        assert!(finding.is_none());
+    }
+
+    #[tokio::test]
+    async fn missing_downloads_has_no_finding() {
+        let finding = run("demo", &["alice".to_string()], None, THRESHOLD).await;
+        assert!(finding.is_none());
+    }
+}
@@ -41,14 +41,19 @@ impl Check for ExistenceCheck {
         &self,
         context: &CheckExecutionContext<'_>,
     ) -> Result<Vec<CheckFinding>, RegistryError> {
+        let policy = &context.policy.existence;
         if context.package.is_none() {
-            return Ok(vec![missing_package(context.package_name)]);
+            return Ok(vec![missing_package(
+                context.package_name,
+                policy.missing_package,
+            )]);
         }
 
         if context.resolved_version.is_none() {
             return Ok(vec![missing_version(
                 context.package_name,
                 context.requested_version.unwrap_or("latest"),
+                policy.missing_version,
             )]);
         }
 
@@ -56,21 +61,23 @@ impl Check for ExistenceCheck {
     }
 }
 
-fn missing_package(package_name: &str) -> CheckFinding {
+fn missing_package(package_name: &str, severity: Severity) -> CheckFinding {
     CheckFinding::new(
-        Severity::Critical,
+        severity,
         format!("{package_name} does not exist (possible hallucination / slopsquatting)"),
         "missing_package",
     )
+    .with_code("EXIST001")
     .with_fact("package_name", package_name)
 }
 
-fn missing_version(package_name: &str, version: &str) -> CheckFinding {
+fn missing_version(package_name: &str, version: &str, severity: Severity) -> CheckFinding {
     CheckFinding::new(
-        Severity::Critical,
+        severity,
         format!("{package_name}@{version} does not exist (possible hallucinated version)"),
         "missing_version",
     )
+    .with_code("EXIST002")
     .with_fact("package_name", package_name)
     .with_fact("requested_version", version)
 }
@@ -81,17 +88,29 @@ mod tests {
 
     #[test]
     fn missing_package_is_critical_with_expected_reason() {
-        let finding = missing_package("imaginary-pkg");
+        let finding = missing_package("imaginary-pkg", Severity::Critical);
         assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.code, "EXIST001");
         assert!(finding.reason.contains("imaginary-pkg"));
         assert!(finding.reason.contains("does not exist"));
     }
 
     #[test]
     fn missing_version_is_critical_with_expected_reason() {
-        let finding = missing_version("real-pkg", "9.9.9");
+        let finding = missing_version("real-pkg", "9.9.9", Severity::Critical);
         assert_eq!(finding.severity, Severity::Critical);
         assert!(finding.reason.contains("real-pkg@9.9.9"));
         assert!(finding.reason.contains("hallucinated version"));
     }
+
+    #[test]
+    fn configured_missing_version_severity_is_used() {
+        let finding = missing_version("real-pkg", "9.9.9", Severity::High);
+        assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.reason_code, "missing_version");
+        assert_eq!(finding.code, "EXIST002");
+
+        let finding = missing_package("imaginary-pkg", Severity::High);
+        assert_eq!(finding.severity, Severity::High);
+    }
 }
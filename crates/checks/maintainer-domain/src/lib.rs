@@ -0,0 +1,226 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use safe_pkgs_core::{
+    Check, CheckExecutionContext, CheckFinding, CheckId, PackageRecord, PackageVersion,
+    RegistryError, Severity,
+};
+
+const CHECK_ID: CheckId = "maintainer_domain";
+/// Free webmail providers; moving to one of these from an organization domain is a
+/// common account-takeover pattern.
+const FREE_WEBMAIL_DOMAINS: [&str; 8] = [
+    "gmail.com",
+    "googlemail.com",
+    "outlook.com",
+    "hotmail.com",
+    "yahoo.com",
+    "proton.me",
+    "protonmail.com",
+    "mail.ru",
+];
+
+pub fn create_check() -> Box<dyn Check> {
+    Box::new(MaintainerDomainCheck)
+}
+
+pub struct MaintainerDomainCheck;
+
+#[async_trait]
+impl Check for MaintainerDomainCheck {
+    fn id(&self) -> CheckId {
+        CHECK_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags versions whose maintainer email domains changed from the previous release."
+    }
+
+    fn needs_full_metadata(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        context: &CheckExecutionContext<'_>,
+    ) -> Result<Vec<CheckFinding>, RegistryError> {
+        let (Some(package), Some(resolved_version)) = (context.package, context.resolved_version)
+        else {
+            return Ok(Vec::new());
+        };
+
+        Ok(run(context.package_name, package, resolved_version)
+            .await
+            .into_iter()
+            .collect())
+    }
+}
+
+async fn run(
+    package_name: &str,
+    package: &PackageRecord,
+    version: &PackageVersion,
+) -> Option<CheckFinding> {
+    let current = email_domains(version);
+    if current.is_empty() {
+        return None;
+    }
+    // The most recent earlier release with maintainer data is the baseline; a package's
+    // first observed maintainers are never flagged.
+    // Estimated publish times cannot order releases, so the baseline needs exact ones.
+    let published = version.exact_published()?;
+    let previous = package
+        .versions
+        .values()
+        .filter(|candidate| candidate.version != version.version)
+        .filter(|candidate| !candidate.maintainer_emails.is_empty())
+        .filter_map(|candidate| Some((candidate.exact_published()?, candidate)))
+        .filter(|(candidate_published, _)| *candidate_published < published)
+        .max_by_key(|(candidate_published, _)| *candidate_published)
+        .map(|(_, candidate)| candidate)?;
+    let previous_domains = email_domains(previous);
+    let new_domains = current
+        .difference(&previous_domains)
+        .cloned()
+        .collect::<Vec<_>>();
+    if new_domains.is_empty() {
+        return None;
+    }
+
+    let webmail = new_domains
+        .iter()
+        .any(|domain| FREE_WEBMAIL_DOMAINS.contains(&domain.as_str()));
+    let joined = |domains: &[String]| domains.join(", ");
+    let previous_domains = previous_domains.into_iter().collect::<Vec<_>>();
+    let mut reason = format!(
+        "{package_name}@{} maintainer email domains changed from [{}] in {} to include [{}]",
+        version.version,
+        joined(&previous_domains),
+        previous.version,
+        joined(&new_domains),
+    );
+    if webmail {
+        reason.push_str(" (free webmail)");
+    }
+
+    Some(
+        CheckFinding::new(Severity::Medium, reason, "domain_changed")
+            .with_code("MAINT001")
+            .with_fact("package_name", package_name)
+            .with_fact("resolved_version", version.version.as_str())
+            .with_fact("previous_version", previous.version.as_str())
+            .with_fact("previous_domains", joined(&previous_domains))
+            .with_fact("new_domains", joined(&new_domains))
+            .with_fact("free_webmail", webmail),
+    )
+}
+
+fn email_domains(version: &PackageVersion) -> BTreeSet<String> {
+    version
+        .maintainer_emails
+        .iter()
+        .filter_map(|email| email.rsplit_once('@'))
+        .map(|(_, domain)| domain.trim().to_ascii_lowercase())
+        .filter(|domain| !domain.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use std::collections::BTreeMap;
+
+    fn version(version: &str, days_ago: i64, emails: &[&str]) -> PackageVersion {
+        PackageVersion {
+            version: version.to_string(),
+            published: Some(Utc::now() - Duration::days(days_ago)),
+            deprecated: false,
+            install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: emails.iter().map(ToString::to_string).collect(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        }
+    }
+
+    fn package(versions: Vec<PackageVersion>) -> PackageRecord {
+        PackageRecord {
+            name: "demo".to_string(),
+            latest: versions
+                .last()
+                .map(|version| version.version.clone())
+                .unwrap_or_default(),
+            publishers: Vec::new(),
+            versions: versions
+                .into_iter()
+                .map(|version| (version.version.clone(), version))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    #[tokio::test]
+    async fn switch_to_webmail_domain_is_medium_risk() {
+        let record = package(vec![
+            version("1.0.0", 90, &["alice@acme.com"]),
+            version("1.1.0", 30, &["alice@acme.com", "bob@acme.com"]),
+            version("1.2.0", 1, &["alice@gmail.com"]),
+        ]);
+        let finding = run("demo", &record, &record.versions["1.2.0"])
+            .await
+            .expect("finding");
+        assert_eq!(finding.severity, Severity::Medium);
+        assert_eq!(finding.reason_code, "domain_changed");
+        assert_eq!(finding.code, "MAINT001");
+        assert!(finding.reason.contains("[acme.com] in 1.1.0"));
+        assert!(finding.reason.contains("free webmail"));
+    }
+
+    #[tokio::test]
+    async fn unchanged_domains_have_no_finding() {
+        let record = package(vec![
+            version("1.0.0", 90, &["alice@acme.com"]),
+            version("1.1.0", 1, &["Bob@ACME.com"]),
+        ]);
+        assert!(
+            run("demo", &record, &record.versions["1.1.0"])
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn first_observation_has_no_finding() {
+        let record = package(vec![
+            version("1.0.0", 90, &[]),
+            version("1.1.0", 1, &["alice@gmail.com"]),
+        ]);
+        assert!(
+            run("demo", &record, &record.versions["1.1.0"])
+                .await
+                .is_none()
+        );
+        assert!(
+            run("demo", &record, &record.versions["1.0.0"])
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn older_version_compares_against_its_own_predecessor() {
+        let record = package(vec![
+            version("1.0.0", 90, &["alice@acme.com"]),
+            version("1.1.0", 30, &["alice@acme.com"]),
+            version("2.0.0", 1, &["mallory@evil.example"]),
+        ]);
+        assert!(
+            run("demo", &record, &record.versions["1.1.0"])
+                .await
+                .is_none()
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use async_trait::async_trait;
 use safe_pkgs_core::{
     Check, CheckExecutionContext, CheckFinding, CheckId, PackageVersion, RegistryError, Severity,
+    age_in_days, future_publish_finding,
 };
 
 const CHECK_ID: CheckId = "popularity";
+const FUTURE_PUBLISH_CODE: &str = "POP002";
 const DEFAULT_YOUNG_PACKAGE_AGE_DAYS: i64 = 30;
 
 pub fn create_check() -> Box<dyn Check> {
@@ -26,6 +28,10 @@ impl Check for PopularityCheck {
         true
     }
 
+    fn needs_full_metadata(&self) -> bool {
+        true
+    }
+
     async fn run(
         &self,
         context: &CheckExecutionContext<'_>,
@@ -35,9 +41,9 @@ impl Check for PopularityCheck {
         };
         let age_days = resolved_version
             .published
-            .map(|published| (context.evaluation_time - published).num_days());
+            .map(|published| age_in_days(published, context.evaluation_time));
 
-        Ok(run(
+        let mut findings = run(
             context.package_name,
             resolved_version,
             context.weekly_downloads,
@@ -47,7 +53,14 @@ impl Check for PopularityCheck {
         )
         .await
         .into_iter()
-        .collect())
+        .collect::<Vec<_>>();
+        findings.extend(future_publish_finding(
+            context.package_name,
+            resolved_version,
+            context.evaluation_time,
+            FUTURE_PUBLISH_CODE,
+        ));
+        Ok(findings)
     }
 }
 
@@ -75,6 +88,7 @@ async fn run(
         ),
             "low_adoption_young_package",
         )
+        .with_code("POP001")
         .with_fact("package_name", package_name)
         .with_fact("resolved_version", version.version.as_str())
         .with_fact("weekly_downloads", downloads)
@@ -95,6 +109,13 @@ mod tests {
             published: Some(Utc::now() - Duration::days(days_ago)),
             deprecated: false,
             install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
         }
     }
 
@@ -104,6 +125,7 @@ mod tests {
             .await
             .expect("finding");
         assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.code, "POP001");
         assert!(finding.reason.contains("low adoption"));
     }
 
@@ -129,6 +151,13 @@ mod tests {
             published: None,
             deprecated: false,
             install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
         };
         let no_publish_date = run("lib", &version, Some(10), 50, 30, None).await;
         assert!(no_publish_date.is_none());
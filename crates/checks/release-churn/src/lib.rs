@@ -0,0 +1,178 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use safe_pkgs_core::{
+    Check, CheckExecutionContext, CheckFinding, CheckId, PackageRecord, PackageVersion,
+    RegistryError, Severity,
+};
+
+const CHECK_ID: CheckId = "release_churn";
+const CHURN_WINDOW_DAYS: i64 = 7;
+
+pub fn create_check() -> Box<dyn Check> {
+    Box::new(ReleaseChurnCheck)
+}
+
+pub struct ReleaseChurnCheck;
+
+#[async_trait]
+impl Check for ReleaseChurnCheck {
+    fn id(&self) -> CheckId {
+        CHECK_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags packages that published an unusually large number of versions in the last week."
+    }
+
+    fn needs_full_metadata(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        context: &CheckExecutionContext<'_>,
+    ) -> Result<Vec<CheckFinding>, RegistryError> {
+        let Some(package) = context.package else {
+            return Ok(Vec::new());
+        };
+
+        Ok(run(
+            context.package_name,
+            package,
+            context.evaluation_time,
+            context.policy.release_churn.max_releases_per_week,
+        )
+        .await
+        .into_iter()
+        .collect())
+    }
+}
+
+async fn run(
+    package_name: &str,
+    package: &PackageRecord,
+    evaluation_time: DateTime<Utc>,
+    max_releases_per_week: u64,
+) -> Option<CheckFinding> {
+    let window_start = evaluation_time - Duration::days(CHURN_WINDOW_DAYS);
+    // Versions without their own publish timestamp cannot be placed in the window and are
+    // ignored; an estimated fallback would put every such version at the same instant.
+    let recent_releases = package
+        .versions
+        .values()
+        .filter_map(PackageVersion::exact_published)
+        .filter(|published| *published > window_start && *published <= evaluation_time)
+        .count();
+    let recent_releases = u64::try_from(recent_releases).unwrap_or(u64::MAX);
+    if recent_releases <= max_releases_per_week {
+        return None;
+    }
+
+    let severity = if recent_releases > max_releases_per_week.saturating_mul(2) {
+        Severity::Medium
+    } else {
+        Severity::Low
+    };
+
+    Some(
+        CheckFinding::new(
+            severity,
+            format!(
+                "{package_name} published {recent_releases} versions in the last {CHURN_WINDOW_DAYS} days (> {max_releases_per_week})"
+            ),
+            "high_churn",
+        )
+        .with_code("CHURN001")
+        .with_fact("package_name", package_name)
+        .with_fact("recent_releases", recent_releases)
+        .with_fact("window_days", CHURN_WINDOW_DAYS)
+        .with_fact("max_releases_per_week", max_releases_per_week),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn package(published: Vec<Option<DateTime<Utc>>>) -> PackageRecord {
+        let versions = published
+            .into_iter()
+            .enumerate()
+            .map(|(idx, published)| {
+                let version = format!("1.0.{idx}");
+                (
+                    version.clone(),
+                    PackageVersion {
+                        version,
+                        published,
+                        deprecated: false,
+                        install_scripts: Vec::new(),
+                        unpacked_size: None,
+                        file_count: None,
+                        maintainer_emails: Vec::new(),
+                        bin_targets: Vec::new(),
+                        license: None,
+                        repository_url: None,
+                        published_estimated: false,
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        PackageRecord {
+            name: "demo".to_string(),
+            latest: "1.0.0".to_string(),
+            publishers: Vec::new(),
+            versions,
+        }
+    }
+
+    #[tokio::test]
+    async fn twenty_releases_in_three_days_is_medium_risk() {
+        let now = Utc::now();
+        let record = package(
+            (0..20)
+                .map(|idx| Some(now - Duration::hours(idx * 3)))
+                .collect(),
+        );
+        let finding = run("demo", &record, now, 8).await.expect("finding");
+        assert_eq!(finding.severity, Severity::Medium);
+        assert_eq!(finding.reason_code, "high_churn");
+        assert_eq!(finding.code, "CHURN001");
+        assert!(finding.reason.contains("20 versions"));
+    }
+
+    #[tokio::test]
+    async fn churn_just_above_threshold_is_low_risk() {
+        let now = Utc::now();
+        let record = package(
+            (0..12)
+                .map(|idx| Some(now - Duration::hours(idx * 6)))
+                .collect(),
+        );
+        let finding = run("demo", &record, now, 10).await.expect("finding");
+        assert_eq!(finding.severity, Severity::Low);
+    }
+
+    #[tokio::test]
+    async fn old_or_undated_releases_are_not_counted() {
+        let now = Utc::now();
+        let mut published = (0..20)
+            .map(|idx| Some(now - Duration::days(30 + idx)))
+            .collect::<Vec<_>>();
+        published.extend((0..20).map(|_| None));
+        published.push(Some(now - Duration::days(1)));
+        let record = package(published);
+        assert!(run("demo", &record, now, 10).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn estimated_publish_times_are_not_counted() {
+        let now = Utc::now();
+        let mut record = package((0..20).map(|_| Some(now - Duration::hours(1))).collect());
+        for version in record.versions.values_mut() {
+            version.published_estimated = true;
+        }
+        assert!(run("demo", &record, now, 8).await.is_none());
+    }
+}
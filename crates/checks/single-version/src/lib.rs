@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use safe_pkgs_core::{
+    Check, CheckExecutionContext, CheckFinding, CheckId, RegistryError, Severity,
+};
+
+const CHECK_ID: CheckId = "single_version";
+/// Packages at or above this many weekly downloads are considered established.
+const DEFAULT_OBSCURE_WEEKLY_DOWNLOADS: u64 = 1_000;
+
+pub fn create_check() -> Box<dyn Check> {
+    Box::new(SingleVersionCheck)
+}
+
+pub struct SingleVersionCheck;
+
+#[async_trait]
+impl Check for SingleVersionCheck {
+    fn id(&self) -> CheckId {
+        CHECK_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags obscure packages that have only ever published a single version."
+    }
+
+    fn needs_weekly_downloads(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        context: &CheckExecutionContext<'_>,
+    ) -> Result<Vec<CheckFinding>, RegistryError> {
+        let Some(package) = context.package else {
+            return Ok(Vec::new());
+        };
+
+        Ok(run(
+            context.package_name,
+            package.versions.len(),
+            context.weekly_downloads,
+            context.policy.min_weekly_downloads,
+            DEFAULT_OBSCURE_WEEKLY_DOWNLOADS,
+        )
+        .await
+        .into_iter()
+        .collect())
+    }
+}
+
+async fn run(
+    package_name: &str,
+    version_count: usize,
+    weekly_downloads: Option<u64>,
+    min_weekly_downloads: u64,
+    obscure_weekly_downloads: u64,
+) -> Option<CheckFinding> {
+    if version_count != 1 {
+        return None;
+    }
+    // Without download data we cannot tell a slopsquat from a finished utility.
+    let downloads = weekly_downloads?;
+    let obscure_threshold = obscure_weekly_downloads.max(min_weekly_downloads);
+    if downloads >= obscure_threshold {
+        return None;
+    }
+
+    let severity = if downloads < min_weekly_downloads {
+        Severity::Medium
+    } else {
+        Severity::Low
+    };
+
+    Some(
+        CheckFinding::new(
+            severity,
+            format!(
+                "{package_name} has only ever published a single version and has {downloads} weekly downloads"
+            ),
+            "single_version_package",
+        )
+        .with_code("SINGLE001")
+        .with_fact("package_name", package_name)
+        .with_fact("version_count", version_count)
+        .with_fact("weekly_downloads", downloads)
+        .with_fact("min_weekly_downloads", min_weekly_downloads)
+        .with_fact("obscure_weekly_downloads", obscure_threshold),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn obscure_single_version_package_is_low_risk() {
+        let finding = run("one-shot", 1, Some(400), 50, 1_000)
+            .await
+            .expect("finding");
+        assert_eq!(finding.severity, Severity::Low);
+        assert_eq!(finding.code, "SINGLE001");
+        assert!(finding.reason.contains("single version"));
+    }
+
+    #[tokio::test]
+    async fn low_download_single_version_package_escalates_to_medium() {
+        let finding = run("one-shot", 1, Some(5), 50, 1_000)
+            .await
+            .expect("finding");
+        assert_eq!(finding.severity, Severity::Medium);
+    }
+
+    #[tokio::test]
+    async fn popular_single_version_package_has_no_finding() {
+        let finding = run("is-finished", 1, Some(2_000_000), 50, 1_000).await;
+        assert!(finding.is_none());
+    }
+
+    #[tokio::test]
+    async fn multi_version_or_unknown_downloads_has_no_finding() {
+        assert!(run("lib", 3, Some(5), 50, 1_000).await.is_none());
+        assert!(run("lib", 1, None, 50, 1_000).await.is_none());
+    }
+}
@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use safe_pkgs_core::{
+    Check, CheckExecutionContext, CheckFinding, CheckId, PackageRecord, PackageVersion,
+    RegistryError, Severity,
+};
+
+const CHECK_ID: CheckId = "size_anomaly";
+/// Minimum number of other sized versions needed for a meaningful median.
+const MIN_HISTORY_VERSIONS: usize = 3;
+/// Size ratio (in either direction) against the historical median that counts as anomalous.
+const DEVIATION_FACTOR: u64 = 10;
+/// Average bytes per file above which a package is assumed to ship an oversized file.
+const LARGE_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+pub fn create_check() -> Box<dyn Check> {
+    Box::new(SizeAnomalyCheck)
+}
+
+pub struct SizeAnomalyCheck;
+
+#[async_trait]
+impl Check for SizeAnomalyCheck {
+    fn id(&self) -> CheckId {
+        CHECK_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags package versions whose published size deviates sharply from their history."
+    }
+
+    fn needs_size(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        context: &CheckExecutionContext<'_>,
+    ) -> Result<Vec<CheckFinding>, RegistryError> {
+        let Some(package) = context.package else {
+            return Ok(Vec::new());
+        };
+        let Some(resolved_version) = context.resolved_version else {
+            return Ok(Vec::new());
+        };
+
+        Ok(run(package, resolved_version).await)
+    }
+}
+
+async fn run(package: &PackageRecord, version: &PackageVersion) -> Vec<CheckFinding> {
+    let Some(size) = version.unpacked_size else {
+        return Vec::new();
+    };
+    let mut findings = Vec::new();
+
+    if let Some(median) = historical_median_size(package, version.version.as_str())
+        && deviates(size, median)
+    {
+        findings.push(
+            CheckFinding::new(
+                Severity::Low,
+                format!(
+                    "{}@{} unpacks to {size} bytes, far from the historical median of {median} bytes",
+                    package.name, version.version
+                ),
+                "size_deviation",
+            )
+            .with_code("SIZE001")
+            .with_fact("package_name", package.name.as_str())
+            .with_fact("resolved_version", version.version.as_str())
+            .with_fact("unpacked_size", size)
+            .with_fact("median_unpacked_size", median),
+        );
+    }
+
+    if let Some(file_count) = version.file_count.filter(|count| *count > 0) {
+        let average_file_size = size / file_count;
+        if average_file_size > LARGE_FILE_BYTES {
+            findings.push(
+                CheckFinding::new(
+                    Severity::Medium,
+                    format!(
+                        "{}@{} averages {average_file_size} bytes per file across {file_count} file(s), suggesting an unusually large bundled file",
+                        package.name, version.version
+                    ),
+                    "large_file",
+                )
+                .with_code("SIZE002")
+                .with_fact("package_name", package.name.as_str())
+                .with_fact("resolved_version", version.version.as_str())
+                .with_fact("unpacked_size", size)
+                .with_fact("file_count", file_count),
+            );
+        }
+    }
+
+    findings
+}
+
+fn historical_median_size(package: &PackageRecord, exclude_version: &str) -> Option<u64> {
+    let mut sizes = package
+        .versions
+        .values()
+        .filter(|candidate| candidate.version != exclude_version)
+        .filter_map(|candidate| candidate.unpacked_size)
+        .collect::<Vec<_>>();
+    if sizes.len() < MIN_HISTORY_VERSIONS {
+        return None;
+    }
+    sizes.sort_unstable();
+    Some(sizes[sizes.len() / 2])
+}
+
+fn deviates(size: u64, median: u64) -> bool {
+    if size == 0 || median == 0 {
+        return size != median;
+    }
+    size / median >= DEVIATION_FACTOR || median / size >= DEVIATION_FACTOR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn version(
+        version: &str,
+        unpacked_size: Option<u64>,
+        file_count: Option<u64>,
+    ) -> PackageVersion {
+        PackageVersion {
+            version: version.to_string(),
+            published: None,
+            deprecated: false,
+            install_scripts: Vec::new(),
+            unpacked_size,
+            file_count,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        }
+    }
+
+    fn package(versions: Vec<PackageVersion>) -> PackageRecord {
+        PackageRecord {
+            name: "demo".to_string(),
+            latest: versions
+                .last()
+                .map(|version| version.version.clone())
+                .unwrap_or_default(),
+            publishers: Vec::new(),
+            versions: versions
+                .into_iter()
+                .map(|version| (version.version.clone(), version))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    #[tokio::test]
+    async fn version_far_above_median_is_low_risk() {
+        let record = package(vec![
+            version("1.0.0", Some(10_000), Some(10)),
+            version("1.1.0", Some(12_000), Some(10)),
+            version("1.2.0", Some(11_000), Some(10)),
+            version("1.3.0", Some(1_100_000), Some(10)),
+        ]);
+
+        let findings = run(&record, &record.versions["1.3.0"]).await;
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Low);
+        assert_eq!(findings[0].reason_code, "size_deviation");
+        assert_eq!(findings[0].code, "SIZE001");
+        assert!(findings[0].reason.contains("demo@1.3.0"));
+    }
+
+    #[tokio::test]
+    async fn zero_byte_version_is_flagged() {
+        let record = package(vec![
+            version("1.0.0", Some(10_000), None),
+            version("1.1.0", Some(12_000), None),
+            version("1.2.0", Some(11_000), None),
+            version("1.3.0", Some(0), None),
+        ]);
+
+        let findings = run(&record, &record.versions["1.3.0"]).await;
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].reason_code, "size_deviation");
+        assert_eq!(findings[0].code, "SIZE001");
+    }
+
+    #[tokio::test]
+    async fn consistent_size_has_no_finding() {
+        let record = package(vec![
+            version("1.0.0", Some(10_000), Some(10)),
+            version("1.1.0", Some(12_000), Some(10)),
+            version("1.2.0", Some(11_000), Some(10)),
+            version("1.3.0", Some(13_000), Some(11)),
+        ]);
+
+        assert!(run(&record, &record.versions["1.3.0"]).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn short_history_skips_median_comparison() {
+        let record = package(vec![
+            version("1.0.0", Some(10_000), None),
+            version("1.1.0", Some(1_000_000), None),
+        ]);
+
+        assert!(run(&record, &record.versions["1.1.0"]).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn oversized_single_file_is_medium_risk() {
+        let record = package(vec![version("1.0.0", Some(200 * 1024 * 1024), Some(2))]);
+
+        let findings = run(&record, &record.versions["1.0.0"]).await;
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].reason_code, "large_file");
+        assert_eq!(findings[0].code, "SIZE002");
+    }
+
+    #[tokio::test]
+    async fn missing_size_has_no_finding() {
+        let record = package(vec![version("1.0.0", None, None)]);
+        assert!(run(&record, &record.versions["1.0.0"]).await.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use safe_pkgs_core::{
+    Check, CheckExecutionContext, CheckFinding, CheckId, PackageRecord, PackageVersion,
+    ParsedVersion, RegistryError, Severity, VersionScheme, age_in_days,
+};
+
+const CHECK_ID: CheckId = "stale_stable";
+
+pub fn create_check() -> Box<dyn Check> {
+    Box::new(StaleStableCheck)
+}
+
+pub struct StaleStableCheck;
+
+#[async_trait]
+impl Check for StaleStableCheck {
+    fn id(&self) -> CheckId {
+        CHECK_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags packages whose latest stable release is old while prereleases keep shipping."
+    }
+
+    fn needs_full_metadata(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        context: &CheckExecutionContext<'_>,
+    ) -> Result<Vec<CheckFinding>, RegistryError> {
+        let Some(package) = context.package else {
+            return Ok(Vec::new());
+        };
+
+        Ok(run(
+            context.package_name,
+            package,
+            context.registry_client.ecosystem().version_scheme(),
+            context.evaluation_time,
+            context.policy.stale_stable.window_days,
+        )
+        .await
+        .into_iter()
+        .collect())
+    }
+}
+
+async fn run(
+    package_name: &str,
+    package: &PackageRecord,
+    scheme: VersionScheme,
+    evaluation_time: DateTime<Utc>,
+    window_days: u64,
+) -> Option<CheckFinding> {
+    let window_days = i64::try_from(window_days).unwrap_or(i64::MAX);
+    let parsed = package
+        .versions
+        .values()
+        .filter_map(|version| Some((scheme.parse(&version.version)?, version)))
+        .collect::<Vec<_>>();
+
+    let (stable_parsed, stable) = latest_stable(package, &parsed)?;
+    let stable_age_days = age_in_days(stable.published?, evaluation_time);
+    if stable_age_days <= window_days {
+        return None;
+    }
+
+    // Only prereleases ahead of the stable line count; a backported rc of an older line
+    // says nothing about the stable release lagging behind.
+    let (prerelease, prerelease_published) = parsed
+        .iter()
+        .filter(|(parsed, _)| parsed.is_prerelease() && parsed > stable_parsed)
+        .filter_map(|(_, version)| Some((*version, version.published?)))
+        .filter(|(_, published)| *published <= evaluation_time)
+        .max_by_key(|(_, published)| *published)?;
+    let prerelease_age_days = age_in_days(prerelease_published, evaluation_time);
+    if prerelease_age_days > window_days {
+        return None;
+    }
+
+    Some(
+        CheckFinding::new(
+            Severity::Low,
+            format!(
+                "{package_name} latest stable {} is {stable_age_days} days old while prerelease {} shipped {prerelease_age_days} days ago",
+                stable.version, prerelease.version
+            ),
+            "stale_stable",
+        )
+        .with_code("STABLE001")
+        .with_fact("package_name", package_name)
+        .with_fact("stable_version", stable.version.as_str())
+        .with_fact("stable_age_days", stable_age_days)
+        .with_fact("prerelease_version", prerelease.version.as_str())
+        .with_fact("prerelease_age_days", prerelease_age_days)
+        .with_fact("window_days", window_days)
+        .with_remediation(format!(
+            "check whether {package_name} development has moved to the {} line before depending on {}",
+            prerelease.version, stable.version
+        )),
+    )
+}
+
+/// The registry's latest tag when it is a stable release, otherwise the highest stable version.
+fn latest_stable<'a>(
+    package: &PackageRecord,
+    parsed: &'a [(ParsedVersion, &'a PackageVersion)],
+) -> Option<&'a (ParsedVersion, &'a PackageVersion)> {
+    let stable = || parsed.iter().filter(|(parsed, _)| !parsed.is_prerelease());
+    stable()
+        .find(|(_, version)| version.version == package.latest)
+        .or_else(|| {
+            stable().max_by(|(left, _), (right, _)| {
+                left.partial_cmp(right).unwrap_or(std::cmp::Ordering::Equal)
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::collections::BTreeMap;
+
+    fn package(latest: &str, versions: &[(&str, Option<DateTime<Utc>>)]) -> PackageRecord {
+        let versions = versions
+            .iter()
+            .map(|(version, published)| {
+                (
+                    version.to_string(),
+                    PackageVersion {
+                        version: version.to_string(),
+                        published: *published,
+                        deprecated: false,
+                        install_scripts: Vec::new(),
+                        unpacked_size: None,
+                        file_count: None,
+                        maintainer_emails: Vec::new(),
+                        bin_targets: Vec::new(),
+                        license: None,
+                        repository_url: None,
+                        published_estimated: false,
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        PackageRecord {
+            name: "demo".to_string(),
+            latest: latest.to_string(),
+            publishers: Vec::new(),
+            versions,
+        }
+    }
+
+    #[tokio::test]
+    async fn old_stable_with_recent_prereleases_is_low_risk() {
+        let now = Utc::now();
+        let record = package(
+            "1.4.0",
+            &[
+                ("1.3.0", Some(now - Duration::days(500))),
+                ("1.4.0", Some(now - Duration::days(400))),
+                ("2.0.0-beta.1", Some(now - Duration::days(90))),
+                ("2.0.0-beta.2", Some(now - Duration::days(12))),
+            ],
+        );
+        let finding = run("demo", &record, VersionScheme::SemVer, now, 180)
+            .await
+            .expect("finding");
+        assert_eq!(finding.severity, Severity::Low);
+        assert_eq!(finding.reason_code, "stale_stable");
+        assert_eq!(finding.code, "STABLE001");
+        assert!(finding.reason.contains("1.4.0 is 400 days old"));
+        assert!(finding.reason.contains("2.0.0-beta.2 shipped 12 days ago"));
+    }
+
+    #[tokio::test]
+    async fn pep440_prereleases_are_detected() {
+        let now = Utc::now();
+        let record = package(
+            "3.1",
+            &[
+                ("3.1", Some(now - Duration::days(300))),
+                ("4.0rc1", Some(now - Duration::days(5))),
+            ],
+        );
+        let finding = run("demo", &record, VersionScheme::Pep440, now, 180)
+            .await
+            .expect("finding");
+        assert!(finding.reason.contains("prerelease 4.0rc1"));
+    }
+
+    #[tokio::test]
+    async fn recent_stable_or_quiet_prereleases_are_not_flagged() {
+        let now = Utc::now();
+        let fresh_stable = package(
+            "1.4.0",
+            &[
+                ("1.4.0", Some(now - Duration::days(30))),
+                ("2.0.0-rc.1", Some(now - Duration::days(2))),
+            ],
+        );
+        assert!(
+            run("demo", &fresh_stable, VersionScheme::SemVer, now, 180)
+                .await
+                .is_none()
+        );
+
+        let abandoned_prerelease = package(
+            "1.4.0",
+            &[
+                ("1.4.0", Some(now - Duration::days(700))),
+                ("2.0.0-rc.1", Some(now - Duration::days(365))),
+            ],
+        );
+        assert!(
+            run(
+                "demo",
+                &abandoned_prerelease,
+                VersionScheme::SemVer,
+                now,
+                180
+            )
+            .await
+            .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn prereleases_of_older_lines_are_ignored() {
+        let now = Utc::now();
+        let record = package(
+            "2.0.0",
+            &[
+                ("2.0.0", Some(now - Duration::days(400))),
+                ("1.9.0-rc.1", Some(now - Duration::days(3))),
+            ],
+        );
+        assert!(
+            run("demo", &record, VersionScheme::SemVer, now, 180)
+                .await
+                .is_none()
+        );
+    }
+}
@@ -1,11 +1,11 @@
 use async_trait::async_trait;
 use safe_pkgs_core::{
     Check, CheckExecutionContext, CheckFinding, CheckId, PackageRecord, PackageVersion,
-    RegistryError, Severity, StalenessPolicy,
+    RegistryError, Severity, StalenessPolicy, VersionScheme, age_in_days, future_publish_finding,
 };
-use semver::Version;
 
 const CHECK_ID: CheckId = "staleness";
+const FUTURE_PUBLISH_CODE: &str = "STALE005";
 
 pub fn create_check() -> Box<dyn Check> {
     Box::new(StalenessCheck)
@@ -20,7 +20,11 @@ impl Check for StalenessCheck {
     }
 
     fn description(&self) -> &'static str {
-        "Flags deprecated or stale package versions based on age and semver distance."
+        "Flags deprecated or stale package versions based on age and version distance."
+    }
+
+    fn needs_full_metadata(&self) -> bool {
+        true
     }
 
     async fn run(
@@ -33,20 +37,26 @@ impl Check for StalenessCheck {
         let Some(resolved_version) = context.resolved_version else {
             return Ok(Vec::new());
         };
-        let requested_age_days = resolved_version.published.map(|published| {
-            context
-                .evaluation_time
-                .signed_duration_since(published)
-                .num_days()
-        });
-
-        Ok(run(
+        // An estimated publish time understates age, so age-based findings are skipped.
+        let requested_age_days = resolved_version
+            .exact_published()
+            .map(|published| age_in_days(published, context.evaluation_time));
+
+        let mut findings = run(
             package,
             resolved_version,
             &context.policy.staleness,
             requested_age_days,
+            context.registry_client.ecosystem().version_scheme(),
         )
-        .await)
+        .await;
+        findings.extend(future_publish_finding(
+            context.package_name,
+            resolved_version,
+            context.evaluation_time,
+            FUTURE_PUBLISH_CODE,
+        ));
+        Ok(findings)
     }
 }
 
@@ -55,9 +65,15 @@ async fn run(
     requested: &PackageVersion,
     policy: &StalenessPolicy,
     requested_age_days: Option<i64>,
+    scheme: VersionScheme,
 ) -> Vec<CheckFinding> {
     let mut findings = Vec::new();
-    let ignored = is_ignored(package.name.as_str(), requested.version.as_str(), policy);
+    let ignored = is_ignored(
+        package.name.as_str(),
+        requested.version.as_str(),
+        policy,
+        scheme,
+    );
 
     if requested.deprecated {
         findings.push(
@@ -69,8 +85,10 @@ async fn run(
                 ),
                 "deprecated_version",
             )
+            .with_code("STALE004")
             .with_fact("package_name", package.name.as_str())
-            .with_fact("resolved_version", requested.version.as_str()),
+            .with_fact("resolved_version", requested.version.as_str())
+            .with_remediation(upgrade_remediation(package, requested)),
         );
     }
 
@@ -87,10 +105,12 @@ async fn run(
                 ),
                 "old_release_age",
             )
+            .with_code("STALE003")
             .with_fact("package_name", package.name.as_str())
             .with_fact("resolved_version", requested.version.as_str())
             .with_fact("age_days", age_days)
-            .with_fact("warn_age_days", policy.warn_age_days),
+            .with_fact("warn_age_days", policy.warn_age_days)
+            .with_remediation(upgrade_remediation(package, requested)),
         );
     }
 
@@ -98,20 +118,24 @@ async fn run(
         return findings;
     }
 
-    let Ok(requested_semver) = Version::parse(&requested.version) else {
+    let Some(requested_parsed) = scheme.parse(&requested.version) else {
         return findings;
     };
-    let Ok(latest_semver) = Version::parse(&package.latest) else {
+    let Some(latest_parsed) = scheme.parse(&package.latest) else {
         return findings;
     };
 
-    if latest_semver <= requested_semver {
+    if latest_parsed <= requested_parsed {
         return findings;
     }
 
-    let major_gap = latest_semver.major.saturating_sub(requested_semver.major);
-    let minor_gap = if latest_semver.major == requested_semver.major {
-        latest_semver.minor.saturating_sub(requested_semver.minor)
+    let major_gap = latest_parsed
+        .major()
+        .saturating_sub(requested_parsed.major());
+    let minor_gap = if latest_parsed.major() == requested_parsed.major() {
+        latest_parsed
+            .minor()
+            .saturating_sub(requested_parsed.minor())
     } else {
         0
     };
@@ -126,6 +150,7 @@ async fn run(
                 ),
                 "major_versions_behind",
             )
+            .with_code("STALE002")
             .with_fact("package_name", package.name.as_str())
             .with_fact("resolved_version", requested.version.as_str())
             .with_fact("latest_version", package.latest.as_str())
@@ -133,7 +158,8 @@ async fn run(
             .with_fact(
                 "warn_major_versions_behind",
                 policy.warn_major_versions_behind,
-            ),
+            )
+            .with_remediation(upgrade_remediation(package, requested)),
         );
     } else if major_gap >= 1 || minor_gap >= policy.warn_minor_versions_behind {
         findings.push(
@@ -145,6 +171,7 @@ async fn run(
                 ),
                 "behind_latest",
             )
+            .with_code("STALE001")
             .with_fact("package_name", package.name.as_str())
             .with_fact("resolved_version", requested.version.as_str())
             .with_fact("latest_version", package.latest.as_str())
@@ -153,14 +180,35 @@ async fn run(
             .with_fact(
                 "warn_minor_versions_behind",
                 policy.warn_minor_versions_behind,
-            ),
+            )
+            .with_remediation(upgrade_remediation(package, requested)),
         );
     }
 
     findings
 }
 
-fn is_ignored(package_name: &str, version: &str, policy: &StalenessPolicy) -> bool {
+/// Points at the latest release, or at a replacement when the latest is the flagged version.
+fn upgrade_remediation(package: &PackageRecord, requested: &PackageVersion) -> String {
+    if package.latest == requested.version {
+        format!(
+            "consider replacing {} with a maintained package",
+            package.name
+        )
+    } else {
+        format!(
+            "consider upgrading to latest {}@{}",
+            package.name, package.latest
+        )
+    }
+}
+
+fn is_ignored(
+    package_name: &str,
+    version: &str,
+    policy: &StalenessPolicy,
+    scheme: VersionScheme,
+) -> bool {
     policy.ignore_for.iter().any(|rule| {
         if rule == package_name {
             return true;
@@ -183,11 +231,11 @@ fn is_ignored(package_name: &str, version: &str, policy: &StalenessPolicy) -> bo
         let Ok(rule_major) = major_prefix.parse::<u64>() else {
             return false;
         };
-        let Ok(parsed_version) = Version::parse(version) else {
+        let Some(parsed_version) = scheme.parse(version) else {
             return false;
         };
 
-        parsed_version.major == rule_major
+        parsed_version.major() == rule_major
     })
 }
 
@@ -216,6 +264,13 @@ mod tests {
                 published: Some(Utc::now() - Duration::days(100)),
                 deprecated: false,
                 install_scripts: Vec::new(),
+                unpacked_size: None,
+                file_count: None,
+                maintainer_emails: Vec::new(),
+                bin_targets: Vec::new(),
+                license: None,
+                repository_url: None,
+                published_estimated: false,
             },
         );
         versions.insert(
@@ -225,6 +280,13 @@ mod tests {
                 published: Some(Utc::now() - Duration::days(10)),
                 deprecated: false,
                 install_scripts: Vec::new(),
+                unpacked_size: None,
+                file_count: None,
+                maintainer_emails: Vec::new(),
+                bin_targets: Vec::new(),
+                license: None,
+                repository_url: None,
+                published_estimated: false,
             },
         );
         let package = PackageRecord {
@@ -235,8 +297,16 @@ mod tests {
         };
 
         let requested = package.versions.get("1.0.0").expect("version exists");
-        let findings = run(&package, requested, &default_policy(), Some(100)).await;
+        let findings = run(
+            &package,
+            requested,
+            &default_policy(),
+            Some(100),
+            VersionScheme::SemVer,
+        )
+        .await;
         assert!(findings.iter().any(|f| f.severity == Severity::Medium));
+        assert!(findings.iter().any(|f| f.code == "STALE002"));
     }
 
     #[tokio::test]
@@ -249,6 +319,13 @@ mod tests {
                 published: Some(Utc::now() - Duration::days(1000)),
                 deprecated: false,
                 install_scripts: Vec::new(),
+                unpacked_size: None,
+                file_count: None,
+                maintainer_emails: Vec::new(),
+                bin_targets: Vec::new(),
+                license: None,
+                repository_url: None,
+                published_estimated: false,
             },
         );
         versions.insert(
@@ -258,6 +335,13 @@ mod tests {
                 published: Some(Utc::now() - Duration::days(10)),
                 deprecated: false,
                 install_scripts: Vec::new(),
+                unpacked_size: None,
+                file_count: None,
+                maintainer_emails: Vec::new(),
+                bin_targets: Vec::new(),
+                license: None,
+                repository_url: None,
+                published_estimated: false,
             },
         );
         let package = PackageRecord {
@@ -273,11 +357,122 @@ mod tests {
         };
 
         let requested = package.versions.get("1.0.0").expect("version exists");
-        let findings = run(&package, requested, &policy, Some(1000)).await;
+        let findings = run(
+            &package,
+            requested,
+            &policy,
+            Some(1000),
+            VersionScheme::SemVer,
+        )
+        .await;
         assert!(
             findings
                 .iter()
                 .all(|finding| !finding.reason.contains("behind latest"))
         );
     }
+
+    fn pypi_package(requested: &str, latest: &str) -> PackageRecord {
+        let versions = [requested, latest]
+            .into_iter()
+            .map(|version| {
+                (
+                    version.to_string(),
+                    PackageVersion {
+                        version: version.to_string(),
+                        published: Some(Utc::now() - Duration::days(10)),
+                        deprecated: false,
+                        install_scripts: Vec::new(),
+                        unpacked_size: None,
+                        file_count: None,
+                        maintainer_emails: Vec::new(),
+                        bin_targets: Vec::new(),
+                        license: None,
+                        repository_url: None,
+                        published_estimated: false,
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        PackageRecord {
+            name: "demo".to_string(),
+            latest: latest.to_string(),
+            publishers: Vec::new(),
+            versions,
+        }
+    }
+
+    #[tokio::test]
+    async fn pep440_calendar_version_behind_latest_is_low() {
+        let package = pypi_package("2024.1", "2024.5");
+        let requested = package.versions.get("2024.1").expect("version exists");
+        let findings = run(
+            &package,
+            requested,
+            &default_policy(),
+            Some(10),
+            VersionScheme::Pep440,
+        )
+        .await;
+        let behind = findings
+            .iter()
+            .find(|finding| finding.reason_code == "behind_latest" && finding.code == "STALE001")
+            .expect("behind_latest finding");
+        assert_eq!(
+            behind.remediation.as_deref(),
+            Some("consider upgrading to latest demo@2024.5")
+        );
+    }
+
+    #[tokio::test]
+    async fn deprecated_old_release_reports_both_codes() {
+        let mut package = pypi_package("1.0.0", "1.0.0");
+        if let Some(version) = package.versions.get_mut("1.0.0") {
+            version.deprecated = true;
+        }
+        let requested = package.versions.get("1.0.0").expect("version exists");
+        let findings = run(
+            &package,
+            requested,
+            &default_policy(),
+            Some(400),
+            VersionScheme::SemVer,
+        )
+        .await;
+        let codes = findings
+            .iter()
+            .map(|finding| finding.code)
+            .collect::<Vec<_>>();
+        assert_eq!(codes, ["STALE004", "STALE003"]);
+    }
+
+    #[tokio::test]
+    async fn pep440_release_candidate_of_latest_major_has_no_gap_finding() {
+        let package = pypi_package("1.0.0rc1", "1.0.0");
+        let requested = package.versions.get("1.0.0rc1").expect("version exists");
+        let findings = run(
+            &package,
+            requested,
+            &default_policy(),
+            Some(10),
+            VersionScheme::Pep440,
+        )
+        .await;
+        assert!(findings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pep440_major_gap_is_medium() {
+        let package = pypi_package("1.0rc1", "3.0.post1");
+        let requested = package.versions.get("1.0rc1").expect("version exists");
+        let findings = run(
+            &package,
+            requested,
+            &default_policy(),
+            Some(10),
+            VersionScheme::Pep440,
+        )
+        .await;
+        assert!(findings.iter().any(|f| f.severity == Severity::Medium));
+    }
 }
@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+use safe_pkgs_core::{
+    Check, CheckExecutionContext, CheckFinding, CheckId, RegistryError, Severity,
+};
+
+const CHECK_ID: CheckId = "suspicious_name";
+/// Characters that render with no width and can hide inside an otherwise familiar name.
+const ZERO_WIDTH_CHARACTERS: [char; 6] = [
+    '\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{180E}', '\u{FEFF}',
+];
+/// Bidirectional formatting characters used in Trojan Source style reordering.
+const BIDI_CONTROL_CHARACTERS: [char; 12] = [
+    '\u{061C}', '\u{200E}', '\u{200F}', '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}',
+    '\u{2066}', '\u{2067}', '\u{2068}', '\u{2069}',
+];
+
+pub fn create_check() -> Box<dyn Check> {
+    Box::new(SuspiciousNameCheck)
+}
+
+pub struct SuspiciousNameCheck;
+
+#[async_trait]
+impl Check for SuspiciousNameCheck {
+    fn id(&self) -> CheckId {
+        CHECK_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags package names containing invisible, bidirectional-control, or mixed-script characters."
+    }
+
+    // Only the requested name is inspected, so a missing package is still checked.
+    fn runs_on_missing_package(&self) -> bool {
+        true
+    }
+
+    fn runs_on_missing_version(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        context: &CheckExecutionContext<'_>,
+    ) -> Result<Vec<CheckFinding>, RegistryError> {
+        Ok(run(context.package_name).await)
+    }
+}
+
+async fn run(package_name: &str) -> Vec<CheckFinding> {
+    let mut findings = Vec::new();
+
+    let control = matching_code_points(package_name, |ch| {
+        ch.is_control() || BIDI_CONTROL_CHARACTERS.contains(&ch)
+    });
+    if !control.is_empty() {
+        findings.push(
+            CheckFinding::new(
+                Severity::High,
+                format!(
+                    "{} contains control or bidirectional override characters ({})",
+                    escaped(package_name),
+                    control.join(", ")
+                ),
+                "control_character",
+            )
+            .with_code("NAME001")
+            .with_fact("package_name", escaped(package_name))
+            .with_fact("code_points", control.join(",")),
+        );
+    }
+
+    let zero_width = matching_code_points(package_name, |ch| ZERO_WIDTH_CHARACTERS.contains(&ch));
+    if !zero_width.is_empty() {
+        findings.push(
+            CheckFinding::new(
+                Severity::Medium,
+                format!(
+                    "{} contains zero-width characters ({})",
+                    escaped(package_name),
+                    zero_width.join(", ")
+                ),
+                "zero_width_character",
+            )
+            .with_code("NAME002")
+            .with_fact("package_name", escaped(package_name))
+            .with_fact("code_points", zero_width.join(",")),
+        );
+    }
+
+    // Latin letters next to lookalike Greek or Cyrillic letters read as one familiar word.
+    let has_latin = package_name.chars().any(|ch| ch.is_ascii_alphabetic());
+    let confusables = matching_code_points(package_name, is_latin_confusable_script);
+    if has_latin && !confusables.is_empty() {
+        findings.push(
+            CheckFinding::new(
+                Severity::Medium,
+                format!(
+                    "{} mixes Latin letters with Greek or Cyrillic lookalikes ({})",
+                    escaped(package_name),
+                    confusables.join(", ")
+                ),
+                "mixed_script",
+            )
+            .with_code("NAME003")
+            .with_fact("package_name", escaped(package_name))
+            .with_fact("code_points", confusables.join(",")),
+        );
+    }
+
+    findings
+}
+
+fn is_latin_confusable_script(ch: char) -> bool {
+    // Greek and Coptic, Cyrillic, and Cyrillic Supplement blocks.
+    matches!(ch, '\u{0370}'..='\u{03FF}' | '\u{0400}'..='\u{052F}')
+}
+
+/// Distinct matching characters as `U+XXXX` labels, in order of first appearance.
+fn matching_code_points(name: &str, predicate: impl Fn(char) -> bool) -> Vec<String> {
+    let mut labels = Vec::new();
+    for ch in name.chars().filter(|ch| predicate(*ch)) {
+        let label = format!("U+{:04X}", u32::from(ch));
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// Renders the name with invisible and control characters escaped, so reports do not
+/// reproduce the reordering they describe.
+fn escaped(name: &str) -> String {
+    name.chars()
+        .map(|ch| {
+            if ch.is_control()
+                || BIDI_CONTROL_CHARACTERS.contains(&ch)
+                || ZERO_WIDTH_CHARACTERS.contains(&ch)
+            {
+                format!("\\u{{{:04X}}}", u32::from(ch))
+            } else {
+                ch.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn zero_width_space_is_medium_risk() {
+        let findings = run("left\u{200B}pad").await;
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].reason_code, "zero_width_character");
+        assert_eq!(findings[0].code, "NAME002");
+        assert!(findings[0].reason.contains("U+200B"));
+        assert!(findings[0].reason.starts_with("left\\u{200B}pad"));
+    }
+
+    #[tokio::test]
+    async fn trojan_source_rtl_override_is_high_risk() {
+        let findings = run("safe\u{202E}gkp-evil").await;
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].reason_code, "control_character");
+        assert_eq!(findings[0].code, "NAME001");
+        assert!(findings[0].reason.contains("U+202E"));
+        assert!(!findings[0].reason.contains('\u{202E}'));
+    }
+
+    #[tokio::test]
+    async fn cyrillic_lookalike_in_latin_name_is_mixed_script() {
+        // "rеact" with U+0435 CYRILLIC SMALL LETTER IE.
+        let findings = run("r\u{0435}act").await;
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].reason_code, "mixed_script");
+        assert_eq!(findings[0].code, "NAME003");
+        assert!(findings[0].reason.contains("U+0435"));
+    }
+
+    #[tokio::test]
+    async fn plain_and_single_script_names_have_no_finding() {
+        assert!(run("left-pad").await.is_empty());
+        assert!(run("@scope/my_pkg.js").await.is_empty());
+        assert!(
+            run("\u{0440}\u{0435}\u{0430}\u{043A}\u{0442}")
+                .await
+                .is_empty()
+        );
+    }
+}
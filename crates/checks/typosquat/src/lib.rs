@@ -1,12 +1,16 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use safe_pkgs_core::{
-    Check, CheckExecutionContext, CheckFinding, CheckId, RegistryClient, RegistryError, Severity,
+    Check, CheckExecutionContext, CheckFinding, CheckId, PackageRecord, RegistryClient,
+    RegistryError, Severity,
 };
 
 const CHECK_ID: CheckId = "typosquat";
 const POPULAR_PACKAGE_SAMPLE_SIZE: usize = 5000;
 const OBSCURE_WEEKLY_DOWNLOADS_THRESHOLD: u64 = 50;
 const TYPO_DISTANCE_LIMIT: usize = 2;
+/// Packages whose first release is younger than this count as new for escalation.
+const NEW_PACKAGE_MAX_AGE_DAYS: i64 = 30;
 
 pub fn create_check() -> Box<dyn Check> {
     Box::new(TyposquatCheck)
@@ -32,6 +36,10 @@ impl Check for TyposquatCheck {
         true
     }
 
+    fn needs_full_metadata(&self) -> bool {
+        true
+    }
+
     async fn run(
         &self,
         context: &CheckExecutionContext<'_>,
@@ -40,6 +48,11 @@ impl Check for TyposquatCheck {
             context.package_name,
             context.weekly_downloads,
             context.registry_client,
+            context.policy.typosquat.fail_open,
+            &context.policy.typosquat.ignore,
+            context
+                .package
+                .and_then(|package| new_single_publisher_age(package, context.evaluation_time)),
         )
         .await?
         .into_iter()
@@ -51,15 +64,32 @@ async fn run(
     package_name: &str,
     weekly_downloads: Option<u64>,
     registry_client: &dyn RegistryClient,
+    fail_open: bool,
+    ignore: &[String],
+    new_single_publisher_age_days: Option<i64>,
 ) -> Result<Option<CheckFinding>, RegistryError> {
+    if is_ignored(package_name, ignore) {
+        return Ok(None);
+    }
+
     let weekly_downloads = weekly_downloads.unwrap_or(0);
     if weekly_downloads >= OBSCURE_WEEKLY_DOWNLOADS_THRESHOLD {
         return Ok(None);
     }
 
-    let popular_packages = registry_client
+    let popular_packages = match registry_client
         .fetch_popular_package_names(POPULAR_PACKAGE_SAMPLE_SIZE)
-        .await?;
+        .await
+    {
+        Ok(names) => names,
+        Err(err) if fail_open => {
+            tracing::warn!(
+                "typosquat check inconclusive for {package_name}: popular package index unavailable: {err}"
+            );
+            return Ok(None);
+        }
+        Err(err) => return Err(err),
+    };
 
     if popular_packages
         .iter()
@@ -91,19 +121,65 @@ async fn run(
         return Ok(None);
     };
 
-    Ok(Some(
-        CheckFinding::new(
-            Severity::High,
-            format!(
-            "{package_name} is {distance} edit(s) away from popular package {candidate} and has low adoption ({weekly_downloads} weekly downloads)"
-        ),
-            "close_to_popular_name",
-        )
+    let mut reason = format!(
+        "{package_name} is {distance} edit(s) away from popular package {candidate} and has low adoption ({weekly_downloads} weekly downloads)"
+    );
+    // A lookalike name from a brand-new, single-publisher package is the classic squatting shape.
+    let severity = match new_single_publisher_age_days {
+        Some(age_days) => {
+            reason.push_str(&format!(
+                "; first published {age_days} day(s) ago by a single publisher"
+            ));
+            Severity::Critical
+        }
+        None => Severity::High,
+    };
+
+    let mut finding = CheckFinding::new(severity, reason, "close_to_popular_name")
+        .with_code("TYPO001")
         .with_fact("package_name", package_name)
         .with_fact("closest_package", candidate)
         .with_fact("edit_distance", distance)
-        .with_fact("weekly_downloads", weekly_downloads),
-    ))
+        .with_fact("weekly_downloads", weekly_downloads)
+        .with_remediation(format!("did you mean {candidate}?"));
+    if let Some(age_days) = new_single_publisher_age_days {
+        finding = finding
+            .with_fact("single_publisher", true)
+            .with_fact("package_age_days", age_days);
+    }
+    Ok(Some(finding))
+}
+
+/// Matches `typosquat.ignore` rules: exact names, or `prefix*` globs such as `@acme/*`.
+///
+/// Comparison is ASCII case-insensitive, matching how registries treat package names.
+fn is_ignored(package_name: &str, ignore: &[String]) -> bool {
+    let package_name = package_name.to_ascii_lowercase();
+    ignore.iter().any(|rule| {
+        let rule = rule.trim().to_ascii_lowercase();
+        match rule.strip_suffix('*') {
+            Some(prefix) => package_name.starts_with(prefix),
+            None => package_name == rule,
+        }
+    })
+}
+
+/// Returns the package's age in days when it has exactly one publisher and its first
+/// release is younger than [`NEW_PACKAGE_MAX_AGE_DAYS`].
+fn new_single_publisher_age(
+    package: &PackageRecord,
+    evaluation_time: DateTime<Utc>,
+) -> Option<i64> {
+    if package.publishers.len() != 1 {
+        return None;
+    }
+    let first_published = package
+        .versions
+        .values()
+        .filter_map(|version| version.published)
+        .min()?;
+    let age_days = (evaluation_time - first_published).num_days();
+    (age_days < NEW_PACKAGE_MAX_AGE_DAYS).then_some(age_days)
 }
 
 /// Computes the Levenshtein distance between two strings, returning `None` early
@@ -154,7 +230,7 @@ mod tests {
     use safe_pkgs_core::{PackageRecord, RegistryEcosystem};
 
     struct FakeRegistryClient {
-        popular_packages: Vec<String>,
+        popular_packages: Result<Vec<String>, RegistryError>,
     }
 
     #[async_trait]
@@ -180,35 +256,138 @@ mod tests {
             &self,
             limit: usize,
         ) -> Result<Vec<String>, RegistryError> {
-            Ok(self
-                .popular_packages
-                .iter()
-                .take(limit)
-                .cloned()
-                .collect::<Vec<_>>())
+            self.popular_packages
+                .clone()
+                .map(|names| names.into_iter().take(limit).collect::<Vec<_>>())
         }
     }
 
     #[tokio::test]
     async fn low_download_close_name_is_flagged() {
         let client = FakeRegistryClient {
-            popular_packages: vec!["react".to_string(), "lodash".to_string()],
+            popular_packages: Ok(vec!["react".to_string(), "lodash".to_string()]),
         };
 
-        let result = run("raect", Some(10), &client).await.expect("typosquat");
+        let result = run("raect", Some(10), &client, true, &[], None)
+            .await
+            .expect("typosquat");
         let finding = result.expect("finding expected");
         assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.code, "TYPO001");
         assert!(finding.reason.contains("react"));
+        assert_eq!(finding.remediation.as_deref(), Some("did you mean react?"));
     }
 
     #[tokio::test]
     async fn high_download_package_is_not_flagged() {
         let client = FakeRegistryClient {
-            popular_packages: vec!["react".to_string(), "lodash".to_string()],
+            popular_packages: Ok(vec!["react".to_string(), "lodash".to_string()]),
+        };
+
+        let result = run("raect", Some(1000), &client, true, &[], None)
+            .await
+            .expect("typosquat");
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn popular_index_failure_is_inconclusive_when_fail_open() {
+        let client = FakeRegistryClient {
+            popular_packages: Err(RegistryError::Transport {
+                message: "index unavailable".to_string(),
+            }),
         };
 
-        let result = run("raect", Some(1000), &client).await.expect("typosquat");
+        let result = run("raect", Some(10), &client, true, &[], None)
+            .await
+            .expect("fail-open typosquat");
         assert!(result.is_none());
+
+        let err = run("raect", Some(10), &client, false, &[], None)
+            .await
+            .expect_err("fail-closed typosquat");
+        assert!(matches!(err, RegistryError::Transport { .. }));
+    }
+
+    #[tokio::test]
+    async fn close_name_from_new_single_publisher_is_critical() {
+        let client = FakeRegistryClient {
+            popular_packages: Ok(vec!["react".to_string()]),
+        };
+
+        let result = run("raect", Some(10), &client, true, &[], Some(3))
+            .await
+            .expect("typosquat");
+        let finding = result.expect("finding expected");
+        assert_eq!(finding.severity, Severity::Critical);
+        assert!(finding.reason.contains("single publisher"));
+    }
+
+    #[tokio::test]
+    async fn ignored_names_are_not_flagged() {
+        let client = FakeRegistryClient {
+            popular_packages: Ok(vec!["react".to_string(), "lodash".to_string()]),
+        };
+        let ignore = vec!["reactx".to_string(), "@acme/*".to_string()];
+
+        let ignored = run("reactx", Some(10), &client, true, &ignore, None)
+            .await
+            .expect("typosquat");
+        assert!(ignored.is_none());
+
+        let flagged = run("reacts", Some(10), &client, true, &ignore, None)
+            .await
+            .expect("typosquat");
+        assert!(flagged.is_some());
+    }
+
+    #[test]
+    fn ignore_rules_match_exact_names_and_scope_globs() {
+        let ignore = vec!["ReactX".to_string(), "@acme/*".to_string()];
+        assert!(is_ignored("reactx", &ignore));
+        assert!(is_ignored("@acme/lodahs", &ignore));
+        assert!(!is_ignored("reactxx", &ignore));
+        assert!(!is_ignored("@acmex/lodahs", &ignore));
+    }
+
+    fn package_record(publishers: &[&str], first_published: DateTime<Utc>) -> PackageRecord {
+        let version = safe_pkgs_core::PackageVersion {
+            version: "1.0.0".to_string(),
+            published: Some(first_published),
+            deprecated: false,
+            install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        };
+        PackageRecord {
+            name: "raect".to_string(),
+            latest: "1.0.0".to_string(),
+            publishers: publishers.iter().map(ToString::to_string).collect(),
+            versions: [("1.0.0".to_string(), version)].into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn escalation_requires_single_publisher_and_new_package() {
+        let now = Utc::now();
+        let days = chrono::Duration::days;
+        assert_eq!(
+            new_single_publisher_age(&package_record(&["mallory"], now - days(3)), now),
+            Some(3)
+        );
+        assert_eq!(
+            new_single_publisher_age(&package_record(&["alice", "bob"], now - days(3)), now),
+            None
+        );
+        assert_eq!(
+            new_single_publisher_age(&package_record(&["alice"], now - days(400)), now),
+            None
+        );
     }
 
     #[test]
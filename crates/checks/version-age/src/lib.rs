@@ -1,9 +1,11 @@
 use async_trait::async_trait;
 use safe_pkgs_core::{
     Check, CheckExecutionContext, CheckFinding, CheckId, PackageVersion, RegistryError, Severity,
+    VersionScheme, age_in_days, future_publish_finding,
 };
 
 const CHECK_ID: CheckId = "version_age";
+const FUTURE_PUBLISH_CODE: &str = "AGE004";
 
 pub fn create_check() -> Box<dyn Check> {
     Box::new(VersionAgeCheck)
@@ -21,6 +23,10 @@ impl Check for VersionAgeCheck {
         "Flags versions newer than the configured minimum package age."
     }
 
+    fn needs_full_metadata(&self) -> bool {
+        true
+    }
+
     async fn run(
         &self,
         context: &CheckExecutionContext<'_>,
@@ -30,17 +36,35 @@ impl Check for VersionAgeCheck {
         };
         let age_days = resolved_version
             .published
-            .map(|published| (context.evaluation_time - published).num_days());
+            .map(|published| age_in_days(published, context.evaluation_time));
+        let patch_baseline = context
+            .installed_version
+            .filter(|_| context.policy.version_age.exempt_patch_upgrades)
+            .and_then(|installed| {
+                trusted_patch_baseline(
+                    installed,
+                    resolved_version,
+                    context.registry_client.ecosystem().version_scheme(),
+                )
+            });
 
-        Ok(run(
+        let mut findings = run(
             context.package_name,
             resolved_version,
             context.policy.min_version_age_days,
             age_days,
+            patch_baseline,
         )
         .await
         .into_iter()
-        .collect())
+        .collect::<Vec<_>>();
+        findings.extend(future_publish_finding(
+            context.package_name,
+            resolved_version,
+            context.evaluation_time,
+            FUTURE_PUBLISH_CODE,
+        ));
+        Ok(findings)
     }
 }
 
@@ -49,11 +73,44 @@ async fn run(
     version: &PackageVersion,
     min_version_age_days: i64,
     age_days: Option<i64>,
+    patch_baseline: Option<&str>,
 ) -> Option<CheckFinding> {
-    let age_days = age_days?;
+    let Some(age_days) = age_days else {
+        return Some(
+            CheckFinding::new(
+                Severity::Info,
+                format!(
+                    "{package_name}@{} has no publish time in registry metadata; age checks were skipped",
+                    version.version
+                ),
+                "publish_time_unknown",
+            )
+            .with_code("AGE002")
+            .with_fact("package_name", package_name)
+            .with_fact("resolved_version", version.version.as_str()),
+        );
+    };
     if age_days >= min_version_age_days {
         return None;
     }
+    if let Some(baseline) = patch_baseline {
+        return Some(
+            CheckFinding::new(
+                Severity::Info,
+                format!(
+                    "{package_name}@{} was published {age_days} day(s) ago but is a patch release of trusted {baseline}; age check exempted",
+                    version.version
+                ),
+                "patch_upgrade_exempt",
+            )
+            .with_code("AGE003")
+            .with_fact("package_name", package_name)
+            .with_fact("resolved_version", version.version.as_str())
+            .with_fact("baseline_version", baseline)
+            .with_fact("age_days", age_days)
+            .with_fact("min_age_days", min_version_age_days),
+        );
+    }
 
     Some(
         CheckFinding::new(
@@ -64,30 +121,64 @@ async fn run(
             ),
             "too_new",
         )
+        .with_code("AGE001")
         .with_fact("package_name", package_name)
         .with_fact("resolved_version", version.version.as_str())
         .with_fact("age_days", age_days)
-        .with_fact("min_age_days", min_version_age_days),
+        .with_fact("min_age_days", min_version_age_days)
+        .with_remediation(format!(
+            "wait {} more day(s) or pin a vetted older version",
+            min_version_age_days.saturating_sub(age_days)
+        )),
     )
 }
 
+/// Returns `installed` when `version` is a later patch release in its `major.minor` line.
+///
+/// Only the version the project already has locked or installed counts as trusted; an older
+/// release that merely exists in the registry is not a baseline.
+fn trusted_patch_baseline<'a>(
+    installed: &'a str,
+    version: &PackageVersion,
+    scheme: VersionScheme,
+) -> Option<&'a str> {
+    let resolved = scheme.parse(&version.version)?;
+    let baseline = scheme.parse(installed)?;
+    let same_line = baseline.major() == resolved.major() && baseline.minor() == resolved.minor();
+    (!resolved.is_prerelease() && !baseline.is_prerelease() && same_line && baseline < resolved)
+        .then_some(installed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::{Duration, Utc};
 
     fn version(days_ago: i64) -> PackageVersion {
+        version_at("1.2.3", days_ago)
+    }
+
+    fn version_at(version: &str, days_ago: i64) -> PackageVersion {
         PackageVersion {
-            version: "1.2.3".to_string(),
+            version: version.to_string(),
             published: Some(Utc::now() - Duration::days(days_ago)),
             deprecated: false,
             install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
         }
     }
 
     #[tokio::test]
     async fn recent_release_is_high_risk() {
-        let finding = run("demo", &version(2), 7, Some(2)).await.expect("finding");
+        let finding = run("demo", &version(2), 7, Some(2), None)
+            .await
+            .expect("finding");
         assert_eq!(finding.severity, Severity::High);
         assert!(finding.reason.contains("demo@1.2.3"));
         assert!(finding.reason.contains("< 7 days"));
@@ -95,19 +186,77 @@ mod tests {
 
     #[tokio::test]
     async fn old_enough_release_has_no_finding() {
-        let finding = run("demo", &version(30), 7, Some(30)).await;
+        let finding = run("demo", &version(30), 7, Some(30), None).await;
         assert!(finding.is_none());
     }
 
     #[tokio::test]
-    async fn missing_publish_date_has_no_finding() {
+    async fn missing_publish_date_is_informational() {
         let version = PackageVersion {
             version: "1.2.3".to_string(),
             published: None,
             deprecated: false,
             install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
         };
-        let finding = run("demo", &version, 7, None).await;
-        assert!(finding.is_none());
+        let finding = run("demo", &version, 7, None, None).await.expect("finding");
+        assert_eq!(finding.severity, Severity::Info);
+        assert_eq!(finding.reason_code, "publish_time_unknown");
+        assert_eq!(finding.code, "AGE002");
+    }
+
+    #[tokio::test]
+    async fn fresh_patch_of_installed_version_is_exempt() {
+        let resolved = version_at("1.2.4", 1);
+        let baseline = trusted_patch_baseline("1.2.3", &resolved, VersionScheme::SemVer);
+        assert_eq!(baseline, Some("1.2.3"));
+
+        let finding = run("demo", &resolved, 7, Some(1), baseline)
+            .await
+            .expect("finding");
+        assert_eq!(finding.severity, Severity::Info);
+        assert_eq!(finding.reason_code, "patch_upgrade_exempt");
+        assert_eq!(finding.code, "AGE003");
+    }
+
+    #[tokio::test]
+    async fn fresh_minor_release_is_not_exempt() {
+        let resolved = version_at("1.3.0", 1);
+        let baseline = trusted_patch_baseline("1.2.3", &resolved, VersionScheme::SemVer);
+        assert_eq!(baseline, None);
+
+        let finding = run("demo", &resolved, 7, Some(1), baseline)
+            .await
+            .expect("finding");
+        assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.reason_code, "too_new");
+        assert_eq!(finding.code, "AGE001");
+        assert_eq!(
+            finding.remediation.as_deref(),
+            Some("wait 6 more day(s) or pin a vetted older version")
+        );
+    }
+
+    #[test]
+    fn only_an_older_installed_patch_is_a_baseline() {
+        let resolved = version_at("1.2.4", 1);
+        assert_eq!(
+            trusted_patch_baseline("1.2.4", &resolved, VersionScheme::SemVer),
+            None
+        );
+        assert_eq!(
+            trusted_patch_baseline("1.2.5", &resolved, VersionScheme::SemVer),
+            None
+        );
+        assert_eq!(
+            trusted_patch_baseline("1.2.3-rc.1", &resolved, VersionScheme::SemVer),
+            None
+        );
     }
 }
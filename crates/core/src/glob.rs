@@ -0,0 +1,50 @@
+//! Single-segment glob matching shared by policy patterns and workspace discovery.
+
+/// Glob match where `*` matches any run of characters and `?` exactly one.
+///
+/// Matching is case-sensitive and does not treat `/` specially; callers split paths into
+/// segments first.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // Last `*` seen in the pattern and the text position it is currently absorbing up to.
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&ch) if ch == '?' || ch == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, absorbed)) => {
+                    backtrack = Some((star, absorbed + 1));
+                    p = star + 1;
+                    t = absorbed + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_and_question_mark_match_within_a_segment() {
+        assert!(glob_matches("crates-*", "crates-core"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("a?c", "abc"));
+        assert!(glob_matches("*-*-x", "a-b-c-x"));
+        assert!(!glob_matches("a?c", "ac"));
+        assert!(!glob_matches("crates-*", "crate"));
+        assert!(glob_matches("é*", "émoji"));
+    }
+}
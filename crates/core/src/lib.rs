@@ -3,8 +3,15 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use thiserror::Error;
+use tokio::sync::Semaphore;
+
+mod glob;
+mod version;
+
+pub use glob::glob_matches;
+pub use version::{ParsedVersion, Pep440Specifier, Pep440Version, VersionRange, VersionScheme};
 
 pub type CheckId = &'static str;
 pub type CheckFactory = fn() -> Box<dyn Check>;
@@ -12,6 +19,8 @@ pub type CheckFactory = fn() -> Box<dyn Check>;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
+    /// Informational signal; reported but never counts toward `allow` or aggregate risk.
+    Info,
     Low,
     Medium,
     High,
@@ -24,10 +33,16 @@ pub struct Metadata {
     pub latest: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub requested: Option<String>,
+    /// Concrete version the checks evaluated, when the request resolved to one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub published: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub weekly_downloads: Option<u64>,
+    /// Milliseconds spent fetching package metadata from the registry, when enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetch_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -45,8 +60,13 @@ pub struct CheckFinding {
     /// `too_new`, `missing_package`, `known_advisory`) and should remain
     /// backward-compatible once published.
     pub reason_code: String,
+    /// Short stable code for this finding variant (for example `AGE001`), unique across
+    /// checks so CI can suppress or match findings without parsing `reason`.
+    pub code: &'static str,
     /// Structured machine-readable context attached to the finding.
     pub facts: BTreeMap<String, FindingValue>,
+    /// Actionable next step for resolving the finding (for example "upgrade to demo@1.2.3").
+    pub remediation: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -68,14 +88,26 @@ impl CheckFinding {
             severity,
             reason: reason.into(),
             reason_code: reason_code.into(),
+            code: "",
             facts: BTreeMap::new(),
+            remediation: None,
         }
     }
 
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = code;
+        self
+    }
+
     pub fn with_fact(mut self, key: impl Into<String>, value: impl Into<FindingValue>) -> Self {
         self.facts.insert(key.into(), value.into());
         self
     }
+
+    pub fn with_remediation(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = Some(remediation.into());
+        self
+    }
 }
 
 impl From<String> for FindingValue {
@@ -134,21 +166,89 @@ pub struct StalenessPolicy {
     pub ignore_for: Vec<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct TyposquatPolicy {
+    /// Treat popular-name index failures as inconclusive instead of failing the evaluation.
+    pub fail_open: bool,
+    /// Package names (or `prefix*` globs such as `@acme/*`) that are never flagged.
+    pub ignore: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExistencePolicy {
+    /// Severity when the package does not exist in the registry.
+    pub missing_package: Severity,
+    /// Severity when the package exists but the requested version does not.
+    pub missing_version: Severity,
+}
+
+#[derive(Debug, Clone)]
+pub struct BusFactorPolicy {
+    /// Weekly downloads above which a single-maintainer package is flagged.
+    pub high_download_threshold: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstallScriptPolicy {
+    /// Flag install hooks that register cron jobs, services, or other persistence.
+    pub detect_persistence: bool,
+    /// Flag every install hook whose command is not in `allowed_commands`.
+    pub deny_all: bool,
+    /// Hook commands exempt from `deny_all`, matched exactly (whitespace-insensitive).
+    pub allowed_commands: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReleaseChurnPolicy {
+    /// Releases in the last 7 days above which a package is flagged.
+    pub max_releases_per_week: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct VersionAgePolicy {
+    /// Skip the age check for patch releases of a version that already passed it.
+    pub exempt_patch_upgrades: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdvisoryPolicy {
+    /// Advisories with a CVSS score below this are reported as low risk.
+    pub min_cvss: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StaleStablePolicy {
+    /// Days after which a stable release counts as stale while prereleases keep shipping.
+    pub window_days: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct CheckPolicy {
     pub min_version_age_days: i64,
     pub min_weekly_downloads: u64,
+    pub version_age: VersionAgePolicy,
     pub staleness: StalenessPolicy,
+    pub typosquat: TyposquatPolicy,
+    pub bus_factor: BusFactorPolicy,
+    pub install_script: InstallScriptPolicy,
+    pub release_churn: ReleaseChurnPolicy,
+    pub stale_stable: StaleStablePolicy,
+    pub advisory: AdvisoryPolicy,
+    pub existence: ExistencePolicy,
 }
 
 pub struct CheckExecutionContext<'a> {
     pub registry_key: &'a str,
     pub package_name: &'a str,
     pub requested_version: Option<&'a str>,
+    /// Version the project already has locked or installed, when the caller knows it.
+    pub installed_version: Option<&'a str>,
     pub evaluation_time: DateTime<Utc>,
     pub package: Option<&'a PackageRecord>,
     pub resolved_version: Option<&'a PackageVersion>,
     pub weekly_downloads: Option<u64>,
+    /// Weekly downloads recorded by an earlier evaluation, when a rule asked for it.
+    pub weekly_downloads_baseline: Option<u64>,
     pub advisories: &'a [PackageAdvisory],
     pub registry_client: &'a dyn RegistryClient,
     pub policy: &'a CheckPolicy,
@@ -179,6 +279,15 @@ pub trait Check: Send + Sync {
     fn needs_popular_package_names(&self) -> bool {
         false
     }
+    /// Whether the check relies on package size metadata (`PackageVersion::unpacked_size`).
+    fn needs_size(&self) -> bool {
+        false
+    }
+    /// Whether the check reads publish times, publishers, maintainer emails, or install
+    /// scripts, which abbreviated registry metadata omits.
+    fn needs_full_metadata(&self) -> bool {
+        false
+    }
     async fn run(
         &self,
         context: &CheckExecutionContext<'_>,
@@ -189,6 +298,47 @@ pub fn normalize_check_id(raw: &str) -> String {
     raw.trim().to_ascii_lowercase().replace('-', "_")
 }
 
+/// Whole days from `published` to `evaluation_time`, clamped at zero so a registry clock
+/// that runs ahead never produces a negative age.
+pub fn age_in_days(published: DateTime<Utc>, evaluation_time: DateTime<Utc>) -> i64 {
+    evaluation_time
+        .signed_duration_since(published)
+        .num_days()
+        .max(0)
+}
+
+/// Low-severity `future_publish_time` anomaly for a version published after
+/// `evaluation_time`, tagged with the calling check's `code`.
+pub fn future_publish_finding(
+    package_name: &str,
+    version: &PackageVersion,
+    evaluation_time: DateTime<Utc>,
+    code: &'static str,
+) -> Option<CheckFinding> {
+    let published = version
+        .published
+        .filter(|published| *published > evaluation_time)?;
+    let skew_seconds = published
+        .signed_duration_since(evaluation_time)
+        .num_seconds();
+    Some(
+        CheckFinding::new(
+            Severity::Low,
+            format!(
+                "{package_name}@{} publish timestamp {} is in the future; its age was treated as 0 days",
+                version.version,
+                published.to_rfc3339()
+            ),
+            "future_publish_time",
+        )
+        .with_code(code)
+        .with_fact("package_name", package_name)
+        .with_fact("resolved_version", version.version.as_str())
+        .with_fact("published", published.to_rfc3339())
+        .with_fact("skew_seconds", skew_seconds),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,6 +361,9 @@ mod tests {
                 name: "demo".to_string(),
                 version: Some("1.0.0".to_string()),
                 dependency_paths: vec![vec!["demo".to_string()]],
+                scope: DependencyScope::Prod,
+                unsupported_source: None,
+                hashes: Vec::new(),
             }])
         }
     }
@@ -273,6 +426,68 @@ mod tests {
         assert_eq!(RegistryEcosystem::Npm.osv_name(), "npm");
         assert_eq!(RegistryEcosystem::CratesIo.osv_name(), "crates.io");
         assert_eq!(RegistryEcosystem::PyPI.osv_name(), "PyPI");
+        assert_eq!(RegistryEcosystem::Go.osv_name(), "Go");
+        assert_eq!(RegistryEcosystem::Maven.osv_name(), "Maven");
+        assert_eq!(RegistryEcosystem::RubyGems.osv_name(), "RubyGems");
+        assert_eq!(RegistryEcosystem::NuGet.osv_name(), "NuGet");
+        assert_eq!(RegistryEcosystem::Packagist.osv_name(), "Packagist");
+        assert_eq!(RegistryEcosystem::Hex.osv_name(), "Hex");
+    }
+
+    #[test]
+    fn registry_ecosystem_normalizes_package_names() {
+        assert_eq!(
+            RegistryEcosystem::PyPI.normalize_package_name(" Zope__Interface. "),
+            "zope-interface"
+        );
+        assert_eq!(
+            RegistryEcosystem::Npm.normalize_package_name("@Scope/Left-Pad"),
+            "@scope/left-pad"
+        );
+        assert_eq!(
+            RegistryEcosystem::CratesIo.normalize_package_name("Serde_Json"),
+            "Serde_Json"
+        );
+    }
+
+    #[test]
+    fn dependency_scope_merge_keeps_most_production_relevant_scope() {
+        assert_eq!(
+            DependencyScope::Dev.merge(DependencyScope::Prod),
+            DependencyScope::Prod
+        );
+        assert_eq!(
+            DependencyScope::Dev.merge(DependencyScope::Optional),
+            DependencyScope::Optional
+        );
+        assert_eq!(
+            DependencyScope::Peer.merge(DependencyScope::Dev),
+            DependencyScope::Peer
+        );
+    }
+
+    #[test]
+    fn local_popular_names_skip_comments_blanks_and_duplicates() {
+        let path = unique_temp_path("popular.txt");
+        fs::write(&path, "# top packages\nreact\n\n lodash \nreact\nexpress\n").expect("write");
+
+        let source = LocalPopularNames::new(&path);
+        assert_eq!(
+            source.fetch(10).expect("names"),
+            vec!["react", "lodash", "express"]
+        );
+        assert_eq!(source.fetch(2).expect("names"), vec!["react", "lodash"]);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn local_popular_names_missing_file_is_transport_error() {
+        let source = LocalPopularNames::new(unique_temp_path("missing.txt"));
+        assert!(matches!(
+            source.fetch(10),
+            Err(RegistryError::Transport { .. })
+        ));
     }
 
     #[test]
@@ -285,6 +500,13 @@ mod tests {
                 published: None,
                 deprecated: false,
                 install_scripts: Vec::new(),
+                unpacked_size: None,
+                file_count: None,
+                maintainer_emails: Vec::new(),
+                bin_targets: Vec::new(),
+                license: None,
+                repository_url: None,
+                published_estimated: false,
             },
         );
         versions.insert(
@@ -294,6 +516,13 @@ mod tests {
                 published: None,
                 deprecated: false,
                 install_scripts: Vec::new(),
+                unpacked_size: None,
+                file_count: None,
+                maintainer_emails: Vec::new(),
+                bin_targets: Vec::new(),
+                license: None,
+                repository_url: None,
+                published_estimated: false,
             },
         );
         let record = PackageRecord {
@@ -464,6 +693,40 @@ mod tests {
         let _ = fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn future_publish_time_clamps_age_and_reports_skew() {
+        let now = Utc::now();
+        let published = now + chrono::Duration::hours(1);
+        assert_eq!(age_in_days(published, now), 0);
+        assert_eq!(age_in_days(now - chrono::Duration::days(3), now), 3);
+
+        let version = PackageVersion {
+            version: "1.0.0".to_string(),
+            published: Some(published),
+            deprecated: false,
+            install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
+        };
+        let finding =
+            future_publish_finding("demo", &version, now, "AGE004").expect("future finding");
+        assert_eq!(finding.severity, Severity::Low);
+        assert_eq!(finding.reason_code, "future_publish_time");
+        assert_eq!(finding.code, "AGE004");
+        assert!(finding.reason.contains("is in the future"));
+
+        let past = PackageVersion {
+            published: Some(now - chrono::Duration::hours(1)),
+            ..version
+        };
+        assert!(future_publish_finding("demo", &past, now, "AGE004").is_none());
+    }
+
     #[tokio::test]
     async fn registry_client_default_methods_return_empty_values() {
         let client = DummyClient;
@@ -510,14 +773,73 @@ pub enum RegistryEcosystem {
     Npm,
     CratesIo,
     PyPI,
+    Go,
+    Maven,
+    RubyGems,
+    NuGet,
+    Packagist,
+    Hex,
 }
 
 impl RegistryEcosystem {
+    /// Ecosystem string OSV expects in `package.ecosystem` queries.
     pub fn osv_name(self) -> &'static str {
         match self {
             Self::Npm => "npm",
             Self::CratesIo => "crates.io",
             Self::PyPI => "PyPI",
+            Self::Go => "Go",
+            Self::Maven => "Maven",
+            Self::RubyGems => "RubyGems",
+            Self::NuGet => "NuGet",
+            Self::Packagist => "Packagist",
+            Self::Hex => "Hex",
+        }
+    }
+
+    /// Canonical package-name form used when matching names against policy rules.
+    ///
+    /// npm, NuGet, Packagist, and Hex names are case-insensitive and PyPI names follow
+    /// PEP 503 (lowercase, runs of `-`, `_`, `.` collapse to `-`). crates.io, Go module
+    /// paths, Maven coordinates, and RubyGems names are compared as written.
+    pub fn normalize_package_name(self, name: &str) -> String {
+        let trimmed = name.trim();
+        match self {
+            Self::Npm | Self::NuGet | Self::Packagist | Self::Hex => trimmed.to_ascii_lowercase(),
+            Self::CratesIo | Self::Go | Self::Maven | Self::RubyGems => trimmed.to_string(),
+            Self::PyPI => {
+                let mut normalized = String::with_capacity(trimmed.len());
+                let mut previous_was_separator = false;
+                for ch in trimmed.chars() {
+                    if matches!(ch, '-' | '_' | '.') {
+                        if !previous_was_separator {
+                            normalized.push('-');
+                            previous_was_separator = true;
+                        }
+                    } else {
+                        normalized.push(ch.to_ascii_lowercase());
+                        previous_was_separator = false;
+                    }
+                }
+                normalized.trim_matches('-').to_string()
+            }
+        }
+    }
+
+    /// Version numbering scheme used when comparing this ecosystem's versions.
+    ///
+    /// Hex mandates SemVer and Go module versions are SemVer behind a `v` prefix. Maven,
+    /// NuGet, RubyGems, and Packagist order versions by their own rules, which are not
+    /// implemented; they map to [`VersionScheme::Unsupported`] so version-dependent checks
+    /// fail closed rather than misorder them.
+    pub fn version_scheme(self) -> VersionScheme {
+        match self {
+            Self::Npm | Self::CratesIo | Self::Hex => VersionScheme::SemVer,
+            Self::PyPI => VersionScheme::Pep440,
+            Self::Go => VersionScheme::GoModule,
+            Self::Maven | Self::NuGet | Self::RubyGems | Self::Packagist => {
+                VersionScheme::Unsupported
+            }
         }
     }
 }
@@ -528,6 +850,39 @@ pub struct PackageVersion {
     pub published: Option<DateTime<Utc>>,
     pub deprecated: bool,
     pub install_scripts: Vec<String>,
+    /// Unpacked package size in bytes, when the registry reports it.
+    pub unpacked_size: Option<u64>,
+    /// Number of files in the published package, when the registry reports it.
+    pub file_count: Option<u64>,
+    /// Maintainer email addresses recorded on this version, when the registry reports them.
+    pub maintainer_emails: Vec<String>,
+    /// Executable paths the version installs via npm's `bin` field.
+    pub bin_targets: Vec<String>,
+    /// License expression or name declared for this version, when the registry reports it.
+    pub license: Option<String>,
+    /// Source repository URL, normalized without a `git+` prefix or `.git` suffix.
+    pub repository_url: Option<String>,
+    /// `published` is a package-level fallback (such as the document's last-modified time)
+    /// rather than this version's own publish time.
+    pub published_estimated: bool,
+}
+
+impl PackageVersion {
+    /// This version's own publish time; `None` when it is missing or only estimated.
+    pub fn exact_published(&self) -> Option<DateTime<Utc>> {
+        self.published.filter(|_| !self.published_estimated)
+    }
+}
+
+/// Normalizes a declared repository URL: trims whitespace, a `git+` scheme prefix, a
+/// trailing `.git`, and trailing slashes. Empty values become `None`.
+pub fn normalize_repository_url(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    let trimmed = trimmed.strip_prefix("git+").unwrap_or(trimmed);
+    let trimmed = trimmed.trim_end_matches('/');
+    let trimmed = trimmed.strip_suffix(".git").unwrap_or(trimmed);
+    let trimmed = trimmed.trim_end_matches('/');
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
 }
 
 #[derive(Debug, Clone)]
@@ -543,13 +898,87 @@ pub struct PackageAdvisory {
     pub id: String,
     pub aliases: Vec<String>,
     pub fixed_versions: Vec<String>,
+    /// The advisory only describes affected commits (git ranges), so no fixed release is known.
+    pub git_range_only: bool,
+    /// Highest CVSS base score reported for the advisory, when available.
+    pub cvss_score: Option<f64>,
+    /// Severity entries as reported by the advisory source, such as CVSS vectors.
+    pub severity: Vec<AdvisorySeverity>,
 }
 
-#[derive(Debug, Clone)]
+/// One advisory severity entry: the scoring system (for example `CVSS_V3`) and its score,
+/// either a vector string such as `CVSS:3.1/AV:N/...` or a plain number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdvisorySeverity {
+    pub kind: String,
+    pub score: String,
+}
+
+/// Manifest section a dependency was declared in.
+///
+/// Variants are ordered from most to least production-relevant so that a package
+/// declared in several sections keeps its most production-relevant scope.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyScope {
+    #[default]
+    Prod,
+    Peer,
+    Optional,
+    Dev,
+}
+
+impl DependencyScope {
+    /// Combines scopes for a package seen in multiple sections, keeping the stronger one.
+    pub fn merge(self, other: Self) -> Self {
+        self.min(other)
+    }
+}
+
+/// Non-registry origin of a declared dependency, which registry checks cannot evaluate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnsupportedSource {
+    Git,
+    Path,
+    Url,
+}
+
+impl UnsupportedSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Git => "git",
+            Self::Path => "path",
+            Self::Url => "url",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct DependencySpec {
     pub name: String,
     pub version: Option<String>,
     pub dependency_paths: Vec<Vec<String>>,
+    pub scope: DependencyScope,
+    /// Set when the dependency comes from a git, path, or URL source instead of the
+    /// registry; such specs are not evaluated.
+    pub unsupported_source: Option<UnsupportedSource>,
+    /// Integrity hashes pinned in the dependency file (for example pip `--hash=sha256:...`),
+    /// kept as written; empty when the file pins none.
+    pub hashes: Vec<String>,
+}
+
+/// A direct dependency whose lockfile-pinned version falls outside the range its manifest
+/// declares, which points at a stale or hand-edited lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestDrift {
+    pub name: String,
+    /// Version pinned in the lockfile.
+    pub locked: String,
+    /// Range declared in the manifest, as written.
+    pub declared: String,
 }
 
 impl PackageRecord {
@@ -602,6 +1031,11 @@ pub enum LockfileError {
 #[async_trait]
 pub trait RegistryClient: Send + Sync {
     fn ecosystem(&self) -> RegistryEcosystem;
+    /// Effective package API base URL, used to keep cached decisions from different
+    /// registry instances (for example a private mirror) apart.
+    fn base_url(&self) -> Option<&str> {
+        None
+    }
     async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError>;
     async fn prefetch_weekly_downloads(&self, _packages: &[String]) -> Result<(), RegistryError> {
         Ok(())
@@ -609,6 +1043,14 @@ pub trait RegistryClient: Send + Sync {
     async fn fetch_weekly_downloads(&self, _package: &str) -> Result<Option<u64>, RegistryError> {
         Ok(None)
     }
+    /// Weekly downloads stored for `package` by an earlier evaluation, used as the baseline
+    /// for download-trend rules. Plain registry clients keep no history.
+    async fn fetch_weekly_downloads_baseline(
+        &self,
+        _package: &str,
+    ) -> Result<Option<u64>, RegistryError> {
+        Ok(None)
+    }
     async fn prefetch_popular_package_names(&self) -> Result<(), RegistryError> {
         Ok(())
     }
@@ -618,6 +1060,15 @@ pub trait RegistryClient: Send + Sync {
     ) -> Result<Vec<String>, RegistryError> {
         Ok(Vec::new())
     }
+    /// Fetches advisories for `(name, version)` pairs up front with at most `concurrency`
+    /// advisory requests in flight, so later `fetch_advisories` calls avoid one query each.
+    async fn prefetch_advisories(
+        &self,
+        _packages: &[(String, String)],
+        _concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        Ok(())
+    }
     async fn fetch_advisories(
         &self,
         _package: &str,
@@ -631,6 +1082,13 @@ pub trait LockfileParser: Send + Sync {
     fn supported_files(&self) -> &'static [&'static str];
     fn parse_dependencies(&self, path: &Path) -> Result<Vec<DependencySpec>, LockfileError>;
 
+    /// Compares a lockfile at `path` with the manifest next to it and returns direct
+    /// dependencies pinned outside their declared range. Formats without a lockfile and
+    /// manifest pair report no drift.
+    fn manifest_drift(&self, _path: &Path) -> Result<Vec<ManifestDrift>, LockfileError> {
+        Ok(Vec::new())
+    }
+
     fn resolve_input(&self, path: Option<&str>) -> Result<PathBuf, LockfileError> {
         let candidate = match path {
             Some(value) => PathBuf::from(value),
@@ -668,10 +1126,144 @@ pub trait LockfileParser: Send + Sync {
     }
 }
 
+/// Startup overrides applied when constructing a registry client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryClientOptions {
+    /// Replaces the default package API base URL (takes precedence over env vars).
+    pub base_url: Option<String>,
+    /// Loads popular package names from this local file instead of the network index.
+    pub popular_names_file: Option<PathBuf>,
+    /// Fetches popular package names from this static JSON list instead of the default index.
+    pub popular_index_url: Option<String>,
+    /// Registry-native client config (an `.npmrc` for npm) supplying per-scope registries
+    /// and credentials.
+    pub config_file: Option<PathBuf>,
+    /// Outbound request limits, shared with the other clients of the same service.
+    pub limits: RequestLimits,
+    /// Requests abbreviated package metadata (npm's install document), which omits publish
+    /// times, maintainers, and script bodies. Set only when no enabled check reads them.
+    pub abbreviated_metadata: bool,
+}
+
+/// Outbound request limits for registry and advisory clients.
+///
+/// Clones share one pool of in-flight permits, so a single cap covers every client built
+/// from the same limits. The default is unbounded; services pass their configured limits.
+#[derive(Debug, Clone)]
+pub struct RequestLimits {
+    in_flight: Arc<Semaphore>,
+    max_response_bytes: usize,
+}
+
+impl RequestLimits {
+    /// Caps concurrent requests at `max_in_flight_requests` and response bodies at
+    /// `max_response_bytes`; a limit of 0 is treated as 1.
+    pub fn new(max_in_flight_requests: usize, max_response_bytes: usize) -> Self {
+        Self {
+            in_flight: Arc::new(Semaphore::new(
+                max_in_flight_requests.clamp(1, Semaphore::MAX_PERMITS),
+            )),
+            max_response_bytes: max_response_bytes.max(1),
+        }
+    }
+
+    /// Permits held by requests while they are in flight.
+    pub fn in_flight(&self) -> &Arc<Semaphore> {
+        &self.in_flight
+    }
+
+    /// Largest response body accepted, in bytes.
+    pub fn max_response_bytes(&self) -> usize {
+        self.max_response_bytes
+    }
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self::new(Semaphore::MAX_PERMITS, usize::MAX)
+    }
+}
+
+/// Limits are equal when they share the same permit pool and response cap.
+impl PartialEq for RequestLimits {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.in_flight, &other.in_flight)
+            && self.max_response_bytes == other.max_response_bytes
+    }
+}
+
+impl Eq for RequestLimits {}
+
+/// Popular package names loaded from a local newline-delimited file.
+///
+/// Blank lines and lines starting with `#` are ignored; duplicates keep their first position.
+/// The file is read once and cached for the lifetime of the source.
+#[derive(Debug)]
+pub struct LocalPopularNames {
+    path: PathBuf,
+    names: OnceLock<Vec<String>>,
+}
+
+impl LocalPopularNames {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            names: OnceLock::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns up to `limit` names in file order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RegistryError::Transport`] when the file cannot be read and
+    /// [`RegistryError::InvalidResponse`] when it contains no names.
+    pub fn fetch(&self, limit: usize) -> Result<Vec<String>, RegistryError> {
+        if let Some(names) = self.names.get() {
+            return Ok(names.iter().take(limit).cloned().collect());
+        }
+
+        let content =
+            std::fs::read_to_string(&self.path).map_err(|err| RegistryError::Transport {
+                message: format!(
+                    "unable to read popular names file {}: {err}",
+                    self.path.display()
+                ),
+            })?;
+        let names = parse_popular_names(&content);
+        if names.is_empty() {
+            return Err(RegistryError::InvalidResponse {
+                message: format!(
+                    "popular names file {} contains no package names",
+                    self.path.display()
+                ),
+            });
+        }
+
+        let names = self.names.get_or_init(|| names);
+        Ok(names.iter().take(limit).cloned().collect())
+    }
+}
+
+fn parse_popular_names(content: &str) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| seen.insert(*line))
+        .map(str::to_string)
+        .collect()
+}
+
 #[derive(Clone, Copy)]
 pub struct RegistryDefinition {
     pub key: &'static str,
-    pub create_client: fn() -> Arc<dyn RegistryClient>,
+    pub create_client: fn(&RegistryClientOptions) -> Arc<dyn RegistryClient>,
     pub create_lockfile_parser: Option<fn() -> Arc<dyn LockfileParser>>,
     /// Check IDs this registry does not support.
     pub excluded_checks: &'static [CheckId],
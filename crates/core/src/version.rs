@@ -0,0 +1,653 @@
+//! Ecosystem-aware version parsing and ordering.
+
+use std::cmp::Ordering;
+
+/// Version numbering scheme used by a registry ecosystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionScheme {
+    /// Semantic Versioning 2.0 (npm, crates.io).
+    SemVer,
+    /// PEP 440 (PyPI): epochs plus pre-, post- and dev-releases.
+    Pep440,
+    /// Go module versions: SemVer behind an optional `v` prefix (`v1.2.3`, pseudo-versions,
+    /// `v2.0.0+incompatible`).
+    GoModule,
+    /// Ecosystems whose ordering rules are not implemented (Maven, NuGet, RubyGems,
+    /// Packagist). Nothing parses, so version-dependent checks fail closed instead of
+    /// comparing these versions as SemVer.
+    Unsupported,
+}
+
+impl VersionScheme {
+    /// Human-readable scheme name for diagnostics.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::SemVer => "semver",
+            Self::Pep440 => "PEP 440",
+            Self::GoModule => "Go module",
+            Self::Unsupported => "unsupported",
+        }
+    }
+
+    /// Parses a version string under this scheme.
+    pub fn parse(self, raw: &str) -> Option<ParsedVersion> {
+        match self {
+            Self::SemVer => semver::Version::parse(raw).ok().map(ParsedVersion::SemVer),
+            Self::Pep440 => Pep440Version::parse(raw).map(ParsedVersion::Pep440),
+            Self::GoModule => parse_go_version(raw).map(ParsedVersion::SemVer),
+            Self::Unsupported => None,
+        }
+    }
+
+    /// Compares two version strings, or `None` when either does not parse.
+    pub fn compare(self, left: &str, right: &str) -> Option<Ordering> {
+        let left = self.parse(left)?;
+        let right = self.parse(right)?;
+        left.partial_cmp(&right)
+    }
+
+    /// Parses a version range under this scheme.
+    ///
+    /// SemVer ranges use comparator syntax (`^1.2`, `~1.2.3`, `>=1, <2`, `1.x`), as do Go
+    /// ranges with optional `v` prefixes (`>=v1.2.0`); PEP 440 ranges are comma-separated
+    /// specifiers (`>=2.0,<3`, `~=1.4`, `==1.*`, `!=1.5`).
+    pub fn parse_range(self, raw: &str) -> Option<VersionRange> {
+        match self {
+            Self::SemVer => semver::VersionReq::parse(raw.trim())
+                .ok()
+                .map(VersionRange::SemVer),
+            Self::Pep440 => raw
+                .split(',')
+                .map(Pep440Specifier::parse)
+                .collect::<Option<Vec<_>>>()
+                .map(VersionRange::Pep440),
+            Self::GoModule => semver::VersionReq::parse(&strip_go_range_prefixes(raw.trim()))
+                .ok()
+                .map(VersionRange::GoModule),
+            Self::Unsupported => None,
+        }
+    }
+}
+
+/// Parses a Go module version, with or without its `v` prefix.
+fn parse_go_version(raw: &str) -> Option<semver::Version> {
+    let raw = raw.trim();
+    semver::Version::parse(raw.strip_prefix('v').unwrap_or(raw)).ok()
+}
+
+/// Drops the `v` that starts each version in a Go range (`>=v1.2.0` -> `>=1.2.0`), leaving
+/// a `v` inside identifiers such as `-dev` alone.
+fn strip_go_range_prefixes(raw: &str) -> String {
+    let mut stripped = String::with_capacity(raw.len());
+    let mut previous = None;
+    let mut chars = raw.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let starts_version = ch == 'v'
+            && chars.peek().is_some_and(char::is_ascii_digit)
+            && !previous.is_some_and(|previous: char| previous.is_ascii_alphanumeric());
+        if !starts_version {
+            stripped.push(ch);
+        }
+        previous = Some(ch);
+    }
+    stripped
+}
+
+/// A version range parsed under a specific [`VersionScheme`].
+#[derive(Debug, Clone)]
+pub enum VersionRange {
+    SemVer(semver::VersionReq),
+    Pep440(Vec<Pep440Specifier>),
+    GoModule(semver::VersionReq),
+}
+
+impl VersionRange {
+    /// Whether `version` parses under the range's scheme and satisfies it.
+    ///
+    /// Pre-releases only match when the range itself names a pre-release.
+    pub fn matches(&self, version: &str) -> bool {
+        match self {
+            Self::SemVer(req) => {
+                semver::Version::parse(version).is_ok_and(|version| req.matches(&version))
+            }
+            Self::GoModule(req) => {
+                parse_go_version(version).is_some_and(|version| req.matches(&version))
+            }
+            Self::Pep440(specifiers) => {
+                let Some(version) = Pep440Version::parse(version) else {
+                    return false;
+                };
+                let allows_pre = specifiers
+                    .iter()
+                    .any(|specifier| specifier.version.is_prerelease());
+                (allows_pre || !version.is_prerelease())
+                    && specifiers
+                        .iter()
+                        .all(|specifier| specifier.matches(&version))
+            }
+        }
+    }
+}
+
+/// Comparison operator of a PEP 440 version specifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpecifierOp {
+    Compatible,
+    Equal,
+    NotEqual,
+    LessEqual,
+    GreaterEqual,
+    Less,
+    Greater,
+}
+
+/// One PEP 440 version specifier such as `>=2.0` or `==1.*`.
+#[derive(Debug, Clone)]
+pub struct Pep440Specifier {
+    op: SpecifierOp,
+    version: Pep440Version,
+    wildcard: bool,
+}
+
+impl Pep440Specifier {
+    fn parse(raw: &str) -> Option<Self> {
+        // Two-character operators first so `<=` is not read as `<`.
+        const OPS: &[(&str, SpecifierOp)] = &[
+            ("~=", SpecifierOp::Compatible),
+            ("==", SpecifierOp::Equal),
+            ("!=", SpecifierOp::NotEqual),
+            ("<=", SpecifierOp::LessEqual),
+            (">=", SpecifierOp::GreaterEqual),
+            ("<", SpecifierOp::Less),
+            (">", SpecifierOp::Greater),
+        ];
+        let raw = raw.trim();
+        let (prefix, op) = OPS.iter().find(|(prefix, _)| raw.starts_with(prefix))?;
+        let operand = raw[prefix.len()..].trim();
+        let (operand, wildcard) = match operand.strip_suffix(".*") {
+            Some(operand) if matches!(op, SpecifierOp::Equal | SpecifierOp::NotEqual) => {
+                (operand, true)
+            }
+            _ => (operand, false),
+        };
+        let version = Pep440Version::parse(operand)?;
+        if *op == SpecifierOp::Compatible && version.release.len() < 2 {
+            return None;
+        }
+        Some(Self {
+            op: *op,
+            version,
+            wildcard,
+        })
+    }
+
+    fn matches(&self, version: &Pep440Version) -> bool {
+        match self.op {
+            SpecifierOp::Compatible => {
+                let prefix_len = self.version.release.len() - 1;
+                version >= &self.version && version.has_release_prefix(&self.version, prefix_len)
+            }
+            SpecifierOp::Equal if self.wildcard => {
+                version.has_release_prefix(&self.version, self.version.release.len())
+            }
+            SpecifierOp::NotEqual if self.wildcard => {
+                !version.has_release_prefix(&self.version, self.version.release.len())
+            }
+            SpecifierOp::Equal => version == &self.version,
+            SpecifierOp::NotEqual => version != &self.version,
+            SpecifierOp::LessEqual => version <= &self.version,
+            SpecifierOp::GreaterEqual => version >= &self.version,
+            SpecifierOp::Less => version < &self.version,
+            SpecifierOp::Greater => version > &self.version,
+        }
+    }
+}
+
+/// A version parsed under a specific [`VersionScheme`].
+///
+/// Versions from different schemes are unordered. SemVer build metadata (`+build.5`) is
+/// ignored for ordering, as the spec requires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedVersion {
+    SemVer(semver::Version),
+    Pep440(Pep440Version),
+}
+
+impl ParsedVersion {
+    /// Whether this is a pre-release (SemVer pre-release tag; PEP 440 pre or dev release).
+    pub fn is_prerelease(&self) -> bool {
+        match self {
+            Self::SemVer(version) => !version.pre.is_empty(),
+            Self::Pep440(version) => version.is_prerelease(),
+        }
+    }
+
+    /// First release component.
+    pub fn major(&self) -> u64 {
+        match self {
+            Self::SemVer(version) => version.major,
+            Self::Pep440(version) => version.release_part(0),
+        }
+    }
+
+    /// Second release component (0 when absent).
+    pub fn minor(&self) -> u64 {
+        match self {
+            Self::SemVer(version) => version.minor,
+            Self::Pep440(version) => version.release_part(1),
+        }
+    }
+}
+
+impl PartialOrd for ParsedVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            // `semver::Version`'s `Ord` breaks ties on build metadata; precedence does not.
+            (Self::SemVer(left), Self::SemVer(right)) => Some(left.cmp_precedence(right)),
+            (Self::Pep440(left), Self::Pep440(right)) => Some(left.cmp(right)),
+            _ => None,
+        }
+    }
+}
+
+/// Pre-release phase, ordered as PEP 440 requires (`a` < `b` < `rc`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PrePhase {
+    Alpha,
+    Beta,
+    ReleaseCandidate,
+}
+
+/// A PEP 440 public version. Local version labels (`+local`) are ignored for ordering.
+#[derive(Debug, Clone)]
+pub struct Pep440Version {
+    epoch: u64,
+    release: Vec<u64>,
+    pre: Option<(PrePhase, u64)>,
+    post: Option<u64>,
+    dev: Option<u64>,
+}
+
+impl Pep440Version {
+    /// Parses a PEP 440 version, accepting the spec's permitted spelling variations.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let normalized = raw.trim().to_ascii_lowercase();
+        let public = normalized.split('+').next()?;
+        let public = public.strip_prefix('v').unwrap_or(public);
+        let mut cursor = Cursor::new(public);
+
+        let epoch = match public.split_once('!') {
+            Some((epoch, _)) => {
+                let epoch = epoch.parse().ok()?;
+                cursor.advance(public.find('!')? + 1);
+                epoch
+            }
+            None => 0,
+        };
+
+        let mut release = vec![cursor.number()?];
+        while cursor.peek() == Some('.') && cursor.peek_digit_after(1) {
+            cursor.advance(1);
+            release.push(cursor.number()?);
+        }
+
+        let pre = cursor.pre_release();
+        let post = cursor.post_release();
+        let dev = cursor.dev_release();
+        if !cursor.is_done() {
+            return None;
+        }
+
+        Some(Self {
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+        })
+    }
+
+    fn release_part(&self, index: usize) -> u64 {
+        self.release.get(index).copied().unwrap_or(0)
+    }
+
+    fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    /// Whether the epoch and first `len` release components equal `other`'s.
+    fn has_release_prefix(&self, other: &Self, len: usize) -> bool {
+        self.epoch == other.epoch
+            && (0..len).all(|index| self.release_part(index) == other.release_part(index))
+    }
+
+    /// Sort key for the pre-release slot: dev-only releases sort before any
+    /// pre-release, and final releases sort after all of them.
+    fn pre_key(&self) -> (u8, Option<(PrePhase, u64)>) {
+        match (self.pre, self.post, self.dev) {
+            (None, None, Some(_)) => (0, None),
+            (Some(pre), _, _) => (1, Some(pre)),
+            (None, _, _) => (2, None),
+        }
+    }
+
+    /// Sort key for the dev slot: a missing dev segment sorts last.
+    fn dev_key(&self) -> (u8, u64) {
+        match self.dev {
+            Some(dev) => (0, dev),
+            None => (1, 0),
+        }
+    }
+}
+
+impl PartialEq for Pep440Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pep440Version {}
+
+impl PartialOrd for Pep440Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pep440Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| compare_release(&self.release, &other.release))
+            .then_with(|| self.pre_key().cmp(&other.pre_key()))
+            .then_with(|| self.post.cmp(&other.post))
+            .then_with(|| self.dev_key().cmp(&other.dev_key()))
+    }
+}
+
+/// Compares release segments as if the shorter one were padded with zeros.
+fn compare_release(left: &[u64], right: &[u64]) -> Ordering {
+    let len = left.len().max(right.len());
+    (0..len)
+        .map(|index| {
+            let lhs = left.get(index).copied().unwrap_or(0);
+            let rhs = right.get(index).copied().unwrap_or(0);
+            lhs.cmp(&rhs)
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { rest: input }
+    }
+
+    fn is_done(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    fn peek_digit_after(&self, offset: usize) -> bool {
+        self.rest
+            .get(offset..)
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|ch| ch.is_ascii_digit())
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.rest = self.rest.get(len..).unwrap_or_default();
+    }
+
+    fn number(&mut self) -> Option<u64> {
+        let len = self
+            .rest
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        if len == 0 {
+            return None;
+        }
+        let value = self.rest[..len].parse().ok()?;
+        self.advance(len);
+        Some(value)
+    }
+
+    /// Consumes an optional `.`, `-` or `_` separator.
+    fn separator(&mut self) {
+        if matches!(self.peek(), Some('.' | '-' | '_')) {
+            self.advance(1);
+        }
+    }
+
+    /// Consumes `[sep]label[sep][N]` for the first matching label; N defaults to 0.
+    fn labeled_number(&mut self, labels: &[&str]) -> Option<(usize, u64)> {
+        let saved = self.rest;
+        self.separator();
+        let Some((index, label)) = labels
+            .iter()
+            .enumerate()
+            .find(|(_, label)| self.rest.starts_with(**label))
+        else {
+            self.rest = saved;
+            return None;
+        };
+        self.advance(label.len());
+        let before_number = self.rest;
+        self.separator();
+        let number = match self.number() {
+            Some(number) => number,
+            None => {
+                self.rest = before_number;
+                0
+            }
+        };
+        Some((index, number))
+    }
+
+    fn pre_release(&mut self) -> Option<(PrePhase, u64)> {
+        // Longer spellings first so `alpha` is not consumed as `a`.
+        const LABELS: &[(&str, PrePhase)] = &[
+            ("alpha", PrePhase::Alpha),
+            ("a", PrePhase::Alpha),
+            ("beta", PrePhase::Beta),
+            ("b", PrePhase::Beta),
+            ("preview", PrePhase::ReleaseCandidate),
+            ("pre", PrePhase::ReleaseCandidate),
+            ("rc", PrePhase::ReleaseCandidate),
+            ("c", PrePhase::ReleaseCandidate),
+        ];
+        let labels = LABELS.iter().map(|(label, _)| *label).collect::<Vec<_>>();
+        let (index, number) = self.labeled_number(&labels)?;
+        Some((LABELS[index].1, number))
+    }
+
+    fn post_release(&mut self) -> Option<u64> {
+        // Implicit post-release: `1.0-1`.
+        if self.peek() == Some('-') && self.peek_digit_after(1) {
+            self.advance(1);
+            return self.number();
+        }
+        self.labeled_number(&["post", "rev", "r"])
+            .map(|(_, number)| number)
+    }
+
+    fn dev_release(&mut self) -> Option<u64> {
+        self.labeled_number(&["dev"]).map(|(_, number)| number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pep440_less(left: &str, right: &str) {
+        assert_eq!(
+            VersionScheme::Pep440.compare(left, right),
+            Some(Ordering::Less),
+            "{left} < {right}"
+        );
+    }
+
+    #[test]
+    fn semver_ordering_ignores_build_metadata() {
+        let compare = |left, right| VersionScheme::SemVer.compare(left, right);
+        assert_eq!(compare("1.0.0+build.5", "1.0.0"), Some(Ordering::Equal));
+        assert_eq!(
+            compare("1.0.0+build.9", "1.0.0+build.10"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare("1.0.0-alpha+zzz", "1.0.0"), Some(Ordering::Less));
+        assert_eq!(compare("1.0.1+a", "1.0.0+z"), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn pep440_release_candidate_sorts_before_final() {
+        pep440_less("1.0.0rc1", "1.0.0");
+    }
+
+    #[test]
+    fn pep440_calendar_versions_order_numerically() {
+        pep440_less("2024.1", "2024.2");
+        pep440_less("2024.2", "2024.10");
+    }
+
+    #[test]
+    fn pep440_full_ordering_matches_spec() {
+        let ordered = [
+            "1.0.dev0",
+            "1.0a1.dev1",
+            "1.0a1",
+            "1.0b2",
+            "1.0rc1",
+            "1.0",
+            "1.0.post1.dev0",
+            "1.0.post1",
+            "1.1",
+            "1!0.1",
+        ];
+        for pair in ordered.windows(2) {
+            pep440_less(pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn pep440_normalizes_spellings_and_trailing_zeros() {
+        assert_eq!(
+            VersionScheme::Pep440.compare("1.0", "1.0.0"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            VersionScheme::Pep440.compare("1.0-alpha.1", "1.0a1"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            VersionScheme::Pep440.compare("v1.0-1", "1.0.post1"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            VersionScheme::Pep440.compare("1.0+local.7", "1.0"),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn pep440_rejects_garbage() {
+        assert!(VersionScheme::Pep440.parse("not-a-version").is_none());
+        assert!(VersionScheme::Pep440.parse("1.0xyz").is_none());
+    }
+
+    #[test]
+    fn semver_scheme_rejects_pep440_only_versions() {
+        assert!(VersionScheme::SemVer.parse("1.0.0rc1").is_none());
+        assert_eq!(
+            VersionScheme::SemVer.compare("1.0.0-rc.1", "1.0.0"),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn go_versions_accept_the_v_prefix() {
+        let compare = |left, right| VersionScheme::GoModule.compare(left, right);
+        assert_eq!(compare("v1.2.3", "1.2.3"), Some(Ordering::Equal));
+        assert_eq!(
+            compare("v0.0.0-20240101000000-abcdef123456", "v0.1.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare("v2.0.0+incompatible", "v1.9.9"),
+            Some(Ordering::Greater)
+        );
+        assert!(VersionScheme::SemVer.parse("v1.2.3").is_none());
+
+        let range = VersionScheme::GoModule
+            .parse_range(">=v1.2.0, <v2.0.0-dev")
+            .expect("range");
+        assert!(range.matches("v1.4.0"));
+        assert!(range.matches("1.4.0"));
+        assert!(!range.matches("v2.0.0"));
+    }
+
+    #[test]
+    fn unsupported_scheme_parses_nothing() {
+        assert!(VersionScheme::Unsupported.parse("1.0.0").is_none());
+        assert!(VersionScheme::Unsupported.parse_range(">=1.0.0").is_none());
+        assert_eq!(VersionScheme::Unsupported.compare("1.0.0", "1.0.0"), None);
+    }
+
+    #[test]
+    fn parsed_version_exposes_major_and_minor() {
+        let version = VersionScheme::Pep440.parse("2024.3").expect("parse");
+        assert_eq!((version.major(), version.minor()), (2024, 3));
+        let version = VersionScheme::Pep440.parse("7").expect("parse");
+        assert_eq!((version.major(), version.minor()), (7, 0));
+    }
+
+    #[test]
+    fn semver_ranges_use_comparator_syntax() {
+        let range = VersionScheme::SemVer.parse_range("^1.2").expect("range");
+        assert!(range.matches("1.4.0"));
+        assert!(!range.matches("2.0.0"));
+        assert!(!range.matches("1.5.0-beta.1"));
+
+        let range = VersionScheme::SemVer
+            .parse_range(">=1.0, <1.1")
+            .expect("range");
+        assert!(range.matches("1.0.9"));
+        assert!(!range.matches("1.1.0"));
+        assert!(VersionScheme::SemVer.parse_range("not a range").is_none());
+    }
+
+    #[test]
+    fn pep440_ranges_support_specifier_operators() {
+        let range = VersionScheme::Pep440
+            .parse_range(">=2.0, <3, !=2.5")
+            .expect("range");
+        assert!(range.matches("2.4"));
+        assert!(!range.matches("2.5.0"));
+        assert!(!range.matches("3.0"));
+        assert!(!range.matches("2.6rc1"));
+
+        let range = VersionScheme::Pep440.parse_range("~=1.4.2").expect("range");
+        assert!(range.matches("1.4.9"));
+        assert!(!range.matches("1.5.0"));
+
+        let range = VersionScheme::Pep440.parse_range("==1.*").expect("range");
+        assert!(range.matches("1.9.3"));
+        assert!(!range.matches("2.0"));
+
+        let range = VersionScheme::Pep440
+            .parse_range(">=2.0rc1")
+            .expect("range");
+        assert!(range.matches("2.0rc2"));
+
+        assert!(VersionScheme::Pep440.parse_range("~=1").is_none());
+        assert!(VersionScheme::Pep440.parse_range("1.0").is_none());
+    }
+}
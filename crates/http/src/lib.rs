@@ -1,7 +1,9 @@
 use reqwest::{Client, RequestBuilder, Response, StatusCode, header::HeaderMap};
-use safe_pkgs_core::RegistryError;
+use safe_pkgs_core::{RegistryError, RequestLimits};
 use serde::de::DeserializeOwned;
+use std::ops::Deref;
 use std::time::Duration;
+use tokio::sync::OwnedSemaphorePermit;
 
 const DEFAULT_MAX_ATTEMPTS: u8 = 3;
 const DEFAULT_INITIAL_BACKOFF_MILLIS: u64 = 250;
@@ -10,7 +12,6 @@ const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 5;
 const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 20;
 /// Hard cap on Retry-After directive to prevent registry servers from hanging the client.
 const MAX_RETRY_AFTER_SECS: u64 = 60;
-
 pub const DEFAULT_USER_AGENT: &str = concat!("safe-pkgs/", env!("CARGO_PKG_VERSION"));
 
 #[derive(Debug, Clone, Copy)]
@@ -30,6 +31,57 @@ impl Default for RetryPolicy {
     }
 }
 
+/// Credentials attached to private-registry requests as an `Authorization` header.
+#[derive(Clone, PartialEq, Eq)]
+pub enum RegistryAuth {
+    Bearer(String),
+    Basic { username: String, password: String },
+}
+
+impl RegistryAuth {
+    /// Reads registry credentials from env vars, preferring a bearer token over basic auth.
+    ///
+    /// Empty or whitespace-only values are treated as unset.
+    pub fn from_env(token_var: &str, username_var: &str, password_var: &str) -> Option<Self> {
+        if let Some(token) = non_empty_env(token_var) {
+            return Some(Self::Bearer(token));
+        }
+        let username = non_empty_env(username_var)?;
+        Some(Self::Basic {
+            username,
+            password: non_empty_env(password_var).unwrap_or_default(),
+        })
+    }
+
+    pub fn apply(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self {
+            Self::Bearer(token) => builder.bearer_auth(token),
+            Self::Basic { username, password } => builder.basic_auth(username, Some(password)),
+        }
+    }
+}
+
+/// Redacts credentials so they never reach logs or error output.
+impl std::fmt::Debug for RegistryAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bearer(_) => f.write_str("Bearer(<redacted>)"),
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+        }
+    }
+}
+
+fn non_empty_env(var: &str) -> Option<String> {
+    std::env::var(var)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
 pub fn build_http_client() -> Client {
     let custom = std::env::var("SAFE_PKGS_HTTP_USER_AGENT")
         .ok()
@@ -54,20 +106,47 @@ pub fn build_http_client() -> Client {
         })
 }
 
+/// A registry response that keeps its in-flight permit until the body is read or dropped,
+/// and carries the response size cap of the limits it was sent under.
+#[derive(Debug)]
+pub struct LimitedResponse {
+    response: Response,
+    max_response_bytes: usize,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for LimitedResponse {
+    type Target = Response;
+
+    fn deref(&self) -> &Response {
+        &self.response
+    }
+}
+
+/// Sends a request with retries, holding one of `limits`' in-flight permits for each
+/// attempt until the returned response body is consumed.
 pub async fn send_with_retry<F>(
     mut build_request: F,
     operation: &str,
     policy: RetryPolicy,
-) -> Result<Response, RegistryError>
+    limits: &RequestLimits,
+) -> Result<LimitedResponse, RegistryError>
 where
     F: FnMut() -> RequestBuilder,
 {
     let max_attempts = policy.max_attempts.max(1);
     let mut attempt = 1u8;
     loop {
-        let response = build_request().send().await;
-
-        match response {
+        let permit = limits
+            .in_flight()
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| RegistryError::Transport {
+                message: format!("{operation} request limiter closed"),
+            })?;
+
+        match build_request().send().await {
             Ok(response) => {
                 if attempt < max_attempts && should_retry_status(response.status()) {
                     let delay = compute_retry_delay(
@@ -75,14 +154,23 @@ where
                         policy,
                         parse_retry_after_seconds(response.headers()).map(Duration::from_secs),
                     );
+                    // Permits are released before backoff sleeps so retries do not starve
+                    // other requests.
+                    drop(response);
+                    drop(permit);
                     tokio::time::sleep(delay).await;
                     attempt = attempt.saturating_add(1);
                     continue;
                 }
 
-                return Ok(response);
+                return Ok(LimitedResponse {
+                    response,
+                    max_response_bytes: limits.max_response_bytes(),
+                    _permit: permit,
+                });
             }
             Err(source) => {
+                drop(permit);
                 if attempt < max_attempts && should_retry_transport_error(&source) {
                     let delay = compute_retry_delay(attempt, policy, None);
                     tokio::time::sleep(delay).await;
@@ -102,16 +190,67 @@ pub fn map_status_error(operation: &str, status: StatusCode) -> RegistryError {
     }
 }
 
-pub async fn parse_json<T>(response: Response, operation: &str) -> Result<T, RegistryError>
+pub async fn parse_json<T>(response: LimitedResponse, operation: &str) -> Result<T, RegistryError>
 where
     T: DeserializeOwned,
 {
-    response
-        .json()
-        .await
-        .map_err(|source| RegistryError::InvalidResponse {
-            message: format!("failed to parse {operation} JSON: {source}"),
-        })
+    let LimitedResponse {
+        response,
+        max_response_bytes,
+        _permit,
+    } = response;
+    let body = read_body_limited(response, operation, max_response_bytes).await?;
+    serde_json::from_slice(&body).map_err(|source| RegistryError::InvalidResponse {
+        message: format!("failed to parse {operation} JSON: {source}"),
+    })
+}
+
+/// Reads a raw response body such as a package tarball, failing once it exceeds `limit`
+/// bytes or the response cap it was sent under, whichever is smaller.
+pub async fn read_bytes(
+    response: LimitedResponse,
+    operation: &str,
+    limit: usize,
+) -> Result<Vec<u8>, RegistryError> {
+    let LimitedResponse {
+        response,
+        max_response_bytes,
+        _permit,
+    } = response;
+    read_body_limited(response, operation, limit.min(max_response_bytes)).await
+}
+
+/// Streams the response body, aborting once it grows past `limit` bytes.
+async fn read_body_limited(
+    mut response: Response,
+    operation: &str,
+    limit: usize,
+) -> Result<Vec<u8>, RegistryError> {
+    let too_large = || RegistryError::InvalidResponse {
+        message: format!("{operation} body exceeded the {limit}-byte response limit"),
+    };
+    // Reject early when the server announces an oversized body.
+    if let Some(length) = response.content_length()
+        && usize::try_from(length).map_or(true, |length| length > limit)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) =
+        response
+            .chunk()
+            .await
+            .map_err(|source| RegistryError::InvalidResponse {
+                message: format!("failed to read {operation} body: {source}"),
+            })?
+    {
+        if body.len().saturating_add(chunk.len()) > limit {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
 }
 
 pub fn transport_error(operation: &str, source: reqwest::Error) -> RegistryError {
@@ -172,6 +311,19 @@ mod tests {
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
+    #[test]
+    fn registry_auth_debug_redacts_credentials() {
+        let bearer = RegistryAuth::Bearer("secret-token".to_string());
+        let basic = RegistryAuth::Basic {
+            username: "ci".to_string(),
+            password: "secret-password".to_string(),
+        };
+
+        let rendered = format!("{bearer:?} {basic:?}");
+        assert!(!rendered.contains("secret"));
+        assert!(rendered.contains("ci"));
+    }
+
     #[test]
     fn exponential_backoff_caps_at_maximum() {
         let delay = exponential_backoff(8, Duration::from_millis(100), Duration::from_secs(1));
@@ -236,6 +388,7 @@ mod tests {
                 initial_backoff: Duration::from_millis(1),
                 max_backoff: Duration::from_millis(10),
             },
+            &RequestLimits::default(),
         )
         .await
         .expect("request should complete with response");
@@ -258,6 +411,7 @@ mod tests {
                 initial_backoff: Duration::from_millis(1),
                 max_backoff: Duration::from_millis(2),
             },
+            &RequestLimits::default(),
         )
         .await
         .expect_err("transport errors should bubble up after retries");
@@ -265,4 +419,107 @@ mod tests {
         assert!(matches!(err, RegistryError::Transport { .. }));
         assert_eq!(attempts, 2);
     }
+
+    #[tokio::test]
+    async fn saturated_limiter_serializes_excess_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/slow"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(200)))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = build_http_client();
+        let url = format!("{}/slow", server.uri());
+        let limits = RequestLimits::new(1, usize::MAX);
+        let send = || async {
+            let response = send_with_retry(
+                || client.get(&url),
+                "limiter test",
+                RetryPolicy::default(),
+                &limits,
+            )
+            .await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            read_bytes(response, "limiter test", 1024).await
+        };
+
+        let started = std::time::Instant::now();
+        let (first, second, third) = tokio::join!(send(), send(), send());
+        let elapsed = started.elapsed();
+
+        for body in [first, second, third] {
+            body.expect("body");
+        }
+        // One permit forces the three 200ms requests to run back to back.
+        assert!(elapsed >= Duration::from_millis(600), "elapsed {elapsed:?}");
+        assert_eq!(limits.in_flight().available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn in_flight_permit_is_held_until_the_body_is_read() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/body"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .mount(&server)
+            .await;
+
+        let client = build_http_client();
+        let url = format!("{}/body", server.uri());
+        let limits = RequestLimits::new(2, usize::MAX);
+        let response = send_with_retry(
+            || client.get(&url),
+            "permit test",
+            RetryPolicy::default(),
+            &limits,
+        )
+        .await
+        .expect("response");
+        assert_eq!(limits.in_flight().available_permits(), 1);
+
+        let _: serde_json::Value = parse_json(response, "permit test").await.expect("json");
+        assert_eq!(limits.in_flight().available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn oversized_response_body_is_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/large"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(2048)))
+            .mount(&server)
+            .await;
+
+        let client = build_http_client();
+        let url = format!("{}/large", server.uri());
+        let fetch = |limits: RequestLimits| {
+            let client = client.clone();
+            let url = url.clone();
+            async move {
+                let response = send_with_retry(
+                    || client.get(&url),
+                    "oversize test",
+                    RetryPolicy::default(),
+                    &limits,
+                )
+                .await?;
+                read_bytes(response, "oversize test", usize::MAX).await
+            }
+        };
+
+        let error = fetch(RequestLimits::new(1, 1024))
+            .await
+            .expect_err("body over limit");
+        assert!(
+            matches!(&error, RegistryError::InvalidResponse { message } if message.contains("1024-byte")),
+            "{error:?}"
+        );
+
+        let body = fetch(RequestLimits::new(1, 4096))
+            .await
+            .expect("body within limit");
+        assert_eq!(body.len(), 2048);
+    }
 }
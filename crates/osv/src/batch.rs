@@ -0,0 +1,449 @@
+//! Batched advisory lookups through OSV's `querybatch` endpoint.
+//!
+//! `querybatch` only returns the ids of matching vulnerabilities, so a prefetch issues one
+//! batch request per [`OSV_BATCH_SIZE`] packages plus one detail request per distinct
+//! vulnerability, all under a shared concurrency limit.
+
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use safe_pkgs_core::{PackageAdvisory, RegistryEcosystem, RegistryError, RequestLimits};
+use safe_pkgs_registry_http::{
+    RetryPolicy, build_http_client, map_status_error, parse_json, send_with_retry,
+};
+
+use crate::{
+    OsvPackage, OsvQueryRequest, OsvVulnerability, osv_api_url, query_advisories_with_url,
+};
+
+/// Largest number of queries OSV accepts in one `querybatch` request.
+const OSV_BATCH_SIZE: usize = 1000;
+
+/// How long a prefetched entry may wait for its lookup before it is treated as stale.
+const PREFETCH_TTL: Duration = Duration::from_secs(10 * 60);
+
+type PackageKey = (String, String);
+
+/// Prefetched advisories with the time they were fetched.
+type PrefetchedEntries = HashMap<PackageKey, (Instant, Vec<PackageAdvisory>)>;
+
+/// Advisories fetched ahead of a lockfile audit, keyed by package name and version.
+///
+/// Each prefetched entry answers one [`AdvisoryPrefetch::query`] and is then dropped, and
+/// unused entries expire after [`PREFETCH_TTL`], so a long-running server never serves stale
+/// prefetched advisories. Misses query OSV directly.
+#[derive(Debug, Clone, Default)]
+pub struct AdvisoryPrefetch {
+    entries: Arc<Mutex<PrefetchedEntries>>,
+    limits: RequestLimits,
+}
+
+impl AdvisoryPrefetch {
+    /// Creates an empty prefetch whose OSV requests share `limits` with the owning client.
+    pub fn new(limits: RequestLimits) -> Self {
+        Self {
+            entries: Arc::default(),
+            limits,
+        }
+    }
+
+    /// Fetches advisories for `(name, version)` pairs with batched OSV queries, running at
+    /// most `concurrency` OSV requests at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a batch or vulnerability request fails; nothing is stored then,
+    /// and later lookups fall back to per-package queries.
+    pub async fn prefetch(
+        &self,
+        packages: &[(String, String)],
+        ecosystem: RegistryEcosystem,
+        concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        self.prefetch_with_url(packages, ecosystem, concurrency, &osv_api_url())
+            .await
+    }
+
+    /// Returns the prefetched advisories for `package@version`, querying OSV on a miss.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the fallback OSV query fails.
+    pub async fn query(
+        &self,
+        package: &str,
+        version: &str,
+        ecosystem: RegistryEcosystem,
+    ) -> Result<Vec<PackageAdvisory>, RegistryError> {
+        self.query_with_url(package, version, ecosystem, &osv_api_url())
+            .await
+    }
+
+    async fn query_with_url(
+        &self,
+        package: &str,
+        version: &str,
+        ecosystem: RegistryEcosystem,
+        api_url: &str,
+    ) -> Result<Vec<PackageAdvisory>, RegistryError> {
+        if let Some(advisories) = self.take(package, version) {
+            return Ok(advisories);
+        }
+        query_advisories_with_url(package, version, ecosystem, api_url, &self.limits).await
+    }
+
+    fn take(&self, package: &str, version: &str) -> Option<Vec<PackageAdvisory>> {
+        // A poisoned lock only means another task panicked mid-insert; the map is still usable.
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&(package.to_string(), version.to_string()))
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < PREFETCH_TTL)
+            .map(|(_, advisories)| advisories)
+    }
+
+    async fn prefetch_with_url(
+        &self,
+        packages: &[(String, String)],
+        ecosystem: RegistryEcosystem,
+        concurrency: usize,
+        api_url: &str,
+    ) -> Result<(), RegistryError> {
+        let queries = packages
+            .iter()
+            .filter(|(name, version)| !name.is_empty() && !version.is_empty())
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if queries.is_empty() {
+            return Ok(());
+        }
+
+        let http = build_http_client();
+        let limiter = Arc::new(Semaphore::new(concurrency.max(1)));
+
+        let batch_url = osv_endpoint(api_url, "querybatch");
+        let batches = queries
+            .chunks(OSV_BATCH_SIZE)
+            .map(|chunk| {
+                let body = OsvBatchRequest {
+                    queries: chunk
+                        .iter()
+                        .map(|(name, version)| OsvQueryRequest {
+                            package: OsvPackage {
+                                name: name.clone(),
+                                ecosystem: ecosystem.osv_name().to_string(),
+                            },
+                            version: version.clone(),
+                        })
+                        .collect(),
+                };
+                query_batch(http.clone(), batch_url.clone(), body, self.limits.clone())
+            })
+            .collect();
+        let results = run_limited(batches, &limiter).await?;
+
+        // Packages whose result is paginated are left for a direct per-package query.
+        let mut matched = Vec::with_capacity(queries.len());
+        for (chunk, results) in queries.chunks(OSV_BATCH_SIZE).zip(results) {
+            if results.len() != chunk.len() {
+                return Err(RegistryError::InvalidResponse {
+                    message: format!(
+                        "OSV advisory batch response had {} results for {} queries",
+                        results.len(),
+                        chunk.len()
+                    ),
+                });
+            }
+            matched.extend(
+                chunk
+                    .iter()
+                    .zip(results)
+                    .filter(|(_, result)| result.next_page_token.is_none())
+                    .map(|(key, result)| {
+                        let ids = result.vulns.into_iter().map(|vuln| vuln.id).collect();
+                        (key.clone(), ids)
+                    }),
+            );
+        }
+
+        let vulns_url = osv_endpoint(api_url, "vulns");
+        let ids = matched
+            .iter()
+            .flat_map(|(_, ids): &(PackageKey, Vec<String>)| ids.iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let details = ids
+            .iter()
+            .map(|id| {
+                fetch_vulnerability(
+                    http.clone(),
+                    format!("{vulns_url}/{id}"),
+                    self.limits.clone(),
+                )
+            })
+            .collect();
+        let details = ids
+            .into_iter()
+            .zip(run_limited(details, &limiter).await?)
+            .collect::<HashMap<_, _>>();
+
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < PREFETCH_TTL);
+        let fetched_at = Instant::now();
+        for (key, ids) in matched {
+            let advisories = ids
+                .iter()
+                .filter_map(|id| details.get(id).cloned().flatten())
+                .collect();
+            entries.insert(key, (fetched_at, advisories));
+        }
+        Ok(())
+    }
+}
+
+/// Sibling endpoint of the configured query URL, e.g. `.../v1/query` -> `.../v1/querybatch`.
+fn osv_endpoint(query_url: &str, endpoint: &str) -> String {
+    let trimmed = query_url.trim_end_matches('/');
+    let base = trimmed.strip_suffix("/query").unwrap_or(trimmed);
+    format!("{base}/{endpoint}")
+}
+
+async fn query_batch(
+    http: Client,
+    url: String,
+    body: OsvBatchRequest,
+    limits: RequestLimits,
+) -> Result<Vec<OsvBatchResult>, RegistryError> {
+    let response = send_with_retry(
+        || http.post(&url).json(&body),
+        "OSV advisory batch API",
+        RetryPolicy::default(),
+        &limits,
+    )
+    .await?;
+    if !response.status().is_success() {
+        return Err(map_status_error(
+            "OSV advisory batch API",
+            response.status(),
+        ));
+    }
+    let body: OsvBatchResponse = parse_json(response, "OSV advisory batch response").await?;
+    Ok(body.results)
+}
+
+/// Fetches one vulnerability; withdrawn vulnerabilities yield `None`.
+async fn fetch_vulnerability(
+    http: Client,
+    url: String,
+    limits: RequestLimits,
+) -> Result<Option<PackageAdvisory>, RegistryError> {
+    let response = send_with_retry(
+        || http.get(&url),
+        "OSV vulnerability API",
+        RetryPolicy::default(),
+        &limits,
+    )
+    .await?;
+    if !response.status().is_success() {
+        return Err(map_status_error("OSV vulnerability API", response.status()));
+    }
+    let vuln: OsvVulnerability = parse_json(response, "OSV vulnerability response").await?;
+    Ok(vuln.into_advisory())
+}
+
+/// Runs `tasks` with at most `limiter`'s permits in flight, returning results in input order.
+async fn run_limited<T, F>(tasks: Vec<F>, limiter: &Arc<Semaphore>) -> Result<Vec<T>, RegistryError>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T, RegistryError>> + Send + 'static,
+{
+    let total = tasks.len();
+    let mut join_set = JoinSet::new();
+    for (idx, task) in tasks.into_iter().enumerate() {
+        let limiter = Arc::clone(limiter);
+        join_set.spawn(async move {
+            let _permit = limiter
+                .acquire_owned()
+                .await
+                .map_err(|_| RegistryError::Transport {
+                    message: "OSV request limiter closed".to_string(),
+                })?;
+            task.await.map(|value| (idx, value))
+        });
+    }
+
+    let mut ordered = (0..total).map(|_| None).collect::<Vec<_>>();
+    while let Some(joined) = join_set.join_next().await {
+        let (idx, value) = joined.map_err(|source| RegistryError::Transport {
+            message: format!("OSV request task failed: {source}"),
+        })??;
+        ordered[idx] = Some(value);
+    }
+    Ok(ordered.into_iter().flatten().collect())
+}
+
+#[derive(Debug, Serialize)]
+struct OsvBatchRequest {
+    queries: Vec<OsvQueryRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvBatchResponse {
+    #[serde(default)]
+    results: Vec<OsvBatchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvBatchResult {
+    #[serde(default)]
+    vulns: Vec<OsvVulnerabilityId>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvVulnerabilityId {
+    id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn osv_endpoint_replaces_query_path() {
+        assert_eq!(
+            osv_endpoint("https://api.osv.dev/v1/query", "querybatch"),
+            "https://api.osv.dev/v1/querybatch"
+        );
+        assert_eq!(
+            osv_endpoint("http://127.0.0.1:8080/v1/query/", "vulns"),
+            "http://127.0.0.1:8080/v1/vulns"
+        );
+    }
+
+    #[tokio::test]
+    async fn fifty_package_prefetch_issues_bounded_osv_requests() {
+        let mock_server = MockServer::start().await;
+        let results = (0..50)
+            .map(|idx| {
+                if idx == 7 {
+                    r#"{"vulns": [{"id": "GHSA-demo-0007"}]}"#
+                } else {
+                    "{}"
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        Mock::given(method("POST"))
+            .and(path("/v1/querybatch"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(format!(r#"{{"results": [{results}]}}"#), "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/vulns/GHSA-demo-0007"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"id": "GHSA-demo-0007", "affected": [{"ranges": [{"events": [{"fixed": "2.0.0"}]}]}]}"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("{}", "application/json"))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let api_url = format!("{}/v1/query", mock_server.uri());
+        let packages = (0..50)
+            .map(|idx| (format!("pkg-{idx:02}"), "1.0.0".to_string()))
+            .collect::<Vec<_>>();
+        let prefetch = AdvisoryPrefetch::new(RequestLimits::default());
+        prefetch
+            .prefetch_with_url(&packages, RegistryEcosystem::Npm, 4, &api_url)
+            .await
+            .expect("batch prefetch");
+
+        for (name, version) in &packages {
+            let advisories = prefetch
+                .query_with_url(name, version, RegistryEcosystem::Npm, &api_url)
+                .await
+                .expect("prefetched advisories");
+            if name == "pkg-07" {
+                assert_eq!(advisories.len(), 1);
+                assert_eq!(advisories[0].fixed_versions, vec!["2.0.0"]);
+            } else {
+                assert!(advisories.is_empty(), "{name} should have no advisories");
+            }
+        }
+        let requests = mock_server.received_requests().await.unwrap_or_default();
+        assert_eq!(
+            requests.len(),
+            2,
+            "one batch query plus one vulnerability fetch"
+        );
+    }
+
+    #[tokio::test]
+    async fn paginated_and_unprefetched_packages_fall_back_to_direct_queries() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"results": [{"vulns": [{"id": "OSV-1"}], "next_page_token": "more"}]}"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"vulns": [{"id": "OSV-1"}, {"id": "OSV-2"}]}"#,
+                "application/json",
+            ))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let api_url = format!("{}/v1/query", mock_server.uri());
+        let prefetch = AdvisoryPrefetch::new(RequestLimits::default());
+        prefetch
+            .prefetch_with_url(
+                &[("busy".to_string(), "1.0.0".to_string())],
+                RegistryEcosystem::Npm,
+                2,
+                &api_url,
+            )
+            .await
+            .expect("batch prefetch");
+
+        for name in ["busy", "other"] {
+            let advisories = prefetch
+                .query_with_url(name, "1.0.0", RegistryEcosystem::Npm, &api_url)
+                .await
+                .expect("direct query");
+            assert_eq!(advisories.len(), 2);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! CVSS v3.x base score calculation for OSV `severity` entries.
+
+/// Parses an OSV severity score: either a plain number or a `CVSS:3.x/...` vector.
+///
+/// Vectors of other CVSS versions are not scored.
+pub(crate) fn parse_score(score: &str) -> Option<f64> {
+    let score = score.trim();
+    if let Ok(value) = score.parse::<f64>() {
+        return (0.0..=10.0).contains(&value).then_some(value);
+    }
+    base_score_v3(score)
+}
+
+/// Computes the CVSS v3.0/v3.1 base score from a vector string.
+fn base_score_v3(vector: &str) -> Option<f64> {
+    let mut metrics = vector.split('/');
+    let version = metrics.next()?;
+    if version != "CVSS:3.0" && version != "CVSS:3.1" {
+        return None;
+    }
+
+    let mut attack_vector = None;
+    let mut attack_complexity = None;
+    let mut privileges = None;
+    let mut user_interaction = None;
+    let mut scope_changed = None;
+    let mut confidentiality = None;
+    let mut integrity = None;
+    let mut availability = None;
+    for metric in metrics {
+        let (name, value) = metric.split_once(':')?;
+        match name {
+            "AV" => attack_vector = Some(value),
+            "AC" => attack_complexity = Some(value),
+            "PR" => privileges = Some(value),
+            "UI" => user_interaction = Some(value),
+            "S" => scope_changed = Some(value),
+            "C" => confidentiality = Some(value),
+            "I" => integrity = Some(value),
+            "A" => availability = Some(value),
+            // Temporal and environmental metrics do not affect the base score.
+            _ => {}
+        }
+    }
+
+    let scope_changed = match scope_changed? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let attack_vector: f64 = match attack_vector? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let attack_complexity = match attack_complexity? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let privileges = match (privileges?, scope_changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let user_interaction = match user_interaction? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact_weight = |value: &str| -> Option<f64> {
+        match value {
+            "H" => Some(0.56),
+            "L" => Some(0.22),
+            "N" => Some(0.0),
+            _ => None,
+        }
+    };
+    let confidentiality = impact_weight(confidentiality?)?;
+    let integrity = impact_weight(integrity?)?;
+    let availability = impact_weight(availability?)?;
+
+    let impact_subscore = 1.0 - (1.0 - confidentiality) * (1.0 - integrity) * (1.0 - availability);
+    let impact = if scope_changed {
+        7.52 * (impact_subscore - 0.029) - 3.25 * (impact_subscore - 0.02).powi(15)
+    } else {
+        6.42 * impact_subscore
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * attack_vector * attack_complexity * privileges * user_interaction;
+    let score = if scope_changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+    Some(round_up(score))
+}
+
+/// CVSS v3.1 `Roundup`: smallest one-decimal value >= `value`, robust to float noise.
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        ((scaled / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_common_v3_vectors() {
+        assert_eq!(
+            parse_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            parse_score("CVSS:3.1/AV:N/AC:H/PR:N/UI:R/S:U/C:L/I:N/A:N"),
+            Some(3.1)
+        );
+        assert_eq!(
+            parse_score("CVSS:3.0/AV:N/AC:L/PR:L/UI:N/S:C/C:H/I:H/A:H"),
+            Some(9.9)
+        );
+        assert_eq!(
+            parse_score("CVSS:3.1/AV:L/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N"),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn accepts_numeric_scores_and_rejects_unknown_vectors() {
+        assert_eq!(parse_score("7.5"), Some(7.5));
+        assert_eq!(parse_score("11"), None);
+        assert_eq!(
+            parse_score("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"),
+            None
+        );
+        assert_eq!(parse_score("CVSS:3.1/AV:N/AC:L"), None);
+    }
+}
@@ -1,22 +1,33 @@
+mod batch;
+mod cvss;
+
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use std::env;
 
-use safe_pkgs_core::{PackageAdvisory, RegistryEcosystem, RegistryError};
+use safe_pkgs_core::{
+    AdvisorySeverity, PackageAdvisory, RegistryEcosystem, RegistryError, RequestLimits,
+};
 use safe_pkgs_registry_http::{
     RetryPolicy, build_http_client, map_status_error, parse_json, send_with_retry,
 };
 
+pub use batch::AdvisoryPrefetch;
+
 const OSV_API_URL: &str = "https://api.osv.dev/v1/query";
 
+/// OSV query endpoint, overridable with `SAFE_PKGS_OSV_API_BASE_URL`.
+fn osv_api_url() -> String {
+    env::var("SAFE_PKGS_OSV_API_BASE_URL").unwrap_or_else(|_| OSV_API_URL.to_string())
+}
+
 pub async fn query_advisories(
     package_name: &str,
     version: &str,
     ecosystem: RegistryEcosystem,
+    limits: &RequestLimits,
 ) -> Result<Vec<PackageAdvisory>, RegistryError> {
-    let api_url =
-        env::var("SAFE_PKGS_OSV_API_BASE_URL").unwrap_or_else(|_| OSV_API_URL.to_string());
-    query_advisories_with_url(package_name, version, ecosystem, &api_url).await
+    query_advisories_with_url(package_name, version, ecosystem, &osv_api_url(), limits).await
 }
 
 async fn query_advisories_with_url(
@@ -24,6 +35,7 @@ async fn query_advisories_with_url(
     version: &str,
     ecosystem: RegistryEcosystem,
     api_url: &str,
+    limits: &RequestLimits,
 ) -> Result<Vec<PackageAdvisory>, RegistryError> {
     let http = build_http_client();
     let body = OsvQueryRequest {
@@ -38,6 +50,7 @@ async fn query_advisories_with_url(
         || http.post(api_url).json(&body),
         "OSV advisory API",
         RetryPolicy::default(),
+        limits,
     )
     .await?;
 
@@ -54,14 +67,7 @@ async fn query_advisories_with_url(
     Ok(body
         .vulns
         .into_iter()
-        .map(|vuln| {
-            let fixed_versions = vuln.fixed_versions();
-            PackageAdvisory {
-                id: vuln.id,
-                aliases: vuln.aliases,
-                fixed_versions,
-            }
-        })
+        .filter_map(OsvVulnerability::into_advisory)
         .collect())
 }
 
@@ -90,31 +96,109 @@ struct OsvVulnerability {
     aliases: Vec<String>,
     #[serde(default)]
     affected: Vec<OsvAffected>,
+    #[serde(default)]
+    severity: Vec<OsvSeverity>,
+    /// Timestamp at which the advisory was withdrawn; withdrawn advisories are ignored.
+    withdrawn: Option<String>,
 }
 
 impl OsvVulnerability {
+    /// Converts the vulnerability into an advisory; withdrawn advisories yield `None`.
+    fn into_advisory(self) -> Option<PackageAdvisory> {
+        if self.withdrawn.is_some() {
+            return None;
+        }
+        let fixed_versions = self.fixed_versions();
+        let git_range_only = self.git_range_only();
+        let cvss_score = self.cvss_score();
+        Some(PackageAdvisory {
+            id: self.id,
+            aliases: self.aliases,
+            fixed_versions,
+            git_range_only,
+            cvss_score,
+            severity: self
+                .severity
+                .into_iter()
+                .filter(|severity| !severity.score.trim().is_empty())
+                .map(|severity| AdvisorySeverity {
+                    kind: severity.kind,
+                    score: severity.score.trim().to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Fixed release versions; `GIT` range events carry commit hashes and are skipped.
     fn fixed_versions(&self) -> Vec<String> {
         self.affected
             .iter()
             .flat_map(|affected| affected.ranges.iter())
+            .filter(|range| !range.is_git())
             .flat_map(|range| range.events.iter())
             .filter_map(|event| event.fixed.clone())
             .collect()
     }
+
+    /// Highest scorable CVSS base score across the advisory's severity entries.
+    fn cvss_score(&self) -> Option<f64> {
+        self.severity
+            .iter()
+            .filter_map(|severity| cvss::parse_score(&severity.score))
+            .reduce(f64::max)
+    }
+
+    /// Whether the advisory only describes affected commits.
+    ///
+    /// Entries without ranges (for example ones that only carry `database_specific` or
+    /// `ecosystem_specific` metadata) do not count as version information.
+    fn git_range_only(&self) -> bool {
+        if self
+            .affected
+            .iter()
+            .any(|affected| !affected.versions.is_empty())
+        {
+            return false;
+        }
+        let mut ranges = self
+            .affected
+            .iter()
+            .flat_map(|affected| affected.ranges.iter())
+            .peekable();
+        ranges.peek().is_some() && ranges.all(OsvRange::is_git)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvSeverity {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    score: String,
 }
 
 #[derive(Debug, Deserialize)]
 struct OsvAffected {
     #[serde(default)]
     ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct OsvRange {
+    #[serde(rename = "type", default)]
+    range_type: String,
     #[serde(default)]
     events: Vec<OsvEvent>,
 }
 
+impl OsvRange {
+    fn is_git(&self) -> bool {
+        self.range_type.eq_ignore_ascii_case("GIT")
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct OsvEvent {
     fixed: Option<String>,
@@ -123,7 +207,7 @@ struct OsvEvent {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{method, path};
+    use wiremock::matchers::{body_string_contains, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[tokio::test]
@@ -140,12 +224,38 @@ mod tests {
             "1.0.0",
             RegistryEcosystem::Npm,
             &format!("{}/v1/query", mock_server.uri()),
+            &RequestLimits::default(),
         )
         .await
         .expect("404 should map to empty advisory list");
         assert!(result.is_empty());
     }
 
+    #[tokio::test]
+    async fn query_uses_osv_ecosystem_name() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .and(body_string_contains(r#""ecosystem":"Go""#))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(r#"{"vulns": []}"#, "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let result = query_advisories_with_url(
+            "github.com/gin-gonic/gin",
+            "1.9.0",
+            RegistryEcosystem::Go,
+            &format!("{}/v1/query", mock_server.uri()),
+            &RequestLimits::default(),
+        )
+        .await
+        .expect("Go query");
+        assert!(result.is_empty());
+    }
+
     #[tokio::test]
     async fn returns_transport_error_on_5xx() {
         let mock_server = MockServer::start().await;
@@ -160,6 +270,7 @@ mod tests {
             "1.0.0",
             RegistryEcosystem::Npm,
             &format!("{}/v1/query", mock_server.uri()),
+            &RequestLimits::default(),
         )
         .await
         .expect_err("500 should be treated as transport error");
@@ -198,6 +309,7 @@ mod tests {
             "1.0.0",
             RegistryEcosystem::Npm,
             &format!("{}/v1/query", mock_server.uri()),
+            &RequestLimits::default(),
         )
         .await
         .expect("valid OSV response");
@@ -208,6 +320,139 @@ mod tests {
         assert_eq!(advisories[0].fixed_versions, vec!["1.2.3", "2.0.0"]);
     }
 
+    async fn advisories_from_body(body: &'static str) -> Vec<PackageAdvisory> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+            .mount(&mock_server)
+            .await;
+
+        query_advisories_with_url(
+            "demo",
+            "1.0.0",
+            RegistryEcosystem::Npm,
+            &format!("{}/v1/query", mock_server.uri()),
+            &RequestLimits::default(),
+        )
+        .await
+        .expect("valid OSV response")
+    }
+
+    #[tokio::test]
+    async fn git_only_ranges_surface_without_fixed_versions() {
+        let advisories = advisories_from_body(
+            r#"{
+                "vulns": [{
+                    "id": "OSV-2024-456",
+                    "affected": [{
+                        "ranges": [{
+                            "type": "GIT",
+                            "repo": "https://github.com/example/demo",
+                            "events": [
+                                {"introduced": "0"},
+                                {"fixed": "8f2a6c1d0e9b7a3f4c5d6e7f8a9b0c1d2e3f4a5b"}
+                            ]
+                        }],
+                        "database_specific": {"source": "https://example.test/demo.json"},
+                        "ecosystem_specific": {"severity": "HIGH"}
+                    }]
+                }]
+            }"#,
+        )
+        .await;
+
+        assert_eq!(advisories.len(), 1);
+        assert!(advisories[0].fixed_versions.is_empty());
+        assert!(advisories[0].git_range_only);
+    }
+
+    #[tokio::test]
+    async fn git_range_alongside_semver_range_keeps_fixed_versions() {
+        let advisories = advisories_from_body(
+            r#"{
+                "vulns": [{
+                    "id": "OSV-2024-789",
+                    "affected": [{
+                        "ranges": [
+                            {
+                                "type": "GIT",
+                                "repo": "https://github.com/example/demo",
+                                "events": [{"introduced": "0"}, {"fixed": "abc123"}]
+                            },
+                            {
+                                "type": "SEMVER",
+                                "events": [{"introduced": "0"}, {"fixed": "1.4.0"}]
+                            }
+                        ]
+                    }]
+                }]
+            }"#,
+        )
+        .await;
+
+        assert_eq!(advisories[0].fixed_versions, vec!["1.4.0"]);
+        assert!(!advisories[0].git_range_only);
+    }
+
+    #[tokio::test]
+    async fn captures_highest_cvss_score() {
+        let advisories = advisories_from_body(
+            r#"{
+                "vulns": [
+                    {
+                        "id": "OSV-LOW",
+                        "severity": [
+                            {"type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:H/PR:N/UI:R/S:U/C:L/I:N/A:N"}
+                        ]
+                    },
+                    {
+                        "id": "OSV-CRITICAL",
+                        "severity": [
+                            {"type": "CVSS_V4", "score": "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"},
+                            {"type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"}
+                        ]
+                    },
+                    {"id": "OSV-UNSCORED"}
+                ]
+            }"#,
+        )
+        .await;
+
+        let scores = advisories
+            .iter()
+            .map(|advisory| advisory.cvss_score)
+            .collect::<Vec<_>>();
+        assert_eq!(scores, vec![Some(3.1), Some(9.8), None]);
+        assert_eq!(
+            advisories[1].severity[1],
+            AdvisorySeverity {
+                kind: "CVSS_V3".to_string(),
+                score: "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".to_string(),
+            }
+        );
+        assert!(advisories[2].severity.is_empty());
+    }
+
+    #[tokio::test]
+    async fn withdrawn_advisories_are_excluded() {
+        let advisories = advisories_from_body(
+            r#"{
+                "vulns": [
+                    {"id": "OSV-ACTIVE"},
+                    {"id": "OSV-WITHDRAWN", "withdrawn": "2024-05-01T00:00:00Z"}
+                ]
+            }"#,
+        )
+        .await;
+
+        let ids = advisories
+            .iter()
+            .map(|advisory| advisory.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["OSV-ACTIVE"]);
+    }
+
     #[tokio::test]
     async fn returns_invalid_response_when_json_is_malformed() {
         let mock_server = MockServer::start().await;
@@ -224,6 +469,7 @@ mod tests {
             "1.0.0",
             RegistryEcosystem::Npm,
             &format!("{}/v1/query", mock_server.uri()),
+            &RequestLimits::default(),
         )
         .await
         .expect_err("malformed JSON should fail parsing");
@@ -5,19 +5,25 @@ use std::sync::Arc;
 
 pub use lockfile::CargoLockfileParser;
 pub use registry::CargoRegistryClient;
-use safe_pkgs_core::{LockfileParser, RegistryClient, RegistryDefinition};
+use safe_pkgs_core::{LockfileParser, RegistryClient, RegistryClientOptions, RegistryDefinition};
 
 pub fn registry_definition() -> RegistryDefinition {
     RegistryDefinition {
         key: "cargo",
         create_client,
         create_lockfile_parser: Some(create_lockfile_parser),
-        excluded_checks: &["install_script"],
+        excluded_checks: &[
+            "install_script",
+            "size_anomaly",
+            "bus_factor",
+            "maintainer_domain",
+            "binary_only",
+        ],
     }
 }
 
-fn create_client() -> Arc<dyn RegistryClient> {
-    Arc::new(CargoRegistryClient::new())
+fn create_client(options: &RegistryClientOptions) -> Arc<dyn RegistryClient> {
+    Arc::new(CargoRegistryClient::with_options(options))
 }
 
 fn create_lockfile_parser() -> Arc<dyn LockfileParser> {
@@ -1,6 +1,8 @@
-use safe_pkgs_core::{DependencySpec, LockfileError, LockfileParser};
+use safe_pkgs_core::{
+    DependencyScope, DependencySpec, LockfileError, LockfileParser, UnsupportedSource, glob_matches,
+};
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use toml::Value;
 
 #[derive(Debug, Clone, Default)]
@@ -50,7 +52,7 @@ fn parse_cargo_lock(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
     })?;
 
     let mut nodes = BTreeMap::<String, LockNode>::new();
-    let mut dependencies = BTreeMap::<String, Option<String>>::new();
+    let mut dependencies = DependencySpecs::new();
 
     let packages = root
         .get("package")
@@ -90,25 +92,32 @@ fn parse_cargo_lock(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
                 workspace_root,
             });
 
-        if !is_crates_io_source(source) {
+        // Git packages are kept with a marker so callers can report them; workspace
+        // members and alternate registries are left out.
+        let unsupported_source = if is_crates_io_source(source) {
+            None
+        } else if is_git_source(source) {
+            Some(UnsupportedSource::Git)
+        } else {
             continue;
-        }
+        };
 
         let version = table
             .get("version")
             .and_then(|value| value.as_str())
             .and_then(normalize_cargo_exact_version);
-        insert_dependency_spec(&mut dependencies, direct_dependency_spec(name, version));
+        let mut spec = direct_dependency_spec(name, version);
+        spec.unsupported_source = unsupported_source;
+        insert_dependency_spec(&mut dependencies, spec);
     }
 
     let roots = lockfile_root_packages(&nodes);
     let shortest_paths = compute_shortest_paths(&nodes, &roots);
 
-    Ok(dependencies
+    Ok(dependency_specs(dependencies)
         .into_iter()
-        .map(|(name, version)| {
-            let mut spec = direct_dependency_spec(name.clone(), version);
-            if let Some(path) = shortest_paths.get(&name) {
+        .map(|mut spec| {
+            if let Some(path) = shortest_paths.get(&spec.name) {
                 spec.dependency_paths = parent_chain_from_full_path(path);
             }
             spec
@@ -116,52 +125,174 @@ fn parse_cargo_lock(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
         .collect())
 }
 
+/// Parses a manifest plus every `workspace.members` crate it declares, so a virtual
+/// manifest (only `[workspace]`) reports its members' dependencies.
 fn parse_cargo_manifest(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
+    let root = read_manifest(path)?;
+    let mut dependencies = DependencySpecs::new();
+    collect_manifest_dependencies(&root, &mut dependencies);
+
+    let workspace_dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let mut member_names = BTreeSet::new();
+    for member_path in workspace_member_manifests(&root, workspace_dir) {
+        let member = read_manifest(&member_path)?;
+        if let Some(name) = member
+            .get("package")
+            .and_then(|value| value.get("name"))
+            .and_then(|value| value.as_str())
+        {
+            member_names.insert(name.to_string());
+        }
+        collect_manifest_dependencies(&member, &mut dependencies);
+    }
+    // Path dependencies on sibling members are part of the workspace, not external sources.
+    dependencies.retain(|(name, _), spec| {
+        spec.unsupported_source != Some(UnsupportedSource::Path) || !member_names.contains(name)
+    });
+
+    Ok(dependency_specs(dependencies))
+}
+
+fn read_manifest(path: &Path) -> Result<Value, LockfileError> {
     let raw = std::fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
         path: path.display().to_string(),
         source,
     })?;
-    let root: Value = toml::from_str(&raw).map_err(|error| LockfileError::ParseFile {
+    toml::from_str(&raw).map_err(|error| LockfileError::ParseFile {
         path: path.display().to_string(),
         message: error.to_string(),
-    })?;
+    })
+}
 
-    let mut dependencies = BTreeMap::<String, Option<String>>::new();
-    parse_manifest_dependency_section(root.get("dependencies"), &mut dependencies);
-    parse_manifest_dependency_section(root.get("dev-dependencies"), &mut dependencies);
-    parse_manifest_dependency_section(root.get("build-dependencies"), &mut dependencies);
+fn collect_manifest_dependencies(root: &Value, dependencies: &mut DependencySpecs) {
+    // Build dependencies run code at build time, so they are treated as production scope.
+    parse_manifest_dependency_section(
+        root.get("dependencies"),
+        DependencyScope::Prod,
+        dependencies,
+    );
+    parse_manifest_dependency_section(
+        root.get("dev-dependencies"),
+        DependencyScope::Dev,
+        dependencies,
+    );
+    parse_manifest_dependency_section(
+        root.get("build-dependencies"),
+        DependencyScope::Prod,
+        dependencies,
+    );
     parse_manifest_dependency_section(
         root.get("workspace")
             .and_then(|value| value.get("dependencies")),
-        &mut dependencies,
+        DependencyScope::Prod,
+        dependencies,
     );
 
     if let Some(targets) = root.get("target").and_then(|value| value.as_table()) {
         for target in targets.values() {
-            parse_manifest_dependency_section(target.get("dependencies"), &mut dependencies);
-            parse_manifest_dependency_section(target.get("dev-dependencies"), &mut dependencies);
-            parse_manifest_dependency_section(target.get("build-dependencies"), &mut dependencies);
+            parse_manifest_dependency_section(
+                target.get("dependencies"),
+                DependencyScope::Prod,
+                dependencies,
+            );
+            parse_manifest_dependency_section(
+                target.get("dev-dependencies"),
+                DependencyScope::Dev,
+                dependencies,
+            );
+            parse_manifest_dependency_section(
+                target.get("build-dependencies"),
+                DependencyScope::Prod,
+                dependencies,
+            );
         }
     }
+}
 
-    Ok(dependencies
+/// Resolves `workspace.members` (with `*`/`?` globs per path segment) to member manifest
+/// paths, minus `workspace.exclude`. Glob matches without a `Cargo.toml` are skipped.
+fn workspace_member_manifests(root: &Value, workspace_dir: &Path) -> Vec<PathBuf> {
+    let Some(workspace) = root.get("workspace") else {
+        return Vec::new();
+    };
+    let string_list = |key: &str| {
+        workspace
+            .get(key)
+            .and_then(|value| value.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|value| value.as_str())
+            .collect::<Vec<_>>()
+    };
+    let excluded = string_list("exclude")
         .into_iter()
-        .map(|(name, version)| direct_dependency_spec(name, version))
-        .collect())
+        .map(|member| workspace_dir.join(member))
+        .collect::<BTreeSet<_>>();
+
+    let mut manifests = BTreeSet::new();
+    for pattern in string_list("members") {
+        let is_glob = pattern.contains(['*', '?']);
+        for member_dir in expand_member_pattern(workspace_dir, pattern) {
+            if member_dir == workspace_dir || excluded.contains(&member_dir) {
+                continue;
+            }
+            let manifest = member_dir.join("Cargo.toml");
+            if !is_glob || manifest.is_file() {
+                manifests.insert(manifest);
+            }
+        }
+    }
+    manifests.into_iter().collect()
+}
+
+fn expand_member_pattern(workspace_dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut candidates = vec![workspace_dir.to_path_buf()];
+    for segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        if segment == "." {
+            continue;
+        }
+        if !segment.contains(['*', '?']) {
+            for candidate in &mut candidates {
+                candidate.push(segment);
+            }
+            continue;
+        }
+        candidates = candidates
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| glob_matches(segment, name))
+            })
+            .map(|entry| entry.path())
+            .collect();
+    }
+    candidates
 }
 
 fn parse_manifest_dependency_section(
     section: Option<&Value>,
-    dependencies: &mut BTreeMap<String, Option<String>>,
+    scope: DependencyScope,
+    dependencies: &mut DependencySpecs,
 ) {
     let Some(table) = section.and_then(|value| value.as_table()) else {
         return;
     };
 
     for (declared_name, value) in table {
-        let Some(spec) = parse_manifest_dependency(declared_name, value) else {
+        let Some(mut spec) = parse_manifest_dependency(declared_name, value) else {
             continue;
         };
+        // `optional = true` only downgrades an otherwise-production dependency.
+        spec.scope = spec.scope.max(scope);
         insert_dependency_spec(dependencies, spec);
     }
 }
@@ -173,9 +304,15 @@ fn parse_manifest_dependency(declared_name: &str, value: &Value) -> Option<Depen
             normalize_cargo_manifest_version(raw_version),
         )),
         Value::Table(entries) => {
-            if !manifest_dependency_is_supported_registry(entries) {
+            let unsupported_source = if entries.contains_key("git") {
+                Some(UnsupportedSource::Git)
+            } else if entries.contains_key("path") {
+                Some(UnsupportedSource::Path)
+            } else if manifest_dependency_is_supported_registry(entries) {
+                None
+            } else {
                 return None;
-            }
+            };
             let name = entries
                 .get("package")
                 .and_then(|value| value.as_str())
@@ -185,16 +322,18 @@ fn parse_manifest_dependency(declared_name: &str, value: &Value) -> Option<Depen
                 .get("version")
                 .and_then(|value| value.as_str())
                 .and_then(normalize_cargo_manifest_version);
-            Some(direct_dependency_spec(name.to_string(), version))
+            let mut spec = direct_dependency_spec(name.to_string(), version);
+            spec.unsupported_source = unsupported_source;
+            if entries.get("optional").and_then(|value| value.as_bool()) == Some(true) {
+                spec.scope = DependencyScope::Optional;
+            }
+            Some(spec)
         }
         _ => None,
     }
 }
 
 fn manifest_dependency_is_supported_registry(entries: &toml::value::Table) -> bool {
-    if entries.contains_key("path") || entries.contains_key("git") {
-        return false;
-    }
     if entries.get("workspace").and_then(|value| value.as_bool()) == Some(true) {
         return false;
     }
@@ -258,10 +397,15 @@ fn is_crates_io_source(raw: Option<&str>) -> bool {
     let Some(value) = raw.map(str::trim) else {
         return false;
     };
-    value.starts_with("registry+")
+    // Lockfile v4 may record the sparse index (`sparse+https://index.crates.io/`).
+    (value.starts_with("registry+") || value.starts_with("sparse+"))
         && (value.contains("crates.io") || value.contains("index.crates.io"))
 }
 
+fn is_git_source(raw: Option<&str>) -> bool {
+    raw.is_some_and(|value| value.trim().starts_with("git+"))
+}
+
 /// Extracts normalized dependency package names from a `Cargo.lock` dependency array.
 ///
 /// Invalid or unparsable entries are skipped.
@@ -361,18 +505,45 @@ fn compute_shortest_paths(
     shortest_paths
 }
 
-fn insert_dependency_spec(
-    dependencies: &mut BTreeMap<String, Option<String>>,
-    spec: DependencySpec,
-) {
-    dependencies
-        .entry(spec.name)
-        .and_modify(|existing| {
-            if existing.is_none() && spec.version.is_some() {
-                *existing = spec.version.clone();
-            }
-        })
-        .or_insert(spec.version);
+/// Collected specs keyed by `(name, version)`, so every locked or declared version of a
+/// crate is evaluated rather than only the first one seen.
+type DependencySpecs = BTreeMap<(String, Option<String>), DependencySpec>;
+
+fn insert_dependency_spec(dependencies: &mut DependencySpecs, spec: DependencySpec) {
+    match dependencies.get_mut(&(spec.name.clone(), spec.version.clone())) {
+        Some(existing) => merge_dependency_spec(existing, &spec),
+        None => {
+            dependencies.insert((spec.name.clone(), spec.version.clone()), spec);
+        }
+    }
+}
+
+fn merge_dependency_spec(existing: &mut DependencySpec, spec: &DependencySpec) {
+    existing.scope = existing.scope.merge(spec.scope);
+    // A registry declaration of the same crate makes it evaluable.
+    existing.unsupported_source = existing.unsupported_source.and(spec.unsupported_source);
+}
+
+/// Flattens collected specs; a versionless spec is folded into the same crate's versioned
+/// specs when any exist.
+fn dependency_specs(mut dependencies: DependencySpecs) -> Vec<DependencySpec> {
+    let versioned = dependencies
+        .keys()
+        .filter(|(_, version)| version.is_some())
+        .map(|(name, _)| name.clone())
+        .collect::<BTreeSet<_>>();
+    for name in versioned {
+        let Some(unversioned) = dependencies.remove(&(name.clone(), None)) else {
+            continue;
+        };
+        for (_, existing) in dependencies
+            .range_mut((name.clone(), None)..)
+            .take_while(|((candidate, _), _)| *candidate == name)
+        {
+            merge_dependency_spec(existing, &unversioned);
+        }
+    }
+    dependencies.into_values().collect()
 }
 
 /// Builds a `DependencySpec` for a direct (non-transitive) dependency.
@@ -383,6 +554,9 @@ fn direct_dependency_spec(name: String, version: Option<String>) -> DependencySp
         dependency_paths: Vec::new(),
         name,
         version,
+        scope: DependencyScope::Prod,
+        unsupported_source: None,
+        hashes: Vec::new(),
     }
 }
 
@@ -482,7 +656,7 @@ tokio = "1.0.0"
     }
 
     #[test]
-    fn parse_cargo_lock_includes_only_crates_io_registry_packages() {
+    fn parse_cargo_lock_includes_crates_io_and_marked_git_packages() {
         let dir = unique_temp_dir("lock");
         let path = dir.join("Cargo.lock");
         std::fs::write(
@@ -513,9 +687,12 @@ version = "0.1.0"
         .expect("write lock");
 
         let deps = parse_cargo_lock(&path).expect("parse lock");
-        assert_eq!(deps.len(), 1);
-        assert_eq!(deps[0].name, "serde");
-        assert_eq!(deps[0].version.as_deref(), Some("1.0.210"));
+        assert_eq!(deps.len(), 2);
+        assert_eq!(deps[0].name, "git-only");
+        assert_eq!(deps[0].unsupported_source, Some(UnsupportedSource::Git));
+        assert_eq!(deps[1].name, "serde");
+        assert_eq!(deps[1].version.as_deref(), Some("1.0.210"));
+        assert_eq!(deps[1].unsupported_source, None);
 
         let _ = std::fs::remove_file(path);
         let _ = std::fs::remove_dir_all(dir);
@@ -590,6 +767,9 @@ cc = "^1.0"
 [dev-dependencies]
 tempfile = { version = "=3.12.0" }
 
+[build-dependencies]
+cfg-if = { version = "1.0.0", optional = true }
+
 [target.'cfg(unix)'.dependencies]
 libc = "0.2.155"
 
@@ -606,15 +786,213 @@ tracing = "0.1.40"
         assert_eq!(find_version(&deps, "libc"), Some("0.2.155"));
         assert_eq!(find_version(&deps, "tracing"), Some("0.1.40"));
         assert_eq!(find_version(&deps, "cc"), None);
-        assert!(deps.iter().all(|dep| dep.name != "local_dep"));
-        assert!(deps.iter().all(|dep| dep.name != "git_dep"));
         assert!(deps.iter().all(|dep| dep.name != "workspace_dep"));
         assert!(deps.iter().all(|dep| dep.name != "private_dep"));
 
+        let scope_of = |name: &str| {
+            deps.iter()
+                .find(|spec| spec.name == name)
+                .map(|spec| spec.scope)
+        };
+        assert_eq!(scope_of("serde"), Some(DependencyScope::Prod));
+        assert_eq!(scope_of("tempfile"), Some(DependencyScope::Dev));
+        assert_eq!(scope_of("cfg-if"), Some(DependencyScope::Optional));
+
+        let source_of = |name: &str| {
+            deps.iter()
+                .find(|spec| spec.name == name)
+                .map(|spec| spec.unsupported_source)
+        };
+        assert_eq!(source_of("serde"), Some(None));
+        assert_eq!(source_of("git_dep"), Some(Some(UnsupportedSource::Git)));
+        assert_eq!(source_of("local_dep"), Some(Some(UnsupportedSource::Path)));
+
         let _ = std::fs::remove_file(path);
         let _ = std::fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn parse_cargo_lock_reads_version_4_lockfiles() {
+        let dir = unique_temp_dir("lock-v4");
+        let path = dir.join("Cargo.lock");
+        std::fs::write(
+            &path,
+            r#"
+# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "serde",
+ "itoa 1.0.11",
+]
+
+[[package]]
+name = "itoa"
+version = "1.0.11"
+source = "sparse+https://index.crates.io/"
+checksum = "49f1f14873335454500d59611f1cf4a4b0f786f9ac11f4312a78e4cf2566695b"
+
+[[package]]
+name = "serde"
+version = "1.0.210"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "c8e3592472072e6e22e0a54d5904d9febf8508f65fb8552499a1abc7d1078c3a"
+"#,
+        )
+        .expect("write lock");
+
+        let deps = parse_cargo_lock(&path).expect("parse v4 lock");
+        assert_eq!(deps.len(), 2);
+        assert_eq!(find_version(&deps, "serde"), Some("1.0.210"));
+        assert_eq!(find_version(&deps, "itoa"), Some("1.0.11"));
+        assert_eq!(
+            find_paths(&deps, "itoa"),
+            Some(vec![vec!["app".to_string()]])
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn parse_cargo_manifest_merges_virtual_workspace_members() {
+        let dir = unique_temp_dir("virtual-workspace");
+        let write = |relative: &str, contents: &str| {
+            let path = dir.join(relative);
+            std::fs::create_dir_all(path.parent().expect("parent dir")).expect("create dir");
+            std::fs::write(path, contents).expect("write manifest");
+        };
+        write(
+            "Cargo.toml",
+            r#"
+[workspace]
+members = ["crates/*", "tools/cli"]
+exclude = ["crates/scratch"]
+
+[workspace.dependencies]
+tracing = "0.1.40"
+"#,
+        );
+        write(
+            "crates/core/Cargo.toml",
+            r#"
+[package]
+name = "demo-core"
+version = "0.1.0"
+
+[dependencies]
+serde = "1.0.210"
+tracing = { workspace = true }
+"#,
+        );
+        write(
+            "tools/cli/Cargo.toml",
+            r#"
+[package]
+name = "demo-cli"
+version = "0.1.0"
+
+[dependencies]
+demo-core = { path = "../../crates/core" }
+clap = "4.5.4"
+
+[dev-dependencies]
+serde = "1.0.210"
+"#,
+        );
+        write(
+            "crates/scratch/Cargo.toml",
+            "[package]\nname = \"scratch\"\n\n[dependencies]\nleft-pad = \"0.1.0\"\n",
+        );
+        std::fs::create_dir_all(dir.join("crates/docs")).expect("create non-crate dir");
+
+        let deps = parse_cargo_manifest(&dir.join("Cargo.toml")).expect("parse workspace");
+        let mut names = deps
+            .iter()
+            .map(|spec| spec.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, ["clap", "serde", "tracing"]);
+        assert_eq!(find_version(&deps, "clap"), Some("4.5.4"));
+        assert_eq!(
+            deps.iter()
+                .find(|spec| spec.name == "serde")
+                .map(|spec| spec.scope),
+            Some(DependencyScope::Prod)
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn every_version_of_a_crate_is_reported() {
+        let dir = unique_temp_dir("multi-version");
+        let lock = dir.join("Cargo.lock");
+        std::fs::write(
+            &lock,
+            r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["syn 1.0.109", "syn 2.0.77"]
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.77"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .expect("write lock");
+        let versions = |deps: &[DependencySpec], name: &str| {
+            deps.iter()
+                .filter(|spec| spec.name == name)
+                .map(|spec| spec.version.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let deps = parse_cargo_lock(&lock).expect("parse lock");
+        assert_eq!(
+            versions(&deps, "syn"),
+            vec![Some("1.0.109".to_string()), Some("2.0.77".to_string())]
+        );
+
+        let write = |relative: &str, contents: &str| {
+            let path = dir.join(relative);
+            std::fs::create_dir_all(path.parent().expect("parent dir")).expect("create dir");
+            std::fs::write(path, contents).expect("write manifest");
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\nrand = \"*\"\n",
+        );
+        write(
+            "crates/old/Cargo.toml",
+            "[package]\nname = \"old\"\n\n[dependencies]\nrand = \"=0.7.3\"\n",
+        );
+        write(
+            "crates/new/Cargo.toml",
+            "[package]\nname = \"new\"\n\n[dependencies]\nrand = \"=0.8.5\"\n",
+        );
+        let deps = parse_cargo_manifest(&dir.join("Cargo.toml")).expect("parse workspace");
+        // The unpinned root declaration folds into the pinned member versions.
+        assert_eq!(
+            versions(&deps, "rand"),
+            vec![Some("0.7.3".to_string()), Some("0.8.5".to_string())]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn parse_cargo_manifest_rejects_invalid_toml() {
         let dir = unique_temp_dir("manifest-invalid");
@@ -3,17 +3,17 @@ use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
 use serde::Deserialize;
 use std::collections::BTreeMap;
-use std::env;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use safe_pkgs_core::{
-    PackageAdvisory, PackageRecord, PackageVersion, RegistryClient, RegistryEcosystem,
-    RegistryError,
+    LocalPopularNames, PackageAdvisory, PackageRecord, PackageVersion, RegistryClient,
+    RegistryClientOptions, RegistryEcosystem, RegistryError, RequestLimits,
+    normalize_repository_url,
 };
-use safe_pkgs_osv::query_advisories;
+use safe_pkgs_osv::AdvisoryPrefetch;
 use safe_pkgs_registry_http::{
-    RetryPolicy, build_http_client, map_status_error, parse_json, send_with_retry,
+    RegistryAuth, RetryPolicy, build_http_client, map_status_error, parse_json, send_with_retry,
 };
 
 const CRATES_PAGE_SIZE: usize = 100;
@@ -21,33 +21,49 @@ const CRATES_PAGE_SIZE: usize = 100;
 #[derive(Clone)]
 pub struct CargoRegistryClient {
     http: reqwest::Client,
+    limits: RequestLimits,
     api_base_url: String,
-    auth_token: Option<String>,
+    auth: Option<RegistryAuth>,
     popular_names_cache: Arc<RwLock<Option<Vec<String>>>>,
-}
-
-/// Reads a registry token env var, treating empty/whitespace values as `None`.
-fn token_from_env(var: &str) -> Option<String> {
-    env::var(var)
-        .ok()
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
+    local_popular_names: Option<Arc<LocalPopularNames>>,
+    advisories: AdvisoryPrefetch,
 }
 
 impl CargoRegistryClient {
     pub fn new() -> Self {
         Self {
             http: build_http_client(),
+            limits: RequestLimits::default(),
             api_base_url: "https://crates.io/api/v1".to_string(),
-            auth_token: token_from_env("SAFE_PKGS_CARGO_REGISTRY_TOKEN"),
+            auth: RegistryAuth::from_env(
+                "SAFE_PKGS_CARGO_REGISTRY_TOKEN",
+                "SAFE_PKGS_CARGO_REGISTRY_USERNAME",
+                "SAFE_PKGS_CARGO_REGISTRY_PASSWORD",
+            ),
             popular_names_cache: Arc::new(RwLock::new(None)),
+            local_popular_names: None,
+            advisories: AdvisoryPrefetch::default(),
+        }
+    }
+
+    /// Creates a client with startup overrides applied on top of env/default settings.
+    pub fn with_options(options: &RegistryClientOptions) -> Self {
+        let mut client = Self::new();
+        client.limits = options.limits.clone();
+        client.advisories = AdvisoryPrefetch::new(options.limits.clone());
+        if let Some(base_url) = &options.base_url {
+            client.api_base_url = base_url.clone();
+        }
+        if let Some(path) = &options.popular_names_file {
+            client.local_popular_names = Some(Arc::new(LocalPopularNames::new(path)));
         }
+        client
     }
 
-    /// Adds a bearer token to the request when a private-registry token is configured.
+    /// Adds the `Authorization` header when private-registry credentials are configured.
     fn authorized(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-        match &self.auth_token {
-            Some(token) => builder.bearer_auth(token),
+        match &self.auth {
+            Some(auth) => auth.apply(builder),
             None => builder,
         }
     }
@@ -65,6 +81,10 @@ impl RegistryClient for CargoRegistryClient {
         RegistryEcosystem::CratesIo
     }
 
+    fn base_url(&self) -> Option<&str> {
+        Some(self.api_base_url.as_str())
+    }
+
     async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
         let url = format!(
             "{}/crates/{}",
@@ -75,6 +95,7 @@ impl RegistryClient for CargoRegistryClient {
             || self.authorized(self.http.get(&url)),
             "crates.io API",
             RetryPolicy::default(),
+            &self.limits,
         )
         .await?;
 
@@ -100,6 +121,12 @@ impl RegistryClient for CargoRegistryClient {
                 message: "missing crate latest version".to_string(),
             })?;
 
+        // crates.io records the repository per crate, so every version shares it.
+        let repository_url = body
+            .krate
+            .repository
+            .as_deref()
+            .and_then(normalize_repository_url);
         let versions = body
             .versions
             .into_iter()
@@ -114,6 +141,16 @@ impl RegistryClient for CargoRegistryClient {
                         published,
                         deprecated: version.yanked,
                         install_scripts: Vec::new(),
+                        unpacked_size: None,
+                        file_count: None,
+                        maintainer_emails: Vec::new(),
+                        bin_targets: Vec::new(),
+                        license: version
+                            .license
+                            .map(|license| license.trim().to_string())
+                            .filter(|license| !license.is_empty()),
+                        repository_url: repository_url.clone(),
+                        published_estimated: false,
                     },
                 )
             })
@@ -137,6 +174,7 @@ impl RegistryClient for CargoRegistryClient {
             || self.authorized(self.http.get(&url)),
             "crates.io API",
             RetryPolicy::default(),
+            &self.limits,
         )
         .await?;
 
@@ -161,6 +199,10 @@ impl RegistryClient for CargoRegistryClient {
             return Ok(Vec::new());
         }
 
+        if let Some(local) = &self.local_popular_names {
+            return local.fetch(limit);
+        }
+
         {
             let cache_guard = self.popular_names_cache.read().await;
             if let Some(cached) = cache_guard.as_ref()
@@ -185,6 +227,7 @@ impl RegistryClient for CargoRegistryClient {
                 || self.authorized(self.http.get(&url).query(&query)),
                 "crates.io popular crates index",
                 RetryPolicy::default(),
+                &self.limits,
             )
             .await?;
 
@@ -223,12 +266,24 @@ impl RegistryClient for CargoRegistryClient {
         Ok(names.into_iter().take(limit).collect())
     }
 
+    async fn prefetch_advisories(
+        &self,
+        packages: &[(String, String)],
+        concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        self.advisories
+            .prefetch(packages, self.ecosystem(), concurrency)
+            .await
+    }
+
     async fn fetch_advisories(
         &self,
         package: &str,
         version: &str,
     ) -> Result<Vec<PackageAdvisory>, RegistryError> {
-        query_advisories(package, version, self.ecosystem()).await
+        self.advisories
+            .query(package, version, self.ecosystem())
+            .await
     }
 }
 
@@ -251,6 +306,7 @@ struct CrateSummary {
     max_stable_version: Option<String>,
     max_version: Option<String>,
     recent_downloads: Option<u64>,
+    repository: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -258,6 +314,7 @@ struct CrateVersion {
     num: String,
     created_at: String,
     yanked: bool,
+    license: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -284,9 +341,12 @@ mod tests {
     fn test_client_with_token(base_url: &str, auth_token: Option<&str>) -> CargoRegistryClient {
         CargoRegistryClient {
             http: build_http_client(),
+            limits: RequestLimits::default(),
             api_base_url: base_url.to_string(),
-            auth_token: auth_token.map(str::to_string),
+            auth: auth_token.map(|token| RegistryAuth::Bearer(token.to_string())),
             popular_names_cache: Arc::new(RwLock::new(None)),
+            local_popular_names: None,
+            advisories: AdvisoryPrefetch::default(),
         }
     }
 
@@ -307,6 +367,65 @@ mod tests {
         assert!(matches!(err, RegistryError::NotFound { .. }));
     }
 
+    #[tokio::test]
+    async fn with_options_base_url_is_used_for_fetch_package() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/crates/demo"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "crate": { "max_stable_version": "1.0.0", "max_version": "1.0.0" },
+                  "versions": [
+                    { "num": "1.0.0", "created_at": "2024-01-01T00:00:00Z", "yanked": false }
+                  ]
+                }"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let client = CargoRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(mock_server.uri()),
+            ..RegistryClientOptions::default()
+        });
+
+        let record = client
+            .fetch_package("demo")
+            .await
+            .expect("override base url should serve package");
+        assert_eq!(record.latest, "1.0.0");
+    }
+
+    #[tokio::test]
+    async fn with_options_limits_cap_response_size() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/crates/demo"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                format!(
+                    r#"{{"crate": {{"description": "{}"}}, "versions": []}}"#,
+                    "x".repeat(256)
+                ),
+                "application/json",
+            ))
+            .mount(&mock_server)
+            .await;
+        let client = CargoRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(mock_server.uri()),
+            limits: RequestLimits::new(1, 128),
+            ..RegistryClientOptions::default()
+        });
+
+        let err = client
+            .fetch_package("demo")
+            .await
+            .expect_err("body over the configured cap");
+        assert!(
+            matches!(err, RegistryError::InvalidResponse { .. }),
+            "{err:?}"
+        );
+    }
+
     #[tokio::test]
     async fn fetch_package_parses_latest_and_versions() {
         let mock_server = MockServer::start().await;
@@ -317,10 +436,11 @@ mod tests {
                   "crate": {
                     "max_stable_version": "1.2.3",
                     "max_version": "1.2.4",
-                    "recent_downloads": 1234
+                    "recent_downloads": 1234,
+                    "repository": "https://github.com/example/demo/"
                   },
                   "versions": [
-                    { "num": "1.2.3", "created_at": "2024-01-01T00:00:00Z", "yanked": false },
+                    { "num": "1.2.3", "created_at": "2024-01-01T00:00:00Z", "yanked": false, "license": "MIT OR Apache-2.0" },
                     { "num": "1.2.2", "created_at": "2023-12-01T00:00:00Z", "yanked": true }
                   ]
                 }"#,
@@ -335,6 +455,15 @@ mod tests {
         assert_eq!(record.versions.len(), 2);
         assert!(!record.versions["1.2.3"].deprecated);
         assert!(record.versions["1.2.2"].deprecated);
+        assert_eq!(
+            record.versions["1.2.3"].license.as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(record.versions["1.2.2"].license, None);
+        assert_eq!(
+            record.versions["1.2.2"].repository_url.as_deref(),
+            Some("https://github.com/example/demo")
+        );
     }
 
     #[tokio::test]
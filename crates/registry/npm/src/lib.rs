@@ -1,11 +1,12 @@
 mod lockfile;
+mod npmrc;
 mod registry;
 
 use std::sync::Arc;
 
 pub use lockfile::NpmLockfileParser;
 pub use registry::NpmRegistryClient;
-use safe_pkgs_core::{LockfileParser, RegistryClient, RegistryDefinition};
+use safe_pkgs_core::{LockfileParser, RegistryClient, RegistryClientOptions, RegistryDefinition};
 
 pub fn registry_definition() -> RegistryDefinition {
     RegistryDefinition {
@@ -16,8 +17,8 @@ pub fn registry_definition() -> RegistryDefinition {
     }
 }
 
-fn create_client() -> Arc<dyn RegistryClient> {
-    Arc::new(NpmRegistryClient::new())
+fn create_client(options: &RegistryClientOptions) -> Arc<dyn RegistryClient> {
+    Arc::new(NpmRegistryClient::with_options(options))
 }
 
 fn create_lockfile_parser() -> Arc<dyn LockfileParser> {
@@ -1,7 +1,10 @@
-use safe_pkgs_core::{DependencySpec, LockfileError, LockfileParser};
-use semver::Version;
+use safe_pkgs_core::{
+    DependencyScope, DependencySpec, LockfileError, LockfileParser, ManifestDrift,
+    UnsupportedSource, glob_matches,
+};
+use semver::{Version, VersionReq};
 use std::collections::{BTreeMap, BTreeSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Default)]
 pub struct NpmLockfileParser;
@@ -20,6 +23,20 @@ impl LockfileParser for NpmLockfileParser {
     fn parse_dependencies(&self, path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
         parse_npm_dependencies(path)
     }
+
+    fn manifest_drift(&self, path: &Path) -> Result<Vec<ManifestDrift>, LockfileError> {
+        if path.file_name().and_then(|name| name.to_str()) != Some("package-lock.json") {
+            return Ok(Vec::new());
+        }
+        let manifest_path = path.with_file_name("package.json");
+        if !manifest_path.is_file() {
+            return Ok(Vec::new());
+        }
+        Ok(lockfile_manifest_drift(
+            &read_json(path)?,
+            &read_json(&manifest_path)?,
+        ))
+    }
 }
 
 fn parse_npm_dependencies(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
@@ -39,17 +56,20 @@ fn parse_npm_dependencies(path: &Path) -> Result<Vec<DependencySpec>, LockfileEr
     }
 }
 
-fn parse_package_lock(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
+fn read_json(path: &Path) -> Result<serde_json::Value, LockfileError> {
     let raw = std::fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
         path: path.display().to_string(),
         source,
     })?;
-    let root: serde_json::Value =
-        serde_json::from_str(&raw).map_err(|error| LockfileError::ParseFile {
-            path: path.display().to_string(),
-            message: error.to_string(),
-        })?;
-    let mut dependencies = BTreeMap::<String, LockDependencyRecord>::new();
+    serde_json::from_str(&raw).map_err(|error| LockfileError::ParseFile {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    })
+}
+
+fn parse_package_lock(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
+    let root = read_json(path)?;
+    let mut dependencies = LockDependencies::new();
 
     if let Some(top_level) = root.get("dependencies").and_then(|value| value.as_object()) {
         for (raw_name, value) in top_level {
@@ -75,33 +95,95 @@ fn parse_package_lock(path: &Path) -> Result<Vec<DependencySpec>, LockfileError>
                 name,
                 raw_version.and_then(normalize_requested_version),
                 ancestry,
+                lock_entry_scope(value),
+                lock_entry_source(value, raw_version),
             );
         }
     }
 
-    Ok(dependencies
+    Ok(dependency_specs(dependencies))
+}
+
+fn parse_package_manifest(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
+    let root = read_json(path)?;
+    let mut dependencies = LockDependencies::new();
+    collect_manifest_dependencies(&root, &mut dependencies);
+
+    // Workspace members are local packages: merge their dependencies, but never audit the
+    // members themselves when siblings depend on each other.
+    let root_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut member_names = BTreeSet::new();
+    for member_dir in workspace_member_dirs(root_dir, &root) {
+        let member = read_json(&member_dir.join("package.json"))?;
+        if let Some(name) = member
+            .get("name")
+            .and_then(|value| value.as_str())
+            .and_then(normalize_npm_package_name)
+        {
+            member_names.insert(name);
+        }
+        collect_manifest_dependencies(&member, &mut dependencies);
+    }
+    dependencies.retain(|(name, _), _| !member_names.contains(name));
+
+    Ok(dependency_specs(dependencies))
+}
+
+/// Converts collected records into specs, one per distinct `(name, version)`.
+///
+/// A versionless record is dropped when the same name also has a concrete version; its
+/// ancestry and scope are folded into each versioned record instead.
+fn dependency_specs(mut dependencies: LockDependencies) -> Vec<DependencySpec> {
+    let versioned = dependencies
+        .keys()
+        .filter(|(_, version)| version.is_some())
+        .map(|(name, _)| name.clone())
+        .collect::<BTreeSet<_>>();
+    for name in versioned {
+        // A git, path, or URL spec is its own dependency, not a range of the registry one.
+        let key = (name.clone(), None);
+        if dependencies
+            .get(&key)
+            .is_none_or(|record| record.unsupported_source.is_some())
+        {
+            continue;
+        }
+        let Some(unversioned) = dependencies.remove(&key) else {
+            continue;
+        };
+        for ((_, _), record) in dependencies
+            .range_mut((name.clone(), None)..)
+            .take_while(|((candidate, _), _)| *candidate == name)
+        {
+            record
+                .dependency_paths
+                .extend(unversioned.dependency_paths.iter().cloned());
+            if let Some(scope) = unversioned.scope {
+                record.scope = Some(record.scope.map_or(scope, |existing| existing.merge(scope)));
+            }
+        }
+    }
+    dependencies
         .into_iter()
-        .map(|(name, record)| DependencySpec {
+        .map(|((name, version), record)| DependencySpec {
             name,
-            version: record.version,
+            version,
             dependency_paths: record.dependency_paths.into_iter().collect(),
+            scope: record.scope.unwrap_or_default(),
+            unsupported_source: record.unsupported_source,
+            hashes: Vec::new(),
         })
-        .collect())
+        .collect()
 }
 
-fn parse_package_manifest(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
-    let raw = std::fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
-        path: path.display().to_string(),
-        source,
-    })?;
-    let root: serde_json::Value =
-        serde_json::from_str(&raw).map_err(|error| LockfileError::ParseFile {
-            path: path.display().to_string(),
-            message: error.to_string(),
-        })?;
-    let mut dependencies = BTreeMap::<String, LockDependencyRecord>::new();
-
-    for section in ["dependencies", "devDependencies", "optionalDependencies"] {
+/// Adds a manifest's dependency sections to `dependencies`, one record per name and version.
+fn collect_manifest_dependencies(root: &serde_json::Value, dependencies: &mut LockDependencies) {
+    for (section, scope) in [
+        ("dependencies", DependencyScope::Prod),
+        ("devDependencies", DependencyScope::Dev),
+        ("optionalDependencies", DependencyScope::Optional),
+        ("peerDependencies", DependencyScope::Peer),
+    ] {
         let Some(items) = root.get(section).and_then(|value| value.as_object()) else {
             continue;
         };
@@ -109,23 +191,245 @@ fn parse_package_manifest(path: &Path) -> Result<Vec<DependencySpec>, LockfileEr
             let Some(name) = normalize_npm_package_name(raw_name) else {
                 continue;
             };
+            let raw_version = raw_version.as_str();
             upsert_dependency(
-                &mut dependencies,
+                dependencies,
                 name.clone(),
-                raw_version.as_str().and_then(normalize_requested_version),
+                raw_version.and_then(normalize_requested_version),
                 Vec::new(),
+                scope,
+                raw_version.and_then(spec_source),
             );
         }
     }
+}
 
-    Ok(dependencies
-        .into_iter()
-        .map(|(name, record)| DependencySpec {
-            dependency_paths: record.dependency_paths.into_iter().collect(),
-            name,
-            version: record.version,
+/// Root-manifest dependencies whose top-level lockfile version is outside the declared
+/// range. Ranges that are not plain semver (dist-tags, aliases, URLs, hyphen ranges) and
+/// dependencies missing from the lockfile are skipped.
+fn lockfile_manifest_drift(
+    lock: &serde_json::Value,
+    manifest: &serde_json::Value,
+) -> Vec<ManifestDrift> {
+    let mut drift = BTreeMap::<String, ManifestDrift>::new();
+    for section in [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "peerDependencies",
+    ] {
+        let Some(items) = manifest.get(section).and_then(|value| value.as_object()) else {
+            continue;
+        };
+        for (raw_name, declared) in items {
+            let (Some(name), Some(declared)) =
+                (normalize_npm_package_name(raw_name), declared.as_str())
+            else {
+                continue;
+            };
+            let Some(locked) = top_level_locked_version(lock, raw_name) else {
+                continue;
+            };
+            let Ok(version) = Version::parse(locked) else {
+                continue;
+            };
+            if satisfies_npm_range(declared, &version) == Some(false) {
+                drift.insert(
+                    name.clone(),
+                    ManifestDrift {
+                        name,
+                        locked: locked.to_string(),
+                        declared: declared.to_string(),
+                    },
+                );
+            }
+        }
+    }
+    drift.into_values().collect()
+}
+
+/// Version installed at the project root: `packages["node_modules/<name>"]` in lockfile
+/// v2/v3, or the top-level `dependencies` entry in v1.
+fn top_level_locked_version<'a>(lock: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+    lock.get("packages")
+        .and_then(|packages| packages.get(format!("node_modules/{name}")))
+        .or_else(|| {
+            lock.get("dependencies")
+                .and_then(|dependencies| dependencies.get(name))
         })
-        .collect())
+        .and_then(|entry| entry.get("version"))
+        .and_then(|version| version.as_str())
+}
+
+/// Whether `version` satisfies an npm range, or `None` when the range cannot be read as
+/// semver. npm separates comparators with spaces and alternatives with `||`.
+fn satisfies_npm_range(range: &str, version: &Version) -> Option<bool> {
+    if range.contains(" - ") {
+        return None;
+    }
+    let mut satisfied = false;
+    for alternative in range.split("||") {
+        let comparators = alternative
+            .split_whitespace()
+            .map(npm_comparator)
+            .collect::<Vec<_>>()
+            .join(", ");
+        satisfied |= VersionReq::parse(&comparators).ok()?.matches(version);
+    }
+    Some(satisfied)
+}
+
+/// Rewrites an operator-less npm comparator into its Cargo `VersionReq` equivalent: a full
+/// version is an exact pin (`=`) and a partial one (`1`, `1.2`) is an X-range (`~`), where
+/// Cargo would otherwise read both as caret requirements.
+fn npm_comparator(comparator: &str) -> String {
+    let bare = comparator.starts_with(|c: char| c.is_ascii_digit());
+    if !bare || comparator.contains(['x', 'X', '*']) {
+        return comparator.to_string();
+    }
+    let release = comparator.split(['-', '+']).next().unwrap_or(comparator);
+    if release.split('.').count() >= 3 {
+        format!("={comparator}")
+    } else {
+        format!("~{comparator}")
+    }
+}
+
+/// Resolves a manifest's `workspaces` globs (array or `{ "packages": [...] }`) to member
+/// directories containing a `package.json`.
+///
+/// Patterns support `*`/`?` within a path segment and `**` for any depth; `!`-prefixed
+/// patterns exclude members. `node_modules`, hidden directories, and symlinks are never searched.
+fn workspace_member_dirs(root_dir: &Path, manifest: &serde_json::Value) -> Vec<PathBuf> {
+    let patterns = match manifest.get("workspaces") {
+        Some(serde_json::Value::Array(items)) => items.as_slice(),
+        Some(serde_json::Value::Object(object)) => object
+            .get("packages")
+            .and_then(|value| value.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+        _ => &[],
+    };
+    let mut included = BTreeSet::new();
+    let mut excluded = BTreeSet::new();
+    for pattern in patterns.iter().filter_map(|value| value.as_str()) {
+        let (target, pattern) = match pattern.strip_prefix('!') {
+            Some(negated) => (&mut excluded, negated),
+            None => (&mut included, pattern),
+        };
+        let segments = pattern
+            .split('/')
+            .filter(|segment| !segment.is_empty() && *segment != ".")
+            .collect::<Vec<_>>();
+        // Parent traversal would escape the project root.
+        if segments.contains(&"..") {
+            continue;
+        }
+        expand_workspace_pattern(root_dir, &segments, target);
+    }
+    included
+        .into_iter()
+        .filter(|dir| !excluded.contains(dir) && dir.join("package.json").is_file())
+        .collect()
+}
+
+fn expand_workspace_pattern(dir: &Path, segments: &[&str], matches: &mut BTreeSet<PathBuf>) {
+    let Some((segment, rest)) = segments.split_first() else {
+        matches.insert(dir.to_path_buf());
+        return;
+    };
+    if !segment.contains(['*', '?']) {
+        let next = dir.join(segment);
+        if next.is_dir() {
+            expand_workspace_pattern(&next, rest, matches);
+        }
+        return;
+    }
+
+    if *segment == "**" {
+        expand_workspace_pattern(dir, rest, matches);
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let Some(name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+            continue;
+        };
+        // `file_type` does not follow symlinks; skipping them keeps `**` from looping.
+        let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+        if !is_dir || name == "node_modules" || name.starts_with('.') {
+            continue;
+        }
+        if *segment == "**" {
+            expand_workspace_pattern(&entry_path, segments, matches);
+        } else if glob_matches(segment, &name) {
+            expand_workspace_pattern(&entry_path, rest, matches);
+        }
+    }
+}
+
+/// Maps package-lock `dev`/`devOptional`/`optional`/`peer` flags to a dependency scope.
+/// Non-registry origin of a lockfile entry: a workspace or `file:` link, or a git checkout.
+///
+/// `resolved` is only trusted for git and local sources, since registry tarballs resolve to
+/// URLs too; a URL dependency is recognized from its lockfile v1 `version` spec instead.
+fn lock_entry_source(
+    value: &serde_json::Value,
+    raw_version: Option<&str>,
+) -> Option<UnsupportedSource> {
+    if value.get("link").and_then(serde_json::Value::as_bool) == Some(true) {
+        return Some(UnsupportedSource::Path);
+    }
+    let resolved = value
+        .get("resolved")
+        .and_then(serde_json::Value::as_str)
+        .and_then(spec_source)
+        .filter(|source| *source != UnsupportedSource::Url);
+    resolved.or_else(|| raw_version.and_then(spec_source))
+}
+
+/// Non-registry origin of an npm dependency spec such as `git+https://...`, `github:o/r`,
+/// `o/r`, `file:../lib`, or `https://host/pkg.tgz`; `None` for versions, ranges, tags, and
+/// `npm:` aliases.
+fn spec_source(spec: &str) -> Option<UnsupportedSource> {
+    const GIT_PREFIXES: [&str; 6] = ["git+", "git:", "github:", "gitlab:", "bitbucket:", "gist:"];
+    let spec = spec.trim();
+    let lower = spec.to_ascii_lowercase();
+    if ["file:", "link:", "./", "../", "/", "~/"]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+    {
+        Some(UnsupportedSource::Path)
+    } else if GIT_PREFIXES.iter().any(|prefix| lower.starts_with(prefix)) {
+        Some(UnsupportedSource::Git)
+    } else if lower.starts_with("http://") || lower.starts_with("https://") {
+        Some(UnsupportedSource::Url)
+    } else if !lower.starts_with("npm:") && !spec.contains(' ') && spec.contains('/') {
+        // GitHub `owner/repo` shorthand; ranges never contain a slash.
+        Some(UnsupportedSource::Git)
+    } else {
+        None
+    }
+}
+
+fn lock_entry_scope(value: &serde_json::Value) -> DependencyScope {
+    let flag = |key: &str| {
+        value
+            .get(key)
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    };
+    if flag("dev") || flag("devOptional") {
+        DependencyScope::Dev
+    } else if flag("optional") {
+        DependencyScope::Optional
+    } else if flag("peer") {
+        DependencyScope::Peer
+    } else {
+        DependencyScope::Prod
+    }
 }
 
 /// Recursively walks npm `dependencies` tree entries and collects ancestry.
@@ -136,7 +440,7 @@ fn collect_dependency_tree(
     raw_name: &str,
     value: &serde_json::Value,
     parent_path: &[String],
-    dependencies: &mut BTreeMap<String, LockDependencyRecord>,
+    dependencies: &mut LockDependencies,
 ) {
     let Some(name) = normalize_npm_package_name(raw_name) else {
         return;
@@ -153,6 +457,8 @@ fn collect_dependency_tree(
         name.clone(),
         raw_version.and_then(normalize_requested_version),
         ancestry.clone(),
+        lock_entry_scope(value),
+        lock_entry_source(value, raw_version),
     );
 
     let mut child_path = ancestry;
@@ -171,20 +477,22 @@ fn collect_dependency_tree(
     }
 }
 
-/// Inserts or updates a dependency record and accumulates unique ancestry paths.
+/// Inserts or updates the record for `(name, version)` and accumulates unique ancestry paths.
 ///
-/// When updating, a non-`None` version is preferred over an existing `None`
-/// version. Non-empty paths are deduplicated via the path set.
+/// Distinct versions of the same package stay separate records, so a nested or
+/// workspace-specific version is never hidden behind the first one seen. Scopes merge
+/// toward the most production-relevant one; non-empty paths are deduplicated via the set.
 fn upsert_dependency(
-    dependencies: &mut BTreeMap<String, LockDependencyRecord>,
+    dependencies: &mut LockDependencies,
     name: String,
     version: Option<String>,
     path: Vec<String>,
+    scope: DependencyScope,
+    source: Option<UnsupportedSource>,
 ) {
-    let record = dependencies.entry(name).or_default();
-    if record.version.is_none() && version.is_some() {
-        record.version = version;
-    }
+    let record = dependencies.entry((name, version)).or_default();
+    record.scope = Some(record.scope.map_or(scope, |existing| existing.merge(scope)));
+    record.unsupported_source = record.unsupported_source.or(source);
 
     if !path.is_empty() {
         record.dependency_paths.insert(path);
@@ -293,10 +601,14 @@ fn normalize_requested_version(raw: &str) -> Option<String> {
     None
 }
 
+/// Collected dependency records keyed by `(name, version)`.
+type LockDependencies = BTreeMap<(String, Option<String>), LockDependencyRecord>;
+
 #[derive(Debug, Clone, Default)]
 struct LockDependencyRecord {
-    version: Option<String>,
     dependency_paths: BTreeSet<Vec<String>>,
+    scope: Option<DependencyScope>,
+    unsupported_source: Option<UnsupportedSource>,
 }
 
 #[cfg(test)]
@@ -333,20 +645,201 @@ mod tests {
         let temp = dir.join("package.json");
         std::fs::write(
             &temp,
-            r#"{"dependencies":{"a":"1.2.3"},"devDependencies":{"b":"^2.0.0"}}"#,
+            r#"{"dependencies":{"a":"1.2.3"},"devDependencies":{"b":"^2.0.0"},"optionalDependencies":{"c":"1.0.0"},"peerDependencies":{"d":"1.0.0"}}"#,
         )
         .expect("write temp file");
 
         let deps = parse_package_manifest(&temp).expect("parse package manifest");
-        assert_eq!(deps.len(), 2);
+        assert_eq!(deps.len(), 4);
         assert_eq!(find_version(&deps, "a"), Some("1.2.3"));
         assert_eq!(find_version(&deps, "b"), None);
         assert_eq!(find_paths(&deps, "a"), Some(vec![]));
 
+        let scope_of = |name: &str| {
+            deps.iter()
+                .find(|spec| spec.name == name)
+                .map(|spec| spec.scope)
+        };
+        assert_eq!(scope_of("a"), Some(DependencyScope::Prod));
+        assert_eq!(scope_of("b"), Some(DependencyScope::Dev));
+        assert_eq!(scope_of("c"), Some(DependencyScope::Optional));
+        assert_eq!(scope_of("d"), Some(DependencyScope::Peer));
+
         let _ = std::fs::remove_file(temp);
         let _ = std::fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn non_registry_sources_are_marked_unsupported() {
+        let dir = unique_temp_dir("sources");
+        let manifest = dir.join("package.json");
+        std::fs::write(
+            &manifest,
+            r#"{"dependencies":{"a":"1.2.3","b":"git+https://example.com/b.git","c":"file:../c","d":"https://example.com/d.tgz","e":"owner/e","f":"npm:real-f@1.0.0"}}"#,
+        )
+        .expect("write manifest");
+        let deps = parse_package_manifest(&manifest).expect("parse manifest");
+        let source_of = |name: &str| {
+            deps.iter()
+                .find(|spec| spec.name == name)
+                .map(|spec| spec.unsupported_source)
+        };
+        assert_eq!(source_of("a"), Some(None));
+        assert_eq!(source_of("b"), Some(Some(UnsupportedSource::Git)));
+        assert_eq!(source_of("c"), Some(Some(UnsupportedSource::Path)));
+        assert_eq!(source_of("d"), Some(Some(UnsupportedSource::Url)));
+        assert_eq!(source_of("e"), Some(Some(UnsupportedSource::Git)));
+        assert_eq!(source_of("f"), Some(None));
+
+        let lock = dir.join("package-lock.json");
+        std::fs::write(
+            &lock,
+            r#"{"lockfileVersion":3,"packages":{"":{},"node_modules/a":{"version":"1.2.3","resolved":"https://registry.npmjs.org/a/-/a-1.2.3.tgz"},"node_modules/b":{"version":"1.0.0","resolved":"git+ssh://git@example.com/b.git#abc"},"node_modules/member":{"resolved":"packages/member","link":true}}}"#,
+        )
+        .expect("write lockfile");
+        let deps = parse_package_lock(&lock).expect("parse lockfile");
+        let source_of = |name: &str| {
+            deps.iter()
+                .find(|spec| spec.name == name)
+                .map(|spec| spec.unsupported_source)
+        };
+        assert_eq!(source_of("a"), Some(None));
+        assert_eq!(source_of("b"), Some(Some(UnsupportedSource::Git)));
+        assert_eq!(source_of("member"), Some(Some(UnsupportedSource::Path)));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn package_manifest_merges_workspace_member_dependencies() {
+        let dir = unique_temp_dir("workspaces");
+        let root = dir.join("package.json");
+        std::fs::write(
+            &root,
+            r#"{"name":"monorepo","workspaces":["packages/*","!packages/ignored"],"devDependencies":{"typescript":"5.4.5"}}"#,
+        )
+        .expect("write root manifest");
+        for (member, manifest) in [
+            (
+                "api",
+                r#"{"name":"@acme/api","dependencies":{"express":"4.19.2","@acme/shared":"*"}}"#,
+            ),
+            (
+                "web",
+                r#"{"name":"@acme/web","dependencies":{"react":"18.3.1"},"devDependencies":{"typescript":"5.4.5"}}"#,
+            ),
+            (
+                "shared",
+                r#"{"name":"@acme/shared","dependencies":{"lodash":"4.17.21"}}"#,
+            ),
+            ("ignored", r#"{"dependencies":{"left-pad":"1.3.0"}}"#),
+        ] {
+            let member_dir = dir.join("packages").join(member);
+            std::fs::create_dir_all(&member_dir).expect("create member dir");
+            std::fs::write(member_dir.join("package.json"), manifest).expect("write member");
+        }
+        // Directories without a package.json are not members.
+        std::fs::create_dir_all(dir.join("packages").join("docs")).expect("create docs dir");
+
+        let deps = parse_package_manifest(&root).expect("parse workspace manifest");
+        let names = deps
+            .iter()
+            .map(|spec| spec.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["express", "lodash", "react", "typescript"]);
+        assert_eq!(find_version(&deps, "express"), Some("4.19.2"));
+        assert_eq!(find_version(&deps, "react"), Some("18.3.1"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn workspace_patterns_support_recursive_globs_and_object_form() {
+        let dir = unique_temp_dir("workspace-globs");
+        let nested = dir.join("apps").join("group").join("site");
+        std::fs::create_dir_all(&nested).expect("create nested member");
+        std::fs::write(nested.join("package.json"), r#"{"name":"site"}"#).expect("write member");
+        let hidden = dir.join("apps").join("node_modules").join("dep");
+        std::fs::create_dir_all(&hidden).expect("create node_modules member");
+        std::fs::write(hidden.join("package.json"), r#"{"name":"dep"}"#).expect("write dep");
+
+        let manifest = serde_json::json!({ "workspaces": { "packages": ["apps/**"] } });
+        let members = workspace_member_dirs(&dir, &manifest);
+        assert_eq!(members, vec![nested]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn recursive_workspace_globs_skip_symlinked_directories() {
+        let dir = unique_temp_dir("workspace-symlinks");
+        let member = dir.join("apps").join("site");
+        std::fs::create_dir_all(&member).expect("create member");
+        std::fs::write(member.join("package.json"), r#"{"name":"site"}"#).expect("write member");
+        // A link back to an ancestor would make `**` recurse forever if followed.
+        std::os::unix::fs::symlink(&dir, member.join("loop")).expect("create loop symlink");
+
+        let manifest = serde_json::json!({ "workspaces": ["apps/**"] });
+        let members = workspace_member_dirs(&dir, &manifest);
+        assert_eq!(members, vec![member]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn differing_versions_of_one_package_are_all_reported() {
+        let dir = unique_temp_dir("workspace-versions");
+        let root = dir.join("package.json");
+        std::fs::write(
+            &root,
+            r#"{"name":"monorepo","workspaces":["packages/*"],"dependencies":{"lodash":"4.17.21"}}"#,
+        )
+        .expect("write root manifest");
+        let member_dir = dir.join("packages").join("legacy");
+        std::fs::create_dir_all(&member_dir).expect("create member dir");
+        std::fs::write(
+            member_dir.join("package.json"),
+            r#"{"name":"legacy","dependencies":{"lodash":"4.17.4","react":"^18.0.0"}}"#,
+        )
+        .expect("write member");
+
+        let deps = parse_package_manifest(&root).expect("parse workspace manifest");
+        let lodash = deps
+            .iter()
+            .filter(|spec| spec.name == "lodash")
+            .map(|spec| spec.version.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(lodash, vec![Some("4.17.21"), Some("4.17.4")]);
+        assert_eq!(find_version(&deps, "react"), None);
+
+        let lock = dir.join("package-lock.json");
+        std::fs::write(
+            &lock,
+            r#"{"lockfileVersion":3,"packages":{
+                "node_modules/ms":{"version":"2.1.3"},
+                "node_modules/debug":{"version":"2.6.9"},
+                "node_modules/debug/node_modules/ms":{"version":"2.0.0"}
+            }}"#,
+        )
+        .expect("write lockfile");
+        let deps = parse_package_lock(&lock).expect("parse lockfile");
+        let ms = deps
+            .iter()
+            .filter(|spec| spec.name == "ms")
+            .map(|spec| (spec.version.as_deref(), spec.dependency_paths.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ms,
+            vec![
+                (Some("2.0.0"), vec![vec!["debug".to_string()]]),
+                (Some("2.1.3"), Vec::new()),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn parse_dependencies_dispatches_by_filename() {
         let dir = unique_temp_dir("dispatch");
@@ -417,7 +910,7 @@ mod tests {
               "packages": {
                 "": { "name": "demo" },
                 "node_modules/react": { "version": "18.2.0" },
-                "node_modules/@types/node": { "version": "=20.11.0" },
+                "node_modules/@types/node": { "version": "=20.11.0", "dev": true },
                 "node_modules/react/node_modules/loose-envify": { "version": "1.4.0" },
                 "node_modules/invalid": { "version": "^1.0.0" }
               }
@@ -431,6 +924,13 @@ mod tests {
         assert_eq!(find_version(&deps, "@types/node"), Some("20.11.0"));
         assert_eq!(find_version(&deps, "invalid"), None);
         assert_eq!(find_paths(&deps, "react"), Some(vec![]));
+        let scope_of = |name: &str| {
+            deps.iter()
+                .find(|spec| spec.name == name)
+                .map(|spec| spec.scope)
+        };
+        assert_eq!(scope_of("react"), Some(DependencyScope::Prod));
+        assert_eq!(scope_of("@types/node"), Some(DependencyScope::Dev));
         assert_eq!(
             find_paths(&deps, "loose-envify"),
             Some(vec![vec!["react".to_string()]])
@@ -489,6 +989,115 @@ mod tests {
         let _ = std::fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn manifest_drift_flags_lock_versions_outside_declared_ranges() {
+        let dir = unique_temp_dir("drift");
+        let lock_path = dir.join("package-lock.json");
+        std::fs::write(
+            &lock_path,
+            r#"{"lockfileVersion":3,"packages":{
+                "":{"name":"app"},
+                "node_modules/left-pad":{"version":"2.0.0"},
+                "node_modules/lodash":{"version":"4.17.21"},
+                "node_modules/react":{"version":"18.3.1"},
+                "node_modules/typescript":{"version":"5.4.5","dev":true},
+                "node_modules/alias":{"version":"1.0.0"}
+            }}"#,
+        )
+        .expect("write lock");
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"dependencies":{"left-pad":"^1.0.0","lodash":"^4.17.0","react":">=17 <18 || ^18.2","alias":"npm:other@1"},"devDependencies":{"typescript":"~5.3.0"},"peerDependencies":{"missing":"^1.0.0"}}"#,
+        )
+        .expect("write manifest");
+
+        let drift = NpmLockfileParser::new()
+            .manifest_drift(&lock_path)
+            .expect("drift");
+        assert_eq!(
+            drift,
+            vec![
+                ManifestDrift {
+                    name: "left-pad".to_string(),
+                    locked: "2.0.0".to_string(),
+                    declared: "^1.0.0".to_string(),
+                },
+                ManifestDrift {
+                    name: "typescript".to_string(),
+                    locked: "5.4.5".to_string(),
+                    declared: "~5.3.0".to_string(),
+                },
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn manifest_drift_treats_bare_versions_as_exact_pins() {
+        let dir = unique_temp_dir("drift-exact");
+        let lock_path = dir.join("package-lock.json");
+        std::fs::write(
+            &lock_path,
+            r#"{"lockfileVersion":3,"packages":{
+                "":{"name":"app"},
+                "node_modules/lodash":{"version":"4.17.21"},
+                "node_modules/react":{"version":"18.3.1"},
+                "node_modules/zod":{"version":"3.2.9"}
+            }}"#,
+        )
+        .expect("write lock");
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"dependencies":{"lodash":"4.17.0","react":"18.3.1","zod":"3.2"}}"#,
+        )
+        .expect("write manifest");
+
+        let drift = NpmLockfileParser::new()
+            .manifest_drift(&lock_path)
+            .expect("drift");
+        assert_eq!(
+            drift,
+            vec![ManifestDrift {
+                name: "lodash".to_string(),
+                locked: "4.17.21".to_string(),
+                declared: "4.17.0".to_string(),
+            }]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn manifest_drift_reads_v1_lockfiles_and_skips_missing_manifests() {
+        let dir = unique_temp_dir("drift-v1");
+        let lock_path = dir.join("package-lock.json");
+        std::fs::write(
+            &lock_path,
+            r#"{"dependencies":{"left-pad":{"version":"2.0.0"}}}"#,
+        )
+        .expect("write lock");
+        let parser = NpmLockfileParser::new();
+        assert!(parser.manifest_drift(&lock_path).expect("drift").is_empty());
+
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"dependencies":{"left-pad":"^1.0.0"}}"#,
+        )
+        .expect("write manifest");
+        let drift = parser.manifest_drift(&lock_path).expect("drift");
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].locked, "2.0.0");
+        assert!(
+            parser
+                .manifest_drift(&dir.join("package.json"))
+                .expect("manifest input")
+                .is_empty()
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn extract_dependency_path_from_node_modules_path_handles_nested_scopes() {
         assert_eq!(
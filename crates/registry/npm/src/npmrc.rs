@@ -0,0 +1,162 @@
+//! Registry routing and credentials read from an `.npmrc` file.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Registry settings from an `.npmrc`: the default registry, per-scope registries, and
+/// `_authToken` credentials keyed by registry URL without its scheme (`//host/path/`).
+///
+/// Other keys are ignored. `${NPM_*}` references expand from the environment, as npm does;
+/// other variables are left unexpanded so an `.npmrc` cannot send arbitrary environment
+/// secrets to the registry it names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Npmrc {
+    /// Default registry from `registry=`.
+    pub registry: Option<String>,
+    /// Registries from `@scope:registry=`, keyed by `@scope`.
+    pub scoped_registries: BTreeMap<String, String>,
+    auth_tokens: BTreeMap<String, String>,
+}
+
+impl Npmrc {
+    /// Reads and parses `path`; an unreadable file yields empty settings.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .map(|raw| Self::parse(&raw))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(raw: &str) -> Self {
+        let mut npmrc = Self::default();
+        for line in raw.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            let value = expand_env(value.trim().trim_matches('"'));
+            if value.is_empty() {
+                continue;
+            }
+
+            if key == "registry" {
+                npmrc.registry = Some(value);
+            } else if let Some(scope) = key
+                .strip_suffix(":registry")
+                .filter(|scope| scope.starts_with('@'))
+            {
+                npmrc
+                    .scoped_registries
+                    .insert(scope.to_ascii_lowercase(), value);
+            } else if let Some(prefix) = key
+                .strip_suffix(":_authToken")
+                .filter(|prefix| prefix.starts_with("//"))
+            {
+                npmrc.auth_tokens.insert(with_trailing_slash(prefix), value);
+            }
+        }
+        npmrc
+    }
+
+    /// Token whose `//host/path/` key is the longest prefix of `registry_url`.
+    pub fn auth_token_for(&self, registry_url: &str) -> Option<&str> {
+        let unschemed = registry_url
+            .split_once("://")
+            .map_or(registry_url, |(_, rest)| rest);
+        let target = with_trailing_slash(&format!("//{unschemed}"));
+        self.auth_tokens
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, token)| token.as_str())
+    }
+}
+
+fn with_trailing_slash(value: &str) -> String {
+    if value.ends_with('/') {
+        value.to_string()
+    } else {
+        format!("{value}/")
+    }
+}
+
+/// Prefix a variable needs before an `.npmrc` may expand it.
+const EXPANDABLE_ENV_PREFIX: &str = "NPM_";
+
+/// Replaces `${NPM_*}` with the variable's value, or nothing when it is unset. References to
+/// other variables are kept verbatim.
+fn expand_env(value: &str) -> String {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        let name = &rest[start + 2..start + end];
+        if name.starts_with(EXPANDABLE_ENV_PREFIX) {
+            expanded.push_str(&std::env::var(name).unwrap_or_default());
+        } else {
+            expanded.push_str(&rest[start..=start + end]);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_registries_and_tokens() {
+        let npmrc = Npmrc::parse(
+            r#"
+# company registries
+registry=https://registry.npmjs.org/
+@Acme:registry=https://nexus.example/repository/npm-private/
+; tokens
+//nexus.example/repository/:_authToken=outer
+//nexus.example/repository/npm-private/:_authToken="inner"
+always-auth=true
+"#,
+        );
+
+        assert_eq!(
+            npmrc.registry.as_deref(),
+            Some("https://registry.npmjs.org/")
+        );
+        assert_eq!(
+            npmrc.scoped_registries.get("@acme").map(String::as_str),
+            Some("https://nexus.example/repository/npm-private/")
+        );
+        assert_eq!(npmrc.scoped_registries.len(), 1);
+        assert_eq!(
+            npmrc.auth_token_for("https://nexus.example/repository/npm-private"),
+            Some("inner")
+        );
+        assert_eq!(
+            npmrc.auth_token_for("https://nexus.example/repository/npm-public/"),
+            Some("outer")
+        );
+        assert_eq!(npmrc.auth_token_for("https://registry.npmjs.org/"), None);
+    }
+
+    #[test]
+    fn expand_env_only_substitutes_npm_variables() {
+        assert_eq!(expand_env("${PATH}"), "${PATH}");
+        assert_eq!(
+            expand_env("a${NPM_SAFE_PKGS_TEST_UNSET_VAR}b"),
+            "ab".to_string()
+        );
+        assert_eq!(
+            expand_env("${HOME}:${NPM_SAFE_PKGS_TEST_UNSET_VAR}"),
+            "${HOME}:".to_string()
+        );
+        assert_eq!(expand_env("${unterminated"), "${unterminated");
+    }
+}
@@ -8,14 +8,17 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use safe_pkgs_core::{
-    PackageAdvisory, PackageRecord, PackageVersion, RegistryClient, RegistryEcosystem,
-    RegistryError,
+    LocalPopularNames, PackageAdvisory, PackageRecord, PackageVersion, RegistryClient,
+    RegistryClientOptions, RegistryEcosystem, RegistryError, RequestLimits,
+    normalize_repository_url,
 };
-use safe_pkgs_osv::query_advisories;
+use safe_pkgs_osv::AdvisoryPrefetch;
 use safe_pkgs_registry_http::{
-    RetryPolicy, build_http_client, map_status_error, parse_json, send_with_retry,
+    RegistryAuth, RetryPolicy, build_http_client, map_status_error, parse_json, send_with_retry,
 };
 
+use crate::npmrc::Npmrc;
+
 const NPMS_POPULAR_QUERY: &str = "not:deprecated";
 const NPMS_PAGE_SIZE: usize = 250;
 const NPM_BULK_DOWNLOAD_MAX_PACKAGES: usize = 128;
@@ -23,76 +26,238 @@ const NPM_BULK_DOWNLOAD_MAX_PACKAGES: usize = 128;
 /// Chosen to match the typosquat check's sample size so subsequent per-package
 /// calls always hit the in-process cache.
 const POPULAR_PACKAGE_PREFETCH_SIZE: usize = 5000;
+/// Media type of npm's abbreviated ("corgi") packument. It omits publish times, maintainers,
+/// licenses, repository links, and script bodies, so it is only requested when nothing reads them.
+const ABBREVIATED_METADATA_ACCEPT: &str = "application/vnd.npm.install-v1+json";
+const FULL_METADATA_ACCEPT: &str = "application/json";
+
+/// Registry serving one package scope, configured by `@scope:registry` in an `.npmrc`.
+#[derive(Clone)]
+struct ScopedRegistry {
+    base_url: String,
+    auth: Option<RegistryAuth>,
+}
 
 #[derive(Clone)]
 pub struct NpmRegistryClient {
     http: reqwest::Client,
+    limits: RequestLimits,
     base_url: String,
     downloads_api_base_url: String,
     popular_index_api_base_url: String,
-    auth_token: Option<String>,
+    /// Static JSON list of popular names; replaces npms.io search pagination when set.
+    popular_index_url: Option<String>,
+    auth: Option<RegistryAuth>,
+    /// Per-scope registries keyed by lowercase `@scope`; other packages use `base_url`.
+    scoped_registries: Arc<HashMap<String, ScopedRegistry>>,
     popular_names_cache: Arc<RwLock<Option<Vec<String>>>>,
+    local_popular_names: Option<Arc<LocalPopularNames>>,
+    advisories: AdvisoryPrefetch,
     prefetched_downloads: Arc<RwLock<HashMap<String, Option<u64>>>>,
-}
-
-/// Reads a registry token env var, treating empty/whitespace values as `None`.
-fn token_from_env(var: &str) -> Option<String> {
-    env::var(var)
-        .ok()
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
+    /// Requests the abbreviated packument instead of the full document.
+    abbreviated_metadata: bool,
 }
 
 impl NpmRegistryClient {
     pub fn new() -> Self {
         Self {
             http: build_http_client(),
+            limits: RequestLimits::default(),
             base_url: env::var("SAFE_PKGS_NPM_REGISTRY_API_BASE_URL")
                 .unwrap_or_else(|_| "https://registry.npmjs.org".to_string()),
             downloads_api_base_url: env::var("SAFE_PKGS_NPM_DOWNLOADS_API_BASE_URL")
                 .unwrap_or_else(|_| "https://api.npmjs.org".to_string()),
             popular_index_api_base_url: env::var("SAFE_PKGS_NPM_POPULAR_INDEX_API_BASE_URL")
                 .unwrap_or_else(|_| "https://api.npms.io".to_string()),
-            auth_token: token_from_env("SAFE_PKGS_NPM_REGISTRY_TOKEN"),
+            popular_index_url: env::var("SAFE_PKGS_NPM_POPULAR_INDEX_URL").ok(),
+            auth: RegistryAuth::from_env(
+                "SAFE_PKGS_NPM_REGISTRY_TOKEN",
+                "SAFE_PKGS_NPM_REGISTRY_USERNAME",
+                "SAFE_PKGS_NPM_REGISTRY_PASSWORD",
+            ),
+            scoped_registries: Arc::new(HashMap::new()),
             popular_names_cache: Arc::new(RwLock::new(None)),
+            local_popular_names: None,
+            advisories: AdvisoryPrefetch::default(),
             prefetched_downloads: Arc::new(RwLock::new(HashMap::new())),
+            abbreviated_metadata: false,
         }
     }
 
-    /// Adds a bearer token to the request when a private-registry token is configured.
-    fn authorized(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-        match &self.auth_token {
-            Some(token) => builder.bearer_auth(token),
-            None => builder,
+    /// Creates a client with startup overrides applied on top of env/default settings.
+    pub fn with_options(options: &RegistryClientOptions) -> Self {
+        let mut client = Self::new();
+        client.limits = options.limits.clone();
+        client.advisories = AdvisoryPrefetch::new(options.limits.clone());
+        client.abbreviated_metadata = options.abbreviated_metadata;
+        if let Some(base_url) = &options.base_url {
+            client.base_url = base_url.clone();
+        }
+        if let Some(path) = &options.popular_names_file {
+            client.local_popular_names = Some(Arc::new(LocalPopularNames::new(path)));
         }
+        if let Some(url) = &options.popular_index_url {
+            client.popular_index_url = Some(url.clone());
+        }
+        if let Some(path) = &options.config_file {
+            client.apply_npmrc(&Npmrc::load(path), options.base_url.is_none());
+        }
+        client
+    }
+
+    /// Routes scoped packages to their `.npmrc` registries. The `.npmrc` default registry
+    /// replaces `base_url` only when `replace_default` is set (no explicit override).
+    fn apply_npmrc(&mut self, npmrc: &Npmrc, replace_default: bool) {
+        let bearer = |url: &str| {
+            npmrc
+                .auth_token_for(url)
+                .map(|token| RegistryAuth::Bearer(token.to_string()))
+        };
+        if replace_default && let Some(registry) = &npmrc.registry {
+            self.base_url = registry.clone();
+            if let Some(auth) = bearer(registry) {
+                self.auth = Some(auth);
+            }
+        }
+        self.scoped_registries = Arc::new(
+            npmrc
+                .scoped_registries
+                .iter()
+                .map(|(scope, url)| {
+                    let registry = ScopedRegistry {
+                        base_url: url.clone(),
+                        auth: bearer(url),
+                    };
+                    (scope.clone(), registry)
+                })
+                .collect(),
+        );
+    }
+
+    /// Base URL and credentials serving `package`: its scope's registry when one is
+    /// configured, otherwise the default registry.
+    fn registry_for(&self, package: &str) -> (&str, Option<&RegistryAuth>) {
+        let scoped = package
+            .split_once('/')
+            .filter(|(scope, _)| scope.starts_with('@'))
+            .and_then(|(scope, _)| self.scoped_registries.get(&scope.to_ascii_lowercase()));
+        match scoped {
+            Some(registry) => (registry.base_url.as_str(), registry.auth.as_ref()),
+            None => (self.base_url.as_str(), self.auth.as_ref()),
+        }
+    }
+
+    /// Fetches the packument for `package`: the abbreviated document when the client was
+    /// configured for it, otherwise the full one.
+    async fn fetch_packument(&self, package: &str) -> Result<NpmPackageResponse, RegistryError> {
+        let encoded_name = Self::encode_package_name(package);
+        let (base_url, auth) = self.registry_for(package);
+        let url = format!("{}/{}", base_url.trim_end_matches('/'), encoded_name);
+        let accept = if self.abbreviated_metadata {
+            ABBREVIATED_METADATA_ACCEPT
+        } else {
+            FULL_METADATA_ACCEPT
+        };
+
+        let response = send_with_retry(
+            || {
+                let request = self.http.get(&url).header(reqwest::header::ACCEPT, accept);
+                match auth {
+                    Some(auth) => auth.apply(request),
+                    None => request,
+                }
+            },
+            "npm registry",
+            RetryPolicy::default(),
+            &self.limits,
+        )
+        .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(RegistryError::NotFound {
+                registry: "npm",
+                package: package.to_string(),
+            });
+        }
+
+        if !response.status().is_success() {
+            return Err(map_status_error("npm registry", response.status()));
+        }
+
+        parse_json(response, "npm registry response").await
     }
 
     fn encode_package_name(package: &str) -> String {
         package.replace('@', "%40").replace('/', "%2f")
     }
 
+    /// Encodes a package name for the downloads API path.
+    ///
+    /// Unlike the registry, the downloads API expects the literal `@scope/name` form;
+    /// an encoded `%2f` separator is reported as an unknown package.
+    fn encode_downloads_package_name(package: &str) -> String {
+        match package
+            .strip_prefix('@')
+            .and_then(|rest| rest.split_once('/'))
+        {
+            Some((scope, name)) => format!(
+                "@{}/{}",
+                Self::encode_package_name(scope),
+                Self::encode_package_name(name)
+            ),
+            None => Self::encode_package_name(package),
+        }
+    }
+
     pub async fn prefetch_weekly_downloads_bulk(
         &self,
         packages: &[String],
     ) -> Result<(), RegistryError> {
         let mut unique_unscoped = Vec::new();
+        let mut unique_scoped = Vec::new();
         let mut seen = HashSet::new();
         {
             let cache = self.prefetched_downloads.read().await;
             for package in packages {
-                if package.starts_with('@') {
-                    continue;
-                }
-                if cache.contains_key(package) {
+                if cache.contains_key(package) || !seen.insert(package.clone()) {
                     continue;
                 }
-                if seen.insert(package.clone()) {
+                if package.starts_with('@') {
+                    unique_scoped.push(package.clone());
+                } else {
                     unique_unscoped.push(package.clone());
                 }
             }
         }
 
-        for chunk in unique_unscoped.chunks(NPM_BULK_DOWNLOAD_MAX_PACKAGES) {
+        // The bulk endpoint rejects scoped names, so those are fetched (and cached) one by one.
+        let mut scoped_fetches = tokio::task::JoinSet::new();
+        for package in unique_scoped {
+            let client = self.clone();
+            scoped_fetches
+                .spawn(async move { client.fetch_weekly_downloads(&package).await.map(|_| ()) });
+        }
+
+        let mut first_error = self
+            .prefetch_unscoped_downloads(&unique_unscoped)
+            .await
+            .err();
+        while let Some(joined) = scoped_fetches.join_next().await {
+            let result = joined.unwrap_or_else(|err| {
+                Err(RegistryError::Transport {
+                    message: format!("scoped downloads prefetch task failed: {err}"),
+                })
+            });
+            if let Err(err) = result {
+                first_error.get_or_insert(err);
+            }
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn prefetch_unscoped_downloads(&self, packages: &[String]) -> Result<(), RegistryError> {
+        for chunk in packages.chunks(NPM_BULK_DOWNLOAD_MAX_PACKAGES) {
             let joined = chunk
                 .iter()
                 .map(String::as_str)
@@ -108,6 +273,7 @@ impl NpmRegistryClient {
                 || self.http.get(&url),
                 "npm bulk downloads API",
                 RetryPolicy::default(),
+                &self.limits,
             )
             .await?;
 
@@ -129,6 +295,98 @@ impl NpmRegistryClient {
 
         Ok(())
     }
+
+    /// Pages through npms.io search results until `limit` unique names are collected.
+    async fn fetch_npms_popular_names(&self, limit: usize) -> Result<Vec<String>, RegistryError> {
+        let mut names = Vec::new();
+        let mut seen = HashSet::new();
+        let mut from = 0usize;
+
+        while names.len() < limit {
+            let url = format!(
+                "{}/v2/search",
+                self.popular_index_api_base_url.trim_end_matches('/')
+            );
+            let size = NPMS_PAGE_SIZE.min(limit.saturating_sub(names.len()));
+            let query = vec![
+                ("q", NPMS_POPULAR_QUERY.to_string()),
+                ("size", size.to_string()),
+                ("from", from.to_string()),
+            ];
+            let response = send_with_retry(
+                || self.http.get(&url).query(&query),
+                "npms popularity index",
+                RetryPolicy::default(),
+                &self.limits,
+            )
+            .await?;
+
+            if !response.status().is_success() {
+                return Err(map_status_error("npms popularity index", response.status()));
+            }
+
+            let body: NpmsSearchResponse = parse_json(response, "npms search response").await?;
+
+            if body.results.is_empty() {
+                break;
+            }
+
+            for result in body.results {
+                if seen.insert(result.package.name.clone()) {
+                    names.push(result.package.name);
+                    if names.len() >= limit {
+                        break;
+                    }
+                }
+            }
+
+            from = from.saturating_add(size);
+        }
+
+        if names.is_empty() {
+            return Err(RegistryError::InvalidResponse {
+                message: "npms popularity index returned no package names".to_string(),
+            });
+        }
+        Ok(names)
+    }
+
+    /// Fetches a static popular-names list in one request.
+    async fn fetch_static_popular_names(&self, url: &str) -> Result<Vec<String>, RegistryError> {
+        let response = send_with_retry(
+            || self.http.get(url),
+            "npm popularity list",
+            RetryPolicy::default(),
+            &self.limits,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            return Err(map_status_error("npm popularity list", response.status()));
+        }
+
+        let entries: Vec<StaticPopularEntry> =
+            parse_json(response, "npm popularity list response").await?;
+        let names = parse_static_popular_names(entries);
+        if names.is_empty() {
+            return Err(RegistryError::InvalidResponse {
+                message: "npm popularity list contained no package names".to_string(),
+            });
+        }
+        Ok(names)
+    }
+}
+
+/// Names from a static popularity list, most popular first, keeping the first of any duplicates.
+fn parse_static_popular_names(entries: Vec<StaticPopularEntry>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    entries
+        .into_iter()
+        .map(|entry| match entry {
+            StaticPopularEntry::Name(name) | StaticPopularEntry::Package { name } => name,
+        })
+        .filter(|name| !name.trim().is_empty() && seen.insert(name.clone()))
+        .collect()
 }
 
 impl Default for NpmRegistryClient {
@@ -143,6 +401,10 @@ impl RegistryClient for NpmRegistryClient {
         RegistryEcosystem::Npm
     }
 
+    fn base_url(&self) -> Option<&str> {
+        Some(self.base_url.as_str())
+    }
+
     async fn prefetch_weekly_downloads(&self, packages: &[String]) -> Result<(), RegistryError> {
         self.prefetch_weekly_downloads_bulk(packages).await
     }
@@ -154,28 +416,7 @@ impl RegistryClient for NpmRegistryClient {
     }
 
     async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
-        let encoded_name = Self::encode_package_name(package);
-        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), encoded_name);
-
-        let response = send_with_retry(
-            || self.authorized(self.http.get(&url)),
-            "npm registry",
-            RetryPolicy::default(),
-        )
-        .await?;
-
-        if response.status() == StatusCode::NOT_FOUND {
-            return Err(RegistryError::NotFound {
-                registry: "npm",
-                package: package.to_string(),
-            });
-        }
-
-        if !response.status().is_success() {
-            return Err(map_status_error("npm registry", response.status()));
-        }
-
-        let body: NpmPackageResponse = parse_json(response, "npm registry response").await?;
+        let body = self.fetch_packument(package).await?;
 
         let latest = body
             .dist_tags
@@ -184,22 +425,37 @@ impl RegistryClient for NpmRegistryClient {
                 message: "missing dist-tags.latest".to_string(),
             })?;
 
+        let parse_time = |key: &str| {
+            body.time
+                .as_ref()
+                .and_then(|times| times.get(key))
+                .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+                .map(|value| value.with_timezone(&Utc))
+        };
+        // `modified` is never earlier than any publish, so it understates age rather than
+        // overstating it; `created` is the last resort. Either is marked as estimated.
+        let fallback_published = parse_time("modified").or_else(|| parse_time("created"));
+
         let versions = body
             .versions
             .into_iter()
             .map(|(version, metadata)| {
-                let published = body
-                    .time
-                    .as_ref()
-                    .and_then(|times| times.get(&version))
-                    .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
-                    .map(|value| value.with_timezone(&Utc));
+                let exact_published = parse_time(&version);
+                let published_estimated = exact_published.is_none() && fallback_published.is_some();
+                let published = exact_published.or(fallback_published);
 
                 let package_version = PackageVersion {
                     version: version.clone(),
                     published,
                     deprecated: metadata.deprecated.is_some(),
                     install_scripts: metadata.install_scripts(),
+                    unpacked_size: metadata.dist.as_ref().and_then(|dist| dist.unpacked_size),
+                    file_count: metadata.dist.as_ref().and_then(|dist| dist.file_count),
+                    maintainer_emails: metadata.maintainer_emails(),
+                    bin_targets: metadata.bin_targets(),
+                    license: metadata.license(),
+                    repository_url: metadata.repository_url(),
+                    published_estimated,
                 };
 
                 (version, package_version)
@@ -209,7 +465,12 @@ impl RegistryClient for NpmRegistryClient {
         Ok(PackageRecord {
             name: package.to_string(),
             latest,
-            publishers: body.maintainers.into_iter().map(|m| m.name).collect(),
+            publishers: body
+                .maintainers
+                .unwrap_or_default()
+                .into_iter()
+                .map(|m| m.name)
+                .collect(),
             versions,
         })
     }
@@ -222,7 +483,7 @@ impl RegistryClient for NpmRegistryClient {
             }
         }
 
-        let encoded_name = Self::encode_package_name(package);
+        let encoded_name = Self::encode_downloads_package_name(package);
         let url = format!(
             "{}/downloads/point/last-week/{}",
             self.downloads_api_base_url.trim_end_matches('/'),
@@ -233,6 +494,7 @@ impl RegistryClient for NpmRegistryClient {
             || self.http.get(&url),
             "npm downloads API",
             RetryPolicy::default(),
+            &self.limits,
         )
         .await?;
 
@@ -262,64 +524,24 @@ impl RegistryClient for NpmRegistryClient {
             return Ok(Vec::new());
         }
 
+        if let Some(local) = &self.local_popular_names {
+            return local.fetch(limit);
+        }
+
         {
             let cache_guard = self.popular_names_cache.read().await;
+            // A static list is fetched whole, so it is never re-fetched for a larger limit.
             if let Some(cached) = cache_guard.as_ref()
-                && cached.len() >= limit
+                && (cached.len() >= limit || self.popular_index_url.is_some())
             {
                 return Ok(cached.iter().take(limit).cloned().collect());
             }
         }
 
-        let mut names = Vec::new();
-        let mut seen = HashSet::new();
-        let mut from = 0usize;
-
-        while names.len() < limit {
-            let url = format!(
-                "{}/v2/search",
-                self.popular_index_api_base_url.trim_end_matches('/')
-            );
-            let size = NPMS_PAGE_SIZE.min(limit.saturating_sub(names.len()));
-            let query = vec![
-                ("q", NPMS_POPULAR_QUERY.to_string()),
-                ("size", size.to_string()),
-                ("from", from.to_string()),
-            ];
-            let response = send_with_retry(
-                || self.http.get(&url).query(&query),
-                "npms popularity index",
-                RetryPolicy::default(),
-            )
-            .await?;
-
-            if !response.status().is_success() {
-                return Err(map_status_error("npms popularity index", response.status()));
-            }
-
-            let body: NpmsSearchResponse = parse_json(response, "npms search response").await?;
-
-            if body.results.is_empty() {
-                break;
-            }
-
-            for result in body.results {
-                if seen.insert(result.package.name.clone()) {
-                    names.push(result.package.name);
-                    if names.len() >= limit {
-                        break;
-                    }
-                }
-            }
-
-            from = from.saturating_add(size);
-        }
-
-        if names.is_empty() {
-            return Err(RegistryError::InvalidResponse {
-                message: "npms popularity index returned no package names".to_string(),
-            });
-        }
+        let names = match &self.popular_index_url {
+            Some(url) => self.fetch_static_popular_names(url).await?,
+            None => self.fetch_npms_popular_names(limit).await?,
+        };
 
         let mut cache_guard = self.popular_names_cache.write().await;
         *cache_guard = Some(names.clone());
@@ -327,12 +549,24 @@ impl RegistryClient for NpmRegistryClient {
         Ok(names.into_iter().take(limit).collect())
     }
 
+    async fn prefetch_advisories(
+        &self,
+        packages: &[(String, String)],
+        concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        self.advisories
+            .prefetch(packages, self.ecosystem(), concurrency)
+            .await
+    }
+
     async fn fetch_advisories(
         &self,
         package: &str,
         version: &str,
     ) -> Result<Vec<PackageAdvisory>, RegistryError> {
-        query_advisories(package, version, self.ecosystem()).await
+        self.advisories
+            .query(package, version, self.ecosystem())
+            .await
     }
 }
 
@@ -340,8 +574,7 @@ impl RegistryClient for NpmRegistryClient {
 struct NpmPackageResponse {
     #[serde(rename = "dist-tags")]
     dist_tags: NpmDistTags,
-    #[serde(default)]
-    maintainers: Vec<NpmMaintainer>,
+    maintainers: Option<Vec<NpmMaintainer>>,
     #[serde(default)]
     versions: BTreeMap<String, NpmVersionMetadata>,
     time: Option<BTreeMap<String, String>>,
@@ -362,6 +595,61 @@ struct NpmVersionMetadata {
     deprecated: Option<String>,
     #[serde(default)]
     scripts: BTreeMap<String, String>,
+    dist: Option<NpmDist>,
+    #[serde(default)]
+    maintainers: Vec<NpmVersionMaintainer>,
+    bin: Option<NpmBin>,
+    license: Option<NpmLicense>,
+    repository: Option<NpmRepository>,
+}
+
+/// `license` is an SPDX string; legacy packuments use a `{ "type": ... }` object.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NpmLicense {
+    Expression(String),
+    Object {
+        #[serde(rename = "type")]
+        kind: Option<String>,
+    },
+    Other(serde::de::IgnoredAny),
+}
+
+/// `repository` is a URL or shorthand string, or a `{ "type": "git", "url": ... }` object.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NpmRepository {
+    Url(String),
+    Object { url: Option<String> },
+    Other(serde::de::IgnoredAny),
+}
+
+/// `bin` is either a single path (installed under the package name) or a name-to-path map.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NpmBin {
+    Single(String),
+    Map(BTreeMap<String, String>),
+    Other(serde::de::IgnoredAny),
+}
+
+/// Per-version maintainer entry; legacy packuments sometimes store bare strings.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NpmVersionMaintainer {
+    Person {
+        #[serde(default)]
+        email: Option<String>,
+    },
+    Other(serde::de::IgnoredAny),
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmDist {
+    #[serde(rename = "unpackedSize")]
+    unpacked_size: Option<u64>,
+    #[serde(rename = "fileCount")]
+    file_count: Option<u64>,
 }
 
 impl NpmVersionMetadata {
@@ -372,6 +660,50 @@ impl NpmVersionMetadata {
             .filter_map(|hook| self.scripts.get(*hook).map(|cmd| format!("{hook}: {cmd}")))
             .collect()
     }
+
+    fn bin_targets(&self) -> Vec<String> {
+        let targets = match &self.bin {
+            Some(NpmBin::Single(path)) => vec![path.clone()],
+            Some(NpmBin::Map(entries)) => entries.values().cloned().collect(),
+            Some(NpmBin::Other(_)) | None => Vec::new(),
+        };
+        targets
+            .into_iter()
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .collect()
+    }
+
+    fn license(&self) -> Option<String> {
+        let license = match &self.license {
+            Some(NpmLicense::Expression(expression)) => expression.as_str(),
+            Some(NpmLicense::Object { kind: Some(kind) }) => kind.as_str(),
+            _ => return None,
+        };
+        let license = license.trim();
+        (!license.is_empty()).then(|| license.to_string())
+    }
+
+    fn repository_url(&self) -> Option<String> {
+        match &self.repository {
+            Some(NpmRepository::Url(url)) | Some(NpmRepository::Object { url: Some(url) }) => {
+                normalize_repository_url(url)
+            }
+            _ => None,
+        }
+    }
+
+    fn maintainer_emails(&self) -> Vec<String> {
+        self.maintainers
+            .iter()
+            .filter_map(|maintainer| match maintainer {
+                NpmVersionMaintainer::Person { email: Some(email) } if !email.trim().is_empty() => {
+                    Some(email.trim().to_string())
+                }
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -395,6 +727,14 @@ struct NpmsPackage {
     name: String,
 }
 
+/// One entry of a static popular-names list: a bare name or an object with a `name` field.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StaticPopularEntry {
+    Name(String),
+    Package { name: String },
+}
+
 #[derive(Debug, Deserialize)]
 struct NpmBulkDownloadsResponse {
     #[serde(default)]
@@ -420,12 +760,18 @@ mod tests {
     fn test_client_with_token(base_url: &str, auth_token: Option<&str>) -> NpmRegistryClient {
         NpmRegistryClient {
             http: build_http_client(),
+            limits: RequestLimits::default(),
             base_url: base_url.to_string(),
             downloads_api_base_url: base_url.to_string(),
             popular_index_api_base_url: base_url.to_string(),
-            auth_token: auth_token.map(str::to_string),
+            popular_index_url: None,
+            auth: auth_token.map(|token| RegistryAuth::Bearer(token.to_string())),
+            scoped_registries: Arc::new(HashMap::new()),
             popular_names_cache: Arc::new(RwLock::new(None)),
+            local_popular_names: None,
+            advisories: AdvisoryPrefetch::default(),
             prefetched_downloads: Arc::new(RwLock::new(HashMap::new())),
+            abbreviated_metadata: false,
         }
     }
 
@@ -438,6 +784,266 @@ mod tests {
         assert_eq!(NpmRegistryClient::encode_package_name("lodash"), "lodash");
     }
 
+    #[tokio::test]
+    async fn with_options_base_url_is_used_for_fetch_package() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "dist-tags": { "latest": "1.0.0" },
+                  "maintainers": [],
+                  "versions": { "1.0.0": {} },
+                  "time": { "1.0.0": "2024-01-01T00:00:00Z" }
+                }"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let client = NpmRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(mock_server.uri()),
+            ..RegistryClientOptions::default()
+        });
+
+        let record = client
+            .fetch_package("demo")
+            .await
+            .expect("override base url should serve package");
+        assert_eq!(record.latest, "1.0.0");
+    }
+
+    #[tokio::test]
+    async fn fetch_package_requests_full_packument_in_one_request() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo"))
+            .and(header("accept", FULL_METADATA_ACCEPT))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "_id": "demo",
+                  "name": "demo",
+                  "dist-tags": { "latest": "1.1.0" },
+                  "maintainers": [{ "name": "alice", "email": "alice@example.com" }],
+                  "versions": {
+                    "1.0.0": {
+                      "name": "demo",
+                      "version": "1.0.0",
+                      "deprecated": "use 1.1.0",
+                      "maintainers": [{ "name": "alice", "email": "alice@example.com" }],
+                      "dist": { "tarball": "https://registry.npmjs.org/demo/-/demo-1.0.0.tgz" }
+                    },
+                    "1.1.0": {
+                      "name": "demo",
+                      "version": "1.1.0",
+                      "scripts": { "test": "node test.js", "postinstall": "node setup.js" },
+                      "maintainers": [{ "name": "bob", "email": "bob@example.org" }],
+                      "dist": {
+                        "tarball": "https://registry.npmjs.org/demo/-/demo-1.1.0.tgz",
+                        "unpackedSize": 2048,
+                        "fileCount": 4
+                      }
+                    }
+                  },
+                  "time": {
+                    "created": "2023-01-01T00:00:00Z",
+                    "modified": "2024-06-01T00:00:00Z",
+                    "1.0.0": "2023-01-01T00:00:00Z",
+                    "1.1.0": "2024-06-01T00:00:00Z"
+                  }
+                }"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let client = test_client(&mock_server.uri());
+
+        let record = client.fetch_package("demo").await.expect("package");
+        assert_eq!(record.latest, "1.1.0");
+        assert_eq!(record.publishers, vec!["alice".to_string()]);
+        assert!(record.versions["1.0.0"].deprecated);
+        assert_eq!(record.versions["1.1.0"].unpacked_size, Some(2048));
+        assert_eq!(
+            record.versions["1.1.0"].install_scripts,
+            vec!["postinstall: node setup.js".to_string()]
+        );
+        assert_eq!(
+            record.versions["1.1.0"].maintainer_emails,
+            vec!["bob@example.org".to_string()]
+        );
+        assert_eq!(
+            record.versions["1.1.0"].published,
+            Some("2024-06-01T00:00:00Z".parse().expect("timestamp"))
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_package_requests_abbreviated_packument_when_configured() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo"))
+            .and(header("accept", ABBREVIATED_METADATA_ACCEPT))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "name": "demo",
+                  "modified": "2024-06-01T00:00:00Z",
+                  "dist-tags": { "latest": "1.1.0" },
+                  "versions": {
+                    "1.0.0": { "name": "demo", "version": "1.0.0", "deprecated": "use 1.1.0" },
+                    "1.1.0": {
+                      "name": "demo",
+                      "version": "1.1.0",
+                      "hasInstallScript": true,
+                      "bin": { "demo": "cli.js" },
+                      "dist": { "unpackedSize": 2048 }
+                    }
+                  }
+                }"#,
+                ABBREVIATED_METADATA_ACCEPT,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let client = NpmRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(mock_server.uri()),
+            abbreviated_metadata: true,
+            ..RegistryClientOptions::default()
+        });
+
+        let record = client.fetch_package("demo").await.expect("package");
+        assert_eq!(record.latest, "1.1.0");
+        assert!(record.publishers.is_empty());
+        assert!(record.versions["1.0.0"].deprecated);
+        assert_eq!(record.versions["1.1.0"].unpacked_size, Some(2048));
+        assert_eq!(record.versions["1.1.0"].bin_targets, vec!["cli.js"]);
+        assert_eq!(record.versions["1.1.0"].published, None);
+    }
+
+    #[tokio::test]
+    async fn fetch_package_falls_back_to_modified_time_for_missing_versions() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "dist-tags": { "latest": "1.1.0" },
+                  "versions": { "1.0.0": {}, "1.1.0": {} },
+                  "time": {
+                    "created": "2023-01-01T00:00:00Z",
+                    "modified": "2024-06-01T00:00:00Z",
+                    "1.0.0": "2023-01-01T00:00:00Z"
+                  }
+                }"#,
+                "application/json",
+            ))
+            .mount(&mock_server)
+            .await;
+        let client = NpmRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(mock_server.uri()),
+            ..RegistryClientOptions::default()
+        });
+
+        let record = client.fetch_package("demo").await.expect("package");
+        let published = |version: &str| {
+            record.versions[version]
+                .published
+                .map(|value| value.to_rfc3339())
+        };
+        assert_eq!(
+            published("1.0.0").as_deref(),
+            Some("2023-01-01T00:00:00+00:00")
+        );
+        assert_eq!(
+            published("1.1.0").as_deref(),
+            Some("2024-06-01T00:00:00+00:00")
+        );
+        assert!(!record.versions["1.0.0"].published_estimated);
+        assert!(record.versions["1.1.0"].published_estimated);
+        assert!(record.versions["1.1.0"].exact_published().is_none());
+    }
+
+    #[tokio::test]
+    async fn fetch_package_captures_per_version_maintainer_emails() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "dist-tags": { "latest": "1.1.0" },
+                  "versions": {
+                    "1.0.0": { "maintainers": [{ "name": "alice", "email": "alice@acme.com" }] },
+                    "1.1.0": { "maintainers": ["legacy <legacy@example.com>", { "name": "bob" }] }
+                  }
+                }"#,
+                "application/json",
+            ))
+            .mount(&mock_server)
+            .await;
+        let client = NpmRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(mock_server.uri()),
+            ..RegistryClientOptions::default()
+        });
+
+        let record = client.fetch_package("demo").await.expect("package");
+        assert_eq!(
+            record.versions["1.0.0"].maintainer_emails,
+            vec!["alice@acme.com".to_string()]
+        );
+        assert!(record.versions["1.1.0"].maintainer_emails.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_package_leaves_published_empty_without_any_time_entries() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "dist-tags": { "latest": "1.0.0" },
+                  "versions": { "1.0.0": {} }
+                }"#,
+                "application/json",
+            ))
+            .mount(&mock_server)
+            .await;
+        let client = NpmRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(mock_server.uri()),
+            ..RegistryClientOptions::default()
+        });
+
+        let record = client.fetch_package("demo").await.expect("package");
+        assert!(record.versions["1.0.0"].published.is_none());
+    }
+
+    #[tokio::test]
+    async fn with_options_popular_names_file_skips_popular_index() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/search"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+        let names_path =
+            std::env::temp_dir().join(format!("safe-pkgs-npm-popular-{}.txt", std::process::id()));
+        std::fs::write(&names_path, "react\nlodash\n").expect("write names file");
+
+        let mut client = NpmRegistryClient::with_options(&RegistryClientOptions {
+            popular_names_file: Some(names_path.clone()),
+            ..RegistryClientOptions::default()
+        });
+        client.popular_index_api_base_url = mock_server.uri();
+
+        let names = client
+            .fetch_popular_package_names(10)
+            .await
+            .expect("names from local file");
+        assert_eq!(names, vec!["react", "lodash"]);
+
+        let _ = std::fs::remove_file(names_path);
+    }
+
     #[tokio::test]
     async fn fetch_package_parses_scripts_and_deprecated_versions() {
         let mock_server = MockServer::start().await;
@@ -448,8 +1054,18 @@ mod tests {
                   "dist-tags": { "latest": "1.0.0" },
                   "maintainers": [{ "name": "alice" }],
                   "versions": {
-                    "1.0.0": { "scripts": { "preinstall": "node setup.js" } },
-                    "0.9.0": { "deprecated": "legacy", "scripts": {} }
+                    "1.0.0": {
+                      "scripts": { "preinstall": "node setup.js" },
+                      "dist": { "unpackedSize": 4096, "fileCount": 3 },
+                      "license": "MIT",
+                      "repository": { "type": "git", "url": "git+https://github.com/scope/pkg.git" }
+                    },
+                    "0.9.0": {
+                      "deprecated": "legacy",
+                      "scripts": {},
+                      "license": { "type": "ISC" },
+                      "repository": "github:scope/pkg"
+                    }
                   },
                   "time": {
                     "1.0.0": "2024-01-01T00:00:00Z",
@@ -471,6 +1087,47 @@ mod tests {
         assert_eq!(record.versions["1.0.0"].install_scripts.len(), 1);
         assert!(record.versions["1.0.0"].install_scripts[0].contains("preinstall"));
         assert!(record.versions["0.9.0"].deprecated);
+        assert_eq!(record.versions["1.0.0"].unpacked_size, Some(4096));
+        assert_eq!(record.versions["1.0.0"].file_count, Some(3));
+        assert_eq!(record.versions["0.9.0"].unpacked_size, None);
+        assert_eq!(record.versions["1.0.0"].license.as_deref(), Some("MIT"));
+        assert_eq!(
+            record.versions["1.0.0"].repository_url.as_deref(),
+            Some("https://github.com/scope/pkg")
+        );
+        assert_eq!(record.versions["0.9.0"].license.as_deref(), Some("ISC"));
+        assert_eq!(
+            record.versions["0.9.0"].repository_url.as_deref(),
+            Some("github:scope/pkg")
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_package_captures_bin_targets() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "dist-tags": { "latest": "2.0.0" },
+                  "versions": {
+                    "1.0.0": { "bin": "./cli.js" },
+                    "2.0.0": { "bin": { "demo": "bin/demo", "demo-helper": "bin/helper.js" } }
+                  },
+                  "time": { "1.0.0": "2023-01-01T00:00:00Z", "2.0.0": "2024-01-01T00:00:00Z" }
+                }"#,
+                "application/json",
+            ))
+            .mount(&mock_server)
+            .await;
+        let client = test_client(&mock_server.uri());
+
+        let record = client.fetch_package("demo").await.expect("package");
+        assert_eq!(record.versions["1.0.0"].bin_targets, vec!["./cli.js"]);
+        assert_eq!(
+            record.versions["2.0.0"].bin_targets,
+            vec!["bin/demo", "bin/helper.js"]
+        );
     }
 
     #[tokio::test]
@@ -547,6 +1204,77 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn prefetch_routes_scoped_names_to_individual_requests() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/downloads/point/last-week/demo,other"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "downloads": [
+                    { "package": "demo", "downloads": 42 },
+                    { "package": "other", "downloads": 7 }
+                  ]
+                }"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        for (name, downloads) in [("@scope/pkg", 1200), ("@types/node", 90_000_000)] {
+            Mock::given(method("GET"))
+                .and(path(format!("/downloads/point/last-week/{name}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "package": name,
+                    "downloads": downloads
+                })))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+        let client = test_client(&mock_server.uri());
+
+        client
+            .prefetch_weekly_downloads_bulk(&[
+                "demo".to_string(),
+                "@scope/pkg".to_string(),
+                "other".to_string(),
+                "@types/node".to_string(),
+                "@scope/pkg".to_string(),
+            ])
+            .await
+            .expect("mixed prefetch");
+
+        // Every lookup is served from the prefetch cache; `.expect(1)` verifies no refetch.
+        for (name, downloads) in [
+            ("demo", 42),
+            ("other", 7),
+            ("@scope/pkg", 1200),
+            ("@types/node", 90_000_000),
+        ] {
+            assert_eq!(
+                client
+                    .fetch_weekly_downloads(name)
+                    .await
+                    .expect("cache lookup"),
+                Some(downloads),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn downloads_api_keeps_scope_separator() {
+        assert_eq!(
+            NpmRegistryClient::encode_downloads_package_name("@scope/pkg"),
+            "@scope/pkg"
+        );
+        assert_eq!(
+            NpmRegistryClient::encode_downloads_package_name("left-pad"),
+            "left-pad"
+        );
+    }
+
     #[tokio::test]
     async fn fetch_weekly_downloads_caches_not_found_results() {
         let mock_server = MockServer::start().await;
@@ -606,6 +1334,56 @@ mod tests {
         assert_eq!(second, vec!["react", "lodash"]);
     }
 
+    #[test]
+    fn static_popular_list_accepts_names_and_objects() {
+        let entries: Vec<StaticPopularEntry> = serde_json::from_str(
+            r#"["react", { "name": "lodash", "rank": 2 }, "react", "", "chalk"]"#,
+        )
+        .expect("static list");
+        assert_eq!(
+            parse_static_popular_names(entries),
+            vec!["react", "lodash", "chalk"]
+        );
+    }
+
+    #[test]
+    fn static_popular_list_rejects_non_list_body() {
+        assert!(serde_json::from_str::<Vec<StaticPopularEntry>>(r#"{"rows": []}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn popular_index_url_replaces_npms_search() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/top-npm.json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(r#"["react", "lodash", "chalk"]"#, "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/search"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+        let mut client = test_client(&mock_server.uri());
+        client.popular_index_url = Some(format!("{}/top-npm.json", mock_server.uri()));
+
+        let first = client
+            .fetch_popular_package_names(2)
+            .await
+            .expect("static list lookup");
+        let second = client
+            .fetch_popular_package_names(10)
+            .await
+            .expect("cached static list");
+        assert_eq!(first, vec!["react", "lodash"]);
+        assert_eq!(second, vec!["react", "lodash", "chalk"]);
+    }
+
     #[tokio::test]
     async fn fetch_package_sends_bearer_token_when_configured() {
         let mock_server = MockServer::start().await;
@@ -632,6 +1410,95 @@ mod tests {
         assert_eq!(record.latest, "1.0.0");
     }
 
+    #[tokio::test]
+    async fn npmrc_routes_scoped_packages_to_their_private_registry() {
+        let private_registry = MockServer::start().await;
+        let public_registry = MockServer::start().await;
+        let packument = r#"{
+          "dist-tags": { "latest": "2.0.0" },
+          "versions": { "2.0.0": {} },
+          "time": {}
+        }"#;
+        Mock::given(method("GET"))
+            .and(path("/npm-private/%40acme%2fwidget"))
+            .and(header("authorization", "Bearer private-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(packument, "application/json"))
+            .expect(1..)
+            .mount(&private_registry)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/left-pad"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(packument, "application/json"))
+            .expect(1..)
+            .mount(&public_registry)
+            .await;
+
+        let private_host = private_registry
+            .uri()
+            .trim_start_matches("http://")
+            .to_string();
+        let npmrc_path = std::env::temp_dir().join(format!(
+            "safe-pkgs-npmrc-{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::write(
+            &npmrc_path,
+            format!(
+                "@acme:registry={}/npm-private/\n//{private_host}/npm-private/:_authToken=private-token\n",
+                private_registry.uri()
+            ),
+        )
+        .expect("write npmrc");
+        let client = NpmRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(public_registry.uri()),
+            config_file: Some(npmrc_path.clone()),
+            ..RegistryClientOptions::default()
+        });
+        let _ = std::fs::remove_file(npmrc_path);
+
+        let scoped = client
+            .fetch_package("@acme/widget")
+            .await
+            .expect("scoped package from private registry");
+        assert_eq!(scoped.latest, "2.0.0");
+        let public = client
+            .fetch_package("left-pad")
+            .await
+            .expect("unscoped package from public registry");
+        assert_eq!(public.latest, "2.0.0");
+    }
+
+    #[tokio::test]
+    async fn fetch_package_sends_basic_auth_when_configured() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo"))
+            .and(header("authorization", "Basic Y2k6czNjcmV0"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "dist-tags": { "latest": "1.0.0" },
+                  "versions": { "1.0.0": {} },
+                  "time": {}
+                }"#,
+                "application/json",
+            ))
+            .mount(&mock_server)
+            .await;
+        let client = NpmRegistryClient {
+            auth: Some(RegistryAuth::Basic {
+                username: "ci".to_string(),
+                password: "s3cret".to_string(),
+            }),
+            ..test_client(&mock_server.uri())
+        };
+
+        let record = client
+            .fetch_package("demo")
+            .await
+            .expect("basic-auth request should succeed");
+        assert_eq!(record.latest, "1.0.0");
+    }
+
     #[tokio::test]
     async fn fetch_package_works_without_token() {
         let mock_server = MockServer::start().await;
@@ -5,19 +5,24 @@ use std::sync::Arc;
 
 pub use lockfile::PypiLockfileParser;
 pub use registry::PypiRegistryClient;
-use safe_pkgs_core::{LockfileParser, RegistryClient, RegistryDefinition};
+use safe_pkgs_core::{LockfileParser, RegistryClient, RegistryClientOptions, RegistryDefinition};
 
 pub fn registry_definition() -> RegistryDefinition {
     RegistryDefinition {
         key: "pypi",
         create_client,
         create_lockfile_parser: Some(create_lockfile_parser),
-        excluded_checks: &["install_script"],
+        excluded_checks: &[
+            "install_script",
+            "size_anomaly",
+            "maintainer_domain",
+            "binary_only",
+        ],
     }
 }
 
-fn create_client() -> Arc<dyn RegistryClient> {
-    Arc::new(PypiRegistryClient::new())
+fn create_client(options: &RegistryClientOptions) -> Arc<dyn RegistryClient> {
+    Arc::new(PypiRegistryClient::with_options(options))
 }
 
 fn create_lockfile_parser() -> Arc<dyn LockfileParser> {
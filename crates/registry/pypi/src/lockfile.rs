@@ -1,6 +1,9 @@
-use safe_pkgs_core::{DependencySpec, LockfileError, LockfileParser};
-use std::collections::BTreeMap;
-use std::path::Path;
+use safe_pkgs_core::{
+    DependencyScope, DependencySpec, LockfileError, LockfileParser, RegistryEcosystem,
+    UnsupportedSource,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Default)]
 pub struct PypiLockfileParser;
@@ -39,22 +42,160 @@ fn parse_pypi_dependencies(path: &Path) -> Result<Vec<DependencySpec>, LockfileE
 }
 
 fn parse_requirements_file(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
+    let mut dependencies = BTreeMap::<String, DependencySpec>::new();
+    let mut constraints = BTreeMap::<String, DependencySpec>::new();
+    let mut visited = HashSet::<PathBuf>::new();
+    collect_requirements_file(
+        path,
+        RequirementsRole::Requirements,
+        &mut visited,
+        &mut dependencies,
+        &mut constraints,
+    )?;
+
+    // Constraint files only pin packages that are required elsewhere; they never add any.
+    for (name, constraint) in constraints {
+        if let Some(existing) = dependencies.get_mut(&name) {
+            if existing.version.is_none() {
+                existing.version = constraint.version;
+            }
+            if existing.hashes.is_empty() {
+                existing.hashes = constraint.hashes;
+            }
+        }
+    }
+
+    Ok(dependencies.into_values().collect())
+}
+
+/// Whether a requirements file was reached through `-r` or `-c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequirementsRole {
+    Requirements,
+    Constraints,
+}
+
+/// Reads one requirements file and follows its `-r`/`-c` includes, relative to the file.
+///
+/// Each file is read at most once, which both breaks include cycles and skips files that
+/// several others include.
+fn collect_requirements_file(
+    path: &Path,
+    role: RequirementsRole,
+    visited: &mut HashSet<PathBuf>,
+    dependencies: &mut BTreeMap<String, DependencySpec>,
+    constraints: &mut BTreeMap<String, DependencySpec>,
+) -> Result<(), LockfileError> {
+    let visit_key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(visit_key) {
+        return Ok(());
+    }
+
     let raw = std::fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
         path: path.display().to_string(),
         source,
     })?;
-    let mut dependencies = BTreeMap::<String, Option<String>>::new();
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    for line in requirements_logical_lines(&raw) {
+        if let Some((include_role, include_path)) = parse_requirements_include(&line) {
+            let include_role = if role == RequirementsRole::Constraints {
+                RequirementsRole::Constraints
+            } else {
+                include_role
+            };
+            collect_requirements_file(
+                &base_dir.join(include_path),
+                include_role,
+                visited,
+                dependencies,
+                constraints,
+            )?;
+            continue;
+        }
+
+        let (requirement, hashes) = split_requirement_hashes(&line);
+        if let Some(mut spec) = parse_python_requirement_line(&requirement) {
+            spec.hashes = hashes;
+            match role {
+                RequirementsRole::Requirements => insert_dependency_spec(dependencies, spec),
+                RequirementsRole::Constraints => insert_dependency_spec(constraints, spec),
+            }
+        }
+    }
 
+    Ok(())
+}
+
+/// Joins backslash-continued lines, as pip does before parsing options such as `--hash`.
+fn requirements_logical_lines(raw: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
     for line in raw.lines() {
-        if let Some(spec) = parse_python_requirement_line(line) {
-            insert_dependency_spec(&mut dependencies, spec);
+        match line.strip_suffix('\\') {
+            Some(continued) => {
+                current.push_str(continued);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(line);
+                lines.push(std::mem::take(&mut current));
+            }
         }
     }
+    if !current.trim().is_empty() {
+        lines.push(current);
+    }
+    lines
+}
 
-    Ok(dependencies
-        .into_iter()
-        .map(|(name, version)| direct_dependency_spec(name, version))
-        .collect())
+/// Parses `-r`/`--requirement` and `-c`/`--constraint` lines into their role and path.
+fn parse_requirements_include(line: &str) -> Option<(RequirementsRole, &str)> {
+    let candidate = line.trim();
+    let candidate = candidate
+        .split_once(" #")
+        .map_or(candidate, |(before, _)| before)
+        .trim();
+
+    for (option, role) in [
+        ("--requirement", RequirementsRole::Requirements),
+        ("--constraint", RequirementsRole::Constraints),
+        ("-r", RequirementsRole::Requirements),
+        ("-c", RequirementsRole::Constraints),
+    ] {
+        let Some(rest) = candidate.strip_prefix(option) else {
+            continue;
+        };
+        let target = if option.starts_with("--") {
+            rest.strip_prefix('=').unwrap_or(rest)
+        } else {
+            rest
+        }
+        .trim();
+        if !target.is_empty() {
+            return Some((role, target));
+        }
+    }
+    None
+}
+
+/// Splits `--hash=<algo>:<digest>` options off a requirement line.
+fn split_requirement_hashes(line: &str) -> (String, Vec<String>) {
+    let mut requirement = Vec::new();
+    let mut hashes = Vec::new();
+    let mut tokens = line.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if let Some(hash) = token.strip_prefix("--hash=") {
+            hashes.push(hash.to_string());
+        } else if token == "--hash" {
+            if let Some(hash) = tokens.next() {
+                hashes.push(hash.to_string());
+            }
+        } else {
+            requirement.push(token);
+        }
+    }
+    (requirement.join(" "), hashes)
 }
 
 fn parse_pyproject_manifest(path: &Path) -> Result<Vec<DependencySpec>, LockfileError> {
@@ -66,7 +207,7 @@ fn parse_pyproject_manifest(path: &Path) -> Result<Vec<DependencySpec>, Lockfile
         path: path.display().to_string(),
         message: error.to_string(),
     })?;
-    let mut dependencies = BTreeMap::<String, Option<String>>::new();
+    let mut dependencies = BTreeMap::<String, DependencySpec>::new();
 
     if let Some(project_deps) = root
         .get("project")
@@ -96,7 +237,8 @@ fn parse_pyproject_manifest(path: &Path) -> Result<Vec<DependencySpec>, Lockfile
                 let Some(raw_requirement) = item.as_str() else {
                     continue;
                 };
-                if let Some(spec) = parse_python_requirement_line(raw_requirement) {
+                if let Some(mut spec) = parse_python_requirement_line(raw_requirement) {
+                    spec.scope = DependencyScope::Optional;
                     insert_dependency_spec(&mut dependencies, spec);
                 }
             }
@@ -109,7 +251,16 @@ fn parse_pyproject_manifest(path: &Path) -> Result<Vec<DependencySpec>, Lockfile
         .and_then(|value| value.get("dependencies"))
         .and_then(|value| value.as_table())
     {
-        parse_poetry_dependencies_table(poetry_deps, &mut dependencies);
+        parse_poetry_dependencies_table(poetry_deps, DependencyScope::Prod, &mut dependencies);
+    }
+
+    if let Some(poetry_dev_deps) = root
+        .get("tool")
+        .and_then(|value| value.get("poetry"))
+        .and_then(|value| value.get("dev-dependencies"))
+        .and_then(|value| value.as_table())
+    {
+        parse_poetry_dependencies_table(poetry_dev_deps, DependencyScope::Dev, &mut dependencies);
     }
 
     if let Some(poetry_groups) = root
@@ -118,24 +269,28 @@ fn parse_pyproject_manifest(path: &Path) -> Result<Vec<DependencySpec>, Lockfile
         .and_then(|value| value.get("group"))
         .and_then(|value| value.as_table())
     {
-        for group in poetry_groups.values() {
+        for (group_name, group) in poetry_groups {
             let Some(group_deps) = group.get("dependencies").and_then(|value| value.as_table())
             else {
                 continue;
             };
-            parse_poetry_dependencies_table(group_deps, &mut dependencies);
+            // Poetry installs only the `main` group for production; other groups are dev tooling.
+            let scope = if group_name == "main" {
+                DependencyScope::Prod
+            } else {
+                DependencyScope::Dev
+            };
+            parse_poetry_dependencies_table(group_deps, scope, &mut dependencies);
         }
     }
 
-    Ok(dependencies
-        .into_iter()
-        .map(|(name, version)| direct_dependency_spec(name, version))
-        .collect())
+    Ok(dependencies.into_values().collect())
 }
 
 fn parse_poetry_dependencies_table(
     table: &toml::value::Table,
-    dependencies: &mut BTreeMap<String, Option<String>>,
+    scope: DependencyScope,
+    dependencies: &mut BTreeMap<String, DependencySpec>,
 ) {
     for (name, value) in table {
         if name.eq_ignore_ascii_case("python") {
@@ -154,11 +309,28 @@ fn parse_poetry_dependencies_table(
                 .and_then(normalize_poetry_exact_version),
             _ => None,
         };
-
-        insert_dependency_spec(
-            dependencies,
-            direct_dependency_spec(normalized_name, version),
-        );
+        let unsupported_source = value.as_table().and_then(|entries| {
+            [
+                ("git", UnsupportedSource::Git),
+                ("path", UnsupportedSource::Path),
+                ("url", UnsupportedSource::Url),
+            ]
+            .into_iter()
+            .find_map(|(key, source)| entries.contains_key(key).then_some(source))
+        });
+        let optional = value
+            .get("optional")
+            .and_then(|optional| optional.as_bool())
+            .unwrap_or(false);
+
+        let mut spec = direct_dependency_spec(normalized_name, version);
+        spec.unsupported_source = unsupported_source;
+        spec.scope = if optional {
+            scope.max(DependencyScope::Optional)
+        } else {
+            scope
+        };
+        insert_dependency_spec(dependencies, spec);
     }
 }
 
@@ -180,9 +352,11 @@ fn parse_python_requirement_line(line: &str) -> Option<DependencySpec> {
         return None;
     }
 
-    if let Some((name_part, _)) = candidate.split_once(" @ ") {
+    if let Some((name_part, reference)) = candidate.split_once(" @ ") {
         let name = normalize_python_package_name(name_part)?;
-        return Some(direct_dependency_spec(name, None));
+        let mut spec = direct_dependency_spec(name, None);
+        spec.unsupported_source = Some(direct_reference_source(reference.trim()));
+        return Some(spec);
     }
 
     for operator in ["===", "==", "~=", ">=", "<=", "!=", "<", ">"] {
@@ -201,6 +375,21 @@ fn parse_python_requirement_line(line: &str) -> Option<DependencySpec> {
     Some(direct_dependency_spec(name, None))
 }
 
+/// Origin of a PEP 508 direct reference (`name @ <reference>`).
+fn direct_reference_source(reference: &str) -> UnsupportedSource {
+    let scheme = reference
+        .split_once(':')
+        .map_or("", |(scheme, _)| scheme)
+        .to_ascii_lowercase();
+    if scheme.starts_with("git+") || scheme == "git" {
+        UnsupportedSource::Git
+    } else if scheme == "file" {
+        UnsupportedSource::Path
+    } else {
+        UnsupportedSource::Url
+    }
+}
+
 fn normalize_python_package_name(raw: &str) -> Option<String> {
     let without_extras = raw.split_once('[').map_or(raw, |(name, _)| name);
     let trimmed = without_extras.trim();
@@ -220,19 +409,7 @@ fn normalize_python_package_name(raw: &str) -> Option<String> {
     }
 
     // Normalize per PEP 503: lowercase and collapse runs of [-_.] into '-'.
-    let mut normalized = String::with_capacity(trimmed.len());
-    let mut previous_was_separator = false;
-    for ch in trimmed.chars() {
-        if ch.is_ascii_alphanumeric() {
-            normalized.push(ch.to_ascii_lowercase());
-            previous_was_separator = false;
-        } else if !previous_was_separator {
-            normalized.push('-');
-            previous_was_separator = true;
-        }
-    }
-
-    let normalized = normalized.trim_matches('-').to_string();
+    let normalized = RegistryEcosystem::PyPI.normalize_package_name(trimmed);
     if normalized.is_empty() {
         return None;
     }
@@ -291,17 +468,24 @@ fn normalize_poetry_exact_version(raw: &str) -> Option<String> {
 }
 
 fn insert_dependency_spec(
-    dependencies: &mut BTreeMap<String, Option<String>>,
+    dependencies: &mut BTreeMap<String, DependencySpec>,
     spec: DependencySpec,
 ) {
-    dependencies
-        .entry(spec.name)
-        .and_modify(|existing| {
-            if existing.is_none() && spec.version.is_some() {
-                *existing = spec.version.clone();
+    match dependencies.get_mut(&spec.name) {
+        Some(existing) => {
+            if existing.version.is_none() && spec.version.is_some() {
+                existing.version = spec.version;
             }
-        })
-        .or_insert(spec.version);
+            if existing.hashes.is_empty() {
+                existing.hashes = spec.hashes;
+            }
+            existing.unsupported_source = existing.unsupported_source.or(spec.unsupported_source);
+            existing.scope = existing.scope.merge(spec.scope);
+        }
+        None => {
+            dependencies.insert(spec.name.clone(), spec);
+        }
+    }
 }
 
 /// Builds a `DependencySpec` for a direct (non-transitive) dependency.
@@ -312,6 +496,9 @@ fn direct_dependency_spec(name: String, version: Option<String>) -> DependencySp
         dependency_paths: Vec::new(),
         name,
         version,
+        scope: DependencyScope::Prod,
+        unsupported_source: None,
+        hashes: Vec::new(),
     }
 }
 
@@ -343,7 +530,7 @@ mod tests {
         let temp = dir.join("requirements.txt");
         std::fs::write(
             &temp,
-            "requests==2.31.0\nurllib3>=2.0\nrich[markdown]==13.7.1\n# comment\n-r other.txt\n",
+            "requests==2.31.0\nurllib3>=2.0\nrich[markdown]==13.7.1\n# comment\n",
         )
         .expect("write requirements");
 
@@ -357,6 +544,55 @@ mod tests {
         let _ = std::fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn parse_requirements_file_follows_includes_and_captures_hashes() {
+        let dir = unique_temp_dir("requirements-includes");
+        std::fs::create_dir_all(dir.join("reqs")).expect("create include dir");
+        let root = dir.join("requirements.txt");
+        std::fs::write(
+            &root,
+            "-r reqs/base.txt\n--constraint=constraints.txt\nrequests==2.31.0 \\\n    --hash=sha256:aaaa \\\n    --hash=sha256:bbbb\n",
+        )
+        .expect("write requirements");
+        // Includes resolve relative to the including file, and the cycle back to the root is ignored.
+        std::fs::write(
+            dir.join("reqs/base.txt"),
+            "flask --hash sha256:cccc\n-r ../requirements.txt\n",
+        )
+        .expect("write base requirements");
+        std::fs::write(dir.join("constraints.txt"), "flask==3.0.3\nnumpy==2.0.0\n")
+            .expect("write constraints");
+
+        let deps = parse_requirements_file(&root).expect("parse requirements");
+        assert_eq!(deps.len(), 2);
+        assert_eq!(find_version(&deps, "requests"), Some("2.31.0"));
+        assert_eq!(find_version(&deps, "flask"), Some("3.0.3"));
+        assert!(deps.iter().all(|dep| dep.name != "numpy"));
+
+        let hashes_of = |name: &str| {
+            deps.iter()
+                .find(|spec| spec.name == name)
+                .map(|spec| spec.hashes.clone())
+                .unwrap_or_default()
+        };
+        assert_eq!(hashes_of("requests"), vec!["sha256:aaaa", "sha256:bbbb"]);
+        assert_eq!(hashes_of("flask"), vec!["sha256:cccc"]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn parse_requirements_file_reports_missing_include() {
+        let dir = unique_temp_dir("requirements-missing-include");
+        let root = dir.join("requirements.txt");
+        std::fs::write(&root, "requests==2.31.0\n-r missing.txt\n").expect("write requirements");
+
+        let err = parse_requirements_file(&root).expect_err("missing include should fail");
+        assert!(matches!(err, LockfileError::ReadFile { .. }));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn parse_dependencies_dispatches_by_filename() {
         let parser = PypiLockfileParser::new();
@@ -424,6 +660,7 @@ python = "^3.11"
 httpx = "==0.27.0"
 rich = { version = "=13.7.1" }
 click = "^8.0"
+internal = { git = "https://example.com/internal.git", tag = "v1" }
 
 [tool.poetry.group.docs.dependencies]
 mkdocs = "1.6.0"
@@ -440,6 +677,21 @@ mkdocs = "1.6.0"
         assert_eq!(find_version(&deps, "click"), None);
         assert_eq!(find_version(&deps, "mkdocs"), Some("1.6.0"));
         assert!(deps.iter().all(|dep| dep.name != "python"));
+        let internal = deps
+            .iter()
+            .find(|dep| dep.name == "internal")
+            .expect("git dependency");
+        assert_eq!(internal.unsupported_source, Some(UnsupportedSource::Git));
+
+        let scope_of = |name: &str| {
+            deps.iter()
+                .find(|spec| spec.name == name)
+                .map(|spec| spec.scope)
+        };
+        assert_eq!(scope_of("requests"), Some(DependencyScope::Prod));
+        assert_eq!(scope_of("pytest"), Some(DependencyScope::Optional));
+        assert_eq!(scope_of("httpx"), Some(DependencyScope::Prod));
+        assert_eq!(scope_of("mkdocs"), Some(DependencyScope::Dev));
 
         let _ = std::fs::remove_file(path);
         let _ = std::fs::remove_dir_all(dir);
@@ -476,6 +728,15 @@ mkdocs = "1.6.0"
             parse_python_requirement_line("demo @ https://example.com/demo.whl").expect("direct");
         assert_eq!(direct.name, "demo");
         assert!(direct.version.is_none());
+        assert_eq!(direct.unsupported_source, Some(UnsupportedSource::Url));
+
+        let vcs = parse_python_requirement_line("demo @ git+https://example.com/demo.git@v1")
+            .expect("vcs");
+        assert_eq!(vcs.unsupported_source, Some(UnsupportedSource::Git));
+        let local =
+            parse_python_requirement_line("demo @ file:///src/demo").expect("local reference");
+        assert_eq!(local.unsupported_source, Some(UnsupportedSource::Path));
+        assert_eq!(pinned.unsupported_source, None);
 
         assert!(parse_python_requirement_line("# comment").is_none());
         assert!(parse_python_requirement_line("-r other.txt").is_none());
@@ -524,13 +785,16 @@ mkdocs = "1.6.0"
 
     #[test]
     fn insert_dependency_spec_prefers_exact_pin_over_unpinned() {
-        let mut deps = BTreeMap::<String, Option<String>>::new();
+        let mut deps = BTreeMap::<String, DependencySpec>::new();
         insert_dependency_spec(&mut deps, direct_dependency_spec("demo".to_string(), None));
         insert_dependency_spec(
             &mut deps,
             direct_dependency_spec("demo".to_string(), Some("1.0.0".to_string())),
         );
         insert_dependency_spec(&mut deps, direct_dependency_spec("demo".to_string(), None));
-        assert_eq!(deps.get("demo"), Some(&Some("1.0.0".to_string())));
+        assert_eq!(
+            deps.get("demo").and_then(|spec| spec.version.as_deref()),
+            Some("1.0.0")
+        );
     }
 }
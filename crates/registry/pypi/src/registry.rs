@@ -8,12 +8,13 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use safe_pkgs_core::{
-    PackageAdvisory, PackageRecord, PackageVersion, RegistryClient, RegistryEcosystem,
-    RegistryError,
+    LocalPopularNames, PackageAdvisory, PackageRecord, PackageVersion, RegistryClient,
+    RegistryClientOptions, RegistryEcosystem, RegistryError, RequestLimits,
+    normalize_repository_url,
 };
-use safe_pkgs_osv::query_advisories;
+use safe_pkgs_osv::AdvisoryPrefetch;
 use safe_pkgs_registry_http::{
-    RetryPolicy, build_http_client, map_status_error, parse_json, send_with_retry,
+    RegistryAuth, RetryPolicy, build_http_client, map_status_error, parse_json, send_with_retry,
 };
 
 const DEFAULT_PYPI_API_BASE_URL: &str = "https://pypi.org/pypi";
@@ -24,40 +25,59 @@ const DEFAULT_PYPI_POPULAR_INDEX_URL: &str =
 #[derive(Clone)]
 pub struct PypiRegistryClient {
     http: reqwest::Client,
+    limits: RequestLimits,
     package_api_base_url: String,
     downloads_api_base_url: String,
     popular_index_url: String,
-    auth_token: Option<String>,
+    auth: Option<RegistryAuth>,
     popular_names_cache: Arc<RwLock<Option<Vec<String>>>>,
-}
-
-/// Reads a registry token env var, treating empty/whitespace values as `None`.
-fn token_from_env(var: &str) -> Option<String> {
-    env::var(var)
-        .ok()
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
+    local_popular_names: Option<Arc<LocalPopularNames>>,
+    advisories: AdvisoryPrefetch,
 }
 
 impl PypiRegistryClient {
     pub fn new() -> Self {
         Self {
             http: build_http_client(),
+            limits: RequestLimits::default(),
             package_api_base_url: env::var("SAFE_PKGS_PYPI_PACKAGE_API_BASE_URL")
                 .unwrap_or_else(|_| DEFAULT_PYPI_API_BASE_URL.to_string()),
             downloads_api_base_url: env::var("SAFE_PKGS_PYPI_DOWNLOADS_API_BASE_URL")
                 .unwrap_or_else(|_| DEFAULT_PYPI_DOWNLOADS_API_BASE_URL.to_string()),
             popular_index_url: env::var("SAFE_PKGS_PYPI_POPULAR_INDEX_URL")
                 .unwrap_or_else(|_| DEFAULT_PYPI_POPULAR_INDEX_URL.to_string()),
-            auth_token: token_from_env("SAFE_PKGS_PYPI_REGISTRY_TOKEN"),
+            auth: RegistryAuth::from_env(
+                "SAFE_PKGS_PYPI_REGISTRY_TOKEN",
+                "SAFE_PKGS_PYPI_REGISTRY_USERNAME",
+                "SAFE_PKGS_PYPI_REGISTRY_PASSWORD",
+            ),
             popular_names_cache: Arc::new(RwLock::new(None)),
+            local_popular_names: None,
+            advisories: AdvisoryPrefetch::default(),
         }
     }
 
-    /// Adds a bearer token to the request when a private-registry token is configured.
+    /// Creates a client with startup overrides applied on top of env/default settings.
+    pub fn with_options(options: &RegistryClientOptions) -> Self {
+        let mut client = Self::new();
+        client.limits = options.limits.clone();
+        client.advisories = AdvisoryPrefetch::new(options.limits.clone());
+        if let Some(base_url) = &options.base_url {
+            client.package_api_base_url = base_url.clone();
+        }
+        if let Some(path) = &options.popular_names_file {
+            client.local_popular_names = Some(Arc::new(LocalPopularNames::new(path)));
+        }
+        if let Some(url) = &options.popular_index_url {
+            client.popular_index_url = url.clone();
+        }
+        client
+    }
+
+    /// Adds the `Authorization` header when private-registry credentials are configured.
     fn authorized(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-        match &self.auth_token {
-            Some(token) => builder.bearer_auth(token),
+        match &self.auth {
+            Some(auth) => auth.apply(builder),
             None => builder,
         }
     }
@@ -75,6 +95,10 @@ impl RegistryClient for PypiRegistryClient {
         RegistryEcosystem::PyPI
     }
 
+    fn base_url(&self) -> Option<&str> {
+        Some(self.package_api_base_url.as_str())
+    }
+
     async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
         let url = format!(
             "{}/{}/json",
@@ -85,6 +109,7 @@ impl RegistryClient for PypiRegistryClient {
             || self.authorized(self.http.get(&url)),
             "PyPI API",
             RetryPolicy::default(),
+            &self.limits,
         )
         .await?;
 
@@ -128,19 +153,37 @@ impl RegistryClient for PypiRegistryClient {
                         published,
                         deprecated,
                         install_scripts: Vec::new(),
+                        unpacked_size: None,
+                        file_count: None,
+                        maintainer_emails: Vec::new(),
+                        bin_targets: Vec::new(),
+                        license: None,
+                        repository_url: None,
+                        published_estimated: false,
                     },
                 )
             })
             .collect::<BTreeMap<_, _>>();
 
-        versions
+        // `info` describes the latest release only, so older versions carry no license or
+        // repository.
+        let latest_version = versions
             .entry(latest.clone())
             .or_insert_with(|| PackageVersion {
                 version: latest.clone(),
                 published: None,
                 deprecated: false,
                 install_scripts: Vec::new(),
+                unpacked_size: None,
+                file_count: None,
+                maintainer_emails: Vec::new(),
+                bin_targets: Vec::new(),
+                license: None,
+                repository_url: None,
+                published_estimated: false,
             });
+        latest_version.license = body.info.license();
+        latest_version.repository_url = body.info.repository_url();
 
         Ok(PackageRecord {
             name: package.to_string(),
@@ -160,6 +203,7 @@ impl RegistryClient for PypiRegistryClient {
             || self.http.get(&url),
             "PyPI downloads API",
             RetryPolicy::default(),
+            &self.limits,
         )
         .await?;
 
@@ -184,6 +228,10 @@ impl RegistryClient for PypiRegistryClient {
             return Ok(Vec::new());
         }
 
+        if let Some(local) = &self.local_popular_names {
+            return local.fetch(limit);
+        }
+
         {
             let cache_guard = self.popular_names_cache.read().await;
             if let Some(cached) = cache_guard.as_ref()
@@ -197,6 +245,7 @@ impl RegistryClient for PypiRegistryClient {
             || self.http.get(&self.popular_index_url),
             "PyPI popularity index",
             RetryPolicy::default(),
+            &self.limits,
         )
         .await?;
 
@@ -226,12 +275,24 @@ impl RegistryClient for PypiRegistryClient {
         Ok(names.into_iter().take(limit).collect())
     }
 
+    async fn prefetch_advisories(
+        &self,
+        packages: &[(String, String)],
+        concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        self.advisories
+            .prefetch(packages, self.ecosystem(), concurrency)
+            .await
+    }
+
     async fn fetch_advisories(
         &self,
         package: &str,
         version: &str,
     ) -> Result<Vec<PackageAdvisory>, RegistryError> {
-        query_advisories(package, version, self.ecosystem()).await
+        self.advisories
+            .query(package, version, self.ecosystem())
+            .await
     }
 }
 
@@ -272,6 +333,35 @@ struct PypiInfo {
     version: Option<String>,
     author: Option<String>,
     maintainer: Option<String>,
+    license: Option<String>,
+    license_expression: Option<String>,
+    #[serde(default)]
+    project_urls: Option<BTreeMap<String, String>>,
+}
+
+/// `project_urls` labels that point at the source repository, in preference order.
+const REPOSITORY_URL_LABELS: [&str; 4] = ["repository", "source", "source code", "code"];
+
+impl PypiInfo {
+    /// SPDX `License-Expression` when set, else the free-form `License` field.
+    fn license(&self) -> Option<String> {
+        [&self.license_expression, &self.license]
+            .into_iter()
+            .flatten()
+            .map(|license| license.trim())
+            // Long `License` values are usually the full license text, not a name.
+            .find(|license| !license.is_empty() && !license.contains('\n'))
+            .map(ToOwned::to_owned)
+    }
+
+    fn repository_url(&self) -> Option<String> {
+        let urls = self.project_urls.as_ref()?;
+        REPOSITORY_URL_LABELS.iter().find_map(|label| {
+            urls.iter()
+                .find(|(key, _)| key.trim().eq_ignore_ascii_case(label))
+                .and_then(|(_, url)| normalize_repository_url(url))
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -315,11 +405,14 @@ mod tests {
     fn test_client_with_token(base_url: &str, auth_token: Option<&str>) -> PypiRegistryClient {
         PypiRegistryClient {
             http: build_http_client(),
+            limits: RequestLimits::default(),
             package_api_base_url: base_url.to_string(),
             downloads_api_base_url: base_url.to_string(),
             popular_index_url: format!("{}/top.json", base_url.trim_end_matches('/')),
-            auth_token: auth_token.map(str::to_string),
+            auth: auth_token.map(|token| RegistryAuth::Bearer(token.to_string())),
             popular_names_cache: Arc::new(RwLock::new(None)),
+            local_popular_names: None,
+            advisories: AdvisoryPrefetch::default(),
         }
     }
 
@@ -335,6 +428,9 @@ mod tests {
             version: Some("1.0.0".to_string()),
             author: Some("Alice".to_string()),
             maintainer: Some(" alice ".to_string()),
+            license: None,
+            license_expression: None,
+            project_urls: None,
         };
         assert_eq!(collect_publishers(&info), vec!["alice"]);
     }
@@ -356,6 +452,35 @@ mod tests {
         assert!(matches!(err, RegistryError::NotFound { .. }));
     }
 
+    #[tokio::test]
+    async fn with_options_base_url_is_used_for_fetch_package() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/demo/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{
+                  "info": { "version": "1.0.0", "author": null, "maintainer": null },
+                  "releases": {
+                    "1.0.0": [{ "upload_time_iso_8601": "2024-01-01T00:00:00Z", "yanked": false }]
+                  }
+                }"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let client = PypiRegistryClient::with_options(&RegistryClientOptions {
+            base_url: Some(mock_server.uri()),
+            ..RegistryClientOptions::default()
+        });
+
+        let record = client
+            .fetch_package("demo")
+            .await
+            .expect("override base url should serve package");
+        assert_eq!(record.latest, "1.0.0");
+    }
+
     #[tokio::test]
     async fn fetch_package_parses_releases_and_publishers() {
         let mock_server = MockServer::start().await;
@@ -366,7 +491,12 @@ mod tests {
                   "info": {
                     "version": "1.2.0",
                     "author": "Alice",
-                    "maintainer": "alice"
+                    "maintainer": "alice",
+                    "license": "GPL-3.0-or-later",
+                    "project_urls": {
+                      "Homepage": "https://demo.example",
+                      "Source": "https://gitlab.com/alice/demo.git"
+                    }
                   },
                   "releases": {
                     "1.2.0": [
@@ -388,6 +518,15 @@ mod tests {
         assert_eq!(record.publishers, vec!["alice"]);
         assert!(record.versions.contains_key("1.2.0"));
         assert!(record.versions["1.1.0"].deprecated);
+        assert_eq!(
+            record.versions["1.2.0"].license.as_deref(),
+            Some("GPL-3.0-or-later")
+        );
+        assert_eq!(
+            record.versions["1.2.0"].repository_url.as_deref(),
+            Some("https://gitlab.com/alice/demo")
+        );
+        assert_eq!(record.versions["1.1.0"].license, None);
     }
 
     #[tokio::test]
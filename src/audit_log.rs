@@ -2,18 +2,28 @@
 
 use std::env;
 use std::fs::{self, File, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use chrono::Utc;
 use serde::Serialize;
 
-use crate::types::{Evidence, Metadata, Severity};
+use crate::config::AuditLogConfig;
+use crate::types::{Evidence, EvidenceKind, Metadata, Severity};
 
-/// File-backed logger that writes one JSON record per line.
+/// File-backed logger that writes one JSON record per line (NDJSON).
+///
+/// The file is rotated once a write would take it past `max_bytes`: `<file>.1` shifts to
+/// `<file>.2` and so on, keeping at most `max_files` generations, and the live file becomes
+/// `<file>.1`. Each write holds an in-process mutex plus an exclusive lock on `<file>.lock`,
+/// so concurrent writers in this process or in other processes (CLI runs next to the MCP
+/// server) never interleave records, rotate twice, or write to a half-rotated file.
 pub struct AuditLogger {
-    file: Mutex<File>,
+    lock_file: Mutex<File>,
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
 }
 
 /// Serialized audit event written to the local audit log.
@@ -34,10 +44,21 @@ pub struct AuditRecord {
     reasons: Vec<String>,
     #[serde(default)]
     evidence: Vec<Evidence>,
+    /// Per-check finding breakdown derived from check and custom-rule evidence.
+    findings: Vec<AuditFinding>,
+    /// Checks executed for this decision; empty for cached or short-circuited decisions.
+    checks_run: Vec<String>,
     metadata: Option<Metadata>,
     cached: bool,
 }
 
+/// Compact finding entry used for per-check analytics over the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditFinding {
+    check_id: String,
+    severity: Severity,
+}
+
 /// Input payload for constructing an [`AuditRecord`] package decision.
 pub struct PackageDecision<'a> {
     pub policy_snapshot_version: u8,
@@ -53,6 +74,7 @@ pub struct PackageDecision<'a> {
     pub risk: Severity,
     pub reasons: Vec<String>,
     pub evidence: Vec<Evidence>,
+    pub checks_run: Vec<String>,
     pub metadata: Option<Metadata>,
     pub cached: bool,
 }
@@ -63,41 +85,100 @@ impl AuditLogger {
     /// # Errors
     ///
     /// Returns an error if directories cannot be created or the file cannot be opened.
-    pub fn new() -> anyhow::Result<Self> {
-        let log_path = audit_log_path();
-        if let Some(parent) = log_path.parent() {
+    pub fn new(config: &AuditLogConfig) -> anyhow::Result<Self> {
+        Self::open(audit_log_path(), config.max_log_bytes, config.max_files)
+    }
+
+    fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)?;
+        open_append(&path)?;
+        let lock_file = open_append(&suffixed_path(&path, "lock"))?;
         Ok(Self {
-            file: Mutex::new(file),
+            lock_file: Mutex::new(lock_file),
+            path,
+            max_bytes,
+            max_files: max_files.max(1),
         })
     }
 
-    /// Appends a single JSON record followed by newline.
+    /// Appends a single JSON record followed by newline, rotating the file first when the
+    /// record would push it past the size limit.
     ///
     /// # Errors
     ///
-    /// Returns an error if serialization fails, writing fails, or the mutex is poisoned.
+    /// Returns an error if serialization fails, locking, rotation or writing fails, or the
+    /// mutex is poisoned.
     pub fn log(&self, record: AuditRecord) -> anyhow::Result<()> {
-        let mut file = self
-            .file
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        let lock_file = self
+            .lock_file
             .lock()
             .map_err(|_| anyhow::anyhow!("audit log mutex poisoned"))?;
-        let json = serde_json::to_string(&record)?;
-        file.write_all(json.as_bytes())?;
-        file.write_all(b"\n")?;
+        lock_file.lock()?;
+        let result = self.append_locked(line.as_bytes());
+        lock_file.unlock()?;
+        result
+    }
+
+    /// Rotates if needed and appends `line`. The live file is reopened on every write
+    /// because another process may have rotated it since the last one.
+    fn append_locked(&self, line: &[u8]) -> anyhow::Result<()> {
+        let current_len = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == ErrorKind::NotFound => 0,
+            Err(err) => return Err(err.into()),
+        };
+        if current_len > 0 && current_len.saturating_add(line.len() as u64) > self.max_bytes {
+            self.rotate()?;
+        }
+        let mut file = open_append(&self.path)?;
+        file.write_all(line)?;
         file.flush()?;
         Ok(())
     }
+
+    /// Shifts `<file>.N-1` -> `<file>.N` down to `<file>` -> `<file>.1`, dropping the oldest.
+    fn rotate(&self) -> std::io::Result<()> {
+        for generation in (1..self.max_files).rev() {
+            rename_if_exists(
+                &rotated_path(&self.path, generation),
+                &rotated_path(&self.path, generation + 1),
+            )?;
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))
+    }
+}
+
+fn open_append(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rename_if_exists(from: &Path, to: &Path) -> std::io::Result<()> {
+    match fs::rename(from, to) {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Path of a rotated generation (`audit.log` -> `audit.log.<generation>`).
+fn rotated_path(path: &Path, generation: usize) -> PathBuf {
+    suffixed_path(path, &generation.to_string())
+}
+
+fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut suffixed = path.as_os_str().to_os_string();
+    suffixed.push(".");
+    suffixed.push(suffix);
+    PathBuf::from(suffixed)
 }
 
 impl AuditRecord {
     /// Builds an audit record for a package decision event.
     pub fn package_decision(input: PackageDecision<'_>) -> Self {
+        let findings = input.evidence.iter().filter_map(audit_finding).collect();
         Self {
             timestamp: Utc::now().to_rfc3339(),
             policy_snapshot_version: input.policy_snapshot_version,
@@ -113,12 +194,30 @@ impl AuditRecord {
             risk: input.risk,
             reasons: input.reasons,
             evidence: input.evidence,
+            findings,
+            checks_run: input.checks_run,
             metadata: input.metadata,
             cached: input.cached,
         }
     }
 }
 
+/// Maps check evidence (`<check_id>.<reason_code>`) and custom-rule evidence to a finding entry.
+fn audit_finding(evidence: &Evidence) -> Option<AuditFinding> {
+    let check_id = match evidence.kind {
+        EvidenceKind::Check => evidence
+            .id
+            .split_once('.')
+            .map_or(evidence.id.as_str(), |(check_id, _)| check_id),
+        EvidenceKind::CustomRule => evidence.id.as_str(),
+        EvidenceKind::Policy | EvidenceKind::Runtime => return None,
+    };
+    Some(AuditFinding {
+        check_id: check_id.to_string(),
+        severity: evidence.severity,
+    })
+}
+
 fn audit_log_path() -> PathBuf {
     if let Some(explicit) = env::var_os("SAFE_PKGS_AUDIT_LOG_FILE_PATH") {
         return PathBuf::from(explicit);
@@ -112,9 +112,19 @@ CREATE INDEX IF NOT EXISTS idx_cache_entries_expires_at ON cache_entries (expire
     /// Returns an error if clock math overflows, the SQLite write fails,
     /// or the cache mutex is poisoned.
     pub fn set(&self, key: &str, value: &str) -> anyhow::Result<()> {
+        self.set_with_ttl(key, value, self.ttl)
+    }
+
+    /// Upserts a cache entry that expires after `ttl` instead of the cache-wide TTL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if clock math overflows, the SQLite write fails,
+    /// or the cache mutex is poisoned.
+    pub fn set_with_ttl(&self, key: &str, value: &str, ttl: Duration) -> anyhow::Result<()> {
         let now = unix_now()?;
         let ttl_seconds =
-            i64::try_from(self.ttl.as_secs()).context("cache ttl seconds exceeds i64 range")?;
+            i64::try_from(ttl.as_secs()).context("cache ttl seconds exceeds i64 range")?;
         let expires_at = now
             .checked_add(ttl_seconds)
             .ok_or_else(|| anyhow!("cache expiry timestamp overflow"))?;
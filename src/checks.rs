@@ -2,18 +2,36 @@
 
 use std::collections::{BTreeMap, HashSet};
 use std::sync::OnceLock;
+use std::time::Instant;
 
 use chrono::{DateTime, Utc};
 use safe_pkgs_core::{
-    Check, CheckExecutionContext, CheckId, CheckPolicy, FindingValue, Metadata, PackageRecord,
-    PackageVersion, RegistryClient, RegistryError, Severity, StalenessPolicy, normalize_check_id,
+    AdvisoryPolicy, BusFactorPolicy, Check, CheckExecutionContext, CheckId, CheckPolicy,
+    ExistencePolicy, FindingValue, InstallScriptPolicy, Metadata, PackageAdvisory, PackageRecord,
+    PackageVersion, RegistryClient, RegistryEcosystem, RegistryError, ReleaseChurnPolicy, Severity,
+    StaleStablePolicy, StalenessPolicy, TyposquatPolicy, VersionAgePolicy, VersionScheme,
+    glob_matches, normalize_check_id,
 };
 use serde_json::json;
 
-use crate::config::SafePkgsConfig;
+use crate::config::{
+    EnforcementMode, ExistenceConfig, ListPrecedence, SafePkgsConfig, SuppressionConfig,
+};
 use crate::custom_rules;
 use crate::types::{Evidence, EvidenceKind};
 
+/// Checks whose version comparisons fall back or bail out for unparseable versions.
+const SEMVER_DEPENDENT_CHECKS: &[CheckId] = &["advisory", "staleness"];
+
+/// Evidence id of the `require_exact_version` finding, which denies on its own.
+const NON_EXACT_REQUEST_EVIDENCE_ID: &str = "version.non_exact_request";
+
+/// Evidence id recorded in place of a suppressed finding.
+const SUPPRESSION_EVIDENCE_ID: &str = "suppression.applied";
+
+/// Adoption-based checks skipped for packages from `allowlist.publishers`.
+const TRUSTED_PUBLISHER_SKIPPED_CHECKS: &[CheckId] = &["popularity", "version_age"];
+
 /// Lightweight metadata about each registered check.
 #[derive(Debug, Clone, Copy)]
 pub struct CheckDescriptor {
@@ -25,6 +43,8 @@ pub struct CheckDescriptor {
     pub needs_weekly_downloads: bool,
     /// Whether the check needs advisory data.
     pub needs_advisories: bool,
+    /// Whether the check needs package size metadata.
+    pub needs_size: bool,
 }
 
 /// Data-fetch requirements derived from enabled checks.
@@ -36,6 +56,11 @@ pub struct CheckRuntimeRequirements {
     pub needs_advisories: bool,
     /// True when at least one enabled check needs popular package name data.
     pub needs_popular_package_names: bool,
+    /// True when a custom rule compares weekly downloads against the stored baseline.
+    pub needs_weekly_downloads_baseline: bool,
+    /// True when a check, custom rule, or policy reads metadata that abbreviated registry
+    /// documents omit (publish times, publishers, scripts, licenses, repository links).
+    pub needs_full_metadata: bool,
 }
 
 /// Final result produced by running all enabled checks.
@@ -45,12 +70,40 @@ pub struct CheckReport {
     pub allow: bool,
     /// Aggregated risk level across all findings.
     pub risk: Severity,
+    /// Numeric 0-100 risk score from the findings; see [`risk_score`].
+    pub score: u8,
     /// Human-readable reasons for the decision.
     pub reasons: Vec<String>,
     /// Machine-readable evidence for each emitted finding/policy outcome.
     pub evidence: Vec<Evidence>,
     /// Collected metadata included in the response.
     pub metadata: Metadata,
+    /// IDs of checks that executed; empty when policy short-circuited evaluation.
+    pub checks_run: Vec<String>,
+}
+
+/// Points each finding adds to the risk score, by severity.
+const fn score_weight(severity: Severity) -> u32 {
+    match severity {
+        Severity::Info => 0,
+        Severity::Low => 5,
+        Severity::Medium => 15,
+        Severity::High => 35,
+        Severity::Critical => 70,
+    }
+}
+
+/// Numeric 0-100 risk score for dashboards and trending.
+///
+/// Each finding adds a weight by severity (info 0, low 5, medium 15, high 35, critical 70)
+/// and the sum is capped at 100. Unlike the categorical `risk`, the score grows with the
+/// number of findings, and it never gates allow/deny.
+pub fn risk_score(severities: impl IntoIterator<Item = Severity>) -> u8 {
+    let total = severities
+        .into_iter()
+        .map(score_weight)
+        .fold(0u32, u32::saturating_add);
+    total.min(100) as u8
 }
 
 /// Returns descriptors for all checks registered by the application.
@@ -63,6 +116,7 @@ pub fn check_descriptors() -> Vec<CheckDescriptor> {
             description: check.description(),
             needs_weekly_downloads: check.needs_weekly_downloads(),
             needs_advisories: check.needs_advisories(),
+            needs_size: check.needs_size(),
         })
         .collect()
 }
@@ -87,10 +141,23 @@ pub fn runtime_requirements_for_registry(
         needs_popular_package_names: checks
             .iter()
             .any(|check| check.needs_popular_package_names()),
+        needs_weekly_downloads_baseline: false,
+        needs_full_metadata: checks.iter().any(|check| check.needs_full_metadata())
+            || policy_needs_full_metadata(registry_key, config),
     }
     .merge(custom_requirements)
 }
 
+/// Whether policy outside the checks (publisher lists, quarantine, change freeze, required
+/// publish dates) reads publish times or publishers for `registry_key`.
+fn policy_needs_full_metadata(registry_key: &str, config: &SafePkgsConfig) -> bool {
+    config.quarantine_hours.is_some()
+        || config.require_publish_dates
+        || config.blackout.after_for_registry(registry_key).is_some()
+        || !config.allowlist.publishers.is_empty()
+        || !config.denylist.publishers.is_empty()
+}
+
 /// Returns deterministic enabled check ids for a registry under current config.
 pub fn enabled_check_ids_for_registry(
     registry_key: &str,
@@ -132,6 +199,7 @@ pub async fn run_all_checks(
         supported_checks,
         registry_client,
         config,
+        None,
         Utc::now(),
     )
     .await
@@ -139,9 +207,15 @@ pub async fn run_all_checks(
 
 /// Runs policy checks for a single package and version request at a fixed timestamp.
 ///
+/// `installed_version` is the version the project already has locked or installed, if known.
+///
 /// # Errors
 ///
 /// Returns a registry error when required upstream calls fail.
+#[allow(
+    clippy::too_many_arguments,
+    reason = "mirrors the check execution context fields"
+)]
 pub async fn run_all_checks_at_time(
     package_name: &str,
     requested_version: Option<&str>,
@@ -149,42 +223,72 @@ pub async fn run_all_checks_at_time(
     supported_checks: &[CheckId],
     registry_client: &dyn RegistryClient,
     config: &SafePkgsConfig,
+    installed_version: Option<&str>,
     evaluation_time: DateTime<Utc>,
 ) -> Result<CheckReport, RegistryError> {
-    // Fast path: denylist package rules always block before any registry calls.
-    if let Some(rule) = matching_package_rule(
-        &config.denylist.packages,
-        package_name,
-        requested_version,
-        None,
-    ) {
-        let reason = format!("{package_name} matched denylist package rule '{rule}'");
-        return Ok(deny_report(
-            reason.clone(),
-            vec![policy_evidence(
-                "denylist.package",
-                Severity::Critical,
-                reason,
-                [
-                    ("package", json!(package_name)),
-                    ("matched_rule", json!(rule)),
-                ],
-            )],
-            Metadata {
-                latest: None,
-                requested: requested_version.map(ToOwned::to_owned),
-                published: None,
-                weekly_downloads: None,
-            },
-        ));
+    match config.enforcement {
+        EnforcementMode::Off => Ok(enforcement_off_report(requested_version)),
+        EnforcementMode::Enforce => {
+            evaluate_package(
+                package_name,
+                requested_version,
+                registry_key,
+                supported_checks,
+                registry_client,
+                config,
+                installed_version,
+                evaluation_time,
+            )
+            .await
+        }
+        EnforcementMode::WarnOnly => evaluate_package(
+            package_name,
+            requested_version,
+            registry_key,
+            supported_checks,
+            registry_client,
+            config,
+            installed_version,
+            evaluation_time,
+        )
+        .await
+        .map(warn_only),
+    }
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    reason = "mirrors the check execution context fields"
+)]
+async fn evaluate_package(
+    package_name: &str,
+    requested_version: Option<&str>,
+    registry_key: &str,
+    supported_checks: &[CheckId],
+    registry_client: &dyn RegistryClient,
+    config: &SafePkgsConfig,
+    installed_version: Option<&str>,
+    evaluation_time: DateTime<Utc>,
+) -> Result<CheckReport, RegistryError> {
+    let ecosystem = registry_client.ecosystem();
+    let allow_first = config.list_precedence == ListPrecedence::AllowFirst;
+    // Fast path: denylist name rules block before any registry calls, unless an allowlisted
+    // version could override them.
+    if !allow_first
+        && let Some(report) =
+            name_denylist_report(package_name, requested_version, ecosystem, config)
+    {
+        return Ok(report);
     }
 
-    let package = match registry_client.fetch_package(package_name).await {
-        Ok(package) => Some(package),
-        // Missing package is handled by checks (primarily existence), not as a transport error.
-        Err(RegistryError::NotFound { .. }) => None,
-        Err(err) => return Err(err),
-    };
+    // Missing package is handled by checks (primarily existence), not as a transport error.
+    let fetch_started = Instant::now();
+    let package =
+        fetch_package_with_not_found_retry(registry_client, package_name, &config.existence)
+            .await?;
+    let fetch_ms = config
+        .include_fetch_latency
+        .then(|| u64::try_from(fetch_started.elapsed().as_millis()).unwrap_or(u64::MAX));
 
     // Dependency confusion: a declared-internal name that ALSO resolves on the public
     // registry indicates a public shadow that installers could pull by mistake.
@@ -210,8 +314,10 @@ pub async fn run_all_checks_at_time(
             Metadata {
                 latest: Some(package.latest.clone()),
                 requested: requested_version.map(ToOwned::to_owned),
+                resolved: None,
                 published: None,
                 weekly_downloads: None,
+                fetch_ms,
             },
         ));
     }
@@ -220,10 +326,34 @@ pub async fn run_all_checks_at_time(
         .as_ref()
         .and_then(|record| record.resolve_version(requested_version));
 
+    if allow_first {
+        // An allowlisted version overrides every denylist rule, so the name rules skipped
+        // above only run once the allowlist has missed.
+        if let (Some(package), Some(resolved_version)) = (package.as_ref(), resolved_version)
+            && let Some(report) = allowlist_report(
+                package_name,
+                requested_version,
+                ecosystem,
+                package,
+                resolved_version,
+                config,
+                fetch_ms,
+            )
+        {
+            return Ok(report);
+        }
+        if let Some(report) =
+            name_denylist_report(package_name, requested_version, ecosystem, config)
+        {
+            return Ok(report);
+        }
+    }
+
     if let (Some(package), Some(resolved_version)) = (package.as_ref(), resolved_version) {
         // Re-evaluate package rules with resolved version metadata when available.
         if let Some(rule) = matching_package_rule(
             &config.denylist.packages,
+            ecosystem,
             package_name,
             requested_version,
             Some(&resolved_version.version),
@@ -244,8 +374,10 @@ pub async fn run_all_checks_at_time(
                 Metadata {
                     latest: Some(package.latest.clone()),
                     requested: requested_version.map(ToOwned::to_owned),
+                    resolved: Some(resolved_version.version.clone()),
                     published: resolved_version.published.map(|ts| ts.to_rfc3339()),
                     weekly_downloads: None,
+                    fetch_ms,
                 },
             ));
         }
@@ -269,58 +401,74 @@ pub async fn run_all_checks_at_time(
                 Metadata {
                     latest: Some(package.latest.clone()),
                     requested: requested_version.map(ToOwned::to_owned),
+                    resolved: Some(resolved_version.version.clone()),
                     published: resolved_version.published.map(|ts| ts.to_rfc3339()),
                     weekly_downloads: None,
+                    fetch_ms,
                 },
             ));
         }
 
-        if let Some(rule) = matching_package_rule(
-            &config.allowlist.packages,
-            package_name,
-            requested_version,
-            Some(&resolved_version.version),
-        ) {
-            let reason = format!("{package_name} matched allowlist package rule '{rule}'");
-            return Ok(allow_report(
-                reason.clone(),
-                vec![policy_evidence(
-                    "allowlist.package",
-                    Severity::Low,
-                    reason,
-                    [
-                        ("package", json!(package_name)),
-                        ("matched_rule", json!(rule)),
-                        ("resolved_version", json!(resolved_version.version.as_str())),
-                    ],
-                )],
-                Metadata {
-                    latest: Some(package.latest.clone()),
-                    requested: requested_version.map(ToOwned::to_owned),
-                    published: resolved_version.published.map(|ts| ts.to_rfc3339()),
-                    weekly_downloads: None,
-                },
-            ));
+        if !allow_first
+            && let Some(report) = allowlist_report(
+                package_name,
+                requested_version,
+                ecosystem,
+                package,
+                resolved_version,
+                config,
+                fetch_ms,
+            )
+        {
+            return Ok(report);
         }
     }
 
     let lookup_state = package_lookup_state(package.as_ref(), resolved_version);
-    let checks = enabled_checks(registry_key, supported_checks, lookup_state, config);
+    let mut checks = enabled_checks(registry_key, supported_checks, lookup_state, config);
+    // Trusted publishers skip adoption-based checks; security checks still run.
+    let trusted_publisher = package
+        .as_ref()
+        .and_then(|record| matching_publisher(&config.allowlist.publishers, &record.publishers));
+    let mut trusted_skipped = Vec::new();
+    if trusted_publisher.is_some() {
+        checks.retain(|check| {
+            let skip = TRUSTED_PUBLISHER_SKIPPED_CHECKS.contains(&check.id());
+            if skip {
+                trusted_skipped.push(check.id());
+            }
+            !skip
+        });
+    }
     let requirements = CheckRuntimeRequirements {
         needs_weekly_downloads: checks.iter().any(|check| check.needs_weekly_downloads()),
         needs_advisories: checks.iter().any(|check| check.needs_advisories()),
         needs_popular_package_names: checks
             .iter()
             .any(|check| check.needs_popular_package_names()),
+        needs_weekly_downloads_baseline: false,
+        needs_full_metadata: checks.iter().any(|check| check.needs_full_metadata())
+            || policy_needs_full_metadata(registry_key, config),
     }
     .merge(custom_rules::runtime_requirements_for_registry(
         config,
         registry_key,
     ));
 
+    // Read before the current downloads are fetched, which may record the first baseline.
+    let weekly_downloads_baseline =
+        if resolved_version.is_some() && requirements.needs_weekly_downloads_baseline {
+            registry_client
+                .fetch_weekly_downloads_baseline(package_name)
+                .await?
+        } else {
+            None
+        };
+
     let metadata = Metadata {
         latest: package.as_ref().map(|record| record.latest.clone()),
         requested: requested_version.map(ToOwned::to_owned),
+        resolved: resolved_version.map(|version| version.version.clone()),
         published: resolved_version.and_then(|version| version.published.map(|ts| ts.to_rfc3339())),
         // Avoid extra registry calls when no enabled check depends on downloads.
         weekly_downloads: if resolved_version.is_some() && requirements.needs_weekly_downloads {
@@ -328,6 +476,7 @@ pub async fn run_all_checks_at_time(
         } else {
             None
         },
+        fetch_ms,
     };
 
     let advisories = if requirements.needs_advisories {
@@ -349,18 +498,22 @@ pub async fn run_all_checks_at_time(
         registry_key,
         package_name,
         requested_version,
+        installed_version,
         evaluation_time,
         package: package.as_ref(),
         resolved_version,
         weekly_downloads: metadata.weekly_downloads,
+        weekly_downloads_baseline,
         advisories: &advisories,
         registry_client,
         policy: &policy,
     };
 
     let mut findings = Vec::new();
+    let mut checks_run = Vec::with_capacity(checks.len());
     for check in checks {
         let check_id = check.id();
+        checks_run.push(check_id.to_string());
         findings.extend(
             check
                 .run(&execution_context)
@@ -370,12 +523,19 @@ pub async fn run_all_checks_at_time(
                     let severity = finding.severity;
                     let reason = finding.reason.clone();
                     let evidence_id = format!("{check_id}.{}", finding.reason_code);
+                    // Checks without a registered code fall back to their evidence id.
+                    let code = if finding.code.is_empty() {
+                        evidence_id.clone()
+                    } else {
+                        finding.code.to_string()
+                    };
                     StructuredFinding {
                         severity,
                         reason: reason.clone(),
                         evidence: Evidence {
                             kind: EvidenceKind::Check,
                             id: evidence_id,
+                            code,
                             severity,
                             message: reason,
                             facts: finding
@@ -383,11 +543,75 @@ pub async fn run_all_checks_at_time(
                                 .into_iter()
                                 .map(|(key, value)| (key, finding_value_to_json(value)))
                                 .collect(),
+                            remediation: finding.remediation,
                         },
                     }
                 }),
         );
     }
+    if let Some(publisher) = trusted_publisher
+        && !trusted_skipped.is_empty()
+    {
+        findings.push(trusted_publisher_finding(
+            package_name,
+            publisher,
+            &trusted_skipped,
+        ));
+    }
+    if let Some(finding) = blackout_finding(config, registry_key, package_name, resolved_version) {
+        findings.push(finding);
+    }
+    if let Some(finding) =
+        quarantine_finding(config, package_name, resolved_version, evaluation_time)
+    {
+        findings.push(finding);
+    }
+    if let Some(finding) = pinned_vulnerable_major_finding(
+        package_name,
+        package.as_ref(),
+        resolved_version,
+        &advisories,
+        registry_client.ecosystem().version_scheme(),
+        &checks_run,
+    ) {
+        findings.push(finding);
+    }
+    if let Some(finding) =
+        unsupported_version_scheme_finding(package_name, resolved_version, ecosystem, &checks_run)
+    {
+        findings.push(finding);
+    }
+    if config.warn_on_unparseable_version
+        && let Some(finding) = unparseable_version_finding(
+            package_name,
+            resolved_version,
+            registry_client.ecosystem().version_scheme(),
+            &checks_run,
+        )
+    {
+        findings.push(finding);
+    }
+    if config.require_publish_dates
+        && let Some(finding) = no_publish_dates_finding(package_name, package.as_ref())
+    {
+        findings.push(finding);
+    }
+    if config.require_exact_version
+        && let Some(finding) =
+            non_exact_version_finding(package_name, requested_version, ecosystem, resolved_version)
+    {
+        findings.push(finding);
+    }
+    if let (Some(installed), Some(resolved)) = (installed_version, resolved_version)
+        && let Some(finding) = upgrade_from_current_finding(
+            package_name,
+            installed,
+            &resolved.version,
+            ecosystem.version_scheme(),
+        )
+    {
+        findings.push(finding);
+    }
     findings.extend(
         custom_rules::findings_for_package(config, &execution_context)
             .into_iter()
@@ -400,6 +624,7 @@ pub async fn run_all_checks_at_time(
                     reason: reason.clone(),
                     evidence: Evidence {
                         kind: EvidenceKind::CustomRule,
+                        code: evidence_id.clone(),
                         id: evidence_id,
                         severity,
                         message: reason,
@@ -409,12 +634,158 @@ pub async fn run_all_checks_at_time(
                             .into_iter()
                             .map(|(key, value)| (key, finding_value_to_json(value)))
                             .collect(),
+                        remediation: None,
                     },
                 }
             }),
     );
 
-    Ok(report_from_findings(findings, metadata, config.max_risk))
+    let findings = apply_suppressions(
+        findings,
+        &config.suppressions,
+        ecosystem,
+        package_name,
+        requested_version,
+        resolved_version.map(|version| version.version.as_str()),
+        evaluation_time,
+    );
+
+    let mut report = report_from_findings(
+        findings,
+        metadata,
+        checks_run,
+        config.max_risk,
+        config.never_deny_below,
+        ReasonLimits::from_config(config),
+    );
+    // Strict pinning denies whatever `max_risk` allows; the finding itself stays medium.
+    if report
+        .evidence
+        .iter()
+        .any(|evidence| evidence.id == NON_EXACT_REQUEST_EVIDENCE_ID)
+    {
+        report.allow = false;
+    }
+    Ok(report)
+}
+
+/// Whether the decision for this request is reached without any registry lookup: enforcement
+/// is off, or a denylist name rule blocks it before metadata is fetched.
+pub fn decided_without_registry(
+    package_name: &str,
+    requested_version: Option<&str>,
+    ecosystem: RegistryEcosystem,
+    config: &SafePkgsConfig,
+) -> bool {
+    config.enforcement == EnforcementMode::Off
+        || (config.list_precedence != ListPrecedence::AllowFirst
+            && name_denylist_report(package_name, requested_version, ecosystem, config).is_some())
+}
+
+/// Denylist package and name-pattern rules that match without registry metadata.
+fn name_denylist_report(
+    package_name: &str,
+    requested_version: Option<&str>,
+    ecosystem: RegistryEcosystem,
+    config: &SafePkgsConfig,
+) -> Option<CheckReport> {
+    if let Some(rule) = matching_package_rule(
+        &config.denylist.packages,
+        ecosystem,
+        package_name,
+        requested_version,
+        None,
+    ) {
+        let reason = format!("{package_name} matched denylist package rule '{rule}'");
+        return Some(deny_report(
+            reason.clone(),
+            vec![policy_evidence(
+                "denylist.package",
+                Severity::Critical,
+                reason,
+                [
+                    ("package", json!(package_name)),
+                    ("matched_rule", json!(rule)),
+                ],
+            )],
+            Metadata {
+                latest: None,
+                requested: requested_version.map(ToOwned::to_owned),
+                resolved: None,
+                published: None,
+                weekly_downloads: None,
+                fetch_ms: None,
+            },
+        ));
+    }
+
+    if let Some(pattern) = matching_name_pattern(&config.denylist.patterns, package_name) {
+        let reason = format!("{package_name} matched denylist name pattern '{pattern}'");
+        return Some(deny_report(
+            reason.clone(),
+            vec![policy_evidence(
+                "denylist.pattern",
+                Severity::Critical,
+                reason,
+                [
+                    ("package", json!(package_name)),
+                    ("matched_pattern", json!(pattern)),
+                ],
+            )],
+            Metadata {
+                latest: None,
+                requested: requested_version.map(ToOwned::to_owned),
+                resolved: None,
+                published: None,
+                weekly_downloads: None,
+                fetch_ms: None,
+            },
+        ));
+    }
+    None
+}
+
+/// Allowlist package rules evaluated against the resolved version.
+fn allowlist_report(
+    package_name: &str,
+    requested_version: Option<&str>,
+    ecosystem: RegistryEcosystem,
+    package: &PackageRecord,
+    resolved_version: &PackageVersion,
+    config: &SafePkgsConfig,
+    fetch_ms: Option<u64>,
+) -> Option<CheckReport> {
+    if let Some(rule) = matching_package_rule(
+        &config.allowlist.packages,
+        ecosystem,
+        package_name,
+        requested_version,
+        Some(&resolved_version.version),
+    ) {
+        let reason = format!("{package_name} matched allowlist package rule '{rule}'");
+        return Some(allow_report(
+            reason.clone(),
+            vec![policy_evidence(
+                "allowlist.package",
+                Severity::Low,
+                reason,
+                [
+                    ("package", json!(package_name)),
+                    ("matched_rule", json!(rule)),
+                    ("resolved_version", json!(resolved_version.version.as_str())),
+                ],
+            )],
+            Metadata {
+                latest: Some(package.latest.clone()),
+                requested: requested_version.map(ToOwned::to_owned),
+                resolved: Some(resolved_version.version.clone()),
+                published: resolved_version.published.map(|ts| ts.to_rfc3339()),
+                weekly_downloads: None,
+                fetch_ms,
+            },
+        ));
+    }
+    None
 }
 
 impl CheckRuntimeRequirements {
@@ -423,6 +794,8 @@ impl CheckRuntimeRequirements {
             needs_weekly_downloads: self.needs_weekly_downloads || custom.needs_weekly_downloads,
             needs_advisories: self.needs_advisories || custom.needs_advisories,
             needs_popular_package_names: self.needs_popular_package_names,
+            needs_weekly_downloads_baseline: custom.needs_weekly_downloads_baseline,
+            needs_full_metadata: self.needs_full_metadata || custom.needs_full_metadata,
         }
     }
 }
@@ -434,6 +807,35 @@ enum PackageLookupState {
     Ready,
 }
 
+/// Re-fetches a package that returned NotFound so a transient registry blip is not
+/// reported as a missing (Critical) package. A NotFound that persists is returned as `None`.
+async fn fetch_package_with_not_found_retry(
+    registry_client: &dyn RegistryClient,
+    package_name: &str,
+    existence: &ExistenceConfig,
+) -> Result<Option<PackageRecord>, RegistryError> {
+    let mut retries_left = existence.not_found_retries;
+    loop {
+        match registry_client.fetch_package(package_name).await {
+            Ok(package) => return Ok(Some(package)),
+            Err(RegistryError::NotFound { .. }) if retries_left > 0 => {
+                retries_left -= 1;
+                tracing::debug!(
+                    package = package_name,
+                    retries_left,
+                    "registry returned not found; retrying before concluding package is missing"
+                );
+                tokio::time::sleep(std::time::Duration::from_millis(
+                    existence.not_found_retry_delay_ms,
+                ))
+                .await;
+            }
+            Err(RegistryError::NotFound { .. }) => return Ok(None),
+            Err(err) => return Err(err),
+        }
+    }
+}
+
 fn package_lookup_state(
     package: Option<&PackageRecord>,
     resolved_version: Option<&PackageVersion>,
@@ -502,12 +904,40 @@ fn check_policy_from_config(config: &SafePkgsConfig) -> CheckPolicy {
     CheckPolicy {
         min_version_age_days: config.min_version_age_days,
         min_weekly_downloads: config.min_weekly_downloads,
+        version_age: VersionAgePolicy {
+            exempt_patch_upgrades: config.version_age.exempt_patch_upgrades,
+        },
         staleness: StalenessPolicy {
             warn_major_versions_behind: config.staleness.warn_major_versions_behind,
             warn_minor_versions_behind: config.staleness.warn_minor_versions_behind,
             warn_age_days: config.staleness.warn_age_days,
             ignore_for: config.staleness.ignore_for.clone(),
         },
+        typosquat: TyposquatPolicy {
+            fail_open: config.typosquat.fail_open,
+            ignore: config.typosquat.ignore.clone(),
+        },
+        bus_factor: BusFactorPolicy {
+            high_download_threshold: config.bus_factor.high_download_threshold,
+        },
+        install_script: InstallScriptPolicy {
+            detect_persistence: config.install_script.detect_persistence,
+            deny_all: config.install_script.deny_all,
+            allowed_commands: config.install_script.allowed_commands.clone(),
+        },
+        release_churn: ReleaseChurnPolicy {
+            max_releases_per_week: config.release_churn.max_releases_per_week,
+        },
+        stale_stable: StaleStablePolicy {
+            window_days: config.stale_stable.window_days,
+        },
+        advisory: AdvisoryPolicy {
+            min_cvss: config.advisory.min_cvss,
+        },
+        existence: ExistencePolicy {
+            missing_package: config.existence.missing_package,
+            missing_version: config.existence.missing_version,
+        },
     }
 }
 
@@ -520,23 +950,85 @@ struct StructuredFinding {
     evidence: Evidence,
 }
 
+/// Caps on the reasons listed in a report; evidence is never capped.
+#[derive(Debug, Clone, Copy, Default)]
+struct ReasonLimits {
+    max_reasons: Option<usize>,
+    max_reason_length: Option<usize>,
+}
+
+impl ReasonLimits {
+    fn from_config(config: &SafePkgsConfig) -> Self {
+        Self {
+            max_reasons: config.max_reasons,
+            max_reason_length: config.max_reason_length,
+        }
+    }
+
+    /// Keeps the `max_reasons` most severe reasons (ties keep finding order), summarizes
+    /// the rest in one line, and cuts each kept reason to `max_reason_length` characters.
+    fn apply(self, mut reasons: Vec<(Severity, String)>) -> Vec<String> {
+        let mut omitted = 0;
+        if let Some(max_reasons) = self.max_reasons
+            && reasons.len() > max_reasons
+        {
+            reasons.sort_by_key(|(severity, _)| std::cmp::Reverse(*severity));
+            omitted = reasons.len() - max_reasons;
+            reasons.truncate(max_reasons);
+        }
+        let mut limited = reasons
+            .into_iter()
+            .map(|(_, reason)| match self.max_reason_length {
+                Some(max_chars) => truncate_reason(reason, max_chars),
+                None => reason,
+            })
+            .collect::<Vec<_>>();
+        if omitted > 0 {
+            let plural = if omitted == 1 { "" } else { "s" };
+            limited.push(format!("… and {omitted} more finding{plural}"));
+        }
+        limited
+    }
+}
+
+/// Cuts `reason` to at most `max_chars` characters, ending in "…" when shortened.
+fn truncate_reason(reason: String, max_chars: usize) -> String {
+    if reason.chars().count() <= max_chars {
+        return reason;
+    }
+    let kept = reason
+        .chars()
+        .take(max_chars.saturating_sub(1))
+        .collect::<String>();
+    format!("{}…", kept.trim_end())
+}
+
 fn report_from_findings(
     findings: Vec<StructuredFinding>,
     metadata: Metadata,
+    checks_run: Vec<String>,
     max_risk: Severity,
+    never_deny_below: Option<Severity>,
+    reason_limits: ReasonLimits,
 ) -> CheckReport {
+    // Aggregate risk never reports below low; info findings only add reasons/evidence.
     let mut risk = Severity::Low;
+    let mut risk_bearing = false;
     let mut medium_count = 0u32;
+    let score = risk_score(findings.iter().map(|structured| structured.severity));
     let mut reasons = Vec::with_capacity(findings.len());
     let mut evidence = Vec::with_capacity(findings.len().saturating_add(1));
     for structured in findings {
         if structured.severity == Severity::Medium {
             medium_count = medium_count.saturating_add(1);
         }
+        if structured.severity > Severity::Info {
+            risk_bearing = true;
+        }
         if structured.severity > risk {
             risk = structured.severity;
         }
-        reasons.push(structured.reason);
+        reasons.push((structured.severity, structured.reason));
         evidence.push(structured.evidence);
     }
 
@@ -551,15 +1043,74 @@ fn report_from_findings(
         ));
     }
 
+    // With `max_risk = info`, only packages without any risk-bearing finding are allowed.
+    let mut allow = !risk_bearing || risk <= max_risk;
+    // Deny floor: aggregate risk below it is allowed even when above max_risk.
+    // Critical can never fall below a floor, so it always denies.
+    if !allow && let Some(floor) = never_deny_below.filter(|floor| risk < *floor) {
+        allow = true;
+        evidence.push(policy_evidence(
+            "risk.below_deny_floor",
+            risk,
+            "aggregate risk exceeds max_risk but is below the never_deny_below floor; allowed"
+                .to_string(),
+            [
+                ("risk", json!(risk)),
+                ("max_risk", json!(max_risk)),
+                ("never_deny_below", json!(floor)),
+            ],
+        ));
+    }
+
     CheckReport {
-        allow: risk <= max_risk,
+        allow,
         risk,
-        reasons,
+        score,
+        reasons: reason_limits.apply(reasons),
         evidence,
         metadata,
+        checks_run,
     }
 }
 
+/// `enforcement = "off"`: nothing is checked, and the evidence says so.
+fn enforcement_off_report(requested_version: Option<&str>) -> CheckReport {
+    allow_report(
+        "checks skipped: enforcement is off".to_string(),
+        vec![policy_evidence(
+            "enforcement.off",
+            Severity::Info,
+            "enforcement is off; no checks were run".to_string(),
+            [("enforcement", json!(EnforcementMode::Off))],
+        )],
+        Metadata {
+            latest: None,
+            requested: requested_version.map(ToOwned::to_owned),
+            resolved: None,
+            published: None,
+            weekly_downloads: None,
+            fetch_ms: None,
+        },
+    )
+}
+
+/// `enforcement = "warn_only"`: keeps risk and reasons and only blocks critical reports.
+fn warn_only(mut report: CheckReport) -> CheckReport {
+    if !report.allow && report.risk != Severity::Critical {
+        report.allow = true;
+        report.evidence.push(policy_evidence(
+            "enforcement.warn_only",
+            report.risk,
+            "decision would deny, but enforcement is warn_only; allowed".to_string(),
+            [
+                ("risk", json!(report.risk)),
+                ("enforcement", json!(EnforcementMode::WarnOnly)),
+            ],
+        ));
+    }
+    report
+}
+
 fn finding_value_to_json(value: FindingValue) -> serde_json::Value {
     match value {
         FindingValue::String(value) => json!(value),
@@ -570,6 +1121,429 @@ fn finding_value_to_json(value: FindingValue) -> serde_json::Value {
     }
 }
 
+/// Whether a stored decision applied a suppression that has expired by `now`. Cached and
+/// snapshot decisions carrying one must be re-evaluated so the hidden finding reappears;
+/// an unreadable expiry counts as expired.
+pub fn has_expired_suppression(evidence: &[Evidence], now: DateTime<Utc>) -> bool {
+    evidence
+        .iter()
+        .filter(|item| item.id == SUPPRESSION_EVIDENCE_ID)
+        .any(|item| {
+            item.facts
+                .get("until")
+                .and_then(serde_json::Value::as_str)
+                .and_then(|until| DateTime::parse_from_rfc3339(until).ok())
+                .is_none_or(|until| until <= now)
+        })
+}
+
+/// Replaces findings covered by an unexpired suppression with an `Info` note, so the
+/// suppression stays visible without counting toward risk. Expired suppressions are ignored.
+fn apply_suppressions(
+    findings: Vec<StructuredFinding>,
+    suppressions: &[SuppressionConfig],
+    ecosystem: RegistryEcosystem,
+    package_name: &str,
+    requested_version: Option<&str>,
+    resolved_version: Option<&str>,
+    evaluation_time: DateTime<Utc>,
+) -> Vec<StructuredFinding> {
+    let active = suppressions
+        .iter()
+        .filter(|suppression| suppression.until > evaluation_time)
+        .filter(|suppression| {
+            matching_package_rule(
+                std::slice::from_ref(&suppression.package),
+                ecosystem,
+                package_name,
+                requested_version,
+                resolved_version,
+            )
+            .is_some()
+        })
+        .collect::<Vec<_>>();
+    if active.is_empty() {
+        return findings;
+    }
+
+    findings
+        .into_iter()
+        .map(|finding| {
+            let evidence_id = finding.evidence.id.as_str();
+            let check_id = evidence_id
+                .split_once('.')
+                .map_or(evidence_id, |(id, _)| id);
+            let code = finding.evidence.code.as_str();
+            let Some(suppression) = active.iter().find(|suppression| {
+                let target = normalize_check_id(&suppression.check);
+                target == evidence_id
+                    || target == check_id
+                    || code.eq_ignore_ascii_case(suppression.check.trim())
+            }) else {
+                return finding;
+            };
+
+            let reason = format!(
+                "{package_name}: {evidence_id} suppressed until {} ({})",
+                suppression.until.to_rfc3339(),
+                suppression.reason
+            );
+            StructuredFinding {
+                severity: Severity::Info,
+                reason: reason.clone(),
+                evidence: policy_evidence(
+                    SUPPRESSION_EVIDENCE_ID,
+                    Severity::Info,
+                    reason,
+                    [
+                        ("package", json!(package_name)),
+                        ("suppressed_evidence_id", json!(evidence_id)),
+                        ("suppressed_severity", json!(finding.severity)),
+                        ("suppressed_reason", json!(finding.reason)),
+                        ("until", json!(suppression.until.to_rfc3339())),
+                        ("reason", json!(suppression.reason)),
+                    ],
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Records which adoption checks a trusted publisher skipped, so the bypass stays visible.
+fn trusted_publisher_finding(
+    package_name: &str,
+    publisher: &str,
+    skipped_checks: &[CheckId],
+) -> StructuredFinding {
+    let reason = format!(
+        "{package_name} is published by trusted publisher '{publisher}'; skipped {}",
+        skipped_checks.join(", ")
+    );
+    StructuredFinding {
+        severity: Severity::Low,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            "allowlist.trusted_publisher",
+            Severity::Low,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("publisher", json!(publisher)),
+                ("skipped_checks", json!(skipped_checks)),
+            ],
+        ),
+    }
+}
+
+/// Flags versions published after the configured change-freeze start for this registry.
+fn blackout_finding(
+    config: &SafePkgsConfig,
+    registry_key: &str,
+    package_name: &str,
+    resolved_version: Option<&PackageVersion>,
+) -> Option<StructuredFinding> {
+    let blackout_after = config.blackout.after_for_registry(registry_key)?;
+    let resolved_version = resolved_version?;
+    let published = resolved_version.published?;
+    if published <= blackout_after {
+        return None;
+    }
+
+    let reason = format!(
+        "{package_name}@{} was published during change freeze ({} is after {})",
+        resolved_version.version,
+        published.to_rfc3339(),
+        blackout_after.to_rfc3339()
+    );
+    Some(StructuredFinding {
+        severity: Severity::High,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            "blackout.published_during_freeze",
+            Severity::High,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("resolved_version", json!(resolved_version.version.as_str())),
+                ("published", json!(published.to_rfc3339())),
+                ("blackout_after", json!(blackout_after.to_rfc3339())),
+            ],
+        ),
+    })
+}
+
+/// Denies versions published inside the configured quarantine window (`quarantine_hours`).
+fn quarantine_finding(
+    config: &SafePkgsConfig,
+    package_name: &str,
+    resolved_version: Option<&PackageVersion>,
+    evaluation_time: DateTime<Utc>,
+) -> Option<StructuredFinding> {
+    let quarantine_hours = config.quarantine_hours.filter(|hours| *hours > 0)?;
+    let resolved_version = resolved_version?;
+    let published = resolved_version.published?;
+    let age_hours = (evaluation_time - published).num_hours();
+    if age_hours >= quarantine_hours {
+        return None;
+    }
+
+    let reason = format!(
+        "{package_name}@{} is quarantined: published {age_hours} hour(s) ago, inside the {quarantine_hours}h window",
+        resolved_version.version
+    );
+    Some(StructuredFinding {
+        severity: Severity::Critical,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            "quarantine.within_window",
+            Severity::Critical,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("resolved_version", json!(resolved_version.version.as_str())),
+                ("published", json!(published.to_rfc3339())),
+                ("age_hours", json!(age_hours)),
+                ("quarantine_hours", json!(quarantine_hours)),
+            ],
+        ),
+    })
+}
+
+/// Correlates staleness and advisory outcomes: flags a version that is a major behind
+/// `latest` and affected by an advisory whose only fixes are in newer majors, so staying
+/// on the pinned major cannot pick up the fix.
+fn pinned_vulnerable_major_finding(
+    package_name: &str,
+    package: Option<&PackageRecord>,
+    resolved_version: Option<&PackageVersion>,
+    advisories: &[PackageAdvisory],
+    scheme: VersionScheme,
+    checks_run: &[String],
+) -> Option<StructuredFinding> {
+    let both_checks_ran = ["advisory", "staleness"]
+        .iter()
+        .all(|id| checks_run.iter().any(|ran| ran == id));
+    if !both_checks_ran {
+        return None;
+    }
+    let package = package?;
+    let resolved_version = resolved_version?;
+    let resolved = scheme.parse(&resolved_version.version)?;
+    let latest = scheme.parse(&package.latest)?;
+    if latest.major() <= resolved.major() {
+        return None;
+    }
+
+    let mut advisory_ids = Vec::new();
+    let mut fixed_in = Vec::new();
+    for advisory in advisories {
+        let newer_fixes = advisory
+            .fixed_versions
+            .iter()
+            .filter_map(|fixed| scheme.parse(fixed).map(|parsed| (fixed, parsed)))
+            .filter(|(_, parsed)| parsed > &resolved)
+            .collect::<Vec<_>>();
+        let fixed_only_in_later_major = !newer_fixes.is_empty()
+            && newer_fixes
+                .iter()
+                .all(|(_, parsed)| parsed.major() > resolved.major());
+        if fixed_only_in_later_major {
+            advisory_ids.push(advisory.id.clone());
+            fixed_in.extend(newer_fixes.into_iter().map(|(fixed, _)| fixed.clone()));
+        }
+    }
+    if advisory_ids.is_empty() {
+        return None;
+    }
+
+    let reason = format!(
+        "{package_name}@{} is pinned to a vulnerable major ({}.x); {} fixed only in a later major (latest is {})",
+        resolved_version.version,
+        resolved.major(),
+        advisory_ids.join(", "),
+        package.latest
+    );
+    Some(StructuredFinding {
+        severity: Severity::High,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            "advisory.pinned_vulnerable_major",
+            Severity::High,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("resolved_version", json!(resolved_version.version.as_str())),
+                ("latest_version", json!(package.latest.as_str())),
+                ("advisory_ids", json!(advisory_ids)),
+                ("fixed_versions", json!(fixed_in)),
+            ],
+        ),
+    })
+}
+
+/// Surfaces that age-based checks had nothing to measure because no version of the
+/// package carries a publish date.
+fn no_publish_dates_finding(
+    package_name: &str,
+    package: Option<&PackageRecord>,
+) -> Option<StructuredFinding> {
+    let package = package?;
+    if package.versions.is_empty()
+        || package
+            .versions
+            .values()
+            .any(|version| version.published.is_some())
+    {
+        return None;
+    }
+
+    let reason = format!("{package_name}: no publish dates available; age-based checks skipped");
+    Some(StructuredFinding {
+        severity: Severity::Low,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            "version.no_publish_dates",
+            Severity::Low,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("version_count", json!(package.versions.len())),
+            ],
+        ),
+    })
+}
+
+/// Flags a proposed version that moves the project's current version up a major line
+/// (`medium`) or a minor line (`low`). Under `0.x`, where minor bumps may break the API, a
+/// minor move counts as major. Patch bumps, downgrades, and versions that do not parse
+/// under `scheme` yield nothing.
+fn upgrade_from_current_finding(
+    package_name: &str,
+    current_version: &str,
+    proposed_version: &str,
+    scheme: VersionScheme,
+) -> Option<StructuredFinding> {
+    let current = scheme.parse(current_version)?;
+    let proposed = scheme.parse(proposed_version)?;
+    let minor_bump = proposed.major() == current.major() && proposed.minor() > current.minor();
+    let (severity, delta) =
+        if proposed.major() > current.major() || (minor_bump && current.major() == 0) {
+            (Severity::Medium, "major")
+        } else if minor_bump {
+            (Severity::Low, "minor")
+        } else {
+            return None;
+        };
+
+    let reason = format!(
+        "{package_name}: {delta} upgrade from current {current_version} to proposed {proposed_version}"
+    );
+    Some(StructuredFinding {
+        severity,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            &format!("version.{delta}_upgrade"),
+            severity,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("current_version", json!(current_version)),
+                ("proposed_version", json!(proposed_version)),
+            ],
+        ),
+    })
+}
+
+/// Surfaces that version comparisons were skipped because the resolved version does not
+/// parse under the registry's version scheme.
+fn unparseable_version_finding(
+    package_name: &str,
+    resolved_version: Option<&PackageVersion>,
+    scheme: VersionScheme,
+    checks_run: &[String],
+) -> Option<StructuredFinding> {
+    let resolved_version = resolved_version?;
+    // Unsupported schemes are reported (and denied) by `unsupported_version_scheme_finding`.
+    if scheme == VersionScheme::Unsupported || scheme.parse(&resolved_version.version).is_some() {
+        return None;
+    }
+    let skipped_checks = semver_dependent_checks_run(checks_run);
+    if skipped_checks.is_empty() {
+        return None;
+    }
+
+    let reason = format!(
+        "{package_name}@{} version string is not valid {}; some checks skipped ({})",
+        resolved_version.version,
+        scheme.name(),
+        skipped_checks.join(", ")
+    );
+    Some(StructuredFinding {
+        severity: Severity::Low,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            "version.unparseable",
+            Severity::Low,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("resolved_version", json!(resolved_version.version.as_str())),
+                ("version_scheme", json!(scheme.name())),
+                ("skipped_checks", json!(skipped_checks)),
+            ],
+        ),
+    })
+}
+
+/// Fails closed for ecosystems whose version ordering is not implemented: advisory fix
+/// recommendations and staleness would otherwise compare their versions incorrectly.
+fn unsupported_version_scheme_finding(
+    package_name: &str,
+    resolved_version: Option<&PackageVersion>,
+    ecosystem: RegistryEcosystem,
+    checks_run: &[String],
+) -> Option<StructuredFinding> {
+    let resolved_version = resolved_version?;
+    if ecosystem.version_scheme() != VersionScheme::Unsupported {
+        return None;
+    }
+    let affected_checks = semver_dependent_checks_run(checks_run);
+    if affected_checks.is_empty() {
+        return None;
+    }
+
+    let reason = format!(
+        "{package_name}@{}: {} version ordering is not supported, so version-dependent checks \
+         cannot run ({}); denying",
+        resolved_version.version,
+        ecosystem.osv_name(),
+        affected_checks.join(", ")
+    );
+    Some(StructuredFinding {
+        severity: Severity::High,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            "version.unsupported_scheme",
+            Severity::High,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("resolved_version", json!(resolved_version.version.as_str())),
+                ("ecosystem", json!(ecosystem.osv_name())),
+                ("affected_checks", json!(affected_checks)),
+            ],
+        ),
+    })
+}
+
+fn semver_dependent_checks_run(checks_run: &[String]) -> Vec<String> {
+    checks_run
+        .iter()
+        .filter(|check_id| SEMVER_DEPENDENT_CHECKS.contains(&check_id.as_str()))
+        .cloned()
+        .collect()
+}
+
 fn policy_evidence<const N: usize>(
     id: &str,
     severity: Severity,
@@ -583,19 +1557,59 @@ fn policy_evidence<const N: usize>(
     Evidence {
         kind: EvidenceKind::Policy,
         id: id.to_string(),
+        code: id.to_string(),
         severity,
         message,
         facts,
+        remediation: None,
     }
 }
 
+/// Strict pinning (`require_exact_version`): a `medium` finding, which always denies, for
+/// requests naming a range, a dist-tag, or no version at all, since the resolved version can
+/// change between installs.
+fn non_exact_version_finding(
+    package_name: &str,
+    requested_version: Option<&str>,
+    ecosystem: RegistryEcosystem,
+    resolved_version: Option<&PackageVersion>,
+) -> Option<StructuredFinding> {
+    let is_exact = requested_version.is_some_and(|requested| {
+        requested != "latest" && ecosystem.version_scheme().parse(requested).is_some()
+    });
+    if is_exact {
+        return None;
+    }
+
+    let reason = "non-exact version requested; pin an exact version".to_string();
+    Some(StructuredFinding {
+        severity: Severity::Medium,
+        reason: reason.clone(),
+        evidence: policy_evidence(
+            NON_EXACT_REQUEST_EVIDENCE_ID,
+            Severity::Medium,
+            reason,
+            [
+                ("package", json!(package_name)),
+                ("requested_version", json!(requested_version)),
+                (
+                    "resolved_version",
+                    json!(resolved_version.map(|version| version.version.as_str())),
+                ),
+            ],
+        ),
+    })
+}
+
 fn deny_report(reason: String, evidence: Vec<Evidence>, metadata: Metadata) -> CheckReport {
     CheckReport {
         allow: false,
         risk: Severity::Critical,
+        score: risk_score([Severity::Critical]),
         reasons: vec![reason],
         evidence,
         metadata,
+        checks_run: Vec::new(),
     }
 }
 
@@ -603,25 +1617,31 @@ fn allow_report(reason: String, evidence: Vec<Evidence>, metadata: Metadata) ->
     CheckReport {
         allow: true,
         risk: Severity::Low,
+        score: 0,
         reasons: vec![reason],
         evidence,
         metadata,
+        checks_run: Vec::new(),
     }
 }
 
+/// Matches `package` or `package@version` rules, comparing names in the registry's
+/// canonical form so e.g. a `Flask` rule covers a `flask` request on PyPI.
 fn matching_package_rule<'a>(
     rules: &'a [String],
+    ecosystem: RegistryEcosystem,
     package_name: &str,
     requested_version: Option<&str>,
     resolved_version: Option<&str>,
 ) -> Option<&'a str> {
+    let package_name = ecosystem.normalize_package_name(package_name);
     for rule in rules {
         // Supports either "package" or "package@version".
         // rsplit_once keeps npm-style scoped names intact (e.g. "@scope/pkg@1.2.3").
         if let Some((rule_package, rule_version)) = rule.rsplit_once('@')
             && !rule_package.is_empty()
         {
-            if rule_package == package_name
+            if ecosystem.normalize_package_name(rule_package) == package_name
                 && (requested_version == Some(rule_version)
                     || resolved_version == Some(rule_version))
             {
@@ -630,7 +1650,7 @@ fn matching_package_rule<'a>(
             continue;
         }
 
-        if rule == package_name {
+        if ecosystem.normalize_package_name(rule) == package_name {
             return Some(rule.as_str());
         }
     }
@@ -638,16 +1658,24 @@ fn matching_package_rule<'a>(
     None
 }
 
+fn matching_name_pattern<'a>(patterns: &'a [String], package_name: &str) -> Option<&'a str> {
+    let name = package_name.to_lowercase();
+    patterns
+        .iter()
+        .find(|pattern| glob_matches(&pattern.trim().to_lowercase(), &name))
+        .map(String::as_str)
+}
+
 fn matching_publisher<'a>(
-    denylist_publishers: &'a [String],
+    listed_publishers: &'a [String],
     publishers: &[String],
 ) -> Option<&'a str> {
     // Publisher match is case-insensitive.
-    denylist_publishers.iter().find_map(|denylisted| {
+    listed_publishers.iter().find_map(|listed| {
         publishers
             .iter()
-            .any(|publisher| publisher.eq_ignore_ascii_case(denylisted))
-            .then_some(denylisted.as_str())
+            .any(|publisher| publisher.eq_ignore_ascii_case(listed))
+            .then_some(listed.as_str())
     })
 }
 
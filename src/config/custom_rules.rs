@@ -21,8 +21,11 @@ pub enum CustomRuleField {
     PublisherCount,
     Publishers,
     WeeklyDownloads,
+    WeeklyDownloadsChange,
     AdvisoryCount,
     AdvisoryIds,
+    License,
+    RepositoryUrl,
 }
 
 impl CustomRuleField {
@@ -33,6 +36,7 @@ impl CustomRuleField {
                 | Self::InstallScriptCount
                 | Self::PublisherCount
                 | Self::WeeklyDownloads
+                | Self::WeeklyDownloadsChange
                 | Self::AdvisoryCount
         )
     }
@@ -49,6 +53,8 @@ impl CustomRuleField {
                 | Self::RequestedVersion
                 | Self::LatestVersion
                 | Self::ResolvedVersion
+                | Self::License
+                | Self::RepositoryUrl
         )
     }
 
@@ -11,6 +11,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::Context;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::registries::{CheckId, normalize_check_id};
@@ -33,19 +34,58 @@ pub const DEFAULT_WARN_MAJOR_VERSIONS_BEHIND: u64 = 2;
 pub const DEFAULT_WARN_MINOR_VERSIONS_BEHIND: u64 = 3;
 /// Default staleness age threshold in days.
 pub const DEFAULT_WARN_AGE_DAYS: i64 = 365;
+/// Default weekly-download threshold above which single-maintainer packages are flagged.
+pub const DEFAULT_BUS_FACTOR_HIGH_DOWNLOAD_THRESHOLD: u64 = 1_000_000;
+/// Default number of releases in the last 7 days above which release churn is flagged.
+pub const DEFAULT_RELEASE_CHURN_MAX_RELEASES_PER_WEEK: u64 = 10;
+/// Default age in days after which a stable release is stale while prereleases keep shipping.
+pub const DEFAULT_STALE_STABLE_WINDOW_DAYS: u64 = 180;
 /// Default cache TTL in minutes.
 pub const DEFAULT_CACHE_TTL_MINUTES: u64 = 30;
+/// Default lifetime of persisted popular package name lists, in minutes (one day).
+pub const DEFAULT_POPULAR_NAMES_TTL_MINUTES: u64 = 24 * 60;
+/// Default lifetime of a shared weekly download count, in minutes.
+pub const DEFAULT_DOWNLOADS_TTL_MINUTES: u64 = 60;
+/// Default lifetime of a stored weekly-downloads baseline, in days.
+pub const DEFAULT_DOWNLOAD_BASELINE_DAYS: u64 = 30;
+/// Default audit log size in bytes before it is rotated to `<file>.1`.
+pub const DEFAULT_AUDIT_LOG_MAX_BYTES: u64 = 10 * 1024 * 1024;
+/// Default number of rotated audit log generations kept (`<file>.1` ..= `<file>.5`).
+pub const DEFAULT_AUDIT_LOG_MAX_FILES: usize = 5;
 
 /// Default lockfile evaluation concurrency (number of packages evaluated in parallel).
 ///
 /// Set conservatively to avoid triggering registry rate limits during large lockfile audits.
 pub const DEFAULT_LOCKFILE_EVAL_CONCURRENCY: usize = 5;
 
+/// Default number of concurrent OSV requests while prefetching advisories for a lockfile audit.
+///
+/// Advisories are queried in batches, so a few requests cover even large lockfiles.
+pub const DEFAULT_LOCKFILE_ADVISORY_CONCURRENCY: usize = 4;
+
 /// Default inter-task delay in milliseconds (delay before starting each additional concurrent task).
 ///
 /// Spaces out API requests to avoid triggering rate limits.
 pub const DEFAULT_INTER_BATCH_DELAY_MS: u64 = 100;
 
+/// Default cap on concurrent outbound registry requests.
+///
+/// Shared by a service's `check_package` and `check_lockfile` calls so a busy server cannot
+/// burst past it.
+pub const DEFAULT_MAX_IN_FLIGHT_REQUESTS: usize = 32;
+
+/// Default cap on a single registry or advisory response body, in bytes (32 MiB).
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 32 * 1024 * 1024;
+
+/// Default time a decision hook may run before it is treated as failed, in milliseconds.
+pub const DEFAULT_DECISION_HOOK_TIMEOUT_MS: u64 = 5_000;
+
+/// Default number of re-fetches after a registry NotFound before a package is treated as missing.
+pub const DEFAULT_EXISTENCE_NOT_FOUND_RETRIES: u32 = 1;
+
+/// Default delay in milliseconds before re-fetching a package that returned NotFound.
+pub const DEFAULT_EXISTENCE_NOT_FOUND_RETRY_DELAY_MS: u64 = 500;
+
 /// Top-level runtime configuration for package evaluation.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
@@ -56,22 +96,103 @@ pub struct SafePkgsConfig {
     pub min_weekly_downloads: u64,
     /// Maximum risk threshold that still allows installation.
     pub max_risk: Severity,
+    /// Deny floor: aggregate risk below this severity is allowed even when it exceeds
+    /// `max_risk`. Critical always denies. Default: unset.
+    pub never_deny_below: Option<Severity>,
+    /// Whether decisions block installs. Default: enforce.
+    pub enforcement: EnforcementMode,
+    /// Absolute freshness embargo: versions published within this many hours are denied
+    /// as critical regardless of other checks. Non-positive values disable it. Default: unset.
+    pub quarantine_hours: Option<i64>,
+    /// Emit a low-risk finding when the resolved version does not parse under the registry's
+    /// version scheme (semver or PEP 440), since version-comparison checks are partially
+    /// skipped. Default: true.
+    pub warn_on_unparseable_version: bool,
+    /// Deny, as medium risk, any request that does not name an exact version (a range, a
+    /// dist-tag such as `latest`, or no version at all). Default: false.
+    pub require_exact_version: bool,
+    /// Emit a low-risk finding when no version of a package has a publish date, since
+    /// age-based checks are silently skipped for it. Default: false.
+    pub require_publish_dates: bool,
+    /// Include registry fetch latency (`metadata.fetch_ms`) in responses. Default: false.
+    pub include_fetch_latency: bool,
+    /// Most reasons listed in a response, most severe first; the rest collapse into one
+    /// "… and N more findings" line. Evidence is never capped. Default: unset (unlimited).
+    pub max_reasons: Option<usize>,
+    /// Longest reason listed, in characters; longer reasons are cut and end in "…".
+    /// Default: unset (unlimited).
+    pub max_reason_length: Option<usize>,
+    /// Cap on concurrent outbound registry and advisory requests, shared by every registry
+    /// client of the service. Default: 32.
+    pub max_in_flight_requests: usize,
+    /// Largest registry or advisory response body accepted, in bytes; larger bodies fail the
+    /// fetch with an invalid-response error. Default: 33554432 (32 MiB).
+    pub max_response_bytes: usize,
+    /// Project `.npmrc` whose `registry`, `@scope:registry` and `//host/:_authToken` entries
+    /// route npm lookups to private registries. Only read from trusted layers. Default: unset.
+    pub npmrc: Option<PathBuf>,
+    /// Program run after each package decision with the response JSON on stdin; its
+    /// `{"allow": .., "risk": .., "reason": ..}` stdout overrides the decision. Default: unset.
+    pub decision_hook: Option<PathBuf>,
+    /// How long the decision hook may run, in milliseconds. Default: 5000.
+    pub decision_hook_timeout_ms: u64,
+    /// Keep the original decision when the hook fails, times out, or prints invalid output,
+    /// instead of denying the package. Default: false.
+    pub decision_hook_fail_open: bool,
     /// Package allowlist rules.
     pub allowlist: AllowlistConfig,
     /// Package and publisher denylist rules.
     pub denylist: DenylistConfig,
+    /// Which list wins when a package matches both the allowlist and the denylist.
+    /// Default: deny_first.
+    pub list_precedence: ListPrecedence,
     /// Dependency-confusion defenses for internal/private package names.
     pub dependency_confusion: DependencyConfusionConfig,
+    /// Change-freeze window for newly published versions.
+    pub blackout: BlackoutConfig,
     /// Settings for staleness checks.
     pub staleness: StalenessConfig,
+    /// Settings for typosquat checks.
+    pub typosquat: TyposquatConfig,
+    /// Settings for bus-factor checks.
+    pub bus_factor: BusFactorConfig,
+    /// Settings for version-age checks.
+    pub version_age: VersionAgeConfig,
+    /// Settings for install-script checks.
+    pub install_script: InstallScriptConfig,
+    /// Settings for release-churn checks.
+    pub release_churn: ReleaseChurnConfig,
+    /// Settings for stale-stable checks.
+    pub stale_stable: StaleStableConfig,
+    /// Settings for advisory checks.
+    pub advisory: AdvisoryConfig,
     /// Global and registry-specific check toggles.
     pub checks: ChecksConfig,
     /// Cache configuration.
     pub cache: CacheConfig,
+    /// Audit log settings.
+    pub audit_log: AuditLogConfig,
     /// Lockfile evaluation configuration.
     pub lockfile: LockfileConfig,
+    /// Existence lookup retry settings.
+    pub existence: ExistenceConfig,
     /// User-defined custom policy rules evaluated against package metadata.
     pub custom_rules: Vec<CustomRuleConfig>,
+    /// Time-boxed suppressions of specific findings on specific packages.
+    pub suppressions: Vec<SuppressionConfig>,
+}
+
+/// Suppresses one check's findings on one package until an expiry time.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SuppressionConfig {
+    /// Package rule in `name` or `name@version` form.
+    pub package: String,
+    /// Check id (`popularity`) or full evidence id (`popularity.low_downloads`).
+    pub check: String,
+    /// RFC3339 expiry; once passed the finding reappears.
+    pub until: DateTime<Utc>,
+    /// Why the finding is suppressed; echoed in the suppression evidence.
+    pub reason: String,
 }
 
 /// Allowlist configuration.
@@ -80,6 +201,9 @@ pub struct SafePkgsConfig {
 pub struct AllowlistConfig {
     /// Package rules in `name` or `name@version` form.
     pub packages: Vec<String>,
+    /// Trusted publishers whose packages skip adoption checks (popularity, version_age);
+    /// security checks still run.
+    pub publishers: Vec<String>,
 }
 
 /// Denylist configuration.
@@ -90,6 +214,9 @@ pub struct DenylistConfig {
     pub packages: Vec<String>,
     /// Publisher names blocked regardless of package name.
     pub publishers: Vec<String>,
+    /// Case-insensitive name globs (`*` any run, `?` one character) blocked before any
+    /// registry call, e.g. `*-test-malware`.
+    pub patterns: Vec<String>,
 }
 
 /// Dependency-confusion configuration.
@@ -100,11 +227,14 @@ pub struct DependencyConfusionConfig {
     pub internal_packages: Vec<String>,
     /// Internal scope/prefix patterns (e.g. "@myorg"). Matches "@myorg" and "@myorg/<name>".
     pub internal_scopes: Vec<String>,
+    /// Raw internal name prefixes (e.g. "@acme/", "acme-"). Matches any name starting with one.
+    pub internal_prefixes: Vec<String>,
 }
 
 impl DependencyConfusionConfig {
     /// Returns the matched rule when `package_name` equals an internal package, equals a
-    /// scope, or begins with `"<scope>/"`. Comparisons are case-sensitive over the raw name.
+    /// scope, begins with `"<scope>/"`, or begins with an internal prefix. Comparisons are
+    /// case-sensitive over the raw name.
     pub fn matches(&self, package_name: &str) -> Option<String> {
         if let Some(rule) = self
             .internal_packages
@@ -121,10 +251,43 @@ impl DependencyConfusionConfig {
                         .strip_prefix(scope.as_str())
                         .is_some_and(|rest| rest.starts_with('/'))
             })
+            .or_else(|| {
+                self.internal_prefixes
+                    .iter()
+                    .find(|prefix| !prefix.is_empty() && package_name.starts_with(prefix.as_str()))
+            })
             .cloned()
     }
 }
 
+/// Change-freeze configuration.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BlackoutConfig {
+    /// Versions published after this RFC3339 timestamp are flagged for all registries.
+    pub after: Option<DateTime<Utc>>,
+    /// Per-registry overrides keyed by registry id.
+    pub registry: BTreeMap<String, RegistryBlackoutConfig>,
+}
+
+/// Registry-specific change-freeze settings.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RegistryBlackoutConfig {
+    /// Overrides the global `after` timestamp for this registry.
+    pub after: Option<DateTime<Utc>>,
+}
+
+impl BlackoutConfig {
+    /// Returns the freeze start for a registry, preferring a registry-specific timestamp.
+    pub fn after_for_registry(&self, registry_key: &str) -> Option<DateTime<Utc>> {
+        self.registry
+            .get(normalize_registry_key(registry_key).as_str())
+            .and_then(|entry| entry.after)
+            .or(self.after)
+    }
+}
+
 /// Staleness-check tuning parameters.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
@@ -139,12 +302,109 @@ pub struct StalenessConfig {
     pub ignore_for: Vec<String>,
 }
 
+/// Typosquat-check settings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TyposquatConfig {
+    /// When the popular-name index is unavailable, skip the check (with a warning) instead of
+    /// failing the evaluation. Default: true.
+    pub fail_open: bool,
+    /// Newline-delimited file of popular package names used instead of the network index,
+    /// making the check deterministic and usable offline.
+    pub popular_names_file: Option<PathBuf>,
+    /// Per-registry URL of a static JSON popular-names list, keyed by registry (`npm`, `pypi`).
+    /// For npm this replaces npms.io search; `popular_names_file` still takes precedence, and
+    /// a `SAFE_PKGS_<REGISTRY>_POPULAR_INDEX_URL` env var overrides the entry.
+    pub popular_index_urls: BTreeMap<String, String>,
+    /// Internal package names, or `prefix*` globs such as `@acme/*`, exempt from typosquat
+    /// flagging because they legitimately resemble popular names.
+    pub ignore: Vec<String>,
+}
+
+/// Bus-factor check settings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BusFactorConfig {
+    /// Flag single-maintainer packages with more weekly downloads than this. Default: 1,000,000.
+    pub high_download_threshold: u64,
+}
+
+/// Version-age check settings.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct VersionAgeConfig {
+    /// Exempt fresh patch releases (same major.minor) of a version that is already older than
+    /// `min_version_age_days`. Default: false.
+    pub exempt_patch_upgrades: bool,
+}
+
+/// Install-script check settings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct InstallScriptConfig {
+    /// Flag install hooks that set up persistence (`crontab`, `systemctl`, `launchctl`, `at`,
+    /// `schtasks`, or a registry Run key) as critical. Default: true.
+    pub detect_persistence: bool,
+    /// Flag any install hook not listed in `allowed_commands` as high risk, whatever it
+    /// runs. Default: false.
+    pub deny_all: bool,
+    /// Install hook commands (without the `<hook>:` label) exempt from `deny_all`, matched
+    /// exactly apart from whitespace. Default: empty.
+    pub allowed_commands: Vec<String>,
+}
+
+/// Release-churn check settings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReleaseChurnConfig {
+    /// Flag packages that published more versions than this in the last 7 days. Default: 10.
+    pub max_releases_per_week: u64,
+}
+
+/// Stale-stable check settings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StaleStableConfig {
+    /// Flag packages whose latest stable release is older than this many days while a newer
+    /// prerelease was published within it. Default: 180.
+    pub window_days: u64,
+}
+
+/// Advisory check settings.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AdvisoryConfig {
+    /// Advisories with a CVSS score below this are reported as low risk instead of high.
+    /// Advisories without a score are unaffected. Default: unset.
+    pub min_cvss: Option<f64>,
+}
+
 /// Cache settings.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct CacheConfig {
     /// Cache entry TTL in minutes.
     pub ttl_minutes: u64,
+    /// How long popular package name lists persist in the cache database, so restarts
+    /// reuse them instead of refetching. 0 disables persistence. Default: 1440 (one day).
+    pub popular_names_ttl_minutes: u64,
+    /// How long fetched weekly download counts stay in the cache database, shared by every
+    /// registry client and across restarts. 0 disables sharing. Default: 60.
+    pub downloads_ttl_minutes: u64,
+    /// How long a package's first observed weekly downloads stay the baseline for
+    /// `weekly_downloads_change` rules before the next observation replaces it.
+    /// 0 disables download history. Default: 30.
+    pub download_baseline_days: u64,
+}
+
+/// Audit log settings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AuditLogConfig {
+    /// Rotate the log to `<file>.1` once a write would take it past this many bytes.
+    pub max_log_bytes: u64,
+    /// Rotated generations kept; older ones shift up (`.1` -> `.2`) and the oldest is dropped.
+    pub max_files: usize,
 }
 
 /// Lockfile evaluation settings.
@@ -157,6 +417,75 @@ pub struct LockfileConfig {
     /// Delay in milliseconds between starting each batch of concurrent evaluations.
     /// Default: 100ms. Spaces out API requests to avoid rate limiting. Set to 0 to disable.
     pub inter_batch_delay_ms: u64,
+    /// Number of OSV requests in flight while advisories for a lockfile audit are prefetched
+    /// through the batch endpoint. Default: 4.
+    pub advisory_concurrency: usize,
+    /// When true, dev-scoped dependencies are still reported but do not count toward
+    /// the audit's deny count or aggregate risk. Default: false.
+    pub ignore_dev_dependencies: bool,
+    /// How registry transport failures for a single package affect the audit.
+    /// Default: deny.
+    pub on_transport_error: TransportErrorMode,
+    /// When true, dependencies from git, path, or URL sources are reported as low-risk
+    /// "not evaluated" results instead of being left out. Default: false.
+    pub report_unsupported_sources: bool,
+    /// Fraction of packages (0.0-1.0) that must be fully evaluated for the audit to pass;
+    /// failed, skipped, unsupported-source, and unparseable-version packages count against
+    /// it. Default: unset.
+    pub min_coverage_ratio: Option<f64>,
+}
+
+/// Lockfile audit handling for packages whose registry lookup hit a transport failure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportErrorMode {
+    /// Report the package as a critical denial (fails the audit).
+    #[default]
+    Deny,
+    /// Leave the package out of the report and its totals.
+    Skip,
+    /// Report the package as a medium-risk warning that does not deny the audit.
+    Warn,
+}
+
+/// How package decisions are enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnforcementMode {
+    /// Deny packages whose aggregate risk exceeds `max_risk`.
+    #[default]
+    Enforce,
+    /// Run every check and report risk and reasons, but always allow.
+    WarnOnly,
+    /// Skip checks entirely and allow every package.
+    Off,
+}
+
+/// Order in which allowlist and denylist rules are consulted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListPrecedence {
+    /// Denylist rules are checked first, so a denylisted package can never be allowlisted.
+    #[default]
+    DenyFirst,
+    /// Allowlist package rules are checked first and override denylist package, pattern,
+    /// and publisher rules. Every denylisted package is fetched from the registry first.
+    AllowFirst,
+}
+
+/// Existence lookup settings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ExistenceConfig {
+    /// Re-fetches after a registry NotFound before concluding the package does not exist.
+    /// Default: 1. Set to 0 to treat the first NotFound as final.
+    pub not_found_retries: u32,
+    /// Delay in milliseconds before each NotFound re-fetch. Default: 500ms.
+    pub not_found_retry_delay_ms: u64,
+    /// Severity when the package does not exist. Default: critical.
+    pub missing_package: Severity,
+    /// Severity when the package exists but the requested version does not. Default: critical.
+    pub missing_version: Severity,
 }
 
 /// Check enable/disable policy.
@@ -222,10 +551,67 @@ impl Default for StalenessConfig {
     }
 }
 
+impl Default for TyposquatConfig {
+    fn default() -> Self {
+        Self {
+            fail_open: true,
+            popular_names_file: None,
+            popular_index_urls: BTreeMap::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+impl Default for BusFactorConfig {
+    fn default() -> Self {
+        Self {
+            high_download_threshold: DEFAULT_BUS_FACTOR_HIGH_DOWNLOAD_THRESHOLD,
+        }
+    }
+}
+
+impl Default for InstallScriptConfig {
+    fn default() -> Self {
+        Self {
+            detect_persistence: true,
+            deny_all: false,
+            allowed_commands: Vec::new(),
+        }
+    }
+}
+
+impl Default for ReleaseChurnConfig {
+    fn default() -> Self {
+        Self {
+            max_releases_per_week: DEFAULT_RELEASE_CHURN_MAX_RELEASES_PER_WEEK,
+        }
+    }
+}
+
+impl Default for StaleStableConfig {
+    fn default() -> Self {
+        Self {
+            window_days: DEFAULT_STALE_STABLE_WINDOW_DAYS,
+        }
+    }
+}
+
 impl Default for CacheConfig {
     fn default() -> Self {
         Self {
             ttl_minutes: DEFAULT_CACHE_TTL_MINUTES,
+            popular_names_ttl_minutes: DEFAULT_POPULAR_NAMES_TTL_MINUTES,
+            downloads_ttl_minutes: DEFAULT_DOWNLOADS_TTL_MINUTES,
+            download_baseline_days: DEFAULT_DOWNLOAD_BASELINE_DAYS,
+        }
+    }
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            max_log_bytes: DEFAULT_AUDIT_LOG_MAX_BYTES,
+            max_files: DEFAULT_AUDIT_LOG_MAX_FILES,
         }
     }
 }
@@ -235,6 +621,22 @@ impl Default for LockfileConfig {
         Self {
             eval_concurrency: DEFAULT_LOCKFILE_EVAL_CONCURRENCY,
             inter_batch_delay_ms: DEFAULT_INTER_BATCH_DELAY_MS,
+            advisory_concurrency: DEFAULT_LOCKFILE_ADVISORY_CONCURRENCY,
+            ignore_dev_dependencies: false,
+            on_transport_error: TransportErrorMode::Deny,
+            report_unsupported_sources: false,
+            min_coverage_ratio: None,
+        }
+    }
+}
+
+impl Default for ExistenceConfig {
+    fn default() -> Self {
+        Self {
+            not_found_retries: DEFAULT_EXISTENCE_NOT_FOUND_RETRIES,
+            not_found_retry_delay_ms: DEFAULT_EXISTENCE_NOT_FOUND_RETRY_DELAY_MS,
+            missing_package: Severity::Critical,
+            missing_version: Severity::Critical,
         }
     }
 }
@@ -245,14 +647,41 @@ impl Default for SafePkgsConfig {
             min_version_age_days: DEFAULT_MIN_VERSION_AGE_DAYS,
             min_weekly_downloads: DEFAULT_MIN_WEEKLY_DOWNLOADS,
             max_risk: DEFAULT_MAX_RISK,
+            never_deny_below: None,
+            enforcement: EnforcementMode::Enforce,
+            quarantine_hours: None,
+            warn_on_unparseable_version: true,
+            require_exact_version: false,
+            require_publish_dates: false,
+            include_fetch_latency: false,
+            max_reasons: None,
+            max_reason_length: None,
+            max_in_flight_requests: DEFAULT_MAX_IN_FLIGHT_REQUESTS,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            npmrc: None,
+            decision_hook: None,
+            decision_hook_timeout_ms: DEFAULT_DECISION_HOOK_TIMEOUT_MS,
+            decision_hook_fail_open: false,
             allowlist: AllowlistConfig::default(),
             denylist: DenylistConfig::default(),
+            list_precedence: ListPrecedence::DenyFirst,
             dependency_confusion: DependencyConfusionConfig::default(),
+            blackout: BlackoutConfig::default(),
             staleness: StalenessConfig::default(),
+            typosquat: TyposquatConfig::default(),
+            bus_factor: BusFactorConfig::default(),
+            version_age: VersionAgeConfig::default(),
+            install_script: InstallScriptConfig::default(),
+            release_churn: ReleaseChurnConfig::default(),
+            stale_stable: StaleStableConfig::default(),
+            advisory: AdvisoryConfig::default(),
             checks: ChecksConfig::default(),
             cache: CacheConfig::default(),
+            audit_log: AuditLogConfig::default(),
             lockfile: LockfileConfig::default(),
+            existence: ExistenceConfig::default(),
             custom_rules: Vec::new(),
+            suppressions: Vec::new(),
         }
     }
 }
@@ -264,6 +693,18 @@ pub(crate) struct RemoteConfigSource {
 }
 
 impl SafePkgsConfig {
+    /// Returns a stable SHA-256 fingerprint of the policy-relevant settings.
+    ///
+    /// Thresholds, enabled checks, and allow/deny lists feed the hash; operational
+    /// settings such as cache TTL do not. Decisions are cached under this fingerprint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the canonical policy snapshot cannot be serialized.
+    pub fn config_fingerprint(&self) -> anyhow::Result<String> {
+        crate::policy_snapshot::compute_config_fingerprint(self)
+    }
+
     /// Loads and merges global + project configuration from default paths.
     ///
     /// Retained for local-only/back-compat callers; [`load_async`](Self::load_async)
@@ -274,38 +715,86 @@ impl SafePkgsConfig {
     /// Returns an error if any discovered config file cannot be read or parsed.
     #[allow(dead_code, reason = "back-compat sync local-only loader")]
     pub fn load() -> anyhow::Result<Self> {
-        Self::load_with_paths(global_config_path(), project_config_path())
+        Self::load_with_paths(
+            global_config_path(),
+            project_config_path(),
+            selected_profile(None).as_deref(),
+        )
     }
 
     /// Loads and merges remote + global + project configuration.
     ///
     /// When `SAFE_PKGS_CONFIG_REMOTE_URL` is set, the remote overlay is merged first
     /// as the lowest-precedence layer, then the global and project files override it.
+    /// `profile` (or `SAFE_PKGS_PROFILE` when `None`) selects a `[profiles.<name>]`
+    /// section; every layer's section is applied, in layer order, after all base layers.
+    ///
+    /// When `config_path` is set (the `--config` flag), that file is loaded on its own via
+    /// [`load_file`](Self::load_file) and the remote, global, and project layers are skipped.
     ///
     /// # Errors
     ///
-    /// Returns an error if the remote source or any config file cannot be fetched or parsed.
-    pub async fn load_async() -> anyhow::Result<Self> {
+    /// Returns an error if the remote source or any config file cannot be fetched or parsed,
+    /// or if the selected profile is not defined by any layer.
+    pub async fn load_async(
+        config_path: Option<&Path>,
+        profile: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        if let Some(path) = config_path {
+            return Self::load_file(path, profile);
+        }
         let remote = remote_config_url().map(|url| RemoteConfigSource {
             url,
             token: remote_config_token(),
         });
-        Self::load_with_sources(remote, global_config_path(), project_config_path()).await
+        Self::load_with_sources(
+            remote,
+            global_config_path(),
+            project_config_path(),
+            selected_profile(profile).as_deref(),
+        )
+        .await
+    }
+
+    /// Loads a single config file over the defaults, bypassing global/project discovery.
+    ///
+    /// `profile` (or `SAFE_PKGS_PROFILE` when `None`) selects a `[profiles.<name>]` section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not exist or cannot be read or parsed, or if the
+    /// selected profile is not defined in it.
+    pub fn load_file(path: &Path, profile: Option<&str>) -> anyhow::Result<Self> {
+        if !path.is_file() {
+            anyhow::bail!("config file {} does not exist", path.display());
+        }
+        Self::load_with_paths(
+            Some(path.to_path_buf()),
+            None,
+            selected_profile(profile).as_deref(),
+        )
     }
 
     #[cfg(test)]
     fn load_from_path(path: &Path) -> anyhow::Result<Self> {
-        Self::load_with_paths(Some(path.to_path_buf()), None)
+        Self::load_with_paths(Some(path.to_path_buf()), None, None)
     }
 
-    fn load_with_paths(global: Option<PathBuf>, project: Option<PathBuf>) -> anyhow::Result<Self> {
+    fn load_with_paths(
+        global: Option<PathBuf>,
+        project: Option<PathBuf>,
+        profile: Option<&str>,
+    ) -> anyhow::Result<Self> {
         let mut config = Self::default();
+        let mut profiles = Vec::new();
         if let Some(path) = global {
-            config.merge_from_path(&path)?;
+            profiles.extend(config.merge_from_path(&path, profile, LayerTrust::Trusted)?);
         }
         if let Some(path) = project {
-            config.merge_from_path(&path)?;
+            profiles.extend(config.merge_from_path(&path, profile, LayerTrust::Untrusted)?);
         }
+        let profile_found = config.apply_profiles(profiles)?;
+        ensure_profile_found(profile, profile_found)?;
         config.validate()?;
         Ok(config)
     }
@@ -314,41 +803,80 @@ impl SafePkgsConfig {
         remote: Option<RemoteConfigSource>,
         global: Option<PathBuf>,
         project: Option<PathBuf>,
+        profile: Option<&str>,
     ) -> anyhow::Result<Self> {
         let mut config = Self::default();
+        let mut profiles = Vec::new();
         if let Some(remote) = remote {
-            config
-                .merge_from_url(&remote.url, remote.token.as_deref())
-                .await?;
+            profiles.extend(
+                config
+                    .merge_from_url(&remote.url, remote.token.as_deref(), profile)
+                    .await?,
+            );
         }
         if let Some(path) = global {
-            config.merge_from_path(&path)?;
+            profiles.extend(config.merge_from_path(&path, profile, LayerTrust::Trusted)?);
         }
         if let Some(path) = project {
-            config.merge_from_path(&path)?;
+            profiles.extend(config.merge_from_path(&path, profile, LayerTrust::Untrusted)?);
         }
+        let profile_found = config.apply_profiles(profiles)?;
+        ensure_profile_found(profile, profile_found)?;
         config.validate()?;
         Ok(config)
     }
 
     pub(crate) fn validate(&self) -> anyhow::Result<()> {
-        custom_rules::validate_rules(&self.custom_rules)
+        custom_rules::validate_rules(&self.custom_rules)?;
+        for suppression in &self.suppressions {
+            if suppression.package.trim().is_empty() || suppression.check.trim().is_empty() {
+                anyhow::bail!("suppression package and check must not be empty");
+            }
+            if suppression.reason.trim().is_empty() {
+                anyhow::bail!(
+                    "suppression for '{}' ({}) must give a reason",
+                    suppression.package,
+                    suppression.check
+                );
+            }
+        }
+        Ok(())
     }
 
-    fn merge_from_path(&mut self, path: &Path) -> anyhow::Result<()> {
+    /// Merges one config file's base values; returns its section for the selected profile.
+    fn merge_from_path(
+        &mut self,
+        path: &Path,
+        profile: Option<&str>,
+        trust: LayerTrust,
+    ) -> anyhow::Result<Option<ProfileSection>> {
         if !path.exists() {
-            return Ok(());
+            return Ok(None);
         }
 
         let raw = fs::read_to_string(path)
             .with_context(|| format!("failed to read config file at {}", path.display()))?;
-        let overlay: ConfigOverlay = toml::from_str(&raw)
+        let overlay = parse_config_file(path, &raw)
             .with_context(|| format!("failed to parse config file at {}", path.display()))?;
-        self.apply_overlay(overlay);
-        Ok(())
+        let source = format!("config file at {}", path.display());
+        let section = self
+            .apply_overlay(overlay, profile, trust)
+            .with_context(|| format!("invalid {source}"))?;
+        Ok(section.map(|overlay| ProfileSection {
+            source,
+            overlay,
+            trust,
+        }))
     }
 
-    async fn merge_from_url(&mut self, url: &str, token: Option<&str>) -> anyhow::Result<()> {
+    /// Merges a remote config overlay's base values; returns its section for the selected
+    /// profile.
+    async fn merge_from_url(
+        &mut self,
+        url: &str,
+        token: Option<&str>,
+        profile: Option<&str>,
+    ) -> anyhow::Result<Option<ProfileSection>> {
         // Errors use a redacted URL so credentials in userinfo/query are not leaked.
         let safe_url = redacted_url(url);
         let client = reqwest::Client::builder()
@@ -383,11 +911,49 @@ impl SafePkgsConfig {
             .with_context(|| format!("failed to read remote config body from {safe_url}"))?;
         let overlay: ConfigOverlay = toml::from_str(&raw)
             .with_context(|| format!("failed to parse remote config from {safe_url}"))?;
-        self.apply_overlay(overlay);
-        Ok(())
+        let source = format!("remote config from {safe_url}");
+        let section = self
+            .apply_overlay(overlay, profile, LayerTrust::Untrusted)
+            .with_context(|| format!("invalid {source}"))?;
+        Ok(section.map(|overlay| ProfileSection {
+            source,
+            overlay,
+            trust: LayerTrust::Untrusted,
+        }))
     }
 
-    fn apply_overlay(&mut self, overlay: ConfigOverlay) {
+    /// Applies the profile sections collected from every layer, in layer order, so a
+    /// selected profile overrides all base values. Returns whether any layer defined it.
+    fn apply_profiles(&mut self, sections: Vec<ProfileSection>) -> anyhow::Result<bool> {
+        let found = !sections.is_empty();
+        for section in sections {
+            self.apply_overlay(section.overlay, None, section.trust)
+                .with_context(|| format!("invalid profile in {}", section.source))?;
+        }
+        Ok(found)
+    }
+
+    /// Applies `overlay`'s base values and returns its `[profiles.<name>]` section for the
+    /// selected profile, which the caller applies once every layer is merged.
+    ///
+    /// Untrusted layers may not set keys that run programs or relax decisions (see
+    /// [`TRUSTED_ONLY_KEYS`]).
+    fn apply_overlay(
+        &mut self,
+        mut overlay: ConfigOverlay,
+        profile: Option<&str>,
+        trust: LayerTrust,
+    ) -> anyhow::Result<Option<ConfigOverlay>> {
+        if trust == LayerTrust::Untrusted {
+            reject_trusted_only_keys(&overlay)?;
+        }
+        let profile_overlay = profile.and_then(|name| overlay.profiles.remove(name));
+        if profile_overlay
+            .as_ref()
+            .is_some_and(|section| !section.profiles.is_empty())
+        {
+            anyhow::bail!("config profiles cannot define nested profiles");
+        }
         if let Some(value) = overlay.min_version_age_days {
             self.min_version_age_days = sanitize_positive_i64(value, DEFAULT_MIN_VERSION_AGE_DAYS);
         }
@@ -397,12 +963,64 @@ impl SafePkgsConfig {
         if let Some(value) = overlay.max_risk {
             self.max_risk = value;
         }
+        if let Some(value) = overlay.never_deny_below {
+            self.never_deny_below = Some(value);
+        }
+        if let Some(value) = overlay.enforcement {
+            self.enforcement = value;
+        }
+        if let Some(value) = overlay.quarantine_hours {
+            self.quarantine_hours = (value > 0).then_some(value);
+        }
+        if let Some(value) = overlay.warn_on_unparseable_version {
+            self.warn_on_unparseable_version = value;
+        }
+        if let Some(value) = overlay.require_exact_version {
+            self.require_exact_version = value;
+        }
+        if let Some(value) = overlay.require_publish_dates {
+            self.require_publish_dates = value;
+        }
+        if let Some(value) = overlay.include_fetch_latency {
+            self.include_fetch_latency = value;
+        }
+        if let Some(value) = overlay.max_reasons {
+            self.max_reasons = (value > 0).then_some(value);
+        }
+        if let Some(value) = overlay.max_reason_length {
+            self.max_reason_length = (value > 0).then_some(value);
+        }
+        if let Some(value) = overlay.max_in_flight_requests {
+            self.max_in_flight_requests =
+                sanitize_positive_usize(value, DEFAULT_MAX_IN_FLIGHT_REQUESTS);
+        }
+        if let Some(value) = overlay.max_response_bytes {
+            self.max_response_bytes = sanitize_positive_usize(value, DEFAULT_MAX_RESPONSE_BYTES);
+        }
+        if let Some(value) = overlay.npmrc {
+            self.npmrc = Some(value);
+        }
+        if let Some(value) = overlay.decision_hook {
+            self.decision_hook = Some(value);
+        }
+        if let Some(value) = overlay.decision_hook_timeout_ms {
+            self.decision_hook_timeout_ms =
+                sanitize_positive_u64(value, DEFAULT_DECISION_HOOK_TIMEOUT_MS);
+        }
+        if let Some(value) = overlay.decision_hook_fail_open {
+            self.decision_hook_fail_open = value;
+        }
         if let Some(value) = overlay.allowlist {
             append_unique(&mut self.allowlist.packages, value.packages);
+            append_unique(&mut self.allowlist.publishers, value.publishers);
         }
         if let Some(value) = overlay.denylist {
             append_unique(&mut self.denylist.packages, value.packages);
             append_unique(&mut self.denylist.publishers, value.publishers);
+            append_unique(&mut self.denylist.patterns, value.patterns);
+        }
+        if let Some(value) = overlay.list_precedence {
+            self.list_precedence = value;
         }
         if let Some(value) = overlay.dependency_confusion {
             append_unique(
@@ -413,6 +1031,24 @@ impl SafePkgsConfig {
                 &mut self.dependency_confusion.internal_scopes,
                 value.internal_scopes.unwrap_or_default(),
             );
+            append_unique(
+                &mut self.dependency_confusion.internal_prefixes,
+                value.internal_prefixes.unwrap_or_default(),
+            );
+        }
+        if let Some(value) = overlay.blackout {
+            if let Some(after) = value.after {
+                self.blackout.after = Some(after);
+            }
+            for (registry_key, registry_blackout) in value.registry {
+                if let Some(after) = registry_blackout.after {
+                    self.blackout
+                        .registry
+                        .entry(normalize_registry_key(&registry_key))
+                        .or_default()
+                        .after = Some(after);
+                }
+            }
         }
         if let Some(value) = overlay.staleness {
             if let Some(major) = value.warn_major_versions_behind {
@@ -432,6 +1068,63 @@ impl SafePkgsConfig {
                 value.ignore_for.unwrap_or_default(),
             );
         }
+        if let Some(value) = overlay.typosquat {
+            if let Some(fail_open) = value.fail_open {
+                self.typosquat.fail_open = fail_open;
+            }
+            if let Some(popular_names_file) = value.popular_names_file {
+                self.typosquat.popular_names_file = Some(popular_names_file);
+            }
+            for (registry, url) in value.popular_index_urls {
+                self.typosquat
+                    .popular_index_urls
+                    .insert(registry.to_ascii_lowercase(), url);
+            }
+            append_unique(&mut self.typosquat.ignore, value.ignore.unwrap_or_default());
+        }
+        if let Some(value) = overlay.bus_factor
+            && let Some(threshold) = value.high_download_threshold
+        {
+            self.bus_factor.high_download_threshold =
+                sanitize_positive_u64(threshold, DEFAULT_BUS_FACTOR_HIGH_DOWNLOAD_THRESHOLD);
+        }
+        if let Some(value) = overlay.version_age
+            && let Some(exempt) = value.exempt_patch_upgrades
+        {
+            self.version_age.exempt_patch_upgrades = exempt;
+        }
+        if let Some(value) = overlay.install_script {
+            if let Some(detect) = value.detect_persistence {
+                self.install_script.detect_persistence = detect;
+            }
+            if let Some(deny_all) = value.deny_all {
+                self.install_script.deny_all = deny_all;
+            }
+            append_unique(
+                &mut self.install_script.allowed_commands,
+                value.allowed_commands.unwrap_or_default(),
+            );
+        }
+        if let Some(value) = overlay.release_churn
+            && let Some(max_releases) = value.max_releases_per_week
+        {
+            self.release_churn.max_releases_per_week =
+                sanitize_positive_u64(max_releases, DEFAULT_RELEASE_CHURN_MAX_RELEASES_PER_WEEK);
+        }
+        if let Some(value) = overlay.stale_stable
+            && let Some(window_days) = value.window_days
+        {
+            self.stale_stable.window_days =
+                sanitize_positive_u64(window_days, DEFAULT_STALE_STABLE_WINDOW_DAYS);
+        }
+        if let Some(value) = overlay.advisory
+            && let Some(min_cvss) = value.min_cvss
+        {
+            if !(0.0..=10.0).contains(&min_cvss) {
+                anyhow::bail!("advisory.min_cvss must be between 0.0 and 10.0, got {min_cvss}");
+            }
+            self.advisory.min_cvss = Some(min_cvss);
+        }
         if let Some(value) = overlay.checks {
             append_unique(&mut self.checks.disable, value.disable.unwrap_or_default());
             for (registry_key, registry_checks) in value.registry {
@@ -447,10 +1140,30 @@ impl SafePkgsConfig {
                 );
             }
         }
-        if let Some(value) = overlay.cache
-            && let Some(ttl_minutes) = value.ttl_minutes
-        {
-            self.cache.ttl_minutes = sanitize_positive_u64(ttl_minutes, DEFAULT_CACHE_TTL_MINUTES);
+        if let Some(value) = overlay.cache {
+            if let Some(ttl_minutes) = value.ttl_minutes {
+                self.cache.ttl_minutes =
+                    sanitize_positive_u64(ttl_minutes, DEFAULT_CACHE_TTL_MINUTES);
+            }
+            if let Some(ttl_minutes) = value.popular_names_ttl_minutes {
+                self.cache.popular_names_ttl_minutes = ttl_minutes;
+            }
+            if let Some(ttl_minutes) = value.downloads_ttl_minutes {
+                self.cache.downloads_ttl_minutes = ttl_minutes;
+            }
+            if let Some(days) = value.download_baseline_days {
+                self.cache.download_baseline_days = days;
+            }
+        }
+        if let Some(value) = overlay.audit_log {
+            if let Some(max_log_bytes) = value.max_log_bytes {
+                self.audit_log.max_log_bytes =
+                    sanitize_positive_u64(max_log_bytes, DEFAULT_AUDIT_LOG_MAX_BYTES);
+            }
+            if let Some(max_files) = value.max_files {
+                self.audit_log.max_files =
+                    sanitize_positive_usize(max_files, DEFAULT_AUDIT_LOG_MAX_FILES);
+            }
         }
         if let Some(value) = overlay.lockfile {
             if let Some(eval_concurrency) = value.eval_concurrency {
@@ -460,10 +1173,108 @@ impl SafePkgsConfig {
             if let Some(inter_batch_delay_ms) = value.inter_batch_delay_ms {
                 self.lockfile.inter_batch_delay_ms = inter_batch_delay_ms;
             }
+            if let Some(advisory_concurrency) = value.advisory_concurrency {
+                self.lockfile.advisory_concurrency = sanitize_positive_usize(
+                    advisory_concurrency,
+                    DEFAULT_LOCKFILE_ADVISORY_CONCURRENCY,
+                );
+            }
+            if let Some(ignore_dev_dependencies) = value.ignore_dev_dependencies {
+                self.lockfile.ignore_dev_dependencies = ignore_dev_dependencies;
+            }
+            if let Some(on_transport_error) = value.on_transport_error {
+                self.lockfile.on_transport_error = on_transport_error;
+            }
+            if let Some(report_unsupported_sources) = value.report_unsupported_sources {
+                self.lockfile.report_unsupported_sources = report_unsupported_sources;
+            }
+            if let Some(min_coverage_ratio) = value.min_coverage_ratio {
+                if !(0.0..=1.0).contains(&min_coverage_ratio) {
+                    anyhow::bail!(
+                        "lockfile.min_coverage_ratio must be between 0.0 and 1.0, got {min_coverage_ratio}"
+                    );
+                }
+                self.lockfile.min_coverage_ratio = Some(min_coverage_ratio);
+            }
+        }
+        if let Some(value) = overlay.existence {
+            if let Some(retries) = value.not_found_retries {
+                self.existence.not_found_retries = retries;
+            }
+            if let Some(delay_ms) = value.not_found_retry_delay_ms {
+                self.existence.not_found_retry_delay_ms = delay_ms;
+            }
+            if let Some(severity) = value.missing_package {
+                self.existence.missing_package = severity;
+            }
+            if let Some(severity) = value.missing_version {
+                self.existence.missing_version = severity;
+            }
         }
         if !overlay.custom_rules.is_empty() {
             custom_rules::merge_rules(&mut self.custom_rules, overlay.custom_rules);
         }
+        self.suppressions.extend(overlay.suppressions);
+        Ok(profile_overlay)
+    }
+}
+
+/// A layer's section for the selected profile, held until every base layer is merged.
+struct ProfileSection {
+    /// Where the section came from, for error messages.
+    source: String,
+    overlay: ConfigOverlay,
+    trust: LayerTrust,
+}
+
+/// Whether a config layer is controlled by the operator or by the checked-out project.
+///
+/// The global file and an explicit `--config` file are trusted; the project file and the
+/// remote overlay are not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LayerTrust {
+    Trusted,
+    Untrusted,
+}
+
+/// Keys only accepted from trusted layers.
+const TRUSTED_ONLY_KEYS: &[&str] = &["decision_hook", "decision_hook_fail_open", "npmrc"];
+
+fn reject_trusted_only_keys(overlay: &ConfigOverlay) -> anyhow::Result<()> {
+    let set = [
+        overlay.decision_hook.is_some(),
+        overlay.decision_hook_fail_open.is_some(),
+        overlay.npmrc.is_some(),
+    ];
+    let rejected = TRUSTED_ONLY_KEYS
+        .iter()
+        .zip(set)
+        .filter_map(|(key, is_set)| is_set.then_some(*key))
+        .collect::<Vec<_>>();
+    if !rejected.is_empty() {
+        anyhow::bail!(
+            "{} may only be set in the global config or a --config file",
+            rejected.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Resolves the config profile from an explicit selection or `SAFE_PKGS_PROFILE`.
+fn selected_profile(explicit: Option<&str>) -> Option<String> {
+    explicit
+        .map(ToOwned::to_owned)
+        .or_else(|| env::var("SAFE_PKGS_PROFILE").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn ensure_profile_found(profile: Option<&str>, found: bool) -> anyhow::Result<()> {
+    match profile {
+        Some(name) if !found => {
+            anyhow::bail!("config profile '{name}' is not defined in any config source")
+        }
+        _ => Ok(()),
     }
 }
 
@@ -476,7 +1287,10 @@ fn global_config_path() -> Option<PathBuf> {
         .or_else(|| env::var_os("USERPROFILE"))
         .map(PathBuf::from)?;
 
-    Some(home.join(".config").join("safe-pkgs").join("config.toml"))
+    Some(discover_config_file(
+        &home.join(".config").join("safe-pkgs"),
+        "config",
+    ))
 }
 
 fn project_config_path() -> Option<PathBuf> {
@@ -485,7 +1299,49 @@ fn project_config_path() -> Option<PathBuf> {
     }
 
     let cwd = env::current_dir().ok()?;
-    Some(cwd.join(".safe-pkgs.toml"))
+    Some(discover_config_file(&cwd, ".safe-pkgs"))
+}
+
+/// Extensions tried for a discovered config file, in order; TOML wins when several exist.
+const CONFIG_FILE_EXTENSIONS: [&str; 3] = ["toml", "yaml", "yml"];
+
+/// The first existing `<stem>.<ext>` in `dir`, or the TOML path when none exists.
+///
+/// Warns when several exist, since only the first one is loaded.
+fn discover_config_file(dir: &Path, stem: &str) -> PathBuf {
+    let mut existing = CONFIG_FILE_EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{stem}.{extension}")))
+        .filter(|path| path.is_file());
+    let Some(found) = existing.next() else {
+        return dir.join(format!("{stem}.toml"));
+    };
+    let ignored = existing
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+    if !ignored.is_empty() {
+        tracing::warn!(
+            "loading config file {} and ignoring {}; remove the files that should not apply",
+            found.display(),
+            ignored.join(", ")
+        );
+    }
+    found
+}
+
+/// Parses a config file as YAML for `.yaml`/`.yml` extensions and as TOML otherwise.
+fn parse_config_file(path: &Path, raw: &str) -> anyhow::Result<ConfigOverlay> {
+    let is_yaml = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml")
+        });
+    if is_yaml {
+        // An empty YAML document deserializes as null rather than an empty mapping.
+        return Ok(serde_yaml_ng::from_str::<Option<ConfigOverlay>>(raw)?.unwrap_or_default());
+    }
+    Ok(toml::from_str(raw)?)
 }
 
 /// HTTP timeout for fetching a remote config overlay.
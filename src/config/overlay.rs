@@ -1,16 +1,22 @@
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 use crate::types::Severity;
 
-use super::{AllowlistConfig, CustomRuleConfig, DenylistConfig};
+use super::{
+    AllowlistConfig, CustomRuleConfig, DenylistConfig, EnforcementMode, ListPrecedence,
+    SuppressionConfig, TransportErrorMode,
+};
 
 #[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 pub(super) struct DependencyConfusionOverlay {
     pub internal_packages: Option<Vec<String>>,
     pub internal_scopes: Option<Vec<String>>,
+    pub internal_prefixes: Option<Vec<String>>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -19,14 +25,55 @@ pub(super) struct ConfigOverlay {
     pub min_version_age_days: Option<i64>,
     pub min_weekly_downloads: Option<u64>,
     pub max_risk: Option<Severity>,
+    pub never_deny_below: Option<Severity>,
+    pub enforcement: Option<EnforcementMode>,
+    pub quarantine_hours: Option<i64>,
+    pub warn_on_unparseable_version: Option<bool>,
+    pub require_exact_version: Option<bool>,
+    pub require_publish_dates: Option<bool>,
+    pub include_fetch_latency: Option<bool>,
+    pub max_reasons: Option<usize>,
+    pub max_reason_length: Option<usize>,
+    pub max_in_flight_requests: Option<usize>,
+    pub max_response_bytes: Option<usize>,
+    pub npmrc: Option<PathBuf>,
+    pub decision_hook: Option<PathBuf>,
+    pub decision_hook_timeout_ms: Option<u64>,
+    pub decision_hook_fail_open: Option<bool>,
     pub allowlist: Option<AllowlistConfig>,
     pub denylist: Option<DenylistConfig>,
+    pub list_precedence: Option<ListPrecedence>,
     pub dependency_confusion: Option<DependencyConfusionOverlay>,
+    pub blackout: Option<BlackoutOverlay>,
     pub staleness: Option<StalenessOverlay>,
+    pub typosquat: Option<TyposquatOverlay>,
+    pub bus_factor: Option<BusFactorOverlay>,
+    pub version_age: Option<VersionAgeOverlay>,
+    pub install_script: Option<InstallScriptOverlay>,
+    pub release_churn: Option<ReleaseChurnOverlay>,
+    pub stale_stable: Option<StaleStableOverlay>,
+    pub advisory: Option<AdvisoryOverlay>,
     pub checks: Option<ChecksOverlay>,
     pub cache: Option<CacheOverlay>,
+    pub audit_log: Option<AuditLogOverlay>,
     pub lockfile: Option<LockfileOverlay>,
+    pub existence: Option<ExistenceOverlay>,
     pub custom_rules: Vec<CustomRuleConfig>,
+    pub suppressions: Vec<SuppressionConfig>,
+    pub profiles: BTreeMap<String, ConfigOverlay>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct BlackoutOverlay {
+    pub after: Option<DateTime<Utc>>,
+    pub registry: BTreeMap<String, RegistryBlackoutOverlay>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct RegistryBlackoutOverlay {
+    pub after: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -38,6 +85,53 @@ pub(super) struct StalenessOverlay {
     pub ignore_for: Option<Vec<String>>,
 }
 
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct TyposquatOverlay {
+    pub fail_open: Option<bool>,
+    pub popular_names_file: Option<PathBuf>,
+    pub popular_index_urls: BTreeMap<String, String>,
+    pub ignore: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct BusFactorOverlay {
+    pub high_download_threshold: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct VersionAgeOverlay {
+    pub exempt_patch_upgrades: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct InstallScriptOverlay {
+    pub detect_persistence: Option<bool>,
+    pub deny_all: Option<bool>,
+    pub allowed_commands: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct ReleaseChurnOverlay {
+    pub max_releases_per_week: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct StaleStableOverlay {
+    pub window_days: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct AdvisoryOverlay {
+    pub min_cvss: Option<f64>,
+}
+
 #[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 pub(super) struct ChecksOverlay {
@@ -55,6 +149,16 @@ pub(super) struct RegistryChecksOverlay {
 #[serde(default)]
 pub(super) struct CacheOverlay {
     pub ttl_minutes: Option<u64>,
+    pub popular_names_ttl_minutes: Option<u64>,
+    pub downloads_ttl_minutes: Option<u64>,
+    pub download_baseline_days: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct AuditLogOverlay {
+    pub max_log_bytes: Option<u64>,
+    pub max_files: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -62,4 +166,18 @@ pub(super) struct CacheOverlay {
 pub(super) struct LockfileOverlay {
     pub eval_concurrency: Option<usize>,
     pub inter_batch_delay_ms: Option<u64>,
+    pub advisory_concurrency: Option<usize>,
+    pub ignore_dev_dependencies: Option<bool>,
+    pub on_transport_error: Option<TransportErrorMode>,
+    pub report_unsupported_sources: Option<bool>,
+    pub min_coverage_ratio: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(super) struct ExistenceOverlay {
+    pub not_found_retries: Option<u32>,
+    pub not_found_retry_delay_ms: Option<u64>,
+    pub missing_package: Option<Severity>,
+    pub missing_version: Option<Severity>,
 }
@@ -12,6 +12,8 @@ use crate::config::{
 pub struct CustomRuleRuntimeRequirements {
     pub needs_weekly_downloads: bool,
     pub needs_advisories: bool,
+    pub needs_weekly_downloads_baseline: bool,
+    pub needs_full_metadata: bool,
 }
 
 /// Structured custom-rule finding with rule identity.
@@ -35,9 +37,20 @@ pub fn runtime_requirements_for_registry(
         for condition in &rule.conditions {
             match condition.field {
                 CustomRuleField::WeeklyDownloads => requirements.needs_weekly_downloads = true,
+                CustomRuleField::WeeklyDownloadsChange => {
+                    requirements.needs_weekly_downloads = true;
+                    requirements.needs_weekly_downloads_baseline = true;
+                }
                 CustomRuleField::AdvisoryCount | CustomRuleField::AdvisoryIds => {
                     requirements.needs_advisories = true
                 }
+                CustomRuleField::VersionAgeDays
+                | CustomRuleField::HasInstallScripts
+                | CustomRuleField::InstallScriptCount
+                | CustomRuleField::PublisherCount
+                | CustomRuleField::Publishers
+                | CustomRuleField::License
+                | CustomRuleField::RepositoryUrl => requirements.needs_full_metadata = true,
                 _ => {}
             }
         }
@@ -248,6 +261,11 @@ fn actual_value(
         CustomRuleField::WeeklyDownloads => context
             .weekly_downloads
             .map(|downloads| RuntimeValue::Number(i128::from(downloads))),
+        CustomRuleField::WeeklyDownloadsChange => context
+            .weekly_downloads
+            .zip(context.weekly_downloads_baseline)
+            .and_then(|(current, baseline)| weekly_downloads_change_percent(current, baseline))
+            .map(RuntimeValue::Number),
         CustomRuleField::AdvisoryCount => Some(RuntimeValue::Number(
             i128::try_from(context.advisories.len()).unwrap_or(i128::MAX),
         )),
@@ -258,7 +276,25 @@ fn actual_value(
                 .map(|advisory| advisory.id.clone())
                 .collect(),
         )),
+        CustomRuleField::License => context
+            .resolved_version
+            .and_then(|version| version.license.clone())
+            .map(RuntimeValue::String),
+        CustomRuleField::RepositoryUrl => context
+            .resolved_version
+            .and_then(|version| version.repository_url.clone())
+            .map(RuntimeValue::String),
+    }
+}
+
+/// Percent change from `baseline` to `current`, truncated toward zero; undefined for a
+/// zero baseline.
+fn weekly_downloads_change_percent(current: u64, baseline: u64) -> Option<i128> {
+    if baseline == 0 {
+        return None;
     }
+    let delta = i128::from(current) - i128::from(baseline);
+    Some(delta * 100 / i128::from(baseline))
 }
 
 #[derive(Debug, Clone)]
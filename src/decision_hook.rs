@@ -0,0 +1,145 @@
+//! External decision post-processor that can override package decisions.
+//!
+//! The hook program receives the [`ToolResponse`] JSON on stdin and prints an override
+//! object (`{"allow": bool, "risk"?: severity, "reason"?: string}`) on stdout.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{Context, anyhow};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::config::SafePkgsConfig;
+use crate::types::{Evidence, EvidenceKind, Severity, ToolResponse};
+
+/// Evidence id recorded when the hook changes a decision.
+const OVERRIDE_EVIDENCE_ID: &str = "decision_hook.override";
+/// Evidence id recorded when the hook could not be run or returned unusable output.
+const FAILED_EVIDENCE_ID: &str = "decision_hook.failed";
+
+/// Decision printed by the hook program.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DecisionOverride {
+    allow: bool,
+    #[serde(default)]
+    risk: Option<Severity>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Runs the configured decision hook, if any, and applies its decision to `response`.
+///
+/// When the hook fails, times out, or prints invalid output, the package is denied unless
+/// `decision_hook_fail_open` is set, in which case the original decision stands. The hook
+/// cannot allow a package or lower the risk of a report whose risk is already critical.
+pub async fn apply(config: &SafePkgsConfig, mut response: ToolResponse) -> ToolResponse {
+    let Some(program) = config.decision_hook.as_deref() else {
+        return response;
+    };
+
+    let timeout = Duration::from_millis(config.decision_hook_timeout_ms);
+    match run(program, &response, timeout).await {
+        Ok(decision) => {
+            let original_allow = response.allow;
+            let original_risk = response.risk;
+            // A critical report is never relaxed: the hook may only deny it.
+            let critical = original_risk == Severity::Critical;
+            if !(critical && decision.allow) {
+                response.allow = decision.allow;
+            }
+            if let Some(risk) = decision.risk.filter(|_| !critical) {
+                response.risk = risk;
+            }
+            if let Some(reason) = decision.reason.filter(|reason| !reason.trim().is_empty()) {
+                response.reasons.push(reason);
+            }
+            if response.allow != original_allow || response.risk != original_risk {
+                response.evidence.push(Evidence {
+                    kind: EvidenceKind::Policy,
+                    id: OVERRIDE_EVIDENCE_ID.to_string(),
+                    code: OVERRIDE_EVIDENCE_ID.to_string(),
+                    severity: response.risk,
+                    message: format!(
+                        "decision hook changed allow from {original_allow} to {}",
+                        response.allow
+                    ),
+                    facts: BTreeMap::from([
+                        ("original_allow".to_string(), json!(original_allow)),
+                        ("original_risk".to_string(), json!(original_risk)),
+                    ]),
+                    remediation: None,
+                });
+            }
+        }
+        Err(err) => {
+            let fail_open = config.decision_hook_fail_open;
+            let message = format!("decision hook {} failed: {err:#}", program.display());
+            tracing::warn!("{message}");
+            if !fail_open {
+                response.allow = false;
+                response.risk = Severity::Critical;
+                response.reasons.push(message.clone());
+            }
+            response.evidence.push(Evidence {
+                kind: EvidenceKind::Runtime,
+                id: FAILED_EVIDENCE_ID.to_string(),
+                code: FAILED_EVIDENCE_ID.to_string(),
+                severity: if fail_open {
+                    Severity::Low
+                } else {
+                    Severity::Critical
+                },
+                message,
+                facts: BTreeMap::from([("fail_open".to_string(), json!(fail_open))]),
+                remediation: None,
+            });
+        }
+    }
+    response
+}
+
+async fn run(
+    program: &Path,
+    response: &ToolResponse,
+    timeout: Duration,
+) -> anyhow::Result<DecisionOverride> {
+    let input = serde_json::to_vec(response)?;
+    let mut child = Command::new(program)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start")?;
+
+    let output = tokio::time::timeout(timeout, async move {
+        // A hook that decides without reading stdin may exit before the write completes.
+        if let Some(mut stdin) = child.stdin.take()
+            && let Err(err) = stdin.write_all(&input).await
+            && err.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            return Err(anyhow::Error::new(err).context("failed to write the decision to stdin"));
+        }
+        child
+            .wait_with_output()
+            .await
+            .context("failed to wait for exit")
+    })
+    .await
+    .map_err(|_| anyhow!("timed out after {} ms", timeout.as_millis()))??;
+
+    if !output.status.success() {
+        return Err(anyhow!("exited with {}", output.status));
+    }
+    serde_json::from_slice(&output.stdout).context("printed an invalid decision")
+}
+
+#[cfg(all(test, unix))]
+#[path = "tests/decision_hook.rs"]
+mod tests;
@@ -0,0 +1,105 @@
+//! Persisted lockfile snapshots for incremental audits.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use safe_pkgs_core::DependencySpec;
+
+use crate::types::ToolResponse;
+
+/// Decisions from the last audited version of one lockfile.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LockfileSnapshot {
+    /// SHA-256 of the dependency file content that produced these decisions.
+    pub content_hash: String,
+    /// Successful decisions keyed by package identity.
+    pub entries: Vec<LockfileSnapshotEntry>,
+}
+
+/// One reusable package decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockfileSnapshotEntry {
+    pub name: String,
+    pub requested: Option<String>,
+    pub response: ToolResponse,
+    /// When the decision was actually evaluated. Reuse keeps the original time, so entries
+    /// age out; snapshots written before this field existed count as expired.
+    #[serde(default)]
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl LockfileSnapshot {
+    /// Indexes entries by `(name, requested)` for unchanged-dependency lookups.
+    pub fn into_index(self) -> HashMap<(String, Option<String>), LockfileSnapshotEntry> {
+        self.entries
+            .into_iter()
+            .map(|entry| ((entry.name.clone(), entry.requested.clone()), entry))
+            .collect()
+    }
+}
+
+/// Returns the prior decision and its evaluation time for a dependency whose name and
+/// version are unchanged, unless the decision is `max_age` or older at `now`, so new
+/// advisories and yanks are eventually picked up.
+pub fn reusable_response(
+    index: &HashMap<(String, Option<String>), LockfileSnapshotEntry>,
+    spec: &DependencySpec,
+    now: DateTime<Utc>,
+    max_age: Duration,
+) -> Option<(ToolResponse, DateTime<Utc>)> {
+    let entry = index.get(&(spec.name.clone(), spec.version.clone()))?;
+    (now.signed_duration_since(entry.evaluated_at) < max_age)
+        .then(|| (entry.response.clone(), entry.evaluated_at))
+}
+
+/// Hex SHA-256 of a dependency file's content.
+pub fn content_hash(path: &Path) -> anyhow::Result<String> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("failed to read {} for snapshot hashing", path.display()))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Cache key for a complete lockfile audit response.
+///
+/// Besides the file hash, the key covers the parsed dependency set, because manifests can
+/// pull in other files (such as workspace members) that the file hash alone would miss.
+/// Specs are hashed as JSON lines, so the key does not depend on `Debug` formatting.
+pub fn response_cache_key(
+    config_fingerprint: &str,
+    policy_fingerprint: &str,
+    registry: &str,
+    content_hash: &str,
+    specs: &[DependencySpec],
+) -> anyhow::Result<String> {
+    let mut dependencies = Sha256::new();
+    for spec in specs {
+        dependencies.update(serde_json::to_vec(spec).context("failed to serialize dependency")?);
+        dependencies.update(b"\n");
+    }
+    Ok(format!(
+        "lockfile_response:v2:{config_fingerprint}:{policy_fingerprint}:{registry}:{content_hash}:{:x}",
+        dependencies.finalize()
+    ))
+}
+
+/// Cache key for a lockfile snapshot, scoped by policy so policy changes re-evaluate everything.
+pub fn snapshot_cache_key(policy_fingerprint: &str, registry: &str, input_path: &Path) -> String {
+    let canonical = input_path
+        .canonicalize()
+        .unwrap_or_else(|_| input_path.to_path_buf());
+    format!(
+        "lockfile_snapshot:{}:{}:{}",
+        policy_fingerprint,
+        registry,
+        canonical.display()
+    )
+}
+
+#[cfg(test)]
+#[path = "tests/lockfile_snapshot.rs"]
+mod tests;
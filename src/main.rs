@@ -5,19 +5,26 @@ mod cache;
 mod checks;
 mod config;
 mod custom_rules;
+mod decision_hook;
+mod lockfile_snapshot;
 mod mcp;
 mod metrics;
+mod output;
 mod policy_snapshot;
 mod registries;
+mod sbom;
 mod service;
 mod support_map;
+mod tarball;
+#[cfg(test)]
+mod test_support;
 mod types;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use mcp::SafePkgsServer;
 use rmcp::ServiceExt;
 use service::SafePkgsService;
-use std::io::IsTerminal;
+use std::io::{BufRead, IsTerminal};
 
 #[cfg(windows)]
 fn hide_console_window() {
@@ -52,6 +59,43 @@ fn hide_console_window() {}
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Override the npm registry base URL (takes precedence over SAFE_PKGS_NPM_REGISTRY_API_BASE_URL)
+    #[arg(long, global = true, value_name = "URL")]
+    npm_registry_url: Option<String>,
+    /// Override the crates.io API base URL
+    #[arg(long, global = true, value_name = "URL")]
+    cargo_registry_url: Option<String>,
+    /// Override the PyPI package API base URL (takes precedence over SAFE_PKGS_PYPI_PACKAGE_API_BASE_URL)
+    #[arg(long, global = true, value_name = "URL")]
+    pypi_registry_url: Option<String>,
+    /// Load config from this file only, skipping remote, global, and project config discovery
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<std::path::PathBuf>,
+    /// Apply the `[profiles.<NAME>]` config section over the base config (takes precedence over SAFE_PKGS_PROFILE)
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+    /// Print JSON output on a single line instead of pretty-printed
+    #[arg(long, global = true)]
+    json_compact: bool,
+}
+
+impl Cli {
+    /// Collects registry client overrides from global CLI flags.
+    fn registry_overrides(&self) -> registries::RegistryClientOverrides {
+        registries::RegistryClientOverrides::default()
+            .with_base_url("npm", self.npm_registry_url.clone())
+            .with_base_url("cargo", self.cargo_registry_url.clone())
+            .with_base_url("pypi", self.pypi_registry_url.clone())
+    }
+}
+
+/// Serializes CLI output, pretty-printed unless `compact` is set.
+fn render_json<T: serde::Serialize>(value: &T, compact: bool) -> serde_json::Result<String> {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
 }
 
 #[derive(Subcommand)]
@@ -62,10 +106,51 @@ enum Commands {
     /// Run a one-off dependency audit from supported lockfile/manifest formats
     Audit {
         /// Path to a dependency file or project directory
-        path: String,
+        #[arg(required_unless_present = "sbom")]
+        path: Option<String>,
+        /// Audit a CycloneDX JSON SBOM instead, routing each component by its purl
+        #[arg(long, value_name = "FILE", conflicts_with = "path")]
+        sbom: Option<String>,
         /// Registry for dependency file parsing and package checks
         #[arg(long, default_value_t = crate::registries::default_lockfile_registry_key().to_string())]
         registry: String,
+        /// Only re-check dependencies that changed since the last audit of this file
+        #[arg(long)]
+        incremental: bool,
+        /// Only list denied packages or those at medium risk and above (totals still cover all)
+        #[arg(long)]
+        risky_only: bool,
+        /// Output format for the audit report
+        #[arg(long, value_enum, default_value_t = AuditFormat::Human)]
+        format: AuditFormat,
+    },
+    /// Check standalone package specs such as `lodash@4.17.21` or `cargo:serde`
+    Check {
+        /// Package specs in `[<registry>:]<name>[@<version>]` form
+        #[arg(required_unless_present = "stdin")]
+        specs: Vec<String>,
+        /// Read newline-delimited specs from stdin (blank lines and `#` comments are skipped)
+        #[arg(long, conflicts_with = "specs")]
+        stdin: bool,
+        /// Registry for every spec (a conflicting `<registry>:` prefix is an error)
+        #[arg(long)]
+        registry: Option<String>,
+        /// Emit one compact JSON result per line instead of a JSON array
+        #[arg(long, conflicts_with = "format")]
+        ndjson: bool,
+        /// Output format for the results
+        #[arg(long, value_enum, default_value_t = CheckFormat::Human)]
+        format: CheckFormat,
+    },
+    /// Vet a package tarball (npm .tgz, cargo .crate, or PyPI sdist) without a registry lookup
+    #[command(alias = "check_tarball")]
+    CheckTarball {
+        /// Local path or http(s) URL of the tarball
+        source: String,
+        /// Archive format (npm, cargo, or pypi); inferred from a .tgz/.crate extension or
+        /// the archive's only manifest when omitted
+        #[arg(long)]
+        registry: Option<String>,
     },
     /// Simulate policy decisions for a dependency file without enforcing them (what-if)
     Simulate {
@@ -75,6 +160,32 @@ enum Commands {
         #[arg(long, default_value_t = crate::registries::default_lockfile_registry_key().to_string())]
         registry: String,
     },
+    /// Pre-populate the decision cache for a dependency file, printing only counts
+    Warm {
+        /// Path to a dependency file or project directory
+        #[arg(long)]
+        path: String,
+        /// Registry for dependency file parsing and package checks
+        #[arg(long, default_value_t = crate::registries::default_lockfile_registry_key().to_string())]
+        registry: String,
+    },
+    /// Report packages whose decision changes between two config files (dry run)
+    PolicyDiff {
+        /// Path to a dependency file or project directory
+        #[arg(long)]
+        path: String,
+        /// Baseline config file
+        #[arg(long, value_name = "FILE")]
+        config_a: std::path::PathBuf,
+        /// Candidate config file
+        #[arg(long, value_name = "FILE")]
+        config_b: std::path::PathBuf,
+        /// Registry for dependency file parsing and package checks
+        #[arg(long, default_value_t = crate::registries::default_lockfile_registry_key().to_string())]
+        registry: String,
+    },
+    /// Verify the config loads and the cache is usable, without network calls (liveness probe)
+    Ready,
     /// Print check support for registries
     SupportMap {
         /// Disable ANSI colors
@@ -83,6 +194,26 @@ enum Commands {
     },
 }
 
+/// Output formats for `safe-pkgs check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CheckFormat {
+    /// Decision, risk, and reasons per package (colored on a terminal)
+    Human,
+    /// JSON array of per-package results
+    Json,
+}
+
+/// Output formats for `safe-pkgs audit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AuditFormat {
+    /// Audit decision, totals, and per-package reasons (colored on a terminal)
+    Human,
+    /// safe-pkgs lockfile response JSON
+    Json,
+    /// CycloneDX JSON BOM with decisions as properties and advisories as vulnerabilities
+    Cyclonedx,
+}
+
 /// Returns registry definitions wired into this application build.
 pub(crate) fn app_registry_definitions() -> Vec<registries::RegistryDefinition> {
     vec![
@@ -102,12 +233,22 @@ pub(crate) fn app_check_factories() -> Vec<safe_pkgs_core::CheckFactory> {
         safe_pkgs_check_install_script::create_check,
         safe_pkgs_check_typosquat::create_check,
         safe_pkgs_check_advisory::create_check,
+        safe_pkgs_check_size_anomaly::create_check,
+        safe_pkgs_check_bus_factor::create_check,
+        safe_pkgs_check_single_version::create_check,
+        safe_pkgs_check_maintainer_domain::create_check,
+        safe_pkgs_check_release_churn::create_check,
+        safe_pkgs_check_binary_only::create_check,
+        safe_pkgs_check_suspicious_name::create_check,
+        safe_pkgs_check_stale_stable::create_check,
     ]
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    let overrides = cli.registry_overrides();
+    let compact = cli.json_compact;
 
     match cli.command {
         Commands::Serve => {
@@ -121,37 +262,188 @@ async fn main() -> anyhow::Result<()> {
 
             tracing::info!("safe-pkgs MCP server starting");
 
-            let server = SafePkgsServer::new().await?;
+            let server =
+                SafePkgsServer::new(&overrides, cli.config.as_deref(), cli.profile.as_deref())
+                    .await?;
             let service = server.serve(rmcp::transport::stdio()).await?;
             service.waiting().await?;
         }
-        Commands::Audit { path, registry } => {
-            let service = SafePkgsService::new().await?;
-            let report = service
-                .audit_lockfile_path_with_registry(&path, &registry)
+        Commands::Audit {
+            path,
+            sbom,
+            registry,
+            incremental,
+            risky_only,
+            format,
+        } => {
+            let service =
+                SafePkgsService::new(&overrides, cli.config.as_deref(), cli.profile.as_deref())
+                    .await?;
+            let options = service::LockfileAuditOptions {
+                incremental,
+                risky_only,
+            };
+            // SBOM audits record each component's registry on the package itself.
+            let output_registry = sbom.is_none().then_some(registry.as_str());
+            let report = match (&sbom, path) {
+                (Some(sbom), _) => service.audit_sbom_path(sbom, options).await?,
+                (None, Some(path)) => {
+                    service
+                        .audit_lockfile_path_with_registry(&path, &registry, options)
+                        .await?
+                }
+                (None, None) => anyhow::bail!("audit requires a dependency path or --sbom"),
+            };
+            let rendered = match format {
+                AuditFormat::Human => {
+                    output::human::render_lockfile_report(&report, stdout_color_enabled(false))
+                }
+                AuditFormat::Json => render_json(&report, compact)?,
+                AuditFormat::Cyclonedx => render_json(
+                    &output::cyclonedx::to_cyclonedx(&report, output_registry),
+                    compact,
+                )?,
+            };
+            println!("{rendered}");
+        }
+        Commands::Check {
+            specs,
+            stdin,
+            registry,
+            ndjson,
+            format,
+        } => {
+            let specs = if stdin {
+                parse_spec_lines(std::io::stdin().lock(), registry.as_deref())?
+            } else {
+                parse_specs(specs.iter().map(String::as_str), registry.as_deref())?
+            };
+            let service =
+                SafePkgsService::new(&overrides, cli.config.as_deref(), cli.profile.as_deref())
+                    .await?;
+            let results = service.evaluate_package_specs(specs, "cli").await?;
+            if ndjson {
+                for result in &results {
+                    println!("{}", serde_json::to_string(result)?);
+                }
+            } else if format == CheckFormat::Json {
+                println!("{}", render_json(&results, compact)?);
+            } else {
+                println!(
+                    "{}",
+                    output::human::render_package_results(&results, stdout_color_enabled(false))
+                );
+            }
+        }
+        Commands::CheckTarball { source, registry } => {
+            let service =
+                SafePkgsService::new(&overrides, cli.config.as_deref(), cli.profile.as_deref())
+                    .await?;
+            let result = service
+                .check_tarball(&source, registry.as_deref(), "cli")
                 .await?;
-            let json = serde_json::to_string_pretty(&report)?;
-            println!("{json}");
+            println!("{}", render_json(&result, compact)?);
         }
         Commands::Simulate { path, registry } => {
-            let service = SafePkgsService::new()?;
+            let service =
+                SafePkgsService::new(&overrides, cli.config.as_deref(), cli.profile.as_deref())
+                    .await?;
             let report = service
                 .simulate_lockfile_path_with_registry(&path, &registry)
                 .await?;
-            let json = serde_json::to_string_pretty(&report)?;
+            let json = render_json(&report, compact)?;
             println!("{json}");
         }
+        Commands::Warm { path, registry } => {
+            let service =
+                SafePkgsService::new(&overrides, cli.config.as_deref(), cli.profile.as_deref())
+                    .await?;
+            let report = service
+                .warm_lockfile_path_with_registry(&path, &registry)
+                .await?;
+            println!("{}", render_json(&report, compact)?);
+        }
+        Commands::PolicyDiff {
+            path,
+            config_a,
+            config_b,
+            registry,
+        } => {
+            let profile = cli.profile.as_deref();
+            let service_a = SafePkgsService::from_config(
+                &overrides,
+                config::SafePkgsConfig::load_file(&config_a, profile)?,
+            )?;
+            let service_b = SafePkgsService::from_config(
+                &overrides,
+                config::SafePkgsConfig::load_file(&config_b, profile)?,
+            )?;
+            let report = service_a
+                .policy_diff_lockfile_path(&service_b, &path, &registry)
+                .await?;
+            println!("{}", render_json(&report, compact)?);
+        }
+        Commands::Ready => {
+            let service =
+                SafePkgsService::new(&overrides, cli.config.as_deref(), cli.profile.as_deref())
+                    .await?;
+            println!("{}", render_json(&service.readiness()?, compact)?);
+        }
         Commands::SupportMap { no_color } => {
-            let use_color = !no_color
-                && std::io::stdout().is_terminal()
-                && std::env::var_os("NO_COLOR").is_none();
-            println!("{}", support_map::render_support_map(use_color));
+            println!(
+                "{}",
+                support_map::render_support_map(stdout_color_enabled(no_color))
+            );
         }
     }
 
     Ok(())
 }
 
+/// ANSI colors only go to a terminal, and never when `NO_COLOR` is set.
+fn stdout_color_enabled(no_color: bool) -> bool {
+    !no_color && std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Resolves `[<registry>:]<name>[@<version>]` specs with the same rules as `check_package`.
+fn parse_specs<'a>(
+    specs: impl IntoIterator<Item = &'a str>,
+    registry: Option<&str>,
+) -> anyhow::Result<Vec<service::PackageSpec>> {
+    specs
+        .into_iter()
+        .map(|spec| {
+            mcp::server::resolve_package_spec(&mcp::server::PackageQuery {
+                name: spec.to_string(),
+                version: None,
+                registry: registry.map(ToOwned::to_owned),
+                resolve: None,
+                current_version: None,
+            })
+            .map_err(|err| anyhow::anyhow!(err))
+        })
+        .collect()
+}
+
+/// Reads newline-delimited package specs, skipping blank lines and `#` comments.
+fn parse_spec_lines(
+    reader: impl BufRead,
+    registry: Option<&str>,
+) -> anyhow::Result<Vec<service::PackageSpec>> {
+    let mut specs = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parsed = parse_specs([line], registry)
+            .map_err(|err| anyhow::anyhow!("line {}: {err}", idx + 1))?;
+        specs.append(&mut parsed);
+    }
+    Ok(specs)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,7 +475,33 @@ mod tests {
 
         assert!(npm.excluded_checks.is_empty());
         assert!(cargo.excluded_checks.contains(&"install_script"));
+        assert!(cargo.excluded_checks.contains(&"size_anomaly"));
+        assert!(cargo.excluded_checks.contains(&"bus_factor"));
         assert!(pypi.excluded_checks.contains(&"install_script"));
+        assert!(pypi.excluded_checks.contains(&"size_anomaly"));
+        assert!(cargo.excluded_checks.contains(&"maintainer_domain"));
+        assert!(pypi.excluded_checks.contains(&"maintainer_domain"));
+        assert!(cargo.excluded_checks.contains(&"binary_only"));
+        assert!(pypi.excluded_checks.contains(&"binary_only"));
+    }
+
+    #[test]
+    fn global_registry_url_flags_map_to_client_overrides() {
+        let cli = Cli::try_parse_from([
+            "safe-pkgs",
+            "audit",
+            "package-lock.json",
+            "--npm-registry-url",
+            "https://npm.internal.example",
+        ])
+        .expect("valid cli args");
+        let overrides = cli.registry_overrides();
+
+        assert_eq!(
+            overrides.options_for("npm").base_url.as_deref(),
+            Some("https://npm.internal.example")
+        );
+        assert_eq!(overrides.options_for("pypi").base_url, None);
     }
 
     #[test]
@@ -198,4 +516,203 @@ mod tests {
         assert!(ids.contains(&"version_age"));
         assert!(ids.contains(&"advisory"));
     }
+
+    #[test]
+    fn audit_accepts_sbom_flag_without_path() {
+        let cli = Cli::try_parse_from(["safe-pkgs", "audit", "--sbom", "bom.json"])
+            .expect("valid cli args");
+        let Commands::Audit { path, sbom, .. } = cli.command else {
+            panic!("expected audit command");
+        };
+        assert_eq!(path, None);
+        assert_eq!(sbom.as_deref(), Some("bom.json"));
+
+        assert!(Cli::try_parse_from(["safe-pkgs", "audit"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "safe-pkgs",
+                "audit",
+                "package-lock.json",
+                "--sbom",
+                "bom.json"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn audit_format_defaults_to_human_and_accepts_json_and_cyclonedx() {
+        let cli =
+            Cli::try_parse_from(["safe-pkgs", "audit", "Cargo.lock"]).expect("valid cli args");
+        let Commands::Audit { format, .. } = cli.command else {
+            panic!("expected audit command");
+        };
+        assert_eq!(format, AuditFormat::Human);
+
+        let cli = Cli::try_parse_from(["safe-pkgs", "audit", "Cargo.lock", "--format", "json"])
+            .expect("valid cli args");
+        let Commands::Audit { format, .. } = cli.command else {
+            panic!("expected audit command");
+        };
+        assert_eq!(format, AuditFormat::Json);
+
+        let cli =
+            Cli::try_parse_from(["safe-pkgs", "audit", "Cargo.lock", "--format", "cyclonedx"])
+                .expect("valid cli args");
+        let Commands::Audit { format, .. } = cli.command else {
+            panic!("expected audit command");
+        };
+        assert_eq!(format, AuditFormat::Cyclonedx);
+    }
+
+    #[test]
+    fn parse_spec_lines_skips_blank_lines_and_comments() {
+        let input = "# team allowlist\nlodash@4.17.21\n\ncargo:serde@1.0.0\n  left-pad  \n";
+        let specs = parse_spec_lines(input.as_bytes(), None).expect("valid specs");
+
+        assert_eq!(specs.len(), 3);
+        assert_eq!(specs[0].name, "lodash");
+        assert_eq!(specs[0].version.as_deref(), Some("4.17.21"));
+        assert_eq!(specs[1].registry, "cargo");
+        assert_eq!(specs[2].name, "left-pad");
+        assert_eq!(specs[2].registry, "npm");
+
+        let err = parse_spec_lines("lodash\npypi:requests\n".as_bytes(), Some("npm"))
+            .expect_err("conflicting registry prefix");
+        assert!(err.to_string().starts_with("line 2:"));
+    }
+
+    #[test]
+    fn warm_requires_path_flag() {
+        assert!(Cli::try_parse_from(["safe-pkgs", "warm"]).is_err());
+
+        let cli = Cli::try_parse_from(["safe-pkgs", "warm", "--path", "Cargo.lock"])
+            .expect("valid cli args");
+        let Commands::Warm { path, registry } = cli.command else {
+            panic!("expected warm command");
+        };
+        assert_eq!(path, "Cargo.lock");
+        assert_eq!(registry, registries::default_lockfile_registry_key());
+    }
+
+    #[test]
+    fn policy_diff_requires_both_configs() {
+        assert!(
+            Cli::try_parse_from([
+                "safe-pkgs",
+                "policy-diff",
+                "--path",
+                "package-lock.json",
+                "--config-a",
+                "a.toml"
+            ])
+            .is_err()
+        );
+
+        let cli = Cli::try_parse_from([
+            "safe-pkgs",
+            "policy-diff",
+            "--path",
+            "package-lock.json",
+            "--config-a",
+            "a.toml",
+            "--config-b",
+            "b.toml",
+        ])
+        .expect("valid cli args");
+        let Commands::PolicyDiff {
+            config_a, config_b, ..
+        } = cli.command
+        else {
+            panic!("expected policy-diff command");
+        };
+        assert_eq!(config_a, std::path::PathBuf::from("a.toml"));
+        assert_eq!(config_b, std::path::PathBuf::from("b.toml"));
+    }
+
+    #[test]
+    fn global_config_flag_is_accepted_by_every_command() {
+        let cli = Cli::try_parse_from(["safe-pkgs", "check", "lodash", "--config", "strict.toml"])
+            .expect("valid cli args");
+        assert_eq!(cli.config, Some(std::path::PathBuf::from("strict.toml")));
+
+        let cli = Cli::try_parse_from(["safe-pkgs", "--config", "strict.toml", "serve"])
+            .expect("valid cli args");
+        assert_eq!(cli.config, Some(std::path::PathBuf::from("strict.toml")));
+
+        let cli = Cli::try_parse_from(["safe-pkgs", "ready"]).expect("valid cli args");
+        assert_eq!(cli.config, None);
+    }
+
+    #[test]
+    fn ready_takes_no_arguments() {
+        let cli = Cli::try_parse_from(["safe-pkgs", "ready"]).expect("valid cli args");
+        assert!(matches!(cli.command, Commands::Ready));
+        assert!(Cli::try_parse_from(["safe-pkgs", "ready", "extra"]).is_err());
+    }
+
+    #[test]
+    fn check_requires_specs_or_stdin() {
+        assert!(Cli::try_parse_from(["safe-pkgs", "check"]).is_err());
+        assert!(Cli::try_parse_from(["safe-pkgs", "check", "--stdin", "lodash"]).is_err());
+
+        let cli = Cli::try_parse_from(["safe-pkgs", "check", "--stdin", "--registry", "npm"])
+            .expect("valid cli args");
+        let Commands::Check {
+            stdin, registry, ..
+        } = cli.command
+        else {
+            panic!("expected check command");
+        };
+        assert!(stdin);
+        assert_eq!(registry.as_deref(), Some("npm"));
+    }
+
+    #[test]
+    fn check_format_defaults_to_human_and_conflicts_with_ndjson() {
+        let cli = Cli::try_parse_from(["safe-pkgs", "check", "lodash"]).expect("valid cli args");
+        let Commands::Check { format, .. } = cli.command else {
+            panic!("expected check command");
+        };
+        assert_eq!(format, CheckFormat::Human);
+
+        let cli = Cli::try_parse_from(["safe-pkgs", "check", "lodash", "--format", "json"])
+            .expect("valid cli args");
+        let Commands::Check { format, .. } = cli.command else {
+            panic!("expected check command");
+        };
+        assert_eq!(format, CheckFormat::Json);
+
+        assert!(
+            Cli::try_parse_from([
+                "safe-pkgs",
+                "check",
+                "lodash",
+                "--ndjson",
+                "--format",
+                "json"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn json_compact_flag_prints_single_line_output() {
+        let cli = Cli::try_parse_from(["safe-pkgs", "check", "lodash", "--json-compact"])
+            .expect("valid cli args");
+        assert!(cli.json_compact);
+
+        let value = serde_json::json!({"allow": true, "risk": "low", "reasons": ["ok"]});
+        let compact = render_json(&value, cli.json_compact).expect("compact json");
+        assert!(!compact.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).expect("valid json"),
+            value
+        );
+        assert!(
+            render_json(&value, false)
+                .expect("pretty json")
+                .contains('\n')
+        );
+    }
 }
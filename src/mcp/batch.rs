@@ -0,0 +1,138 @@
+//! Coalescing of concurrent `check_package` calls into shared registry lookups.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use crate::registries::PackageMemo;
+use crate::service::SafePkgsService;
+
+/// How long the first call in a batch waits for others to join before lookups start.
+pub const CHECK_PACKAGE_BATCH_WINDOW: Duration = Duration::from_millis(20);
+
+/// Groups `check_package` calls that arrive within [`CHECK_PACKAGE_BATCH_WINDOW`] of each
+/// other for the same registry.
+///
+/// A batch warms bulk lookups (such as npm weekly downloads), fetches every package record
+/// once into a shared [`PackageMemo`], and queries advisories for the whole batch together.
+/// A call with no other call pending skips the window and is flushed at once.
+#[derive(Default)]
+pub struct CheckPackageBatcher {
+    open: Mutex<HashMap<String, Arc<PackageBatch>>>,
+    /// Calls that have joined a batch whose lookups are not yet warm.
+    pending: AtomicUsize,
+}
+
+/// One coalesced group of package checks for a registry.
+pub struct PackageBatch {
+    requests: Mutex<Vec<(String, Option<String>)>>,
+    ready: watch::Sender<bool>,
+    memo: Arc<PackageMemo>,
+}
+
+impl PackageBatch {
+    fn new() -> Self {
+        Self {
+            requests: Mutex::new(Vec::new()),
+            ready: watch::channel(false).0,
+            memo: Arc::new(PackageMemo::default()),
+        }
+    }
+
+    /// Package metadata shared by every call in this batch.
+    pub fn memo(&self) -> &PackageMemo {
+        &self.memo
+    }
+}
+
+/// Decrements the batcher's pending count when a call stops waiting, even if cancelled.
+struct PendingGuard<'a>(&'a AtomicUsize);
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl CheckPackageBatcher {
+    /// Adds the request to the open batch for `registry`, opening one if needed, and
+    /// returns once that batch's shared lookups have been warmed.
+    pub async fn join(
+        self: &Arc<Self>,
+        service: &Arc<SafePkgsService>,
+        registry: &str,
+        package_name: &str,
+        requested_version: Option<&str>,
+    ) -> Arc<PackageBatch> {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        let _pending = PendingGuard(&self.pending);
+        let registry = registry.to_ascii_lowercase();
+        let (batch, opened) = {
+            let mut open = lock(&self.open);
+            let (batch, opened) = match open.get(&registry) {
+                Some(batch) => (Arc::clone(batch), false),
+                None => {
+                    let batch = Arc::new(PackageBatch::new());
+                    open.insert(registry.clone(), Arc::clone(&batch));
+                    (batch, true)
+                }
+            };
+            // Recorded while the batch is still open so the flush always sees this request.
+            lock(&batch.requests).push((
+                package_name.to_string(),
+                requested_version.map(ToOwned::to_owned),
+            ));
+            (batch, opened)
+        };
+
+        // Subscribe before scheduling the flush so the ready signal cannot be missed.
+        let mut ready = batch.ready.subscribe();
+        if opened {
+            // The flush runs detached so a cancelled caller cannot strand the rest of the batch.
+            let batcher = Arc::clone(self);
+            let service = Arc::clone(service);
+            let flushing = Arc::clone(&batch);
+            tokio::spawn(async move {
+                // Let calls submitted alongside this one register before deciding to wait.
+                tokio::task::yield_now().await;
+                if batcher.pending.load(Ordering::SeqCst) > 1 {
+                    tokio::time::sleep(CHECK_PACKAGE_BATCH_WINDOW).await;
+                }
+                batcher.close(&registry, &flushing);
+                let requests = std::mem::take(&mut *lock(&flushing.requests));
+                if let Err(err) = service
+                    .prefetch_check_batch(&registry, &requests, &flushing.memo)
+                    .await
+                {
+                    tracing::warn!("batched prefetch failed for {registry}: {err}");
+                }
+                flushing.ready.send_replace(true);
+            });
+        }
+
+        // Prefetch is an optimization; per-package lookups still run if it is skipped.
+        let _ = ready.wait_for(|ready| *ready).await;
+        batch
+    }
+
+    /// Stops new calls from joining `batch`; later calls open a fresh one.
+    fn close(&self, registry: &str, batch: &Arc<PackageBatch>) {
+        let mut open = lock(&self.open);
+        if open
+            .get(registry)
+            .is_some_and(|current| Arc::ptr_eq(current, batch))
+        {
+            open.remove(registry);
+        }
+    }
+}
+
+/// A poisoned lock only means another task panicked mid-update; the data is still usable.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
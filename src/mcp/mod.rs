@@ -1,5 +1,6 @@
 //! MCP server module exports.
 
+mod batch;
 pub mod server;
 
 /// MCP server entrypoint type used by CLI startup and tests.
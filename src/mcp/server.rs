@@ -9,11 +9,10 @@ use rmcp::{
 use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::Deserialize;
 
-use crate::service::SafePkgsService;
+use crate::registries::PackageMemo;
+use crate::service::{PackageSpec, SafePkgsService};
 
-fn default_package_registry() -> String {
-    crate::registries::default_package_registry_key().to_string()
-}
+use super::batch::CheckPackageBatcher;
 
 fn package_registry_schema(generator: &mut SchemaGenerator) -> Schema {
     let mut schema = String::json_schema(generator);
@@ -84,9 +83,9 @@ fn lockfile_path_schema(generator: &mut SchemaGenerator) -> Schema {
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct PackageQuery {
     #[schemars(
-        description = "Package name to evaluate, e.g. \"lodash\". Use this tool before dependency actions such as add, install, update, or upgrade."
+        description = "Package name to evaluate, e.g. \"lodash\". Also accepts \"<registry>:<name>[@<version>]\" specs such as \"cargo:serde@1.0.0\"; the prefix selects the registry. Use this tool before dependency actions such as add, install, update, or upgrade."
     )]
-    /// Package name to evaluate.
+    /// Package name or `[<registry>:]<name>[@<version>]` spec to evaluate.
     pub name: String,
 
     #[schemars(
@@ -95,9 +94,73 @@ pub struct PackageQuery {
     /// Optional version. Uses latest when omitted.
     pub version: Option<String>,
 
-    #[serde(default = "default_package_registry")]
+    #[serde(default)]
     #[schemars(schema_with = "package_registry_schema")]
-    pub registry: String,
+    pub registry: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "How to pick the version to evaluate. \"exact\" (default) checks `version` as given. \"safest\" treats `version` as a range (for example \"^1.2\" or \">=2.0,<3\"), evaluates candidates newest first, and returns the newest passing `recommended_version` plus the reasons newer candidates were rejected."
+    )]
+    /// Version resolution mode. Defaults to exact.
+    pub resolve: Option<VersionResolution>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Version the project already has, for example from its lockfile. When the evaluated version is a major (medium risk) or minor (low risk) upgrade from it, the response includes a `version.major_upgrade` or `version.minor_upgrade` finding."
+    )]
+    /// Currently installed version, compared against the evaluated one.
+    pub current_version: Option<String>,
+}
+
+/// Version resolution mode for the `check_package` MCP tool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionResolution {
+    /// Evaluate the requested version as given.
+    #[default]
+    Exact,
+    /// Evaluate versions within the requested range and recommend the newest passing one.
+    Safest,
+}
+
+/// One entry of the `check_packages` MCP tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PackageListEntry {
+    #[schemars(
+        description = "Package name to evaluate. Also accepts \"<registry>:<name>[@<version>]\" specs such as \"cargo:serde@1.0.0\"."
+    )]
+    /// Package name or `[<registry>:]<name>[@<version>]` spec to evaluate.
+    pub name: String,
+
+    #[schemars(description = "Specific version to evaluate. Omit to check the latest release.")]
+    /// Optional version. Uses latest when omitted.
+    pub version: Option<String>,
+
+    #[serde(default)]
+    #[schemars(schema_with = "package_registry_schema")]
+    pub registry: Option<String>,
+}
+
+impl PackageListEntry {
+    fn as_query(&self) -> PackageQuery {
+        PackageQuery {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            registry: self.registry.clone(),
+            resolve: None,
+            current_version: None,
+        }
+    }
+}
+
+/// Parameters for the `check_packages` MCP tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PackageListQuery {
+    #[schemars(
+        description = "Packages to evaluate together, each `{ name, version?, registry? }`. Must not be empty."
+    )]
+    pub packages: Vec<PackageListEntry>,
 }
 
 /// Parameters for the `check_lockfile` MCP tool.
@@ -109,6 +172,13 @@ pub struct LockfileQuery {
     #[serde(default = "default_lockfile_registry")]
     #[schemars(schema_with = "lockfile_registry_schema")]
     pub registry: String,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Only list denied packages or those at medium risk and above. Aggregate `total`, `denied`, and `risk` still cover every package. Defaults to false."
+    )]
+    /// Omits allowed low-risk packages from `packages` when true.
+    pub risky_only: Option<bool>,
 }
 
 /// MCP transport adapter for the shared package safety service.
@@ -117,6 +187,7 @@ pub struct SafePkgsServer {
     #[expect(dead_code, reason = "used by the generated rmcp tool router")]
     tool_router: ToolRouter<Self>,
     service: Arc<SafePkgsService>,
+    batcher: Arc<CheckPackageBatcher>,
 }
 
 #[tool_router]
@@ -126,8 +197,14 @@ impl SafePkgsServer {
     /// # Errors
     ///
     /// Returns an error if the underlying service fails to initialize.
-    pub async fn new() -> anyhow::Result<Self> {
-        Ok(Self::with_service(SafePkgsService::new().await?))
+    pub async fn new(
+        overrides: &crate::registries::RegistryClientOverrides,
+        config_path: Option<&std::path::Path>,
+        profile: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self::with_service(
+            SafePkgsService::new(overrides, config_path, profile).await?,
+        ))
     }
 
     #[cfg(test)]
@@ -136,33 +213,122 @@ impl SafePkgsServer {
         Self::with_service(SafePkgsService::with_config(config))
     }
 
+    #[cfg(test)]
+    /// Creates a test server around an injected registry catalog and cache, so the whole
+    /// evaluation pipeline runs without network access.
+    pub fn with_clients(
+        catalog: crate::registries::RegistryCatalog,
+        config: crate::config::SafePkgsConfig,
+        cache: crate::cache::SqliteCache,
+    ) -> Self {
+        let audit_logger =
+            crate::audit_log::AuditLogger::new(&config.audit_log).expect("audit logger");
+        Self::with_service(
+            SafePkgsService::with_cache(catalog, config, cache, audit_logger)
+                .expect("service init for tests"),
+        )
+    }
+
     fn with_service(service: SafePkgsService) -> Self {
         Self {
             tool_router: Self::tool_router(),
             service: Arc::new(service),
+            batcher: Arc::new(CheckPackageBatcher::default()),
         }
     }
 
     #[tool(
         name = "check_package",
-        description = "FIRST TOOL for single dependency requests. Trigger on prompts like: \"add lodash 1.0.2\", \"install react\", \"update axios\", \"upgrade requests\". MUST run before editing package files or running install commands. Returns `allow`, `risk`, `reasons`, machine-readable `evidence`, `metadata`, and `fingerprints` (`config`, `policy`). Evidence format: `evidence[]` items are `{ kind, id, severity, message, facts }`; `id` is stable for automation (for example, `<check_id>.<reason_code>`, `custom_rule.<rule_id>`, or policy/runtime IDs). If `allow` is false, stop and report findings."
+        description = "FIRST TOOL for single dependency requests. Trigger on prompts like: \"add lodash 1.0.2\", \"install react\", \"update axios\", \"upgrade requests\". MUST run before editing package files or running install commands. Returns `allow`, `risk`, `reasons`, machine-readable `evidence`, `metadata`, and `fingerprints` (`config`, `policy`). Evidence format: `evidence[]` items are `{ kind, id, severity, message, facts }`; `id` is stable for automation (for example, `<check_id>.<reason_code>`, `custom_rule.<rule_id>`, or policy/runtime IDs). If `allow` is false, stop and report findings. Set `resolve` to \"safest\" with a version range to get the newest passing `recommended_version` and the `rejected` newer candidates instead."
     )]
     async fn check_package(
         &self,
         Parameters(query): Parameters<PackageQuery>,
     ) -> Result<CallToolResult, McpError> {
         validate_package_query(&query)?;
+        let spec = resolve_package_spec(&query)
+            .map_err(|message| McpError::invalid_params(message, None))?;
 
+        let safest = query.resolve.unwrap_or_default() == VersionResolution::Safest;
+        let needs_lookups = safest
+            || self
+                .service
+                .needs_registry_lookups(
+                    &spec.name,
+                    spec.version.as_deref(),
+                    &spec.registry,
+                    query.current_version.as_deref(),
+                )
+                .map_err(mcp_internal_error)?;
+        // Calls arriving close together share bulk lookups and fetched package metadata;
+        // cached or denylisted requests are answered without waiting on a batch.
+        let batch = if needs_lookups {
+            Some(
+                self.batcher
+                    .join(
+                        &self.service,
+                        &spec.registry,
+                        &spec.name,
+                        spec.version.as_deref(),
+                    )
+                    .await,
+            )
+        } else {
+            None
+        };
+        let local_memo;
+        let memo = match &batch {
+            Some(batch) => batch.memo(),
+            None => {
+                local_memo = PackageMemo::default();
+                &local_memo
+            }
+        };
+        if safest {
+            let response = self
+                .service
+                .resolve_safest_version(
+                    &spec.name,
+                    spec.version.as_deref(),
+                    &spec.registry,
+                    "check_package",
+                    memo,
+                )
+                .await
+                .map_err(mcp_internal_error)?;
+            let json = serde_json::to_string_pretty(&response).map_err(mcp_internal_error)?;
+            return Ok(CallToolResult::success(vec![Content::text(json)]));
+        }
         let response = self
             .service
-            .evaluate_package(
-                &query.name,
-                query.version.as_deref(),
-                &query.registry,
+            .evaluate_package_in_batch(
+                &spec.name,
+                spec.version.as_deref(),
+                &spec.registry,
                 "check_package",
+                query.current_version.as_deref(),
+                memo,
             )
             .await
             .map_err(mcp_internal_error)?;
+        let json = serde_json::to_string_pretty(&response).map_err(mcp_internal_error)?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(
+        name = "check_packages",
+        description = "Batch version of `check_package` for an explicit list of packages when there is no dependency file. Trigger on prompts like: \"add react, axios and zod\" or \"install serde and tokio\". MUST run before editing package files or running install commands. Returns aggregate `allow`/`risk`/`total`/`denied` and per-package `package`, `registry`, `reasons`, machine-readable `evidence`, `metadata`, and `fingerprints`, in request order. If `allow` is false, stop and report the denied packages."
+    )]
+    async fn check_packages(
+        &self,
+        Parameters(query): Parameters<PackageListQuery>,
+    ) -> Result<CallToolResult, McpError> {
+        let specs = resolve_package_list(&query)?;
+        let response = self
+            .service
+            .evaluate_package_list(specs, "check_packages")
+            .await
+            .map_err(mcp_internal_error)?;
 
         let json = serde_json::to_string_pretty(&response).map_err(mcp_internal_error)?;
         Ok(CallToolResult::success(vec![Content::text(json)]))
@@ -180,7 +346,15 @@ impl SafePkgsServer {
 
         let response = self
             .service
-            .run_lockfile_audit(query.path.as_deref(), &query.registry, "check_lockfile")
+            .run_lockfile_audit(
+                query.path.as_deref(),
+                &query.registry,
+                "check_lockfile",
+                crate::service::LockfileAuditOptions {
+                    risky_only: query.risky_only.unwrap_or(false),
+                    ..crate::service::LockfileAuditOptions::default()
+                },
+            )
             .await
             .map_err(mcp_internal_error)?;
 
@@ -200,7 +374,7 @@ impl ServerHandler for SafePkgsServer {
             .collect::<Vec<_>>()
             .join(", ");
         let instructions = format!(
-            "Dependency safety policy: (1) For single dependency intent keywords (`add`, `install`, `update`, `upgrade`, `bump`, `pin`), call `check_package` first and do not edit files before the result. (2) For batch/file intent keywords ({lockfile_keywords}, `install deps`, `audit lockfile`), call `check_lockfile` first. (3) Enforce gating: if `allow=false`, do not proceed; return risk, reasons, and machine-readable evidence."
+            "Dependency safety policy: (1) For single dependency intent keywords (`add`, `install`, `update`, `upgrade`, `bump`, `pin`), call `check_package` first (or `check_packages` for several named packages) and do not edit files before the result. (2) For batch/file intent keywords ({lockfile_keywords}, `install deps`, `audit lockfile`), call `check_lockfile` first. (3) Enforce gating: if `allow=false`, do not proceed; return risk, reasons, and machine-readable evidence."
         );
         ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
             .with_protocol_version(ProtocolVersion::V_2024_11_05)
@@ -219,7 +393,9 @@ fn validate_package_query(query: &PackageQuery) -> Result<(), McpError> {
             None,
         ));
     }
-    if query.registry.trim().is_empty() {
+    if let Some(registry) = query.registry.as_deref()
+        && registry.trim().is_empty()
+    {
         return Err(McpError::invalid_params("registry must not be empty", None));
     }
     if let Some(version) = query.version.as_deref()
@@ -233,6 +409,89 @@ fn validate_package_query(query: &PackageQuery) -> Result<(), McpError> {
     Ok(())
 }
 
+/// Resolves `[<registry>:]<name>[@<version>]` specs into package coordinates.
+///
+/// A known registry prefix selects the registry and must agree with an explicit `registry`
+/// argument. Scoped npm names (`@types/node`) and URLs are never treated as prefixed or
+/// versioned; an inline version must agree with an explicit `version` argument.
+pub(crate) fn resolve_package_spec(query: &PackageQuery) -> Result<PackageSpec, String> {
+    let raw = query.name.trim();
+    let (prefix_registry, spec) = split_registry_prefix(raw);
+
+    let registry = match (prefix_registry, query.registry.as_deref().map(str::trim)) {
+        (Some(prefix), Some(explicit)) if !prefix.eq_ignore_ascii_case(explicit) => {
+            return Err(format!(
+                "package spec prefix `{prefix}:` conflicts with registry `{explicit}`"
+            ));
+        }
+        (Some(prefix), _) => prefix.to_string(),
+        (None, Some(explicit)) => explicit.to_string(),
+        (None, None) => crate::registries::default_package_registry_key().to_string(),
+    };
+
+    let (name, inline_version) = split_inline_version(spec);
+    if name.is_empty() {
+        return Err(format!("package spec `{raw}` has an empty package name"));
+    }
+    let version = match (inline_version, query.version.as_deref()) {
+        (Some(inline), Some(explicit)) if inline != explicit.trim() => {
+            return Err(format!(
+                "package spec version `{inline}` conflicts with version `{explicit}`"
+            ));
+        }
+        (Some(inline), _) => Some(inline.to_string()),
+        (None, explicit) => explicit.map(ToOwned::to_owned),
+    };
+
+    Ok(PackageSpec {
+        name: name.to_string(),
+        version,
+        registry,
+    })
+}
+
+fn split_registry_prefix(raw: &str) -> (Option<&'static str>, &str) {
+    let Some((prefix, rest)) = raw.split_once(':') else {
+        return (None, raw);
+    };
+    if rest.starts_with("//") {
+        return (None, raw);
+    }
+    crate::registries::supported_package_registry_keys()
+        .into_iter()
+        .find(|key| key.eq_ignore_ascii_case(prefix))
+        .map_or((None, raw), |key| (Some(key), rest))
+}
+
+fn split_inline_version(spec: &str) -> (&str, Option<&str>) {
+    if spec.contains("://") {
+        return (spec, None);
+    }
+    // rsplit_once keeps npm-style scoped names intact (e.g. "@scope/pkg@1.2.3").
+    match spec.rsplit_once('@') {
+        Some((name, version)) if !name.is_empty() && !version.is_empty() => (name, Some(version)),
+        _ => (spec, None),
+    }
+}
+
+fn resolve_package_list(query: &PackageListQuery) -> Result<Vec<PackageSpec>, McpError> {
+    if query.packages.is_empty() {
+        return Err(McpError::invalid_params(
+            "packages must contain at least one entry",
+            None,
+        ));
+    }
+    query
+        .packages
+        .iter()
+        .map(|entry| {
+            let entry = entry.as_query();
+            validate_package_query(&entry)?;
+            resolve_package_spec(&entry).map_err(|message| McpError::invalid_params(message, None))
+        })
+        .collect()
+}
+
 fn validate_lockfile_query(query: &LockfileQuery) -> Result<(), McpError> {
     crate::registries::validate_lockfile_request(&query.registry, query.path.as_deref())
         .map_err(|message| McpError::invalid_params(message, None))
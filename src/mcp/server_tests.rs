@@ -39,6 +39,45 @@ fn tool_is_registered() {
     );
 }
 
+#[test]
+fn check_packages_tool_is_registered() {
+    let server = SafePkgsServer::with_config(SafePkgsConfig::default());
+    let tool = server
+        .get_tool("check_packages")
+        .expect("check_packages exists");
+    let required = tool
+        .input_schema
+        .get("required")
+        .and_then(|value| value.as_array())
+        .expect("required fields");
+    assert!(required.iter().any(|value| value == "packages"));
+}
+
+#[test]
+fn resolve_package_list_rejects_empty_and_invalid_entries() {
+    assert!(resolve_package_list(&PackageListQuery { packages: vec![] }).is_err());
+
+    let query = PackageListQuery {
+        packages: vec![PackageListEntry {
+            name: " ".to_string(),
+            version: None,
+            registry: None,
+        }],
+    };
+    assert!(resolve_package_list(&query).is_err());
+
+    let query = PackageListQuery {
+        packages: vec![PackageListEntry {
+            name: "cargo:serde@1.0.0".to_string(),
+            version: None,
+            registry: None,
+        }],
+    };
+    let specs = resolve_package_list(&query).expect("specs");
+    assert_eq!(specs[0].registry, "cargo");
+    assert_eq!(specs[0].version.as_deref(), Some("1.0.0"));
+}
+
 #[test]
 fn tool_schema_has_required_name() {
     let server = SafePkgsServer::with_config(SafePkgsConfig::default());
@@ -125,7 +164,9 @@ fn validate_package_query_rejects_empty_name() {
     let query = PackageQuery {
         name: "   ".to_string(),
         version: Some("1.0.0".to_string()),
-        registry: "npm".to_string(),
+        registry: Some("npm".to_string()),
+        resolve: None,
+        current_version: None,
     };
     assert!(validate_package_query(&query).is_err());
 }
@@ -135,16 +176,86 @@ fn validate_package_query_rejects_empty_version() {
     let query = PackageQuery {
         name: "lodash".to_string(),
         version: Some(" ".to_string()),
-        registry: "npm".to_string(),
+        registry: Some("npm".to_string()),
+        resolve: None,
+        current_version: None,
     };
     assert!(validate_package_query(&query).is_err());
 }
 
+fn package_query(name: &str, version: Option<&str>, registry: Option<&str>) -> PackageQuery {
+    PackageQuery {
+        name: name.to_string(),
+        version: version.map(ToOwned::to_owned),
+        registry: registry.map(ToOwned::to_owned),
+        resolve: None,
+        current_version: None,
+    }
+}
+
+#[test]
+fn resolve_package_spec_reads_registry_prefix_and_inline_version() {
+    let spec = resolve_package_spec(&package_query("cargo:serde@1.0.0", None, None))
+        .expect("prefixed spec");
+    assert_eq!(
+        spec,
+        PackageSpec {
+            name: "serde".to_string(),
+            version: Some("1.0.0".to_string()),
+            registry: "cargo".to_string(),
+        }
+    );
+}
+
+#[test]
+fn resolve_package_spec_keeps_scoped_names_intact() {
+    let spec =
+        resolve_package_spec(&package_query("@types/node", None, None)).expect("scoped spec");
+    assert_eq!(spec.name, "@types/node");
+    assert_eq!(spec.version, None);
+    assert_eq!(
+        spec.registry,
+        crate::registries::default_package_registry_key()
+    );
+
+    let spec = resolve_package_spec(&package_query("npm:@types/node@20.1.0", None, None))
+        .expect("prefixed scoped spec");
+    assert_eq!(spec.name, "@types/node");
+    assert_eq!(spec.version.as_deref(), Some("20.1.0"));
+}
+
+#[test]
+fn resolve_package_spec_ignores_urls() {
+    let url = "https://example.com/pkg@1.0.0.tgz";
+    let spec = resolve_package_spec(&package_query(url, None, Some("npm"))).expect("url spec");
+    assert_eq!(spec.name, url);
+    assert_eq!(spec.version, None);
+}
+
+#[test]
+fn resolve_package_spec_rejects_conflicting_prefix_and_registry() {
+    let err = resolve_package_spec(&package_query("cargo:serde", None, Some("pypi")))
+        .expect_err("conflicting registry");
+    assert!(err.contains("conflicts with registry"));
+
+    let spec = resolve_package_spec(&package_query("cargo:serde", None, Some("CARGO")))
+        .expect("matching registry");
+    assert_eq!(spec.registry, "cargo");
+}
+
+#[test]
+fn resolve_package_spec_rejects_conflicting_versions() {
+    let err = resolve_package_spec(&package_query("serde@1.0.0", Some("2.0.0"), Some("cargo")))
+        .expect_err("conflicting version");
+    assert!(err.contains("conflicts with version"));
+}
+
 #[test]
 fn validate_lockfile_query_rejects_empty_path() {
     let query = LockfileQuery {
         path: Some(" ".to_string()),
         registry: "npm".to_string(),
+        risky_only: None,
     };
     assert!(validate_lockfile_query(&query).is_err());
 }
@@ -154,6 +265,7 @@ fn validate_lockfile_query_rejects_unknown_registry() {
     let query = LockfileQuery {
         path: None,
         registry: "unknown".to_string(),
+        risky_only: None,
     };
     assert!(validate_lockfile_query(&query).is_err());
 }
@@ -167,6 +279,7 @@ fn validate_lockfile_query_rejects_unsupported_existing_file_for_registry() {
     let query = LockfileQuery {
         path: Some(file_path.to_string_lossy().to_string()),
         registry: "cargo".to_string(),
+        risky_only: None,
     };
     assert!(validate_lockfile_query(&query).is_err());
     let _ = fs::remove_file(file_path);
@@ -182,8 +295,112 @@ fn validate_lockfile_query_accepts_supported_existing_file_for_registry() {
     let query = LockfileQuery {
         path: Some(file_path.to_string_lossy().to_string()),
         registry: "cargo".to_string(),
+        risky_only: None,
     };
     assert!(validate_lockfile_query(&query).is_ok());
     let _ = fs::remove_file(file_path);
     let _ = fs::remove_dir_all(dir);
 }
+
+#[tokio::test]
+async fn with_clients_evaluates_without_network() {
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = MockRegistryClient::new(RegistryEcosystem::CratesIo)
+        .with_package(package_record("serde", &[("1.0.0", 400)]))
+        .with_weekly_downloads("serde", 5_000_000);
+    let config = SafePkgsConfig::default();
+    let cache =
+        crate::cache::SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+    let catalog = crate::registries::register_default_catalog()
+        .with_client("cargo", std::sync::Arc::new(client));
+    let server = SafePkgsServer::with_clients(catalog, config, cache);
+
+    let response = server
+        .service
+        .evaluate_package("serde", Some("1.0.0"), "cargo", "test")
+        .await
+        .expect("decision");
+    assert!(response.allow, "{response:?}");
+    assert_eq!(response.metadata.weekly_downloads, Some(5_000_000));
+}
+
+#[tokio::test]
+async fn current_version_upgrade_finding_counts_toward_the_decision() {
+    use crate::registries::PackageMemo;
+    use crate::test_support::{MockRegistryClient, package_record};
+    use crate::types::Severity;
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(package_record(
+            "lodash",
+            &[("4.17.21", 900), ("4.18.0", 600), ("5.0.0", 400)],
+        ))
+        .with_weekly_downloads("lodash", 5_000_000)
+        .with_package(package_record("zero", &[("0.3.0", 900), ("0.4.0", 400)]))
+        .with_weekly_downloads("zero", 5_000_000);
+    let config = SafePkgsConfig {
+        max_risk: Severity::Low,
+        ..SafePkgsConfig::default()
+    };
+    let cache =
+        crate::cache::SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+    let catalog = crate::registries::register_default_catalog()
+        .with_client("npm", std::sync::Arc::new(client));
+    let server = SafePkgsServer::with_clients(catalog, config, cache);
+    let evaluate = |name: &'static str, version: &'static str, current: &'static str| {
+        let service = server.service.clone();
+        async move {
+            service
+                .evaluate_package_in_batch(
+                    name,
+                    Some(version),
+                    "npm",
+                    "test",
+                    Some(current),
+                    &PackageMemo::default(),
+                )
+                .await
+                .expect("decision")
+        }
+    };
+
+    // A medium finding exceeds max_risk = low, so the upgrade alone denies.
+    let major = evaluate("lodash", "5.0.0", "4.17.21").await;
+    assert!(!major.allow, "{major:?}");
+    assert_eq!(major.risk, Severity::Medium);
+    assert!(
+        major
+            .reasons
+            .iter()
+            .any(|reason| reason == "lodash: major upgrade from current 4.17.21 to proposed 5.0.0")
+    );
+
+    let minor = evaluate("lodash", "4.18.0", "4.17.21").await;
+    assert!(minor.allow, "{minor:?}");
+    assert!(
+        minor
+            .evidence
+            .iter()
+            .any(|item| item.id == "version.minor_upgrade")
+    );
+
+    // Under 0.x a minor bump may break the API, so it counts as major.
+    let zero = evaluate("zero", "0.4.0", "0.3.0").await;
+    assert!(!zero.allow, "{zero:?}");
+    assert!(
+        zero.evidence
+            .iter()
+            .any(|item| item.id == "version.major_upgrade")
+    );
+
+    let patch = evaluate("lodash", "4.17.21", "4.17.20").await;
+    assert!(
+        patch
+            .evidence
+            .iter()
+            .all(|item| !item.id.ends_with("_upgrade"))
+    );
+}
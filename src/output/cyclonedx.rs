@@ -0,0 +1,212 @@
+//! CycloneDX JSON rendering of lockfile audit results.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Value, json};
+
+use crate::types::{Evidence, LockfilePackageResult, LockfileResponse, Severity};
+
+/// CycloneDX specification version emitted by [`to_cyclonedx`].
+pub const SPEC_VERSION: &str = "1.5";
+
+/// Property namespace for safe-pkgs decision data.
+const PROPERTY_PREFIX: &str = "safe-pkgs";
+
+/// Evidence id whose `advisory_ids` fact lists known vulnerabilities.
+const ADVISORY_EVIDENCE_ID: &str = "advisory.known_advisory";
+
+/// Renders an audit as a CycloneDX JSON BOM.
+///
+/// Each package becomes a `library` component whose purl uses the package's own registry
+/// (SBOM audits) or `registry`; packages with neither get no purl. Decisions and reasons
+/// are attached as `safe-pkgs:*` properties and known advisories as `vulnerabilities`.
+/// Packages sharing a purl (the same package reached through several paths) collapse into
+/// one component carrying the strictest decision, so every `bom-ref` is unique.
+pub fn to_cyclonedx(response: &LockfileResponse, registry: Option<&str>) -> Value {
+    let mut merged: Vec<(LockfilePackageResult, Option<&str>, Option<String>)> =
+        Vec::with_capacity(response.packages.len());
+    let mut by_purl: HashMap<String, usize> = HashMap::new();
+
+    for package in &response.packages {
+        let package_registry = package.registry.as_deref().or(registry);
+        let purl = package_registry.and_then(|registry| purl_for(registry, package));
+        if let Some(purl) = &purl
+            && let Some(&index) = by_purl.get(purl)
+        {
+            merge_package(&mut merged[index].0, package);
+            continue;
+        }
+        if let Some(purl) = &purl {
+            by_purl.insert(purl.clone(), merged.len());
+        }
+        merged.push((package.clone(), package_registry, purl));
+    }
+
+    let mut components = Vec::with_capacity(merged.len());
+    let mut vulnerabilities = Vec::new();
+    for (index, (package, package_registry, purl)) in merged.into_iter().enumerate() {
+        let bom_ref = purl.clone().unwrap_or_else(|| format!("component-{index}"));
+        vulnerabilities.extend(vulnerabilities_for(&package.evidence, &bom_ref));
+        components.push(component_for(&package, package_registry, purl, &bom_ref));
+    }
+
+    let mut bom = json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": SPEC_VERSION,
+        "version": 1,
+        "metadata": {
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "safe-pkgs",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "properties": [
+                property("allow", response.allow.to_string()),
+                property("risk", severity_value(response.risk)),
+                property("denied", response.denied.to_string()),
+                property("config_fingerprint", response.fingerprints.config.clone()),
+                property("policy_fingerprint", response.fingerprints.policy.clone()),
+            ],
+        },
+        "components": components,
+    });
+    if !vulnerabilities.is_empty() {
+        bom["vulnerabilities"] = Value::Array(vulnerabilities);
+    }
+    bom
+}
+
+/// Folds a duplicate of `target` into it, keeping the strictest decision and every reason.
+fn merge_package(target: &mut LockfilePackageResult, duplicate: &LockfilePackageResult) {
+    target.allow &= duplicate.allow;
+    target.risk = target.risk.max(duplicate.risk);
+    target.score = target.score.max(duplicate.score);
+    for reason in &duplicate.reasons {
+        if !target.reasons.contains(reason) {
+            target.reasons.push(reason.clone());
+        }
+    }
+    target.evidence.extend(duplicate.evidence.iter().cloned());
+}
+
+fn component_for(
+    package: &LockfilePackageResult,
+    registry: Option<&str>,
+    purl: Option<String>,
+    bom_ref: &str,
+) -> Value {
+    let mut properties = vec![
+        property("allow", package.allow.to_string()),
+        property("risk", severity_value(package.risk)),
+        property("score", package.score.to_string()),
+    ];
+    properties.extend(
+        package
+            .reasons
+            .iter()
+            .map(|reason| property("reason", reason.clone())),
+    );
+
+    let mut component = json!({
+        "type": "library",
+        "bom-ref": bom_ref,
+        "name": package.name,
+        "properties": properties,
+    });
+    // npm scopes map to the CycloneDX `group` field.
+    if registry == Some("npm")
+        && let Some((scope, name)) = package.name.split_once('/')
+    {
+        component["group"] = json!(scope);
+        component["name"] = json!(name);
+    }
+    if let Some(version) = &package.requested {
+        component["version"] = json!(version);
+    }
+    if let Some(purl) = purl {
+        component["purl"] = json!(purl);
+    }
+    component
+}
+
+fn vulnerabilities_for(evidence: &[Evidence], bom_ref: &str) -> Vec<Value> {
+    let mut seen = HashSet::new();
+    evidence
+        .iter()
+        .filter(|item| item.id == ADVISORY_EVIDENCE_ID)
+        .flat_map(|item| {
+            let ids = item
+                .facts
+                .get("advisory_ids")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            ids.into_iter()
+                .filter_map(|id| id.as_str().map(str::to_string))
+                .filter(|id| seen.insert(id.clone()))
+                .map(|id| {
+                    json!({
+                        "id": id,
+                        "ratings": [{ "severity": severity_value(item.severity) }],
+                        "description": item.message,
+                        "affects": [{ "ref": bom_ref }],
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Builds a package URL for a registry package, or `None` for unknown registries.
+fn purl_for(registry: &str, package: &LockfilePackageResult) -> Option<String> {
+    let path = match registry {
+        "npm" => match package.name.split_once('/') {
+            Some((scope, name)) => format!("{}/{}", percent_encode(scope), percent_encode(name)),
+            None => percent_encode(&package.name),
+        },
+        "cargo" => percent_encode(&package.name),
+        // The purl spec normalizes PyPI names to lowercase with `-` separators.
+        "pypi" => percent_encode(&package.name.to_ascii_lowercase().replace('_', "-")),
+        _ => return None,
+    };
+    let version = package
+        .requested
+        .as_deref()
+        .map(|version| format!("@{}", percent_encode(version)))
+        .unwrap_or_default();
+    Some(format!("pkg:{registry}/{path}{version}"))
+}
+
+/// Percent-encodes everything outside the purl unreserved set.
+fn percent_encode(raw: &str) -> String {
+    raw.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn property(name: &str, value: String) -> Value {
+    json!({ "name": format!("{PROPERTY_PREFIX}:{name}"), "value": value })
+}
+
+/// CycloneDX severity names match the lowercase serde form of [`Severity`].
+fn severity_value(severity: Severity) -> String {
+    match severity {
+        Severity::Info => "info",
+        Severity::Low => "low",
+        Severity::Medium => "medium",
+        Severity::High => "high",
+        Severity::Critical => "critical",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+#[path = "../tests/cyclonedx.rs"]
+mod tests;
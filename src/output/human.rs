@@ -0,0 +1,181 @@
+//! Human-readable terminal rendering of `check` and `audit` results.
+
+use crate::types::{LockfileResponse, PackageCheckResult, Severity};
+
+/// Width of the decision column (`ALLOW`/`DENY`).
+const DECISION_WIDTH: usize = 5;
+/// Width of the risk column (longest severity is `critical`).
+const RISK_WIDTH: usize = 8;
+
+/// One rendered row: the decision, risk, package label, and the reasons listed under it.
+struct Row<'a> {
+    allow: bool,
+    risk: Severity,
+    label: String,
+    reasons: &'a [String],
+}
+
+/// Renders `safe-pkgs check` results as a table with each package's reasons beneath it,
+/// followed by an allowed/denied summary line.
+pub fn render_package_results(results: &[PackageCheckResult], use_color: bool) -> String {
+    let rows = results
+        .iter()
+        .map(|result| {
+            let version = result
+                .response
+                .metadata
+                .resolved
+                .as_deref()
+                .or(result.requested.as_deref());
+            Row {
+                allow: result.response.allow,
+                risk: result.response.risk,
+                label: package_label(Some(&result.registry), &result.package, version),
+                reasons: &result.response.reasons,
+            }
+        })
+        .collect::<Vec<_>>();
+    let denied = rows.iter().filter(|row| !row.allow).count();
+
+    let mut lines = render_rows(&rows, use_color);
+    lines.push(String::new());
+    lines.push(format!(
+        "{} checked: {} allowed, {} denied",
+        plural(rows.len(), "package"),
+        rows.len() - denied,
+        denied
+    ));
+    lines.join("\n")
+}
+
+/// Renders a `safe-pkgs audit` report: an overall decision header, totals, and one table
+/// row per listed package with its reasons beneath it.
+pub fn render_lockfile_report(report: &LockfileResponse, use_color: bool) -> String {
+    let mut lines = vec![format!(
+        "{}  risk {}",
+        decision(report.allow, 0, use_color),
+        risk(report.risk, 0, use_color)
+    )];
+    let mut totals = format!(
+        "{}: {} allowed, {} denied, {} evaluated",
+        plural(report.total, "package"),
+        report.total.saturating_sub(report.denied),
+        report.denied,
+        report.evaluated
+    );
+    if report.skipped > 0 {
+        totals.push_str(&format!(", {} skipped", report.skipped));
+    }
+    lines.push(totals);
+    if let Some(shortfall) = &report.coverage_shortfall {
+        lines.push(style(&escape_control(shortfall), "33", use_color));
+    }
+
+    let rows = report
+        .packages
+        .iter()
+        .map(|package| Row {
+            allow: package.allow,
+            risk: package.risk,
+            label: package_label(
+                package.registry.as_deref(),
+                &package.name,
+                package.requested.as_deref(),
+            ),
+            reasons: &package.reasons,
+        })
+        .collect::<Vec<_>>();
+    if !rows.is_empty() {
+        lines.push(String::new());
+        lines.extend(render_rows(&rows, use_color));
+    }
+    lines.join("\n")
+}
+
+fn render_rows(rows: &[Row<'_>], use_color: bool) -> Vec<String> {
+    let mut lines = Vec::with_capacity(rows.len());
+    for row in rows {
+        lines.push(format!(
+            "{}  {}  {}",
+            decision(row.allow, DECISION_WIDTH, use_color),
+            risk(row.risk, RISK_WIDTH, use_color),
+            style(&row.label, "1", use_color)
+        ));
+        lines.extend(
+            row.reasons
+                .iter()
+                .map(|reason| format!("    - {}", escape_control(reason))),
+        );
+    }
+    lines
+}
+
+fn package_label(registry: Option<&str>, name: &str, version: Option<&str>) -> String {
+    let mut label = match registry {
+        Some(registry) => format!("{registry}:{name}"),
+        None => name.to_string(),
+    };
+    if let Some(version) = version {
+        label.push('@');
+        label.push_str(version);
+    }
+    escape_control(&label)
+}
+
+/// Escapes control characters (including ESC) so registry-supplied text cannot inject
+/// terminal sequences or break the table layout.
+fn escape_control(value: &str) -> String {
+    if !value.chars().any(char::is_control) {
+        return value.to_string();
+    }
+    value
+        .chars()
+        .map(|ch| {
+            if ch.is_control() {
+                ch.escape_default().to_string()
+            } else {
+                ch.to_string()
+            }
+        })
+        .collect()
+}
+
+/// `ALLOW`/`DENY` padded to `width`; padding is applied before styling so columns line up.
+fn decision(allow: bool, width: usize, use_color: bool) -> String {
+    let (text, ansi_code) = if allow {
+        ("ALLOW", "1;32")
+    } else {
+        ("DENY", "1;31")
+    };
+    style(&format!("{text:<width$}"), ansi_code, use_color)
+}
+
+fn risk(severity: Severity, width: usize, use_color: bool) -> String {
+    let (text, ansi_code) = match severity {
+        Severity::Info => ("info", "2"),
+        Severity::Low => ("low", "32"),
+        Severity::Medium => ("medium", "33"),
+        Severity::High => ("high", "31"),
+        Severity::Critical => ("critical", "1;31"),
+    };
+    style(&format!("{text:<width$}"), ansi_code, use_color)
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+fn style(value: &str, ansi_code: &str, use_color: bool) -> String {
+    if use_color {
+        return format!("\x1b[{ansi_code}m{value}\x1b[0m");
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+#[path = "../tests/human.rs"]
+mod tests;
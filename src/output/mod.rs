@@ -0,0 +1,4 @@
+//! Alternative renderings of check and audit results.
+
+pub mod cyclonedx;
+pub mod human;
@@ -1,6 +1,7 @@
 //! Deterministic policy snapshot and fingerprint helpers.
 
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 use anyhow::Context;
 use serde::Serialize;
@@ -8,7 +9,8 @@ use serde_json::Value as JsonValue;
 use sha2::{Digest, Sha256};
 
 use crate::config::{
-    CustomRuleCondition, CustomRuleConfig, CustomRuleMatchMode, CustomRuleOperator, SafePkgsConfig,
+    CustomRuleCondition, CustomRuleConfig, CustomRuleMatchMode, CustomRuleOperator,
+    EnforcementMode, ListPrecedence, SafePkgsConfig, TransportErrorMode,
 };
 use crate::registries::normalize_check_id;
 use crate::types::Severity;
@@ -22,19 +24,66 @@ struct ConfigSnapshot {
     min_version_age_days: i64,
     min_weekly_downloads: u64,
     max_risk: Severity,
+    never_deny_below: Option<Severity>,
+    enforcement: EnforcementMode,
+    quarantine_hours: Option<i64>,
+    warn_on_unparseable_version: bool,
+    require_exact_version: bool,
+    require_publish_dates: bool,
+    max_reasons: Option<usize>,
+    max_reason_length: Option<usize>,
+    npmrc: Option<PathBuf>,
+    decision_hook: Option<PathBuf>,
+    decision_hook_fail_open: bool,
     allowlist_packages: Vec<String>,
+    allowlist_publishers: Vec<String>,
     denylist_packages: Vec<String>,
     denylist_publishers: Vec<String>,
+    denylist_patterns: Vec<String>,
+    list_precedence: ListPrecedence,
     dependency_confusion: DependencyConfusionSnapshot,
+    blackout: BlackoutSnapshot,
+    lockfile_ignore_dev_dependencies: bool,
+    lockfile_on_transport_error: TransportErrorMode,
+    lockfile_report_unsupported_sources: bool,
+    lockfile_min_coverage_ratio: Option<f64>,
     staleness: StalenessSnapshot,
+    typosquat_fail_open: bool,
+    typosquat_ignore: Vec<String>,
+    bus_factor_high_download_threshold: u64,
+    version_age_exempt_patch_upgrades: bool,
+    install_script_detect_persistence: bool,
+    install_script_deny_all: bool,
+    install_script_allowed_commands: Vec<String>,
+    release_churn_max_releases_per_week: u64,
+    stale_stable_window_days: u64,
+    advisory_min_cvss: Option<f64>,
+    existence_missing_package: Severity,
+    existence_missing_version: Severity,
     checks: ChecksSnapshot,
     custom_rules: Vec<CustomRuleSnapshot>,
+    suppressions: Vec<SuppressionSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+struct SuppressionSnapshot {
+    package: String,
+    check: String,
+    until: String,
+    reason: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
 struct DependencyConfusionSnapshot {
     internal_packages: Vec<String>,
     internal_scopes: Vec<String>,
+    internal_prefixes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BlackoutSnapshot {
+    after: Option<String>,
+    registry: BTreeMap<String, Option<String>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -164,31 +213,94 @@ fn canonical_config_snapshot(config: &SafePkgsConfig) -> ConfigSnapshot {
             ))
     });
 
+    let mut suppressions = config
+        .suppressions
+        .iter()
+        .map(|suppression| SuppressionSnapshot {
+            package: suppression.package.clone(),
+            check: normalize_check_id(&suppression.check),
+            until: suppression.until.to_rfc3339(),
+            reason: suppression.reason.clone(),
+        })
+        .collect::<Vec<_>>();
+    suppressions.sort();
+    suppressions.dedup();
+
     ConfigSnapshot {
         version: POLICY_SNAPSHOT_VERSION,
         min_version_age_days: config.min_version_age_days,
         min_weekly_downloads: config.min_weekly_downloads,
         max_risk: config.max_risk,
+        never_deny_below: config.never_deny_below,
+        enforcement: config.enforcement,
+        quarantine_hours: config.quarantine_hours,
+        warn_on_unparseable_version: config.warn_on_unparseable_version,
+        require_exact_version: config.require_exact_version,
+        require_publish_dates: config.require_publish_dates,
+        max_reasons: config.max_reasons,
+        max_reason_length: config.max_reason_length,
+        npmrc: config.npmrc.clone(),
+        decision_hook: config.decision_hook.clone(),
+        decision_hook_fail_open: config.decision_hook_fail_open,
         allowlist_packages: sort_and_dedup(config.allowlist.packages.clone()),
+        allowlist_publishers: sort_and_dedup(config.allowlist.publishers.clone()),
         denylist_packages: sort_and_dedup(config.denylist.packages.clone()),
         denylist_publishers: sort_and_dedup(config.denylist.publishers.clone()),
+        denylist_patterns: sort_and_dedup(config.denylist.patterns.clone()),
+        list_precedence: config.list_precedence,
         dependency_confusion: DependencyConfusionSnapshot {
             internal_packages: sort_and_dedup(
                 config.dependency_confusion.internal_packages.clone(),
             ),
             internal_scopes: sort_and_dedup(config.dependency_confusion.internal_scopes.clone()),
+            internal_prefixes: sort_and_dedup(
+                config.dependency_confusion.internal_prefixes.clone(),
+            ),
+        },
+        blackout: BlackoutSnapshot {
+            after: config.blackout.after.map(|ts| ts.to_rfc3339()),
+            registry: config
+                .blackout
+                .registry
+                .iter()
+                .map(|(key, entry)| {
+                    (
+                        key.to_ascii_lowercase(),
+                        entry.after.map(|ts| ts.to_rfc3339()),
+                    )
+                })
+                .collect(),
         },
+        lockfile_ignore_dev_dependencies: config.lockfile.ignore_dev_dependencies,
+        lockfile_on_transport_error: config.lockfile.on_transport_error,
+        lockfile_report_unsupported_sources: config.lockfile.report_unsupported_sources,
+        lockfile_min_coverage_ratio: config.lockfile.min_coverage_ratio,
         staleness: StalenessSnapshot {
             warn_major_versions_behind: config.staleness.warn_major_versions_behind,
             warn_minor_versions_behind: config.staleness.warn_minor_versions_behind,
             warn_age_days: config.staleness.warn_age_days,
             ignore_for: sort_and_dedup(config.staleness.ignore_for.clone()),
         },
+        typosquat_fail_open: config.typosquat.fail_open,
+        typosquat_ignore: sort_and_dedup(config.typosquat.ignore.clone()),
+        bus_factor_high_download_threshold: config.bus_factor.high_download_threshold,
+        version_age_exempt_patch_upgrades: config.version_age.exempt_patch_upgrades,
+        install_script_detect_persistence: config.install_script.detect_persistence,
+        install_script_deny_all: config.install_script.deny_all,
+        install_script_allowed_commands: sort_and_dedup(
+            config.install_script.allowed_commands.clone(),
+        ),
+        release_churn_max_releases_per_week: config.release_churn.max_releases_per_week,
+        stale_stable_window_days: config.stale_stable.window_days,
+        advisory_min_cvss: config.advisory.min_cvss,
+        existence_missing_package: config.existence.missing_package,
+        existence_missing_version: config.existence.missing_version,
         checks: ChecksSnapshot {
             disable: normalize_check_id_list(config.checks.disable.clone()),
             registry: checks_registry,
         },
         custom_rules,
+        suppressions,
     }
 }
 
@@ -287,8 +399,11 @@ fn custom_rule_field_key(field: crate::config::CustomRuleField) -> &'static str
         Field::PublisherCount => "publisher_count",
         Field::Publishers => "publishers",
         Field::WeeklyDownloads => "weekly_downloads",
+        Field::WeeklyDownloadsChange => "weekly_downloads_change",
         Field::AdvisoryCount => "advisory_count",
         Field::AdvisoryIds => "advisory_ids",
+        Field::License => "license",
+        Field::RepositoryUrl => "repository_url",
     }
 }
 
@@ -0,0 +1,163 @@
+//! Weekly download counts shared through the SQLite cache across clients and restarts.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use safe_pkgs_core::{PackageAdvisory, PackageRecord, RegistryEcosystem, RegistryError};
+use serde::{Deserialize, Serialize};
+
+use super::RegistryClient;
+use crate::cache::SqliteCache;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredDownloads {
+    /// `None` records that the registry has no count for the package.
+    weekly_downloads: Option<u64>,
+}
+
+/// SQLite-backed weekly download counts, keyed by registry scope and package name.
+///
+/// Unlike a client's in-memory prefetch map, entries are visible to every client sharing
+/// the cache database, including other registry client instances and later processes.
+pub struct DownloadCountStore {
+    cache: Arc<SqliteCache>,
+    ttl: Duration,
+}
+
+impl DownloadCountStore {
+    pub fn new(cache: Arc<SqliteCache>, ttl: Duration) -> Self {
+        Self { cache, ttl }
+    }
+
+    /// The stored count for `key`: `None` on a miss, `Some(None)` for a stored "no count".
+    fn get(&self, key: &str) -> Option<Option<u64>> {
+        let raw = match self.cache.get(key) {
+            Ok(raw) => raw?,
+            Err(err) => {
+                tracing::warn!("failed to read download count for {key}: {err}");
+                return None;
+            }
+        };
+        match serde_json::from_str::<StoredDownloads>(&raw) {
+            Ok(entry) => Some(entry.weekly_downloads),
+            Err(err) => {
+                tracing::warn!("ignoring unreadable download count for {key}: {err}");
+                None
+            }
+        }
+    }
+
+    fn set(&self, key: &str, weekly_downloads: Option<u64>) {
+        let result = serde_json::to_string(&StoredDownloads { weekly_downloads })
+            .map_err(anyhow::Error::from)
+            .and_then(|encoded| self.cache.set_with_ttl(key, &encoded, self.ttl));
+        if let Err(err) = result {
+            tracing::warn!("failed to persist download count for {key}: {err}");
+        }
+    }
+}
+
+/// Registry client wrapper that serves weekly downloads from a [`DownloadCountStore`]
+/// and writes fetched counts back; without a store every call goes to the wrapped client.
+pub struct PersistedDownloadsClient<'a> {
+    inner: &'a dyn RegistryClient,
+    store: Option<&'a DownloadCountStore>,
+    scope: String,
+}
+
+impl<'a> PersistedDownloadsClient<'a> {
+    /// `scope` identifies the registry instance the download counts come from.
+    pub fn new(
+        inner: &'a dyn RegistryClient,
+        store: Option<&'a DownloadCountStore>,
+        scope: &str,
+    ) -> Self {
+        Self {
+            inner,
+            store,
+            scope: scope.to_string(),
+        }
+    }
+
+    fn key(&self, package: &str) -> String {
+        format!("downloads:{}:{package}", self.scope)
+    }
+}
+
+#[async_trait]
+impl RegistryClient for PersistedDownloadsClient<'_> {
+    fn ecosystem(&self) -> RegistryEcosystem {
+        self.inner.ecosystem()
+    }
+
+    fn base_url(&self) -> Option<&str> {
+        self.inner.base_url()
+    }
+
+    async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
+        self.inner.fetch_package(package).await
+    }
+
+    async fn prefetch_weekly_downloads(&self, packages: &[String]) -> Result<(), RegistryError> {
+        let Some(store) = self.store else {
+            return self.inner.prefetch_weekly_downloads(packages).await;
+        };
+        let missing = packages
+            .iter()
+            .filter(|package| store.get(&self.key(package)).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        self.inner.prefetch_weekly_downloads(&missing).await
+    }
+
+    async fn fetch_weekly_downloads(&self, package: &str) -> Result<Option<u64>, RegistryError> {
+        let Some(store) = self.store else {
+            return self.inner.fetch_weekly_downloads(package).await;
+        };
+        let key = self.key(package);
+        if let Some(stored) = store.get(&key) {
+            return Ok(stored);
+        }
+        let downloads = self.inner.fetch_weekly_downloads(package).await?;
+        store.set(&key, downloads);
+        Ok(downloads)
+    }
+
+    async fn fetch_weekly_downloads_baseline(
+        &self,
+        package: &str,
+    ) -> Result<Option<u64>, RegistryError> {
+        self.inner.fetch_weekly_downloads_baseline(package).await
+    }
+
+    async fn prefetch_popular_package_names(&self) -> Result<(), RegistryError> {
+        self.inner.prefetch_popular_package_names().await
+    }
+
+    async fn fetch_popular_package_names(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<String>, RegistryError> {
+        self.inner.fetch_popular_package_names(limit).await
+    }
+
+    async fn prefetch_advisories(
+        &self,
+        packages: &[(String, String)],
+        concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        self.inner.prefetch_advisories(packages, concurrency).await
+    }
+
+    async fn fetch_advisories(
+        &self,
+        package: &str,
+        version: &str,
+    ) -> Result<Vec<PackageAdvisory>, RegistryError> {
+        self.inner.fetch_advisories(package, version).await
+    }
+}
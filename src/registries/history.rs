@@ -0,0 +1,153 @@
+//! Weekly download baselines persisted in the SQLite cache for download-trend rules.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use safe_pkgs_core::{PackageAdvisory, PackageRecord, RegistryEcosystem, RegistryError};
+use serde::{Deserialize, Serialize};
+
+use super::RegistryClient;
+use crate::cache::SqliteCache;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredBaseline {
+    weekly_downloads: u64,
+}
+
+/// SQLite-backed weekly download baselines, keyed by registry scope and package name.
+///
+/// The first observation for a package becomes its baseline and is kept until `ttl`
+/// expires, so a sudden spike is measured against the value seen before it started.
+pub struct DownloadHistoryStore {
+    cache: Arc<SqliteCache>,
+    ttl: Duration,
+}
+
+impl DownloadHistoryStore {
+    pub fn new(cache: Arc<SqliteCache>, ttl: Duration) -> Self {
+        Self { cache, ttl }
+    }
+
+    fn baseline(&self, key: &str) -> Option<u64> {
+        let raw = match self.cache.get(key) {
+            Ok(raw) => raw?,
+            Err(err) => {
+                tracing::warn!("failed to read download baseline for {key}: {err}");
+                return None;
+            }
+        };
+        match serde_json::from_str::<StoredBaseline>(&raw) {
+            Ok(entry) => Some(entry.weekly_downloads),
+            Err(err) => {
+                tracing::warn!("ignoring unreadable download baseline for {key}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Stores `weekly_downloads` as the baseline unless an unexpired one already exists.
+    fn observe(&self, key: &str, weekly_downloads: u64) {
+        if self.baseline(key).is_some() {
+            return;
+        }
+        let result = serde_json::to_string(&StoredBaseline { weekly_downloads })
+            .map_err(anyhow::Error::from)
+            .and_then(|encoded| self.cache.set_with_ttl(key, &encoded, self.ttl));
+        if let Err(err) = result {
+            tracing::warn!("failed to persist download baseline for {key}: {err}");
+        }
+    }
+}
+
+/// Registry client wrapper that records fetched weekly downloads in a
+/// [`DownloadHistoryStore`] and serves them back as baselines; without a store it
+/// reports no baseline.
+pub struct DownloadHistoryClient<'a> {
+    inner: &'a dyn RegistryClient,
+    store: Option<&'a DownloadHistoryStore>,
+    scope: String,
+}
+
+impl<'a> DownloadHistoryClient<'a> {
+    /// `scope` identifies the registry instance the download counts come from.
+    pub fn new(
+        inner: &'a dyn RegistryClient,
+        store: Option<&'a DownloadHistoryStore>,
+        scope: &str,
+    ) -> Self {
+        Self {
+            inner,
+            store,
+            scope: scope.to_string(),
+        }
+    }
+
+    fn key(&self, package: &str) -> String {
+        format!("download_baseline:v1:{}:{package}", self.scope)
+    }
+}
+
+#[async_trait]
+impl RegistryClient for DownloadHistoryClient<'_> {
+    fn ecosystem(&self) -> RegistryEcosystem {
+        self.inner.ecosystem()
+    }
+
+    fn base_url(&self) -> Option<&str> {
+        self.inner.base_url()
+    }
+
+    async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
+        self.inner.fetch_package(package).await
+    }
+
+    async fn prefetch_weekly_downloads(&self, packages: &[String]) -> Result<(), RegistryError> {
+        self.inner.prefetch_weekly_downloads(packages).await
+    }
+
+    async fn fetch_weekly_downloads(&self, package: &str) -> Result<Option<u64>, RegistryError> {
+        let downloads = self.inner.fetch_weekly_downloads(package).await?;
+        if let (Some(store), Some(downloads)) = (self.store, downloads) {
+            store.observe(&self.key(package), downloads);
+        }
+        Ok(downloads)
+    }
+
+    async fn fetch_weekly_downloads_baseline(
+        &self,
+        package: &str,
+    ) -> Result<Option<u64>, RegistryError> {
+        match self.store {
+            Some(store) => Ok(store.baseline(&self.key(package))),
+            None => self.inner.fetch_weekly_downloads_baseline(package).await,
+        }
+    }
+
+    async fn prefetch_popular_package_names(&self) -> Result<(), RegistryError> {
+        self.inner.prefetch_popular_package_names().await
+    }
+
+    async fn fetch_popular_package_names(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<String>, RegistryError> {
+        self.inner.fetch_popular_package_names(limit).await
+    }
+
+    async fn prefetch_advisories(
+        &self,
+        packages: &[(String, String)],
+        concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        self.inner.prefetch_advisories(packages, concurrency).await
+    }
+
+    async fn fetch_advisories(
+        &self,
+        package: &str,
+        version: &str,
+    ) -> Result<Vec<PackageAdvisory>, RegistryError> {
+        self.inner.fetch_advisories(package, version).await
+    }
+}
@@ -0,0 +1,105 @@
+//! Per-audit memoization of registry package metadata.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use safe_pkgs_core::{PackageAdvisory, PackageRecord, RegistryEcosystem, RegistryError};
+use tokio::sync::OnceCell;
+
+use super::RegistryClient;
+
+/// Package records fetched during one audit, keyed by package name.
+///
+/// Concurrent lookups of the same name share a single in-flight fetch; failed fetches
+/// are not memoized so a later lookup retries.
+#[derive(Debug, Default)]
+pub struct PackageMemo {
+    records: Mutex<HashMap<String, Arc<OnceCell<Arc<PackageRecord>>>>>,
+}
+
+impl PackageMemo {
+    fn slot(&self, package: &str) -> Arc<OnceCell<Arc<PackageRecord>>> {
+        // A poisoned lock only means another task panicked mid-insert; the map is still usable.
+        let mut records = self
+            .records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Arc::clone(records.entry(package.to_string()).or_default())
+    }
+}
+
+/// Registry client wrapper that serves `fetch_package` from a [`PackageMemo`].
+///
+/// All other calls go straight to the wrapped client.
+pub struct MemoizedRegistryClient<'a> {
+    inner: &'a dyn RegistryClient,
+    memo: &'a PackageMemo,
+}
+
+impl<'a> MemoizedRegistryClient<'a> {
+    pub fn new(inner: &'a dyn RegistryClient, memo: &'a PackageMemo) -> Self {
+        Self { inner, memo }
+    }
+}
+
+#[async_trait]
+impl RegistryClient for MemoizedRegistryClient<'_> {
+    fn ecosystem(&self) -> RegistryEcosystem {
+        self.inner.ecosystem()
+    }
+
+    fn base_url(&self) -> Option<&str> {
+        self.inner.base_url()
+    }
+
+    async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
+        let slot = self.memo.slot(package);
+        let record = slot
+            .get_or_try_init(|| async { self.inner.fetch_package(package).await.map(Arc::new) })
+            .await?;
+        Ok(PackageRecord::clone(record))
+    }
+
+    async fn prefetch_weekly_downloads(&self, packages: &[String]) -> Result<(), RegistryError> {
+        self.inner.prefetch_weekly_downloads(packages).await
+    }
+
+    async fn fetch_weekly_downloads(&self, package: &str) -> Result<Option<u64>, RegistryError> {
+        self.inner.fetch_weekly_downloads(package).await
+    }
+
+    async fn fetch_weekly_downloads_baseline(
+        &self,
+        package: &str,
+    ) -> Result<Option<u64>, RegistryError> {
+        self.inner.fetch_weekly_downloads_baseline(package).await
+    }
+
+    async fn prefetch_popular_package_names(&self) -> Result<(), RegistryError> {
+        self.inner.prefetch_popular_package_names().await
+    }
+
+    async fn fetch_popular_package_names(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<String>, RegistryError> {
+        self.inner.fetch_popular_package_names(limit).await
+    }
+
+    async fn prefetch_advisories(
+        &self,
+        packages: &[(String, String)],
+        concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        self.inner.prefetch_advisories(packages, concurrency).await
+    }
+
+    async fn fetch_advisories(
+        &self,
+        package: &str,
+        version: &str,
+    ) -> Result<Vec<PackageAdvisory>, RegistryError> {
+        self.inner.fetch_advisories(package, version).await
+    }
+}
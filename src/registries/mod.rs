@@ -1,19 +1,122 @@
 //! Registry plugin catalog and support policy wiring.
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
 
+mod downloads;
+mod history;
+mod memo;
+mod popular;
+
+pub use downloads::{DownloadCountStore, PersistedDownloadsClient};
+pub use history::{DownloadHistoryClient, DownloadHistoryStore};
+pub use memo::{MemoizedRegistryClient, PackageMemo};
+pub use popular::{PersistedPopularNamesClient, PopularNamesStore};
+use safe_pkgs_core::RegistryEcosystem;
 pub use safe_pkgs_core::{
-    CheckId, LockfileParser, RegistryClient, RegistryDefinition, RegistryPlugin, normalize_check_id,
+    CheckId, LockfileParser, RegistryClient, RegistryClientOptions, RegistryDefinition,
+    RegistryPlugin, RequestLimits, normalize_check_id,
 };
 
+/// Checks that read metadata only some ecosystems publish, with the ecosystems that do.
+const ECOSYSTEM_SPECIFIC_CHECKS: &[(CheckId, &[RegistryEcosystem])] = &[
+    ("install_script", &[RegistryEcosystem::Npm]),
+    ("size_anomaly", &[RegistryEcosystem::Npm]),
+    ("maintainer_domain", &[RegistryEcosystem::Npm]),
+    ("binary_only", &[RegistryEcosystem::Npm]),
+];
+
 /// Runtime registry catalog built from app-registered definitions.
 #[derive(Clone)]
 pub struct RegistryCatalog {
     plugins_by_key: HashMap<&'static str, Arc<dyn RegistryPlugin>>,
     package_registry_keys: Vec<&'static str>,
     lockfile_registry_keys: Vec<&'static str>,
+    request_limits: RequestLimits,
+}
+
+/// Per-registry client overrides supplied at startup (for example via CLI flags).
+#[derive(Debug, Clone, Default)]
+pub struct RegistryClientOverrides {
+    options_by_key: HashMap<String, RegistryClientOptions>,
+    popular_names_file: Option<PathBuf>,
+    request_limits: RequestLimits,
+}
+
+impl RegistryClientOverrides {
+    /// Sets a base URL override for a registry key; `None` leaves the default in place.
+    pub fn with_base_url(mut self, key: &str, base_url: Option<String>) -> Self {
+        if let Some(base_url) = base_url {
+            self.options_by_key
+                .entry(key.to_ascii_lowercase())
+                .or_default()
+                .base_url = Some(base_url);
+        }
+        self
+    }
+
+    /// Sets a static popular-names list URL for a registry key; `None` keeps the default index.
+    pub fn with_popular_index_url(mut self, key: &str, url: Option<String>) -> Self {
+        if let Some(url) = url {
+            self.options_by_key
+                .entry(key.to_ascii_lowercase())
+                .or_default()
+                .popular_index_url = Some(url);
+        }
+        self
+    }
+
+    /// Sets a registry-native client config file (an `.npmrc` for npm); `None` keeps the
+    /// default routing.
+    pub fn with_config_file(mut self, key: &str, path: Option<PathBuf>) -> Self {
+        if let Some(path) = path {
+            self.options_by_key
+                .entry(key.to_ascii_lowercase())
+                .or_default()
+                .config_file = Some(path);
+        }
+        self
+    }
+
+    /// Sets a local popular-names file shared by every registry; `None` keeps the network index.
+    pub fn with_popular_names_file(mut self, path: Option<PathBuf>) -> Self {
+        if path.is_some() {
+            self.popular_names_file = path;
+        }
+        self
+    }
+
+    /// Shares `limits` between every registry client built from these overrides.
+    pub fn with_request_limits(mut self, limits: RequestLimits) -> Self {
+        self.request_limits = limits;
+        self
+    }
+
+    /// Requests abbreviated package metadata for a registry key; only npm serves one.
+    pub fn with_abbreviated_metadata(mut self, key: &str, abbreviated: bool) -> Self {
+        if abbreviated {
+            self.options_by_key
+                .entry(key.to_ascii_lowercase())
+                .or_default()
+                .abbreviated_metadata = true;
+        }
+        self
+    }
+
+    /// Returns client options for a registry key, or defaults when none were set.
+    pub fn options_for(&self, key: &str) -> RegistryClientOptions {
+        let mut options = self
+            .options_by_key
+            .get(key.to_ascii_lowercase().as_str())
+            .cloned()
+            .unwrap_or_default();
+        if options.popular_names_file.is_none() {
+            options.popular_names_file = self.popular_names_file.clone();
+        }
+        options.limits = self.request_limits.clone();
+        options
+    }
 }
 
 /// One row in the check-support matrix.
@@ -40,6 +143,11 @@ impl RegistryCatalog {
             .filter(|plugin| plugin.lockfile_parser().is_some())
     }
 
+    /// Outbound request limits shared by the catalog's registry clients.
+    pub fn request_limits(&self) -> &RequestLimits {
+        &self.request_limits
+    }
+
     /// Ordered list of package registry keys.
     pub fn package_registry_keys(&self) -> &[&'static str] {
         &self.package_registry_keys
@@ -50,6 +158,42 @@ impl RegistryCatalog {
         &self.lockfile_registry_keys
     }
 
+    /// Replaces the client for one registry, keeping its supported checks and lockfile parser.
+    ///
+    /// Unknown registry keys leave the catalog unchanged.
+    #[cfg(test)]
+    pub fn with_client(mut self, key: &str, client: Arc<dyn RegistryClient>) -> Self {
+        let normalized = key.to_ascii_lowercase();
+        let Some(def) = registry_definitions()
+            .iter()
+            .find(|def| def.key == normalized)
+        else {
+            return self;
+        };
+        let plugin = Arc::new(RegisteredPlugin {
+            key: def.key,
+            client,
+            supported_checks: supported_checks(def.excluded_checks, &known_check_ids()),
+            lockfile_parser: def.create_lockfile_parser.map(|build| build()),
+        }) as Arc<dyn RegistryPlugin>;
+        self.plugins_by_key.insert(def.key, plugin);
+        self
+    }
+
+    /// Lists `(registry, check)` pairs where a registry supports a check whose input data
+    /// its ecosystem never provides, which points at a missing `excluded_checks` entry.
+    pub fn unpopulated_check_support(&self) -> Vec<(&'static str, CheckId)> {
+        self.package_registry_keys
+            .iter()
+            .filter_map(|key| self.plugins_by_key.get(key))
+            .flat_map(|plugin| {
+                unpopulated_checks(plugin.client().ecosystem(), plugin.supported_checks())
+                    .into_iter()
+                    .map(|check| (plugin.key(), check))
+            })
+            .collect()
+    }
+
     /// Materializes a full support matrix for all known checks and registries.
     pub fn check_support_rows(&self) -> Vec<CheckSupportRow> {
         let known_checks = known_check_ids();
@@ -72,6 +216,11 @@ impl RegistryCatalog {
 
 /// Builds the default registry catalog from app-level definitions.
 pub fn register_default_catalog() -> RegistryCatalog {
+    register_catalog(&RegistryClientOverrides::default())
+}
+
+/// Builds the registry catalog, applying startup client overrides per registry.
+pub fn register_catalog(overrides: &RegistryClientOverrides) -> RegistryCatalog {
     let package_registry_keys = supported_package_registry_keys();
     let lockfile_registry_keys = supported_lockfile_registry_keys();
 
@@ -81,7 +230,7 @@ pub fn register_default_catalog() -> RegistryCatalog {
         let supported_checks = supported_checks(def.excluded_checks, &known_checks);
         let plugin = Arc::new(RegisteredPlugin {
             key: def.key,
-            client: (def.create_client)(),
+            client: (def.create_client)(&overrides.options_for(def.key)),
             supported_checks,
             lockfile_parser: def.create_lockfile_parser.map(|build| build()),
         }) as Arc<dyn RegistryPlugin>;
@@ -92,6 +241,7 @@ pub fn register_default_catalog() -> RegistryCatalog {
         plugins_by_key,
         package_registry_keys,
         lockfile_registry_keys,
+        request_limits: overrides.request_limits.clone(),
     }
 }
 
@@ -201,6 +351,16 @@ impl RegistryPlugin for RegisteredPlugin {
     }
 }
 
+/// Checks a registry key supports before any client is built; empty for unknown keys.
+pub fn supported_checks_for(key: &str) -> Vec<CheckId> {
+    let normalized = key.to_ascii_lowercase();
+    registry_definitions()
+        .iter()
+        .find(|def| def.key == normalized)
+        .map(|def| supported_checks(def.excluded_checks, &known_check_ids()))
+        .unwrap_or_default()
+}
+
 fn registry_definitions() -> &'static [RegistryDefinition] {
     static DEFINITIONS: OnceLock<Vec<RegistryDefinition>> = OnceLock::new();
     DEFINITIONS
@@ -223,6 +383,20 @@ fn supported_checks(excluded: &[CheckId], known_checks: &[CheckId]) -> Vec<Check
         .collect()
 }
 
+/// Returns the supported checks that depend on metadata `ecosystem` never publishes.
+fn unpopulated_checks(ecosystem: RegistryEcosystem, supported: &[CheckId]) -> Vec<CheckId> {
+    supported
+        .iter()
+        .copied()
+        .filter(|check| {
+            ECOSYSTEM_SPECIFIC_CHECKS.iter().any(|(id, ecosystems)| {
+                normalize_check_id(id) == normalize_check_id(check)
+                    && !ecosystems.contains(&ecosystem)
+            })
+        })
+        .collect()
+}
+
 fn check_is_supported(excluded: &[CheckId], check: CheckId) -> bool {
     let normalized_check = normalize_check_id(check);
     !excluded
@@ -43,6 +43,26 @@ fn check_support_map_has_every_registry_check_pair() {
     assert_eq!(rows.len(), expected);
 }
 
+#[test]
+fn unpopulated_checks_flags_npm_only_checks_on_other_ecosystems() {
+    let supported = ["install_script", "advisory", "binary_only"];
+
+    assert_eq!(
+        unpopulated_checks(RegistryEcosystem::CratesIo, &supported),
+        vec!["install_script", "binary_only"]
+    );
+    assert!(unpopulated_checks(RegistryEcosystem::Npm, &supported).is_empty());
+}
+
+#[test]
+fn default_catalog_has_no_unpopulated_check_support() {
+    assert!(
+        register_default_catalog()
+            .unpopulated_check_support()
+            .is_empty()
+    );
+}
+
 #[test]
 fn supported_lockfile_files_are_exposed_per_registry() {
     let npm_files = supported_lockfile_files_for_registry("npm").expect("npm lockfile files");
@@ -93,3 +113,142 @@ fn validate_lockfile_request_accepts_supported_existing_file() {
     let _ = fs::remove_file(file);
     let _ = fs::remove_dir_all(dir);
 }
+
+struct CountingClient {
+    fetches: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl RegistryClient for CountingClient {
+    fn ecosystem(&self) -> safe_pkgs_core::RegistryEcosystem {
+        safe_pkgs_core::RegistryEcosystem::Npm
+    }
+
+    async fn fetch_package(
+        &self,
+        package: &str,
+    ) -> Result<safe_pkgs_core::PackageRecord, safe_pkgs_core::RegistryError> {
+        let previous = self
+            .fetches
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        // Fail the first fetch so the test can assert errors are not memoized.
+        if previous == 0 {
+            return Err(safe_pkgs_core::RegistryError::Transport {
+                message: "flaky".to_string(),
+            });
+        }
+        tokio::task::yield_now().await;
+        Ok(safe_pkgs_core::PackageRecord {
+            name: package.to_string(),
+            latest: "1.0.0".to_string(),
+            publishers: Vec::new(),
+            versions: std::collections::BTreeMap::new(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn package_memo_fetches_each_name_once_and_retries_failures() {
+    let client = CountingClient {
+        fetches: std::sync::atomic::AtomicUsize::new(0),
+    };
+    let memo = PackageMemo::default();
+    let memoized = MemoizedRegistryClient::new(&client, &memo);
+
+    assert!(memoized.fetch_package("demo").await.is_err());
+    let (first, second) = tokio::join!(
+        memoized.fetch_package("demo"),
+        memoized.fetch_package("demo")
+    );
+    assert_eq!(first.expect("first fetch").name, "demo");
+    assert_eq!(second.expect("second fetch").name, "demo");
+    assert_eq!(
+        client.fetches.load(std::sync::atomic::Ordering::SeqCst),
+        2,
+        "one failed fetch plus one shared successful fetch"
+    );
+}
+
+#[tokio::test]
+async fn persisted_popular_names_survive_a_fresh_client_and_store() {
+    use crate::cache::SqliteCache;
+    use crate::test_support::MockRegistryClient;
+    use safe_pkgs_core::RegistryEcosystem;
+    use std::time::Duration;
+
+    let cache = Arc::new(SqliteCache::in_memory(30).expect("in-memory cache"));
+    let ttl = Duration::from_secs(3600);
+
+    let online = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_popular_names(&["react", "lodash", "express"]);
+    let first_store = PopularNamesStore::new(Arc::clone(&cache), ttl);
+    let first = PersistedPopularNamesClient::new(&online, Some(&first_store), "npm");
+    first
+        .prefetch_popular_package_names()
+        .await
+        .expect("prefetch");
+
+    // A restarted process: new store, and a client whose index would return nothing.
+    let offline = MockRegistryClient::new(RegistryEcosystem::Npm);
+    let second_store = PopularNamesStore::new(Arc::clone(&cache), ttl);
+    let second = PersistedPopularNamesClient::new(&offline, Some(&second_store), "npm");
+    let names = second
+        .fetch_popular_package_names(2)
+        .await
+        .expect("persisted names");
+    assert_eq!(names, vec!["react".to_string(), "lodash".to_string()]);
+
+    let other_scope = PersistedPopularNamesClient::new(&offline, Some(&second_store), "pypi");
+    assert!(
+        other_scope
+            .fetch_popular_package_names(2)
+            .await
+            .expect("names")
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn persisted_download_counts_are_reused_without_a_registry_call() {
+    use crate::cache::SqliteCache;
+    use crate::test_support::MockRegistryClient;
+    use safe_pkgs_core::RegistryEcosystem;
+    use std::time::Duration;
+
+    let cache = Arc::new(SqliteCache::in_memory(30).expect("in-memory cache"));
+    let ttl = Duration::from_secs(3600);
+
+    let online =
+        MockRegistryClient::new(RegistryEcosystem::Npm).with_weekly_downloads("lodash", 5_000);
+    let first_store = DownloadCountStore::new(Arc::clone(&cache), ttl);
+    let first = PersistedDownloadsClient::new(&online, Some(&first_store), "npm");
+    assert_eq!(
+        first.fetch_weekly_downloads("lodash").await.expect("fetch"),
+        Some(5_000)
+    );
+
+    // Another client instance (or a restarted process) whose registry would report nothing.
+    let offline = MockRegistryClient::new(RegistryEcosystem::Npm);
+    let second_store = DownloadCountStore::new(Arc::clone(&cache), ttl);
+    let second = PersistedDownloadsClient::new(&offline, Some(&second_store), "npm");
+    assert_eq!(
+        second
+            .fetch_weekly_downloads("lodash")
+            .await
+            .expect("persisted count"),
+        Some(5_000)
+    );
+    assert_eq!(
+        second.fetch_weekly_downloads("react").await.expect("miss"),
+        None
+    );
+
+    let other_scope = PersistedDownloadsClient::new(&offline, Some(&second_store), "pypi");
+    assert_eq!(
+        other_scope
+            .fetch_weekly_downloads("lodash")
+            .await
+            .expect("other registry"),
+        None
+    );
+}
@@ -0,0 +1,193 @@
+//! Popular package names persisted in the SQLite cache across process restarts.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use safe_pkgs_core::{PackageAdvisory, PackageRecord, RegistryEcosystem, RegistryError};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use super::RegistryClient;
+use crate::cache::SqliteCache;
+
+/// Names fetched per registry when the store fills, so one stored list serves the
+/// typosquat sample and the lockfile prefetch alike.
+const PERSISTED_POPULAR_NAMES_LIMIT: usize = 5000;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedNames {
+    /// Limit the list was fetched with; requests above it refetch.
+    limit: usize,
+    names: Vec<String>,
+}
+
+/// SQLite-backed popular-names lists, keyed by registry scope, with their own TTL.
+///
+/// Lists are also held in memory once loaded, so checks within one process read SQLite
+/// at most once per registry.
+pub struct PopularNamesStore {
+    cache: Arc<SqliteCache>,
+    ttl: Duration,
+    loaded: Mutex<HashMap<String, Arc<PersistedNames>>>,
+}
+
+impl PopularNamesStore {
+    pub fn new(cache: Arc<SqliteCache>, ttl: Duration) -> Self {
+        Self {
+            cache,
+            ttl,
+            loaded: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Serves up to `limit` names for `key`, fetching from `inner` only when neither
+    /// memory nor SQLite holds a large enough list.
+    async fn fetch(
+        &self,
+        key: &str,
+        inner: &dyn RegistryClient,
+        limit: usize,
+    ) -> Result<Vec<String>, RegistryError> {
+        // Held across the fetch so concurrent checks share one network request.
+        let mut loaded = self.loaded.lock().await;
+        if let Some(entry) = loaded.get(key).filter(|entry| entry.limit >= limit) {
+            return Ok(entry.names.iter().take(limit).cloned().collect());
+        }
+        if let Some(entry) = self.read(key).filter(|entry| entry.limit >= limit) {
+            let names = entry.names.iter().take(limit).cloned().collect();
+            loaded.insert(key.to_string(), Arc::new(entry));
+            return Ok(names);
+        }
+
+        let fetch_limit = limit.max(PERSISTED_POPULAR_NAMES_LIMIT);
+        let names = inner.fetch_popular_package_names(fetch_limit).await?;
+        let entry = PersistedNames {
+            limit: fetch_limit,
+            names,
+        };
+        // An empty list is never persisted, so a failing index is retried after restart.
+        if !entry.names.is_empty() {
+            self.write(key, &entry);
+        }
+        let names = entry.names.iter().take(limit).cloned().collect();
+        loaded.insert(key.to_string(), Arc::new(entry));
+        Ok(names)
+    }
+
+    fn read(&self, key: &str) -> Option<PersistedNames> {
+        let raw = match self.cache.get(key) {
+            Ok(raw) => raw?,
+            Err(err) => {
+                tracing::warn!("failed to read persisted popular names for {key}: {err}");
+                return None;
+            }
+        };
+        match serde_json::from_str(&raw) {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                tracing::warn!("ignoring unreadable persisted popular names for {key}: {err}");
+                None
+            }
+        }
+    }
+
+    fn write(&self, key: &str, entry: &PersistedNames) {
+        let result = serde_json::to_string(entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|encoded| self.cache.set_with_ttl(key, &encoded, self.ttl));
+        if let Err(err) = result {
+            tracing::warn!("failed to persist popular names for {key}: {err}");
+        }
+    }
+}
+
+/// Registry client wrapper that reads and writes popular names through a
+/// [`PopularNamesStore`]; without a store every call goes to the wrapped client.
+pub struct PersistedPopularNamesClient<'a> {
+    inner: &'a dyn RegistryClient,
+    store: Option<&'a PopularNamesStore>,
+    key: String,
+}
+
+impl<'a> PersistedPopularNamesClient<'a> {
+    /// `scope` identifies the registry and popular-names source the list belongs to.
+    pub fn new(
+        inner: &'a dyn RegistryClient,
+        store: Option<&'a PopularNamesStore>,
+        scope: &str,
+    ) -> Self {
+        Self {
+            inner,
+            store,
+            key: format!("popular_names:v1:{scope}"),
+        }
+    }
+}
+
+#[async_trait]
+impl RegistryClient for PersistedPopularNamesClient<'_> {
+    fn ecosystem(&self) -> RegistryEcosystem {
+        self.inner.ecosystem()
+    }
+
+    fn base_url(&self) -> Option<&str> {
+        self.inner.base_url()
+    }
+
+    async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
+        self.inner.fetch_package(package).await
+    }
+
+    async fn prefetch_weekly_downloads(&self, packages: &[String]) -> Result<(), RegistryError> {
+        self.inner.prefetch_weekly_downloads(packages).await
+    }
+
+    async fn fetch_weekly_downloads(&self, package: &str) -> Result<Option<u64>, RegistryError> {
+        self.inner.fetch_weekly_downloads(package).await
+    }
+
+    async fn fetch_weekly_downloads_baseline(
+        &self,
+        package: &str,
+    ) -> Result<Option<u64>, RegistryError> {
+        self.inner.fetch_weekly_downloads_baseline(package).await
+    }
+
+    async fn prefetch_popular_package_names(&self) -> Result<(), RegistryError> {
+        match self.store {
+            Some(_) => self
+                .fetch_popular_package_names(PERSISTED_POPULAR_NAMES_LIMIT)
+                .await
+                .map(|_| ()),
+            None => self.inner.prefetch_popular_package_names().await,
+        }
+    }
+
+    async fn fetch_popular_package_names(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<String>, RegistryError> {
+        match self.store {
+            Some(store) => store.fetch(&self.key, self.inner, limit).await,
+            None => self.inner.fetch_popular_package_names(limit).await,
+        }
+    }
+
+    async fn prefetch_advisories(
+        &self,
+        packages: &[(String, String)],
+        concurrency: usize,
+    ) -> Result<(), RegistryError> {
+        self.inner.prefetch_advisories(packages, concurrency).await
+    }
+
+    async fn fetch_advisories(
+        &self,
+        package: &str,
+        version: &str,
+    ) -> Result<Vec<PackageAdvisory>, RegistryError> {
+        self.inner.fetch_advisories(package, version).await
+    }
+}
@@ -0,0 +1,206 @@
+//! CycloneDX SBOM input for multi-ecosystem audits.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use safe_pkgs_core::{DependencyScope, DependencySpec, LockfileError};
+
+/// One SBOM component, routed by its package URL.
+#[derive(Debug, Clone)]
+pub enum SbomComponent {
+    /// Component whose purl names an ecosystem with a registry in this build.
+    Supported {
+        registry: String,
+        spec: DependencySpec,
+    },
+    /// Component that cannot be checked (missing, malformed, or foreign purl).
+    Unsupported {
+        name: String,
+        version: Option<String>,
+        reason: String,
+    },
+}
+
+/// Reads CycloneDX JSON SBOMs and maps each component's purl to a registry.
+pub struct CycloneDxParser;
+
+#[derive(Debug, Deserialize)]
+struct CycloneDxDocument {
+    #[serde(rename = "bomFormat")]
+    bom_format: Option<String>,
+    #[serde(default)]
+    components: Vec<CycloneDxComponent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CycloneDxComponent {
+    name: Option<String>,
+    version: Option<String>,
+    purl: Option<String>,
+    scope: Option<String>,
+    #[serde(default)]
+    components: Vec<CycloneDxComponent>,
+}
+
+impl CycloneDxParser {
+    /// Parses the SBOM at `path` into components in document order (nested components follow
+    /// their parent).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file cannot be read or is not a CycloneDX JSON document.
+    pub fn parse_components(&self, path: &Path) -> Result<Vec<SbomComponent>, LockfileError> {
+        let raw = std::fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
+            path: path.display().to_string(),
+            source,
+        })?;
+        self.parse_str(&raw)
+            .map_err(|message| LockfileError::ParseFile {
+                path: path.display().to_string(),
+                message,
+            })
+    }
+
+    fn parse_str(&self, raw: &str) -> Result<Vec<SbomComponent>, String> {
+        let document: CycloneDxDocument =
+            serde_json::from_str(raw).map_err(|err| err.to_string())?;
+        if document.bom_format.as_deref() != Some("CycloneDX") {
+            return Err("expected bomFormat \"CycloneDX\"".to_string());
+        }
+
+        let mut components = Vec::new();
+        flatten_components(&document.components, &mut components);
+        Ok(components)
+    }
+}
+
+fn flatten_components(components: &[CycloneDxComponent], out: &mut Vec<SbomComponent>) {
+    for component in components {
+        out.push(map_component(component));
+        flatten_components(&component.components, out);
+    }
+}
+
+fn map_component(component: &CycloneDxComponent) -> SbomComponent {
+    let fallback_name = component.name.clone().unwrap_or_default();
+    let Some(purl) = component.purl.as_deref() else {
+        return SbomComponent::Unsupported {
+            name: fallback_name,
+            version: component.version.clone(),
+            reason: "component has no purl".to_string(),
+        };
+    };
+    let Some(package_url) = PackageUrl::parse(purl) else {
+        return SbomComponent::Unsupported {
+            name: fallback_name,
+            version: component.version.clone(),
+            reason: format!("malformed purl '{purl}'"),
+        };
+    };
+    let version = package_url.version.or_else(|| component.version.clone());
+    let Some(registry) = registry_for_purl_type(&package_url.purl_type) else {
+        return SbomComponent::Unsupported {
+            name: package_url.name,
+            version,
+            reason: format!("unsupported purl type '{}'", package_url.purl_type),
+        };
+    };
+
+    let name = match (registry, package_url.namespace) {
+        // Scoped npm packages carry the scope as the purl namespace.
+        ("npm", Some(namespace)) => format!("{namespace}/{}", package_url.name),
+        _ => package_url.name,
+    };
+    let scope = match component.scope.as_deref() {
+        Some("optional") => DependencyScope::Optional,
+        _ => DependencyScope::Prod,
+    };
+
+    SbomComponent::Supported {
+        registry: registry.to_string(),
+        spec: DependencySpec {
+            name,
+            version,
+            dependency_paths: Vec::new(),
+            scope,
+            unsupported_source: None,
+            hashes: Vec::new(),
+        },
+    }
+}
+
+/// Maps a purl type to the registry key that checks it.
+fn registry_for_purl_type(purl_type: &str) -> Option<&'static str> {
+    match purl_type {
+        "npm" => Some("npm"),
+        "cargo" => Some("cargo"),
+        "pypi" => Some("pypi"),
+        _ => None,
+    }
+}
+
+/// The parts of a package URL (`pkg:type/namespace/name@version?qualifiers#subpath`)
+/// needed to route a component.
+#[derive(Debug, PartialEq, Eq)]
+struct PackageUrl {
+    purl_type: String,
+    namespace: Option<String>,
+    name: String,
+    version: Option<String>,
+}
+
+impl PackageUrl {
+    fn parse(raw: &str) -> Option<Self> {
+        let rest = raw.trim().strip_prefix("pkg:")?;
+        let rest = rest.split_once('#').map_or(rest, |(head, _)| head);
+        let rest = rest.split_once('?').map_or(rest, |(head, _)| head);
+        let (purl_type, path) = rest.trim_start_matches('/').split_once('/')?;
+
+        // The version follows the last `@` that is not part of the path.
+        let (path, version) = match path.rsplit_once('@') {
+            Some((head, version)) if !head.is_empty() && !version.contains('/') => {
+                (head, Some(percent_decode(version)?))
+            }
+            _ => (path, None),
+        };
+        let path = path.trim_matches('/');
+        let (namespace, name) = match path.rsplit_once('/') {
+            Some((namespace, name)) => (Some(percent_decode(namespace)?), name),
+            None => (None, path),
+        };
+        let name = percent_decode(name)?;
+        if name.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            purl_type: purl_type.to_ascii_lowercase(),
+            namespace,
+            name,
+            version: version.filter(|value| !value.is_empty()),
+        })
+    }
+}
+
+/// Decodes `%XX` escapes; returns `None` for truncated or non-UTF-8 sequences.
+fn percent_decode(raw: &str) -> Option<String> {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = raw.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+#[path = "tests/sbom.rs"]
+mod tests;
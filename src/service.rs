@@ -1,26 +1,71 @@
 //! Shared application service for package and lockfile evaluation.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, anyhow};
 use chrono::{DateTime, Utc};
+use sha2::Digest;
 use tokio::task::JoinSet;
 
-use safe_pkgs_core::DependencySpec;
+use safe_pkgs_core::{
+    DependencyScope, DependencySpec, ManifestDrift, RegistryError, RequestLimits,
+};
 
 use crate::audit_log::{AuditLogger, AuditRecord, PackageDecision};
 use crate::cache::SqliteCache;
 use crate::checks;
-use crate::config::SafePkgsConfig;
+use crate::config::{SafePkgsConfig, TransportErrorMode};
+use crate::decision_hook;
+use crate::lockfile_snapshot::{
+    LockfileSnapshot, LockfileSnapshotEntry, content_hash, response_cache_key, reusable_response,
+    snapshot_cache_key,
+};
 use crate::metrics::Metrics;
 use crate::policy_snapshot::{RegistryPolicySnapshot, build_registry_policy_snapshot};
-use crate::registries::{RegistryCatalog, register_default_catalog};
+use crate::registries::{
+    DownloadCountStore, DownloadHistoryClient, DownloadHistoryStore, MemoizedRegistryClient,
+    PackageMemo, PersistedDownloadsClient, PersistedPopularNamesClient, PopularNamesStore,
+    RegistryCatalog, RegistryClient, RegistryClientOverrides, RegistryPlugin, register_catalog,
+    supported_checks_for,
+};
+use crate::sbom::{CycloneDxParser, SbomComponent};
+use crate::tarball::{TARBALL_CHECKS, TarballInspector, TarballRegistryClient};
 use crate::types::{
-    DecisionFingerprints, DependencyAncestry, DependencyAncestryPath, Evidence, EvidenceKind,
-    LockfilePackageResult, LockfileResponse, Severity, SimulationReport, ToolResponse,
+    CacheWarmReport, DecisionFingerprints, DependencyAncestry, DependencyAncestryPath, Evidence,
+    EvidenceKind, LockfilePackageResult, LockfileResponse, PackageCheckResult, PackageListResponse,
+    PolicyDiffChange, PolicyDiffDecision, PolicyDiffEntry, PolicyDiffReport, ReadinessReport,
+    RejectedVersion, SafestVersionResponse, Severity, SimulationReport, TarballCheckResult,
+    ToolResponse,
 };
 
+/// A lockfile dependency with its decision, plus the original evaluation time when the
+/// decision was reused from the incremental snapshot.
+type EvaluatedSpec = (
+    DependencySpec,
+    anyhow::Result<ToolResponse>,
+    Option<DateTime<Utc>>,
+);
+
+/// Evidence id attached to lockfile packages whose evaluation failed at runtime.
+const PACKAGE_CHECK_FAILED_EVIDENCE_ID: &str = "lockfile.package_check_failed";
+
+/// Evidence ids marking a package that was reported but not fully evaluated.
+const NOT_FULLY_EVALUATED_EVIDENCE_IDS: [&str; 4] = [
+    PACKAGE_CHECK_FAILED_EVIDENCE_ID,
+    "lockfile.unsupported_source",
+    "sbom.unsupported_component",
+    "version.unparseable",
+];
+
+/// Cache key round-tripped by [`SafePkgsService::readiness`].
+const READINESS_PROBE_CACHE_KEY: &str = "readiness_probe";
+
+/// Maximum number of candidate versions evaluated by [`SafePkgsService::resolve_safest_version`].
+pub const MAX_SAFEST_CANDIDATES: usize = 10;
+
 /// Marker error type that distinguishes audit log failures from check failures.
 ///
 /// This allows callers to detect audit log errors via typed downcast rather than
@@ -40,6 +85,24 @@ impl std::error::Error for AuditLogError {
     }
 }
 
+/// Package coordinates for a single check request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageSpec {
+    pub name: String,
+    pub version: Option<String>,
+    pub registry: String,
+}
+
+/// Per-run options for lockfile audits.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LockfileAuditOptions {
+    /// Reuse decisions from the last audit of the same file for unchanged dependencies.
+    pub incremental: bool,
+    /// Only list denied packages or those at `Medium` risk and above; aggregate
+    /// `total`, `denied` and `risk` still cover every package.
+    pub risky_only: bool,
+}
+
 /// Core runtime service for package and lockfile evaluation.
 #[derive(Clone)]
 pub struct SafePkgsService {
@@ -49,21 +112,58 @@ pub struct SafePkgsService {
     policy_snapshots: Arc<BTreeMap<String, RegistryPolicySnapshot>>,
     evaluation_time_override: Option<DateTime<Utc>>,
     cache: Arc<SqliteCache>,
+    popular_names: Option<Arc<PopularNamesStore>>,
+    download_counts: Option<Arc<DownloadCountStore>>,
+    download_history: Option<Arc<DownloadHistoryStore>>,
     audit_logger: Arc<AuditLogger>,
     metrics: Arc<Metrics>,
+    /// Whether decisions are written to the decision cache and the audit log.
+    record_decisions: bool,
 }
 
 impl SafePkgsService {
     /// Creates a service using default config, on-disk cache, and audit log.
     ///
+    /// `config_path` replaces config discovery with a single explicit file. `profile`
+    /// selects a `[profiles.<name>]` config section, falling back to `SAFE_PKGS_PROFILE`
+    /// when `None`.
+    ///
     /// # Errors
     ///
     /// Returns an error if config, cache, or audit logger initialization fails.
-    pub async fn new() -> anyhow::Result<Self> {
-        let config = SafePkgsConfig::load_async().await?;
+    pub async fn new(
+        overrides: &RegistryClientOverrides,
+        config_path: Option<&Path>,
+        profile: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let config = SafePkgsConfig::load_async(config_path, profile).await?;
+        Self::from_config(overrides, config)
+    }
+
+    /// Creates a service for an already-loaded config, with on-disk cache and audit log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if cache or audit logger initialization fails.
+    pub fn from_config(
+        overrides: &RegistryClientOverrides,
+        config: SafePkgsConfig,
+    ) -> anyhow::Result<Self> {
+        if let Some(path) = &config.npmrc
+            && !path.is_file()
+        {
+            anyhow::bail!("npmrc file {} does not exist", path.display());
+        }
         let cache = SqliteCache::new(config.cache.ttl_minutes)?;
-        let audit_logger = AuditLogger::new()?;
-        Self::with_cache(config, cache, audit_logger)
+        let audit_logger = AuditLogger::new(&config.audit_log)?;
+        let overrides = registry_overrides_from_config(overrides.clone(), &config);
+        let registries = register_catalog(&overrides);
+        for (registry, check) in registries.unpopulated_check_support() {
+            tracing::warn!(
+                "registry '{registry}' supports check '{check}' but never provides the metadata it reads; add it to excluded_checks"
+            );
+        }
+        Self::with_cache(registries, config, cache, audit_logger)
     }
 
     #[cfg(test)]
@@ -71,31 +171,74 @@ impl SafePkgsService {
     pub fn with_config(config: SafePkgsConfig) -> Self {
         let cache = SqliteCache::in_memory(config.cache.ttl_minutes)
             .expect("in-memory sqlite cache for test service");
-        let audit_logger = AuditLogger::new().expect("audit logger");
-        Self::with_cache(config, cache, audit_logger).expect("service init for tests")
+        let audit_logger = AuditLogger::new(&config.audit_log).expect("audit logger");
+        let overrides = registry_overrides_from_config(RegistryClientOverrides::default(), &config);
+        Self::with_cache(register_catalog(&overrides), config, cache, audit_logger)
+            .expect("service init for tests")
     }
 
-    fn with_cache(
+    pub(crate) fn with_cache(
+        registries: RegistryCatalog,
         config: SafePkgsConfig,
         cache: SqliteCache,
         audit_logger: AuditLogger,
     ) -> anyhow::Result<Self> {
-        let registries = register_default_catalog();
-        let config_fingerprint = compute_config_fingerprint(&config)?;
+        let config_fingerprint = config.config_fingerprint()?;
         let policy_snapshots = build_policy_snapshots_by_registry(&registries, &config)?;
         let evaluation_time_override = load_evaluation_time_override()?;
+        let cache = Arc::new(cache);
+        // A local popular-names file is already fast to read, so it is never persisted.
+        let popular_names = (config.cache.popular_names_ttl_minutes > 0
+            && config.typosquat.popular_names_file.is_none())
+        .then(|| {
+            let ttl_seconds = config.cache.popular_names_ttl_minutes.saturating_mul(60);
+            Arc::new(PopularNamesStore::new(
+                Arc::clone(&cache),
+                Duration::from_secs(ttl_seconds),
+            ))
+        });
+        let download_counts = (config.cache.downloads_ttl_minutes > 0).then(|| {
+            let ttl_seconds = config.cache.downloads_ttl_minutes.saturating_mul(60);
+            Arc::new(DownloadCountStore::new(
+                Arc::clone(&cache),
+                Duration::from_secs(ttl_seconds),
+            ))
+        });
+        let download_history = (config.cache.download_baseline_days > 0).then(|| {
+            let ttl_seconds = config
+                .cache
+                .download_baseline_days
+                .saturating_mul(24 * 60 * 60);
+            Arc::new(DownloadHistoryStore::new(
+                Arc::clone(&cache),
+                Duration::from_secs(ttl_seconds),
+            ))
+        });
         Ok(Self {
             registries,
             config: Arc::new(config),
             config_fingerprint,
             policy_snapshots: Arc::new(policy_snapshots),
             evaluation_time_override,
-            cache: Arc::new(cache),
+            cache,
+            popular_names,
+            download_counts,
+            download_history,
             audit_logger: Arc::new(audit_logger),
             metrics: Metrics::new(),
+            record_decisions: true,
         })
     }
 
+    /// A view of this service that still reads cached lookups but writes no decisions to
+    /// the decision cache or the audit log.
+    fn without_recording(&self) -> Self {
+        Self {
+            record_decisions: false,
+            ..self.clone()
+        }
+    }
+
     /// Runs a lockfile audit for a dependency file or project path.
     ///
     /// # Errors
@@ -106,6 +249,7 @@ impl SafePkgsService {
         path: Option<&str>,
         registry: &str,
         context: &str,
+        options: LockfileAuditOptions,
     ) -> anyhow::Result<LockfileResponse> {
         crate::registries::validate_lockfile_request(registry, path).map_err(anyhow::Error::msg)?;
 
@@ -123,14 +267,279 @@ impl SafePkgsService {
                 self.registries.lockfile_registry_keys(),
             ));
         };
-        let registry_key = plugin.key();
-
         let input_path = lockfile_parser.resolve_input(path)?;
-        let package_specs = lockfile_parser.parse_dependencies(&input_path)?;
-        let package_names = package_specs
+        let specs = lockfile_parser.parse_dependencies(&input_path)?;
+
+        // Incremental audits keep their own per-package snapshot and report which packages
+        // were re-evaluated, so only full audits use the whole-response cache.
+        let response_key = if options.incremental {
+            None
+        } else {
+            Some(response_cache_key(
+                &self.config_fingerprint,
+                &self
+                    .policy_snapshot_for_registry(plugin.key())?
+                    .policy_fingerprint,
+                &registry_cache_scope(plugin.as_ref()),
+                &content_hash(&input_path)?,
+                &specs,
+            )?)
+        };
+        let cached_response = match &response_key {
+            Some(key) => self.cached_lockfile_response(key, plugin.key(), context)?,
+            None => None,
+        };
+
+        let mut response = match cached_response {
+            Some(response) => response,
+            None => {
+                let (unsupported_specs, package_specs): (Vec<_>, Vec<_>) = specs
+                    .into_iter()
+                    .partition(|spec| spec.unsupported_source.is_some());
+                let mut response = self
+                    .audit_dependency_specs(plugin, &input_path, package_specs, context, options)
+                    .await?;
+                if self.config.lockfile.report_unsupported_sources {
+                    response.packages.extend(
+                        unsupported_specs
+                            .into_iter()
+                            .filter_map(unsupported_source_result),
+                    );
+                    response.total = response.packages.len();
+                } else {
+                    response.unreported_sources = unsupported_specs.len();
+                }
+                // Responses with failed or skipped packages are not stored, so those
+                // packages are retried on the next run.
+                let complete = response.skipped == 0
+                    && response.packages.iter().all(|package| {
+                        package
+                            .evidence
+                            .iter()
+                            .all(|item| item.id != PACKAGE_CHECK_FAILED_EVIDENCE_ID)
+                    });
+                if let Some(key) = response_key.as_deref()
+                    && complete
+                    && self.record_decisions
+                {
+                    self.cache.set(key, &serde_json::to_string(&response)?)?;
+                }
+                response
+            }
+        };
+        // Drift depends on the manifest too, which the response cache key does not cover.
+        match lockfile_parser.manifest_drift(&input_path) {
+            Ok(drift) => apply_manifest_drift(&mut response, drift),
+            Err(err) => tracing::warn!(
+                "skipping lockfile/manifest drift check for {}: {err}",
+                input_path.display()
+            ),
+        }
+        self.apply_coverage_policy(&mut response);
+        if options.risky_only {
+            retain_risky_packages(&mut response);
+        }
+        Ok(response)
+    }
+
+    /// Recounts fully evaluated packages and denies the audit when they fall below
+    /// `lockfile.min_coverage_ratio` of all packages, including skipped and unreported ones.
+    fn apply_coverage_policy(&self, response: &mut LockfileResponse) {
+        response.evaluated = evaluated_count(&response.packages);
+        response.coverage_shortfall = None;
+        let Some(min_ratio) = self.config.lockfile.min_coverage_ratio else {
+            return;
+        };
+        let considered = response
+            .total
+            .saturating_add(response.skipped)
+            .saturating_add(response.unreported_sources);
+        if considered == 0 {
+            return;
+        }
+        let ratio = response.evaluated as f64 / considered as f64;
+        if ratio < min_ratio {
+            response.allow = false;
+            response.coverage_shortfall = Some(format!(
+                "only {} of {considered} packages were fully evaluated ({:.0}%), below lockfile.min_coverage_ratio {min_ratio}",
+                response.evaluated,
+                ratio * 100.0
+            ));
+        }
+    }
+
+    /// Returns a stored response for an unchanged dependency file, logging each package
+    /// decision as cached.
+    fn cached_lockfile_response(
+        &self,
+        key: &str,
+        registry_key: &str,
+        context: &str,
+    ) -> anyhow::Result<Option<LockfileResponse>> {
+        let Some(response) = self
+            .cache
+            .get(key)?
+            .and_then(|raw| serde_json::from_str::<LockfileResponse>(&raw).ok())
+        else {
+            return Ok(None);
+        };
+        let now = self.current_evaluation_time();
+        if response
+            .packages
             .iter()
-            .map(|spec| spec.name.clone())
-            .collect::<Vec<_>>();
+            .any(|package| checks::has_expired_suppression(&package.evidence, now))
+        {
+            return Ok(None);
+        }
+        tracing::info!(
+            registry = registry_key,
+            packages = response.total,
+            "dependency file unchanged; serving lockfile audit from cache"
+        );
+
+        let registry_policy = self.policy_snapshot_for_registry(registry_key)?;
+        let evaluation_time = now.to_rfc3339();
+        for package in &response.packages {
+            self.log_decision(PackageDecision {
+                context,
+                registry: registry_key,
+                package: package.name.as_str(),
+                requested: package.requested.as_deref(),
+                allow: package.allow,
+                risk: package.risk,
+                reasons: package.reasons.clone(),
+                evidence: package.evidence.clone(),
+                checks_run: Vec::new(),
+                metadata: None,
+                policy_snapshot_version: registry_policy.version,
+                config_fingerprint: self.config_fingerprint.as_str(),
+                policy_fingerprint: registry_policy.policy_fingerprint.as_str(),
+                enabled_checks: registry_policy.enabled_checks.clone(),
+                evaluation_time: evaluation_time.clone(),
+                cached: true,
+            })?;
+        }
+        Ok(Some(response))
+    }
+
+    /// Audits a CycloneDX JSON SBOM, routing each component to the registry named by its purl.
+    ///
+    /// Components whose purl is missing or names an unsupported ecosystem are reported
+    /// as denied rather than skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the SBOM cannot be read or parsed, or package evaluation fails.
+    pub async fn audit_sbom_path(
+        &self,
+        path: &str,
+        options: LockfileAuditOptions,
+    ) -> anyhow::Result<LockfileResponse> {
+        let input_path = std::path::PathBuf::from(path);
+        let components = CycloneDxParser.parse_components(&input_path)?;
+
+        let mut groups = BTreeMap::<&'static str, Vec<(usize, DependencySpec)>>::new();
+        let mut ordered: Vec<Option<LockfilePackageResult>> =
+            (0..components.len()).map(|_| None).collect();
+        for (idx, component) in components.into_iter().enumerate() {
+            match component {
+                SbomComponent::Supported { registry, spec } => {
+                    match self.registries.package_plugin(&registry) {
+                        Some(plugin) => groups.entry(plugin.key()).or_default().push((idx, spec)),
+                        None => {
+                            ordered[idx] = Some(unsupported_component_result(
+                                spec.name,
+                                spec.version,
+                                &format!("registry '{registry}' is not enabled in this build"),
+                            ));
+                        }
+                    }
+                }
+                SbomComponent::Unsupported {
+                    name,
+                    version,
+                    reason,
+                } => {
+                    ordered[idx] = Some(unsupported_component_result(name, version, &reason));
+                }
+            }
+        }
+
+        let mut policy_fingerprints = Vec::with_capacity(groups.len());
+        let mut skipped = 0usize;
+        for (registry_key, entries) in groups {
+            let Some(plugin) = self.registries.package_plugin(registry_key) else {
+                return Err(anyhow!("registry '{registry_key}' missing from catalog"));
+            };
+            let (indices, specs): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+            let spec_keys = specs
+                .iter()
+                .map(|spec| (spec.name.clone(), spec.version.clone()))
+                .collect::<Vec<_>>();
+            let audit = self
+                .audit_dependency_specs(plugin, &input_path, specs, "cli_audit_sbom", options)
+                .await?;
+            policy_fingerprints.push((registry_key, audit.fingerprints.policy));
+            skipped = skipped.saturating_add(audit.skipped);
+            // Skipped packages are absent from `audit.packages`, so match them back by position.
+            let mut packages = audit.packages.into_iter().peekable();
+            for (idx, (name, requested)) in indices.into_iter().zip(spec_keys) {
+                let reported = packages
+                    .peek()
+                    .is_some_and(|package| package.name == name && package.requested == requested);
+                if reported && let Some(mut package) = packages.next() {
+                    package.registry = Some(registry_key.to_string());
+                    ordered[idx] = Some(package);
+                }
+            }
+        }
+
+        let packages = ordered.into_iter().flatten().collect::<Vec<_>>();
+        let mut risk = Severity::Low;
+        let mut denied = 0usize;
+        for package in &packages {
+            let counts_toward_decision = !(self.config.lockfile.ignore_dev_dependencies
+                && package.scope == DependencyScope::Dev);
+            if counts_toward_decision {
+                risk = risk.max(package.risk);
+                if !package.allow {
+                    denied = denied.saturating_add(1);
+                }
+            }
+        }
+
+        let mut response = LockfileResponse {
+            allow: denied == 0,
+            risk,
+            total: packages.len(),
+            denied,
+            skipped,
+            unreported_sources: 0,
+            evaluated: evaluated_count(&packages),
+            coverage_shortfall: None,
+            packages,
+            fingerprints: DecisionFingerprints {
+                config: self.config_fingerprint.clone(),
+                policy: combined_policy_fingerprint(&policy_fingerprints),
+            },
+        };
+        self.apply_coverage_policy(&mut response);
+        if options.risky_only {
+            retain_risky_packages(&mut response);
+        }
+        Ok(response)
+    }
+
+    /// Evaluates already-parsed dependencies against one registry.
+    async fn audit_dependency_specs(
+        &self,
+        plugin: &Arc<dyn RegistryPlugin>,
+        input_path: &Path,
+        package_specs: Vec<DependencySpec>,
+        context: &str,
+        options: LockfileAuditOptions,
+    ) -> anyhow::Result<LockfileResponse> {
+        let registry_key = plugin.key();
 
         let requirements = checks::runtime_requirements_for_registry(
             registry_key,
@@ -141,46 +550,114 @@ impl SafePkgsService {
         let evaluation_time = self.current_evaluation_time();
         let evaluation_time_rfc3339 = evaluation_time.to_rfc3339();
 
-        if !package_names.is_empty() {
-            if requirements.needs_weekly_downloads
-                && let Err(err) = plugin
-                    .client()
-                    .prefetch_weekly_downloads(&package_names)
-                    .await
-            {
-                tracing::warn!("registry prefetch failed for {registry}: {err}");
-            }
+        // Incremental mode: reuse prior decisions for dependencies whose name and
+        // version are unchanged since the last audit of this file under this policy.
+        let snapshot_key = snapshot_cache_key(
+            registry_policy.policy_fingerprint.as_str(),
+            &registry_cache_scope(plugin.as_ref()),
+            input_path,
+        );
+        let current_content_hash = if options.incremental {
+            Some(content_hash(input_path)?)
+        } else {
+            None
+        };
+        let prior_snapshot = if options.incremental {
+            self.cache
+                .get(&snapshot_key)?
+                .and_then(|raw| serde_json::from_str::<LockfileSnapshot>(&raw).ok())
+        } else {
+            None
+        };
+        if let (Some(prior), Some(current)) = (&prior_snapshot, &current_content_hash)
+            && prior.content_hash == *current
+        {
+            tracing::info!(
+                registry = registry_key,
+                "lockfile unchanged since last incremental audit; reusing prior decisions"
+            );
+        }
+        let prior_index = prior_snapshot
+            .map(LockfileSnapshot::into_index)
+            .unwrap_or_default();
 
-            if requirements.needs_popular_package_names
-                && let Err(err) = plugin.client().prefetch_popular_package_names().await
-            {
-                tracing::warn!("popular package prefetch failed for {registry}: {err}");
+        let total = package_specs.len();
+        let mut ordered: Vec<Option<EvaluatedSpec>> = (0..total).map(|_| None).collect();
+        let mut pending = Vec::with_capacity(total);
+        // Snapshot decisions expire with the decision cache TTL.
+        let max_snapshot_age = i64::try_from(self.config.cache.ttl_minutes.max(1))
+            .ok()
+            .and_then(chrono::Duration::try_minutes)
+            .unwrap_or(chrono::Duration::MAX);
+        for (idx, spec) in package_specs.into_iter().enumerate() {
+            let reusable =
+                reusable_response(&prior_index, &spec, evaluation_time, max_snapshot_age).filter(
+                    |(response, _)| {
+                        !checks::has_expired_suppression(&response.evidence, evaluation_time)
+                    },
+                );
+            match reusable {
+                Some((response, evaluated_at)) => {
+                    self.log_decision(PackageDecision {
+                        context,
+                        registry: registry_key,
+                        package: spec.name.as_str(),
+                        requested: spec.version.as_deref(),
+                        allow: response.allow,
+                        risk: response.risk,
+                        reasons: response.reasons.clone(),
+                        evidence: response.evidence.clone(),
+                        checks_run: Vec::new(),
+                        metadata: Some(response.metadata.clone()),
+                        policy_snapshot_version: registry_policy.version,
+                        config_fingerprint: self.config_fingerprint.as_str(),
+                        policy_fingerprint: registry_policy.policy_fingerprint.as_str(),
+                        enabled_checks: registry_policy.enabled_checks.clone(),
+                        evaluation_time: evaluation_time_rfc3339.clone(),
+                        cached: true,
+                    })?;
+                    ordered[idx] = Some((spec, Ok(response), Some(evaluated_at)));
+                }
+                None => pending.push((idx, spec)),
             }
         }
+        let package_names = pending
+            .iter()
+            .map(|(_, spec)| spec.name.clone())
+            .collect::<Vec<_>>();
+
+        if !package_names.is_empty() {
+            self.prefetch_shared_lookups(plugin, &requirements, &package_names)
+                .await;
+        }
+        if requirements.needs_advisories {
+            self.prefetch_lockfile_advisories(plugin, &pending).await;
+        }
 
         // Evaluate packages concurrently with a bounded pool, preserving lockfile order.
-        let total = package_specs.len();
         let eval_concurrency = self.config.lockfile.eval_concurrency;
         let inter_batch_delay_ms = self.config.lockfile.inter_batch_delay_ms;
 
         tracing::info!(
             total_packages = total,
+            pending_packages = pending.len(),
             concurrency = eval_concurrency,
             inter_batch_delay_ms = inter_batch_delay_ms,
             "starting lockfile evaluation with configured concurrency settings"
         );
 
-        let mut queue = package_specs.into_iter().enumerate();
+        // Package metadata is memoized for this audit only, so duplicate names are fetched once.
+        let package_memo = Arc::new(PackageMemo::default());
+        let mut queue = pending.into_iter();
         let mut join_set: JoinSet<(usize, DependencySpec, anyhow::Result<ToolResponse>)> =
             JoinSet::new();
-        let mut ordered: Vec<Option<(DependencySpec, anyhow::Result<ToolResponse>)>> =
-            (0..total).map(|_| None).collect();
 
         // Seed the initial batch of concurrent tasks.
         for (idx, spec) in queue.by_ref().take(eval_concurrency) {
             let svc = self.clone();
             let ctx = context.to_string();
             let reg = registry_key.to_string();
+            let memo = Arc::clone(&package_memo);
             join_set.spawn(async move {
                 let result = svc
                     .evaluate_package_at_time(
@@ -188,7 +665,9 @@ impl SafePkgsService {
                         spec.version.as_deref(),
                         &reg,
                         &ctx,
+                        None,
                         evaluation_time,
+                        Some(&memo),
                     )
                     .await;
                 (idx, spec, result)
@@ -206,7 +685,7 @@ impl SafePkgsService {
                 return Err(result.unwrap_err());
             }
 
-            ordered[idx] = Some((spec, result));
+            ordered[idx] = Some((spec, result, None));
 
             // Keep the concurrency pool full as slots open up.
             if let Some((next_idx, next_spec)) = queue.next() {
@@ -219,6 +698,7 @@ impl SafePkgsService {
                 let svc = self.clone();
                 let ctx = context.to_string();
                 let reg = registry_key.to_string();
+                let memo = Arc::clone(&package_memo);
                 join_set.spawn(async move {
                     let result = svc
                         .evaluate_package_at_time(
@@ -226,7 +706,9 @@ impl SafePkgsService {
                             next_spec.version.as_deref(),
                             &reg,
                             &ctx,
+                            None,
                             evaluation_time,
+                            Some(&memo),
                         )
                         .await;
                     (next_idx, next_spec, result)
@@ -237,51 +719,109 @@ impl SafePkgsService {
         // Aggregate results in original lockfile order.
         let mut risk = Severity::Low;
         let mut denied = 0usize;
+        let mut skipped = 0usize;
         let mut packages = Vec::with_capacity(total);
+        let mut snapshot_entries = Vec::new();
 
         for item in ordered {
-            let Some((spec, result)) = item else { continue };
+            let Some((spec, result, reused_from)) = item else {
+                continue;
+            };
+            let reevaluated = options.incremental.then_some(reused_from.is_none());
+            let counts_toward_decision = !(self.config.lockfile.ignore_dev_dependencies
+                && spec.scope == DependencyScope::Dev);
             match result {
                 Ok(response) => {
-                    if response.risk > risk {
-                        risk = response.risk;
+                    if options.incremental {
+                        snapshot_entries.push(LockfileSnapshotEntry {
+                            name: spec.name.clone(),
+                            requested: spec.version.clone(),
+                            response: response.clone(),
+                            evaluated_at: reused_from.unwrap_or(evaluation_time),
+                        });
                     }
-                    if !response.allow {
-                        denied = denied.saturating_add(1);
+                    if counts_toward_decision {
+                        if response.risk > risk {
+                            risk = response.risk;
+                        }
+                        if !response.allow {
+                            denied = denied.saturating_add(1);
+                        }
                     }
 
                     packages.push(LockfilePackageResult {
                         name: spec.name,
                         requested: spec.version,
+                        scope: spec.scope,
                         allow: response.allow,
                         risk: response.risk,
+                        score: response.score,
                         reasons: response.reasons,
                         evidence: response.evidence,
                         dependency_ancestry: dependency_ancestry_for(&spec.dependency_paths),
+                        reevaluated,
+                        registry: None,
                     });
                 }
                 Err(err) => {
-                    denied = denied.saturating_add(1);
-                    risk = Severity::Critical;
-                    let reason = format!("package check failed: {err}");
+                    let mode = if is_transport_failure(&err) {
+                        self.config.lockfile.on_transport_error
+                    } else {
+                        TransportErrorMode::Deny
+                    };
+                    let (allow, package_risk, reason) = match mode {
+                        TransportErrorMode::Skip => {
+                            tracing::warn!(
+                                registry = registry_key,
+                                package = spec.name.as_str(),
+                                error = %err,
+                                "skipping package after registry transport failure"
+                            );
+                            skipped = skipped.saturating_add(1);
+                            continue;
+                        }
+                        TransportErrorMode::Warn => (
+                            true,
+                            Severity::Medium,
+                            format!("package check failed (reported as warning): {err}"),
+                        ),
+                        TransportErrorMode::Deny => (
+                            false,
+                            Severity::Critical,
+                            format!("package check failed: {err}"),
+                        ),
+                    };
+                    if allow {
+                        if counts_toward_decision {
+                            risk = risk.max(package_risk);
+                        }
+                    } else {
+                        denied = denied.saturating_add(1);
+                        risk = package_risk;
+                    }
                     packages.push(LockfilePackageResult {
                         name: spec.name.clone(),
                         requested: spec.version.clone(),
-                        allow: false,
-                        risk: Severity::Critical,
+                        scope: spec.scope,
+                        allow,
+                        risk: package_risk,
+                        score: checks::risk_score([package_risk]),
                         reasons: vec![reason.clone()],
                         evidence: vec![runtime_error_evidence(&reason)],
                         dependency_ancestry: dependency_ancestry_for(&spec.dependency_paths),
+                        reevaluated,
+                        registry: None,
                     });
                     self.log_decision(PackageDecision {
                         context,
                         registry: registry_key,
                         package: spec.name.as_str(),
                         requested: spec.version.as_deref(),
-                        allow: false,
-                        risk: Severity::Critical,
+                        allow,
+                        risk: package_risk,
                         reasons: vec![reason],
                         evidence: vec![runtime_error_evidence(&err.to_string())],
+                        checks_run: Vec::new(),
                         metadata: None,
                         policy_snapshot_version: registry_policy.version,
                         config_fingerprint: self.config_fingerprint.as_str(),
@@ -294,11 +834,21 @@ impl SafePkgsService {
             }
         }
 
+        // Failed evaluations are left out so they are retried on the next run.
+        if let Some(content_hash) = current_content_hash {
+            let snapshot = LockfileSnapshot {
+                content_hash,
+                entries: snapshot_entries,
+            };
+            self.cache
+                .set(&snapshot_key, &serde_json::to_string(&snapshot)?)?;
+        }
+
         // Counters are service-wide and cumulative (this service is reused by the
         // MCP server), so tag the snapshot with registry/context for disambiguation.
         let snap = self.metrics.snapshot();
         tracing::info!(
-            registry = registry,
+            registry = registry_key,
             context = context,
             evaluations = snap.evaluations,
             cache_hits = snap.cache_hits,
@@ -315,6 +865,10 @@ impl SafePkgsService {
             risk,
             total: packages.len(),
             denied,
+            skipped,
+            unreported_sources: 0,
+            evaluated: evaluated_count(&packages),
+            coverage_shortfall: None,
             packages,
             fingerprints: DecisionFingerprints {
                 config: self.config_fingerprint.clone(),
@@ -332,11 +886,164 @@ impl SafePkgsService {
         &self,
         path: &str,
         registry: &str,
+        options: LockfileAuditOptions,
     ) -> anyhow::Result<LockfileResponse> {
-        self.run_lockfile_audit(Some(path), registry, "cli_audit")
+        self.run_lockfile_audit(Some(path), registry, "cli_audit", options)
             .await
     }
 
+    /// Confirms the service can serve decisions without touching the network: the config
+    /// is already loaded, so this only round-trips a probe entry through the cache database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the cache database cannot be written or read back.
+    pub fn readiness(&self) -> anyhow::Result<ReadinessReport> {
+        let probe = Utc::now().to_rfc3339();
+        self.cache
+            .set_with_ttl(READINESS_PROBE_CACHE_KEY, &probe, Duration::from_secs(60))
+            .context("cache is not writable")?;
+        let stored = self
+            .cache
+            .get(READINESS_PROBE_CACHE_KEY)
+            .context("cache is not readable")?;
+        if stored.as_deref() != Some(probe.as_str()) {
+            anyhow::bail!("cache did not return the readiness probe it just stored");
+        }
+        Ok(ReadinessReport {
+            ready: true,
+            config_fingerprint: self.config_fingerprint.clone(),
+            registries: self
+                .registries
+                .package_registry_keys()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        })
+    }
+
+    /// Pre-populates the decision cache for every dependency in a dependency file.
+    ///
+    /// Uses the lockfile audit path, so `lockfile.eval_concurrency`,
+    /// `lockfile.inter_batch_delay_ms` and the global request cap all apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unsupported registry, an invalid or missing input path,
+    /// lockfile parse/resolve failures, or audit-log failures.
+    pub async fn warm_lockfile_path_with_registry(
+        &self,
+        path: &str,
+        registry: &str,
+    ) -> anyhow::Result<CacheWarmReport> {
+        let audit = self
+            .run_lockfile_audit(
+                Some(path),
+                registry,
+                "cli_warm",
+                LockfileAuditOptions::default(),
+            )
+            .await?;
+        let failed = audit
+            .packages
+            .iter()
+            .filter(|package| {
+                package
+                    .evidence
+                    .iter()
+                    .any(|item| item.id == PACKAGE_CHECK_FAILED_EVIDENCE_ID)
+            })
+            .count()
+            .saturating_add(audit.skipped);
+        let total = audit.total.saturating_add(audit.skipped);
+        Ok(CacheWarmReport {
+            registry: registry.to_string(),
+            total,
+            warmed: total.saturating_sub(failed),
+            failed,
+        })
+    }
+
+    /// Audits one dependency file under this service's config (`a`) and `candidate`'s (`b`),
+    /// reporting packages whose `allow` or `risk` differ and packages only one audit reports.
+    ///
+    /// Neither audit writes decisions to the cache or the audit log, since they are
+    /// comparisons rather than enforced decisions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when either audit fails (see
+    /// [`run_lockfile_audit`](Self::run_lockfile_audit)).
+    pub async fn policy_diff_lockfile_path(
+        &self,
+        candidate: &SafePkgsService,
+        path: &str,
+        registry: &str,
+    ) -> anyhow::Result<PolicyDiffReport> {
+        let options = LockfileAuditOptions::default();
+        let audit_a = self
+            .without_recording()
+            .run_lockfile_audit(Some(path), registry, "cli_policy_diff", options)
+            .await?;
+        let audit_b = candidate
+            .without_recording()
+            .run_lockfile_audit(Some(path), registry, "cli_policy_diff", options)
+            .await?;
+
+        let decision = |package: &LockfilePackageResult| PolicyDiffDecision {
+            allow: package.allow,
+            risk: package.risk,
+        };
+        let key =
+            |package: &LockfilePackageResult| (package.name.clone(), package.requested.clone());
+        let mut decisions_b = audit_b
+            .packages
+            .iter()
+            .map(|package| (key(package), package))
+            .collect::<BTreeMap<_, _>>();
+        let mut changed = Vec::new();
+        for package_a in &audit_a.packages {
+            let entry = match decisions_b.remove(&key(package_a)) {
+                Some(package_b) if decision(package_a) == decision(package_b) => continue,
+                Some(package_b) => (PolicyDiffChange::Changed, Some(decision(package_b))),
+                None => (PolicyDiffChange::Removed, None),
+            };
+            changed.push(PolicyDiffEntry {
+                name: package_a.name.clone(),
+                requested: package_a.requested.clone(),
+                change: entry.0,
+                a: Some(decision(package_a)),
+                b: entry.1,
+            });
+        }
+        changed.extend(
+            audit_b
+                .packages
+                .iter()
+                .filter(|package_b| decisions_b.contains_key(&key(package_b)))
+                .map(|package_b| PolicyDiffEntry {
+                    name: package_b.name.clone(),
+                    requested: package_b.requested.clone(),
+                    change: PolicyDiffChange::Added,
+                    a: None,
+                    b: Some(decision(package_b)),
+                }),
+        );
+
+        Ok(PolicyDiffReport {
+            total: audit_a.total,
+            changed,
+            a: PolicyDiffDecision {
+                allow: audit_a.allow,
+                risk: audit_a.risk,
+            },
+            b: PolicyDiffDecision {
+                allow: audit_b.allow,
+                risk: audit_b.risk,
+            },
+        })
+    }
+
     /// Runs a non-enforcing policy simulation ("what-if") for a dependency file.
     ///
     /// Reports the decision policy would make without ever blocking.
@@ -353,7 +1060,12 @@ impl SafePkgsService {
         registry: &str,
     ) -> anyhow::Result<SimulationReport> {
         let audit = self
-            .run_lockfile_audit(Some(path), registry, "cli_simulate")
+            .run_lockfile_audit(
+                Some(path),
+                registry,
+                "cli_simulate",
+                LockfileAuditOptions::default(),
+            )
             .await?;
         Ok(SimulationReport {
             enforced: false,
@@ -367,6 +1079,7 @@ impl SafePkgsService {
     /// # Errors
     ///
     /// Returns an error for invalid registries, cache failures, or check/runtime failures.
+    #[cfg(test)]
     pub async fn evaluate_package(
         &self,
         package_name: &str,
@@ -380,24 +1093,523 @@ impl SafePkgsService {
             requested_version,
             registry,
             context,
+            None,
             evaluation_time,
+            None,
         )
         .await
     }
 
+    /// Warms registry lookups shared by a batch of package checks, such as bulk weekly
+    /// download counts. Failures are logged; per-package lookups still run afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid registries.
+    pub async fn prefetch_package_batch(
+        &self,
+        registry: &str,
+        package_names: &[String],
+    ) -> anyhow::Result<()> {
+        let Some(plugin) = self.registries.package_plugin(registry) else {
+            return Err(invalid_registry_error(
+                "package",
+                registry,
+                self.registries.package_registry_keys(),
+            ));
+        };
+        if package_names.is_empty() {
+            return Ok(());
+        }
+        let requirements = checks::runtime_requirements_for_registry(
+            plugin.key(),
+            plugin.supported_checks(),
+            self.config.as_ref(),
+        );
+        self.prefetch_shared_lookups(plugin, &requirements, package_names)
+            .await;
+        Ok(())
+    }
+
+    /// Whether evaluating this request would reach the registry. Requests answered by the
+    /// decision cache (unless a suppression in the cached decision has expired), a denylist
+    /// name rule, or disabled enforcement need no batching.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid registries or cache failures.
+    pub fn needs_registry_lookups(
+        &self,
+        package_name: &str,
+        requested_version: Option<&str>,
+        registry: &str,
+        installed_version: Option<&str>,
+    ) -> anyhow::Result<bool> {
+        let Some(plugin) = self.registries.package_plugin(registry) else {
+            return Err(invalid_registry_error(
+                "package",
+                registry,
+                self.registries.package_registry_keys(),
+            ));
+        };
+        if checks::decided_without_registry(
+            package_name,
+            requested_version,
+            plugin.client().ecosystem(),
+            self.config.as_ref(),
+        ) {
+            return Ok(false);
+        }
+        let policy_snapshot = self.policy_snapshot_for_registry(plugin.key())?;
+        let cache_key = cache_key_for_package(
+            policy_snapshot.policy_fingerprint.as_str(),
+            &registry_cache_scope(plugin.as_ref()),
+            package_name,
+            requested_version,
+            installed_version,
+        );
+        let cached = self
+            .cache
+            .get(&cache_key)?
+            .and_then(|raw| serde_json::from_str::<ToolResponse>(&raw).ok());
+        Ok(cached.is_none_or(|response| {
+            checks::has_expired_suppression(&response.evidence, self.current_evaluation_time())
+        }))
+    }
+
+    /// Warms a batch of package checks: shared bulk lookups, every distinct package record
+    /// fetched concurrently into `memo`, then one advisory query for the resolved versions.
+    /// Lookup failures are logged; per-package evaluation retries them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid registries.
+    pub async fn prefetch_check_batch(
+        &self,
+        registry: &str,
+        requests: &[(String, Option<String>)],
+        memo: &Arc<PackageMemo>,
+    ) -> anyhow::Result<()> {
+        let names = requests
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        self.prefetch_package_batch(registry, &names).await?;
+        let Some(plugin) = self.registries.package_plugin(registry) else {
+            return Ok(());
+        };
+
+        let mut fetches = JoinSet::new();
+        for name in names {
+            let plugin = Arc::clone(plugin);
+            let memo = Arc::clone(memo);
+            fetches.spawn(async move {
+                let record = MemoizedRegistryClient::new(plugin.client(), &memo)
+                    .fetch_package(&name)
+                    .await;
+                (name, record)
+            });
+        }
+        let mut resolved = Vec::new();
+        while let Some(joined) = fetches.join_next().await {
+            let Ok((name, Ok(record))) = joined else {
+                continue;
+            };
+            for (_, requested) in requests.iter().filter(|(request, _)| *request == name) {
+                if let Some(version) = record.resolve_version(requested.as_deref()) {
+                    resolved.push((name.clone(), version.version.clone()));
+                }
+            }
+        }
+
+        let requirements = checks::runtime_requirements_for_registry(
+            plugin.key(),
+            plugin.supported_checks(),
+            self.config.as_ref(),
+        );
+        if requirements.needs_advisories
+            && !resolved.is_empty()
+            && let Err(err) = plugin
+                .client()
+                .prefetch_advisories(&resolved, self.config.lockfile.advisory_concurrency)
+                .await
+        {
+            tracing::warn!("advisory prefetch failed for {}: {err}", plugin.key());
+        }
+        Ok(())
+    }
+
+    /// Evaluates one package request as part of a batch, sharing fetched metadata via `memo`.
+    ///
+    /// `installed_version` is the version the project already has locked or installed, if known.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid registries, cache failures, or check/runtime failures.
+    pub async fn evaluate_package_in_batch(
+        &self,
+        package_name: &str,
+        requested_version: Option<&str>,
+        registry: &str,
+        context: &str,
+        installed_version: Option<&str>,
+        memo: &PackageMemo,
+    ) -> anyhow::Result<ToolResponse> {
+        let evaluation_time = self.current_evaluation_time();
+        self.evaluate_package_at_time(
+            package_name,
+            requested_version,
+            registry,
+            context,
+            installed_version,
+            evaluation_time,
+            Some(memo),
+        )
+        .await
+    }
+
+    /// Evaluates standalone package specs concurrently, preserving input order.
+    ///
+    /// Specs for the same registry share bulk lookups and fetched package metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid registries or when any package evaluation fails.
+    pub async fn evaluate_package_specs(
+        &self,
+        specs: Vec<PackageSpec>,
+        context: &str,
+    ) -> anyhow::Result<Vec<PackageCheckResult>> {
+        let mut names_by_registry = BTreeMap::<String, Vec<String>>::new();
+        for spec in &specs {
+            names_by_registry
+                .entry(spec.registry.to_ascii_lowercase())
+                .or_default()
+                .push(spec.name.clone());
+        }
+        let mut memos = BTreeMap::new();
+        for (registry, names) in &names_by_registry {
+            self.prefetch_package_batch(registry, names).await?;
+            memos.insert(registry.clone(), Arc::new(PackageMemo::default()));
+        }
+
+        let total = specs.len();
+        let permits = Arc::new(tokio::sync::Semaphore::new(
+            self.config.lockfile.eval_concurrency.max(1),
+        ));
+        let mut join_set = JoinSet::new();
+        for (idx, spec) in specs.into_iter().enumerate() {
+            let svc = self.clone();
+            let ctx = context.to_string();
+            let memo = memos
+                .get(&spec.registry.to_ascii_lowercase())
+                .cloned()
+                .unwrap_or_default();
+            let permits = Arc::clone(&permits);
+            join_set.spawn(async move {
+                let _permit = permits.acquire_owned().await?;
+                let response = svc
+                    .evaluate_package_in_batch(
+                        &spec.name,
+                        spec.version.as_deref(),
+                        &spec.registry,
+                        &ctx,
+                        None,
+                        &memo,
+                    )
+                    .await
+                    .with_context(|| format!("failed to check package '{}'", spec.name))?;
+                anyhow::Ok((
+                    idx,
+                    PackageCheckResult {
+                        package: spec.name,
+                        requested: spec.version,
+                        registry: spec.registry.to_ascii_lowercase(),
+                        response,
+                    },
+                ))
+            });
+        }
+
+        let mut ordered = (0..total).map(|_| None).collect::<Vec<_>>();
+        while let Some(joined) = join_set.join_next().await {
+            let (idx, result) = joined.context("package check task failed unexpectedly")??;
+            ordered[idx] = Some(result);
+        }
+        Ok(ordered.into_iter().flatten().collect())
+    }
+
+    /// Evaluates standalone package specs and aggregates them into one decision, like a
+    /// lockfile audit without a lockfile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid registries or when any package evaluation fails.
+    pub async fn evaluate_package_list(
+        &self,
+        specs: Vec<PackageSpec>,
+        context: &str,
+    ) -> anyhow::Result<PackageListResponse> {
+        let packages = self.evaluate_package_specs(specs, context).await?;
+        let risk = packages
+            .iter()
+            .map(|package| package.response.risk)
+            .max()
+            .unwrap_or(Severity::Low);
+        let denied = packages
+            .iter()
+            .filter(|package| !package.response.allow)
+            .count();
+        Ok(PackageListResponse {
+            allow: denied == 0,
+            risk,
+            total: packages.len(),
+            denied,
+            packages,
+        })
+    }
+
+    /// Evaluates candidate versions within `range`, newest first, and returns the newest
+    /// one that passes policy along with the reasons newer candidates were denied.
+    ///
+    /// Without a range (or with `latest`) every stable version is a candidate. At most
+    /// [`MAX_SAFEST_CANDIDATES`] versions are evaluated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid registries, unparseable ranges, registry failures,
+    /// or check/runtime failures.
+    pub async fn resolve_safest_version(
+        &self,
+        package_name: &str,
+        range: Option<&str>,
+        registry: &str,
+        context: &str,
+        memo: &PackageMemo,
+    ) -> anyhow::Result<SafestVersionResponse> {
+        let Some(plugin) = self.registries.package_plugin(registry) else {
+            return Err(invalid_registry_error(
+                "package",
+                registry,
+                self.registries.package_registry_keys(),
+            ));
+        };
+        let scheme = plugin.client().ecosystem().version_scheme();
+        let range = range
+            .map(str::trim)
+            .filter(|raw| !raw.is_empty() && !raw.eq_ignore_ascii_case("latest"));
+        let parsed_range = match range {
+            Some(raw) => Some(
+                scheme
+                    .parse_range(raw)
+                    .ok_or_else(|| anyhow!("invalid version range '{raw}' for {registry}"))?,
+            ),
+            None => None,
+        };
+
+        let client = MemoizedRegistryClient::new(plugin.client(), memo);
+        let package = match client.fetch_package(package_name).await {
+            Ok(package) => package,
+            Err(RegistryError::NotFound { .. }) => {
+                let decision = self
+                    .evaluate_package_in_batch(package_name, None, registry, context, None, memo)
+                    .await?;
+                return Ok(SafestVersionResponse {
+                    allow: false,
+                    recommended_version: None,
+                    decision: Some(decision),
+                    rejected: Vec::new(),
+                    note: Some(format!("package '{package_name}' was not found")),
+                });
+            }
+            Err(err) => {
+                self.metrics.record_registry_error();
+                return Err(err.into());
+            }
+        };
+
+        let mut candidates = package
+            .versions
+            .keys()
+            .filter_map(|raw| {
+                let parsed = scheme.parse(raw)?;
+                let in_range = match &parsed_range {
+                    Some(range) => range.matches(raw),
+                    None => !parsed.is_prerelease(),
+                };
+                in_range.then(|| (parsed, raw.clone()))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|(left, _), (right, _)| {
+            right.partial_cmp(left).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let total_candidates = candidates.len();
+
+        let mut rejected = Vec::new();
+        for (_, version) in candidates.into_iter().take(MAX_SAFEST_CANDIDATES) {
+            let response = self
+                .evaluate_package_in_batch(
+                    package_name,
+                    Some(&version),
+                    registry,
+                    context,
+                    None,
+                    memo,
+                )
+                .await?;
+            if response.allow {
+                return Ok(SafestVersionResponse {
+                    allow: true,
+                    recommended_version: Some(version),
+                    decision: Some(response),
+                    rejected,
+                    note: None,
+                });
+            }
+            rejected.push(RejectedVersion {
+                version,
+                risk: response.risk,
+                reasons: response.reasons,
+            });
+        }
+
+        let note = if total_candidates == 0 {
+            match range {
+                Some(raw) => format!("no published versions match '{raw}'"),
+                None => "no stable published versions".to_string(),
+            }
+        } else if total_candidates > MAX_SAFEST_CANDIDATES {
+            format!(
+                "none of the newest {MAX_SAFEST_CANDIDATES} of {total_candidates} candidate versions passed policy"
+            )
+        } else {
+            "no candidate version passed policy".to_string()
+        };
+        Ok(SafestVersionResponse {
+            allow: false,
+            recommended_version: None,
+            decision: None,
+            rejected,
+            note: Some(note),
+        })
+    }
+
+    /// Inspects a package tarball and runs the checks that only need its manifest.
+    ///
+    /// `registry` selects the archive format; when `None` it is inferred from the source
+    /// extension or the single manifest inside the archive.
+    ///
+    /// Registry-backed signals (downloads, advisories, publish history) and dependency
+    /// confusion are skipped, and decisions are not cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the tarball cannot be read or inspected, or the audit log fails.
+    pub async fn check_tarball(
+        &self,
+        source: &str,
+        registry: Option<&str>,
+        context: &str,
+    ) -> anyhow::Result<TarballCheckResult> {
+        let manifest = TarballInspector::new(self.config.max_response_bytes)
+            .with_request_limits(self.registries.request_limits().clone())
+            .inspect_source(source, registry)
+            .await?;
+        let Some(plugin) = self.registries.package_plugin(manifest.registry) else {
+            return Err(invalid_registry_error(
+                "package",
+                manifest.registry,
+                self.registries.package_registry_keys(),
+            ));
+        };
+        let registry_key = plugin.key();
+        let policy_snapshot = self.policy_snapshot_for_registry(registry_key)?;
+        let evaluation_time = self.current_evaluation_time();
+        let supported_checks = plugin
+            .supported_checks()
+            .iter()
+            .copied()
+            .filter(|check| TARBALL_CHECKS.contains(check))
+            .collect::<Vec<_>>();
+        // The tarball is not looked up publicly, so an internal name cannot be a public shadow.
+        let mut config = SafePkgsConfig::clone(&self.config);
+        config.dependency_confusion = Default::default();
+
+        let report = checks::run_all_checks_at_time(
+            &manifest.name,
+            Some(&manifest.version),
+            registry_key,
+            &supported_checks,
+            &TarballRegistryClient::new(&manifest),
+            &config,
+            None,
+            evaluation_time,
+        )
+        .await?;
+        let response = ToolResponse {
+            allow: report.allow,
+            risk: report.risk,
+            score: report.score,
+            reasons: report.reasons,
+            evidence: report.evidence,
+            metadata: report.metadata,
+            fingerprints: DecisionFingerprints {
+                config: self.config_fingerprint.clone(),
+                policy: policy_snapshot.policy_fingerprint.clone(),
+            },
+        };
+        let response = decision_hook::apply(self.config.as_ref(), response).await;
+
+        self.log_decision(PackageDecision {
+            context,
+            registry: registry_key,
+            package: &manifest.name,
+            requested: Some(&manifest.version),
+            allow: response.allow,
+            risk: response.risk,
+            reasons: response.reasons.clone(),
+            evidence: response.evidence.clone(),
+            checks_run: report.checks_run,
+            metadata: Some(response.metadata.clone()),
+            policy_snapshot_version: policy_snapshot.version,
+            config_fingerprint: self.config_fingerprint.as_str(),
+            policy_fingerprint: policy_snapshot.policy_fingerprint.as_str(),
+            enabled_checks: policy_snapshot.enabled_checks.clone(),
+            evaluation_time: evaluation_time.to_rfc3339(),
+            cached: false,
+        })?;
+
+        Ok(TarballCheckResult {
+            source: source.to_string(),
+            package: manifest.name,
+            version: manifest.version,
+            registry: registry_key.to_string(),
+            license: manifest.license,
+            response,
+        })
+    }
+
     /// Returns a point-in-time snapshot of collected runtime metrics.
     #[cfg(test)]
     fn metrics_snapshot(&self) -> crate::metrics::MetricsSnapshot {
         self.metrics.snapshot()
     }
 
+    #[allow(
+        clippy::too_many_arguments,
+        reason = "mirrors the check execution context fields"
+    )]
     async fn evaluate_package_at_time(
         &self,
         package_name: &str,
         requested_version: Option<&str>,
         registry: &str,
         context: &str,
+        installed_version: Option<&str>,
         evaluation_time: DateTime<Utc>,
+        package_memo: Option<&PackageMemo>,
     ) -> anyhow::Result<ToolResponse> {
         let started = std::time::Instant::now();
         let result = self
@@ -406,20 +1618,28 @@ impl SafePkgsService {
                 requested_version,
                 registry,
                 context,
+                installed_version,
                 evaluation_time,
+                package_memo,
             )
             .await;
         self.metrics.record_evaluation(started.elapsed());
         result
     }
 
+    #[allow(
+        clippy::too_many_arguments,
+        reason = "mirrors the check execution context fields"
+    )]
     async fn evaluate_package_inner(
         &self,
         package_name: &str,
         requested_version: Option<&str>,
         registry: &str,
         context: &str,
+        installed_version: Option<&str>,
         evaluation_time: DateTime<Utc>,
+        package_memo: Option<&PackageMemo>,
     ) -> anyhow::Result<ToolResponse> {
         let Some(plugin) = self.registries.package_plugin(registry) else {
             return Err(invalid_registry_error(
@@ -432,16 +1652,19 @@ impl SafePkgsService {
         let policy_snapshot = self.policy_snapshot_for_registry(registry_key)?;
         let cache_key = cache_key_for_package(
             policy_snapshot.policy_fingerprint.as_str(),
-            registry_key,
+            &registry_cache_scope(plugin.as_ref()),
             package_name,
             requested_version,
+            installed_version,
         );
         let evaluation_time_rfc3339 = evaluation_time.to_rfc3339();
 
         if let Some(cached) = self.cache.get(&cache_key)?
             && let Ok(response) = serde_json::from_str::<ToolResponse>(&cached)
+            && !checks::has_expired_suppression(&response.evidence, evaluation_time)
         {
             self.metrics.record_cache_hit();
+            let response = decision_hook::apply(self.config.as_ref(), response).await;
             tracing::debug!(
                 package = package_name,
                 version = requested_version,
@@ -457,6 +1680,7 @@ impl SafePkgsService {
                 risk: response.risk,
                 reasons: response.reasons.clone(),
                 evidence: response.evidence.clone(),
+                checks_run: Vec::new(),
                 metadata: Some(response.metadata.clone()),
                 policy_snapshot_version: policy_snapshot.version,
                 config_fingerprint: self.config_fingerprint.as_str(),
@@ -470,13 +1694,29 @@ impl SafePkgsService {
 
         self.metrics.record_cache_miss();
 
+        let persisted_client = self.persisted_client(plugin.as_ref());
+        let downloads_client = self.downloads_client(plugin.as_ref(), &persisted_client);
+        let history_client = DownloadHistoryClient::new(
+            &downloads_client,
+            self.download_history.as_deref(),
+            &registry_cache_scope(plugin.as_ref()),
+        );
+        let memoized_client;
+        let registry_client = match package_memo {
+            Some(memo) => {
+                memoized_client = MemoizedRegistryClient::new(&history_client, memo);
+                &memoized_client as &dyn RegistryClient
+            }
+            None => &history_client as &dyn RegistryClient,
+        };
         let report = match checks::run_all_checks_at_time(
             package_name,
             requested_version,
             registry_key,
             plugin.supported_checks(),
-            plugin.client(),
+            registry_client,
             self.config.as_ref(),
+            installed_version,
             evaluation_time,
         )
         .await
@@ -488,9 +1728,10 @@ impl SafePkgsService {
             }
         };
 
-        let response = ToolResponse {
+        let mut response = ToolResponse {
             allow: report.allow,
             risk: report.risk,
+            score: report.score,
             reasons: report.reasons,
             evidence: report.evidence,
             metadata: report.metadata,
@@ -500,8 +1741,16 @@ impl SafePkgsService {
             },
         };
 
-        let encoded = serde_json::to_string(&response)?;
-        self.cache.set(&cache_key, &encoded)?;
+        // Cached before the decision hook runs, so every evaluation consults the hook afresh
+        // and a transient hook failure is never served from cache. Fetch latency describes
+        // this evaluation's registry fetch only, so cache hits report none.
+        if self.record_decisions {
+            let fetch_ms = response.metadata.fetch_ms.take();
+            self.cache
+                .set(&cache_key, &serde_json::to_string(&response)?)?;
+            response.metadata.fetch_ms = fetch_ms;
+        }
+        let response = decision_hook::apply(self.config.as_ref(), response).await;
 
         self.log_decision(PackageDecision {
             context,
@@ -512,6 +1761,7 @@ impl SafePkgsService {
             risk: response.risk,
             reasons: response.reasons.clone(),
             evidence: response.evidence.clone(),
+            checks_run: report.checks_run,
             metadata: Some(response.metadata.clone()),
             policy_snapshot_version: policy_snapshot.version,
             config_fingerprint: self.config_fingerprint.as_str(),
@@ -534,11 +1784,91 @@ impl SafePkgsService {
             .ok_or_else(|| anyhow!("missing policy snapshot for registry '{registry_key}'"))
     }
 
+    /// Prefetches bulk lookups the enabled checks will need for `package_names`.
+    async fn prefetch_shared_lookups(
+        &self,
+        plugin: &Arc<dyn RegistryPlugin>,
+        requirements: &checks::CheckRuntimeRequirements,
+        package_names: &[String],
+    ) {
+        let registry_key = plugin.key();
+        let client = self.persisted_client(plugin.as_ref());
+        if requirements.needs_weekly_downloads
+            && let Err(err) = self
+                .downloads_client(plugin.as_ref(), &client)
+                .prefetch_weekly_downloads(package_names)
+                .await
+        {
+            tracing::warn!("registry prefetch failed for {registry_key}: {err}");
+        }
+
+        if requirements.needs_popular_package_names
+            && let Err(err) = client.prefetch_popular_package_names().await
+        {
+            tracing::warn!("popular package prefetch failed for {registry_key}: {err}");
+        }
+    }
+
+    /// Queries advisories for every pinned pending package through the OSV batch endpoint,
+    /// so evaluations do not issue one advisory request each.
+    async fn prefetch_lockfile_advisories(
+        &self,
+        plugin: &Arc<dyn RegistryPlugin>,
+        pending: &[(usize, DependencySpec)],
+    ) {
+        let packages = pending
+            .iter()
+            .filter_map(|(_, spec)| {
+                spec.version
+                    .as_ref()
+                    .map(|version| (spec.name.clone(), version.clone()))
+            })
+            .collect::<Vec<_>>();
+        if packages.is_empty() {
+            return;
+        }
+        if let Err(err) = plugin
+            .client()
+            .prefetch_advisories(&packages, self.config.lockfile.advisory_concurrency)
+            .await
+        {
+            tracing::warn!("advisory prefetch failed for {}: {err}", plugin.key());
+        }
+    }
+
+    /// The plugin's client, with popular names read through the persisted store.
+    fn persisted_client<'a>(
+        &'a self,
+        plugin: &'a dyn RegistryPlugin,
+    ) -> PersistedPopularNamesClient<'a> {
+        PersistedPopularNamesClient::new(
+            plugin.client(),
+            self.popular_names.as_deref(),
+            &popular_names_scope(plugin, self.config.as_ref()),
+        )
+    }
+
+    /// Wraps `inner` so weekly download counts are shared through the cache database.
+    fn downloads_client<'a>(
+        &'a self,
+        plugin: &dyn RegistryPlugin,
+        inner: &'a dyn RegistryClient,
+    ) -> PersistedDownloadsClient<'a> {
+        PersistedDownloadsClient::new(
+            inner,
+            self.download_counts.as_deref(),
+            &registry_cache_scope(plugin),
+        )
+    }
+
     fn current_evaluation_time(&self) -> DateTime<Utc> {
         self.evaluation_time_override.unwrap_or_else(Utc::now)
     }
 
     fn log_decision(&self, decision: PackageDecision<'_>) -> anyhow::Result<()> {
+        if !self.record_decisions {
+            return Ok(());
+        }
         let record = AuditRecord::package_decision(decision);
         self.audit_logger
             .log(record)
@@ -546,23 +1876,53 @@ impl SafePkgsService {
     }
 }
 
+/// Registry segment for cache keys: the registry key plus a short hash of the client's
+/// effective base URL, so decisions from a private mirror never answer for the public
+/// registry (or vice versa).
+fn registry_cache_scope(plugin: &dyn RegistryPlugin) -> String {
+    match plugin.client().base_url() {
+        Some(base_url) => {
+            let digest = sha2::Sha256::digest(base_url.trim_end_matches('/').as_bytes());
+            let hex = format!("{digest:x}");
+            format!("{}+{}", plugin.key(), &hex[..12])
+        }
+        None => plugin.key().to_string(),
+    }
+}
+
+/// Popular-names store segment: the registry cache scope, plus a short hash of a
+/// configured popular index URL so switching sources does not reuse the old list.
+fn popular_names_scope(plugin: &dyn RegistryPlugin, config: &SafePkgsConfig) -> String {
+    let scope = registry_cache_scope(plugin);
+    match popular_index_url(plugin.key(), config) {
+        Some(url) => {
+            let digest = sha2::Sha256::digest(url.trim().as_bytes());
+            let hex = format!("{digest:x}");
+            format!("{scope}+{}", &hex[..12])
+        }
+        None => scope,
+    }
+}
+
 fn cache_key_for_package(
     policy_fingerprint: &str,
     registry: &str,
     package_name: &str,
     requested_version: Option<&str>,
+    installed_version: Option<&str>,
 ) -> String {
     // Policy fingerprint is part of the key so policy changes naturally cold-miss
     // old cache entries and rebuild them under the new policy scope.
     let version = requested_version.unwrap_or("latest");
-    format!(
+    let key = format!(
         "check_package:{}:{}:{}@{}",
         policy_fingerprint, registry, package_name, version
-    )
-}
-
-fn compute_config_fingerprint(config: &SafePkgsConfig) -> anyhow::Result<String> {
-    crate::policy_snapshot::compute_config_fingerprint(config)
+    );
+    // The installed version feeds checks, so decisions made against one are kept apart.
+    match installed_version {
+        Some(installed) => format!("{key}~{installed}"),
+        None => key,
+    }
 }
 
 fn build_policy_snapshots_by_registry(
@@ -614,13 +1974,23 @@ fn is_audit_log_failure(err: &anyhow::Error) -> bool {
     err.downcast_ref::<AuditLogError>().is_some()
 }
 
+/// Returns true when a package evaluation failed on a registry transport error.
+fn is_transport_failure(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<RegistryError>(),
+        Some(RegistryError::Transport { .. })
+    )
+}
+
 fn runtime_error_evidence(message: &str) -> Evidence {
     Evidence {
         kind: EvidenceKind::Runtime,
-        id: "lockfile.package_check_failed".to_string(),
+        id: PACKAGE_CHECK_FAILED_EVIDENCE_ID.to_string(),
+        code: PACKAGE_CHECK_FAILED_EVIDENCE_ID.to_string(),
         severity: Severity::Critical,
         message: message.to_string(),
         facts: std::collections::BTreeMap::new(),
+        remediation: None,
     }
 }
 
@@ -644,3 +2014,185 @@ fn dependency_ancestry_for(dependency_paths: &[Vec<String>]) -> Option<Dependenc
 #[cfg(test)]
 #[path = "tests/service.rs"]
 mod tests;
+
+fn unsupported_component_result(
+    name: String,
+    version: Option<String>,
+    reason: &str,
+) -> LockfilePackageResult {
+    let reason = format!("SBOM component not checked: {reason}");
+    LockfilePackageResult {
+        name,
+        requested: version,
+        scope: DependencyScope::Prod,
+        allow: false,
+        risk: Severity::High,
+        score: checks::risk_score([Severity::High]),
+        reasons: vec![reason.clone()],
+        evidence: vec![Evidence {
+            kind: EvidenceKind::Runtime,
+            id: "sbom.unsupported_component".to_string(),
+            code: "sbom.unsupported_component".to_string(),
+            severity: Severity::High,
+            message: reason,
+            facts: std::collections::BTreeMap::new(),
+            remediation: None,
+        }],
+        dependency_ancestry: None,
+        reevaluated: None,
+        registry: None,
+    }
+}
+
+/// Adds a `low` finding to packages whose lockfile version is outside the manifest range.
+///
+/// The decision is left unchanged: drift is a stale or tampered lockfile signal, not a
+/// package risk.
+fn apply_manifest_drift(response: &mut LockfileResponse, drift: Vec<ManifestDrift>) {
+    for drift in drift {
+        let Some(package) = response.packages.iter_mut().find(|package| {
+            package.name == drift.name
+                && package.requested.as_deref() == Some(drift.locked.as_str())
+        }) else {
+            continue;
+        };
+        let reason = format!(
+            "{} is locked at {} outside the manifest range {}; the lockfile may be stale or tampered with",
+            drift.name, drift.locked, drift.declared
+        );
+        package.risk = package.risk.max(Severity::Low);
+        package.score = package
+            .score
+            .saturating_add(checks::risk_score([Severity::Low]))
+            .min(100);
+        package.reasons.push(reason.clone());
+        package.evidence.push(Evidence {
+            kind: EvidenceKind::Runtime,
+            id: "lockfile.manifest_drift".to_string(),
+            code: "lockfile.manifest_drift".to_string(),
+            severity: Severity::Low,
+            message: reason,
+            facts: std::collections::BTreeMap::from([
+                ("locked".to_string(), serde_json::Value::from(drift.locked)),
+                (
+                    "declared".to_string(),
+                    serde_json::Value::from(drift.declared),
+                ),
+            ]),
+            remediation: None,
+        });
+        response.risk = response.risk.max(Severity::Low);
+    }
+}
+
+/// Reports a dependency from a non-registry source as allowed but not evaluated.
+fn unsupported_source_result(spec: DependencySpec) -> Option<LockfilePackageResult> {
+    let source = spec.unsupported_source?;
+    let reason = format!(
+        "{} comes from a non-registry source ({}) and was not evaluated",
+        spec.name,
+        source.as_str()
+    );
+    Some(LockfilePackageResult {
+        dependency_ancestry: dependency_ancestry_for(&spec.dependency_paths),
+        name: spec.name,
+        requested: spec.version,
+        scope: spec.scope,
+        allow: true,
+        risk: Severity::Low,
+        score: checks::risk_score([Severity::Low]),
+        reasons: vec![reason.clone()],
+        evidence: vec![Evidence {
+            kind: EvidenceKind::Runtime,
+            id: "lockfile.unsupported_source".to_string(),
+            code: "lockfile.unsupported_source".to_string(),
+            severity: Severity::Low,
+            message: reason,
+            facts: std::collections::BTreeMap::from([(
+                "source".to_string(),
+                serde_json::Value::from(source.as_str()),
+            )]),
+            remediation: None,
+        }],
+        reevaluated: None,
+        registry: None,
+    })
+}
+
+/// Layers config-file registry client settings under startup overrides.
+fn registry_overrides_from_config(
+    overrides: RegistryClientOverrides,
+    config: &SafePkgsConfig,
+) -> RegistryClientOverrides {
+    config.typosquat.popular_index_urls.keys().fold(
+        overrides
+            .with_request_limits(RequestLimits::new(
+                config.max_in_flight_requests,
+                config.max_response_bytes,
+            ))
+            .with_popular_names_file(config.typosquat.popular_names_file.clone())
+            .with_config_file("npm", config.npmrc.clone())
+            .with_abbreviated_metadata("npm", !needs_full_metadata("npm", config)),
+        |overrides, registry| {
+            overrides.with_popular_index_url(registry, popular_index_url(registry, config))
+        },
+    )
+}
+
+/// Whether anything enabled for `registry` reads metadata its abbreviated document omits.
+fn needs_full_metadata(registry: &str, config: &SafePkgsConfig) -> bool {
+    checks::runtime_requirements_for_registry(registry, &supported_checks_for(registry), config)
+        .needs_full_metadata
+}
+
+/// Static popular-names list URL for `registry`: a non-empty
+/// `SAFE_PKGS_<REGISTRY>_POPULAR_INDEX_URL` env var overrides `typosquat.popular_index_urls`.
+fn popular_index_url(registry: &str, config: &SafePkgsConfig) -> Option<String> {
+    let env_key = format!(
+        "SAFE_PKGS_{}_POPULAR_INDEX_URL",
+        registry.to_ascii_uppercase()
+    );
+    std::env::var(env_key)
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .or_else(|| config.typosquat.popular_index_urls.get(registry).cloned())
+}
+
+/// Counts fully evaluated packages: those without `NOT_FULLY_EVALUATED_EVIDENCE_IDS` evidence.
+fn evaluated_count(packages: &[LockfilePackageResult]) -> usize {
+    packages
+        .iter()
+        .filter(|package| {
+            package
+                .evidence
+                .iter()
+                .all(|item| !NOT_FULLY_EVALUATED_EVIDENCE_IDS.contains(&item.id.as_str()))
+        })
+        .count()
+}
+
+/// Drops allowed packages below `Medium` risk from the per-package list.
+///
+/// Aggregate fields are left untouched so they still describe the whole audit.
+fn retain_risky_packages(response: &mut LockfileResponse) {
+    response
+        .packages
+        .retain(|package| !package.allow || package.risk >= Severity::Medium);
+}
+
+/// Policy fingerprint for a multi-registry audit.
+///
+/// A single registry keeps its own fingerprint; several are hashed together in key order.
+fn combined_policy_fingerprint(fingerprints: &[(&str, String)]) -> String {
+    match fingerprints {
+        [(_, single)] => single.clone(),
+        _ => {
+            let joined = fingerprints
+                .iter()
+                .map(|(registry, fingerprint)| format!("{registry}={fingerprint}"))
+                .collect::<Vec<_>>()
+                .join(";");
+            format!("{:x}", sha2::Sha256::digest(joined.as_bytes()))
+        }
+    }
+}
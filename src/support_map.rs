@@ -27,7 +27,7 @@ pub fn render_support_map(use_color: bool) -> String {
         .max()
         .unwrap_or("check".len())
         .max("check".len());
-    let flags_col_width = "flags".len().max(2);
+    let flags_col_width = "flags".len().max(3);
     let registry_col_widths = registry_keys
         .iter()
         .map(|key| key.len().max(3))
@@ -41,7 +41,7 @@ pub fn render_support_map(use_color: bool) -> String {
         registry_keys.len()
     ));
     lines.push(format!(
-        "legend: flags [W,A] where W=needs weekly downloads, A=needs advisories; {}=supported, {}=unsupported",
+        "legend: flags [W,A,S] where W=needs weekly downloads, A=needs advisories, S=needs package size; {}=supported, {}=unsupported",
         style("yes", "32", use_color),
         style("no", "31", use_color),
     ));
@@ -149,7 +149,7 @@ fn render_flags(descriptor: CheckDescriptor, width: usize, use_color: bool) -> S
     let mut result = String::new();
     for ch in raw.chars() {
         match ch {
-            'W' | 'A' => result.push_str(style(&ch.to_string(), "33", use_color).as_str()),
+            'W' | 'A' | 'S' => result.push_str(style(&ch.to_string(), "33", use_color).as_str()),
             '-' => result.push_str(style("-", "2", use_color).as_str()),
             _ => result.push(ch),
         }
@@ -159,7 +159,7 @@ fn render_flags(descriptor: CheckDescriptor, width: usize, use_color: bool) -> S
 
 fn flags_for_check(descriptor: CheckDescriptor) -> String {
     format!(
-        "{}{}",
+        "{}{}{}",
         if descriptor.needs_weekly_downloads {
             "W"
         } else {
@@ -169,7 +169,8 @@ fn flags_for_check(descriptor: CheckDescriptor) -> String {
             "A"
         } else {
             "-"
-        }
+        },
+        if descriptor.needs_size { "S" } else { "-" }
     )
 }
 
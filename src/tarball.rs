@@ -0,0 +1,442 @@
+//! Offline inspection of package tarballs for `safe-pkgs check-tarball`.
+//!
+//! A [`TarballInspector`] reads an npm `.tgz`, a cargo `.crate`, or a PyPI sdist from disk or a
+//! URL, extracts its manifest, and exposes it as a single-version [`PackageRecord`] so the
+//! metadata-only checks can run without a registry round-trip.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Component, Path};
+
+use anyhow::{Context, anyhow, bail};
+use async_trait::async_trait;
+use flate2::read::GzDecoder;
+use safe_pkgs_core::{
+    CheckId, PackageRecord, PackageVersion, RegistryClient, RegistryEcosystem, RegistryError,
+    RequestLimits,
+};
+use safe_pkgs_registry_http::{RetryPolicy, build_http_client, send_with_retry};
+
+/// Checks that only need the manifest shipped inside the tarball.
+pub const TARBALL_CHECKS: &[CheckId] = &["install_script", "suspicious_name", "binary_only"];
+
+/// Largest manifest file read from a tarball, in bytes.
+const MAX_MANIFEST_BYTES: u64 = 1024 * 1024;
+
+/// npm lifecycle hooks that run on install.
+const INSTALL_HOOKS: [&str; 3] = ["preinstall", "install", "postinstall"];
+
+/// Manifest file for each supported registry, as `(registry, file name)`.
+const MANIFEST_FILES: [(&str, &str); 3] = [
+    ("npm", "package.json"),
+    ("cargo", "Cargo.toml"),
+    ("pypi", "PKG-INFO"),
+];
+
+/// Package metadata extracted from a tarball manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TarballManifest {
+    /// Registry key inferred from the manifest format (`npm`, `cargo`, or `pypi`).
+    pub registry: &'static str,
+    pub ecosystem: RegistryEcosystem,
+    pub name: String,
+    pub version: String,
+    /// License expression or name declared by the manifest.
+    pub license: Option<String>,
+    /// npm install hooks, formatted as `<hook>: <command>`.
+    pub install_scripts: Vec<String>,
+    /// Executable paths declared by npm's `bin` field.
+    pub bin_targets: Vec<String>,
+    /// Regular files in the archive.
+    pub file_count: u64,
+    /// Total size of the regular files in the archive, in bytes.
+    pub unpacked_size: u64,
+}
+
+impl TarballManifest {
+    /// Builds a registry record holding only the inspected version.
+    pub fn package_record(&self) -> PackageRecord {
+        let version = PackageVersion {
+            version: self.version.clone(),
+            published: None,
+            deprecated: false,
+            install_scripts: self.install_scripts.clone(),
+            unpacked_size: Some(self.unpacked_size),
+            file_count: Some(self.file_count),
+            maintainer_emails: Vec::new(),
+            bin_targets: self.bin_targets.clone(),
+            license: self.license.clone(),
+            repository_url: None,
+            published_estimated: false,
+        };
+        PackageRecord {
+            name: self.name.clone(),
+            latest: self.version.clone(),
+            publishers: Vec::new(),
+            versions: BTreeMap::from([(self.version.clone(), version)]),
+        }
+    }
+}
+
+/// Reads package tarballs and extracts their manifests.
+#[derive(Debug, Clone)]
+pub struct TarballInspector {
+    max_bytes: usize,
+    request_limits: RequestLimits,
+}
+
+impl TarballInspector {
+    /// Creates an inspector that rejects archives larger than `max_bytes`.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes: max_bytes.max(1),
+            request_limits: RequestLimits::default(),
+        }
+    }
+
+    /// Counts tarball downloads against `limits`' in-flight requests.
+    pub fn with_request_limits(mut self, limits: RequestLimits) -> Self {
+        self.request_limits = limits;
+        self
+    }
+
+    /// Reads the tarball at `source` (a local path or an `http(s)://` URL) and inspects it.
+    ///
+    /// The format comes from `registry` when given, otherwise from the `.tgz` (npm) or
+    /// `.crate` (cargo) extension of `source`; see [`inspect`](Self::inspect).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the tarball cannot be read, is too large, or has no
+    /// recognizable manifest.
+    pub async fn inspect_source(
+        &self,
+        source: &str,
+        registry: Option<&str>,
+    ) -> anyhow::Result<TarballManifest> {
+        let registry = registry.or_else(|| registry_from_extension(source));
+        let bytes = if source.starts_with("http://") || source.starts_with("https://") {
+            self.download(source).await?
+        } else {
+            self.read_file(Path::new(source))?
+        };
+        self.inspect(&bytes, registry)
+            .with_context(|| format!("failed to inspect tarball {source}"))
+    }
+
+    /// Extracts the manifest from gzip-compressed or plain tar bytes.
+    ///
+    /// The manifest is the top-level `package.json` (npm), `Cargo.toml` (cargo crate), or
+    /// `PKG-INFO` (PyPI sdist) inside the archive's single root directory. `registry` picks
+    /// which one is read; without it the archive must contain exactly one of them. Install
+    /// hooks in a top-level `package.json` are always reported, whatever the format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for malformed archives, when the expected manifest is missing, or
+    /// when no format is given and the archive carries manifests for several formats.
+    pub fn inspect(&self, bytes: &[u8], registry: Option<&str>) -> anyhow::Result<TarballManifest> {
+        let expected = registry
+            .map(|registry| {
+                MANIFEST_FILES
+                    .iter()
+                    .find(|(key, _)| registry.eq_ignore_ascii_case(key))
+                    .map(|(_, file)| *file)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "tarball inspection supports npm, cargo, and pypi; got '{registry}'"
+                        )
+                    })
+            })
+            .transpose()?;
+        let reader: Box<dyn Read + '_> = if bytes.starts_with(&[0x1f, 0x8b]) {
+            Box::new(GzDecoder::new(bytes))
+        } else {
+            Box::new(bytes)
+        };
+        let mut archive = tar::Archive::new(reader);
+
+        let mut manifests = BTreeMap::<&'static str, String>::new();
+        let mut file_count = 0u64;
+        let mut unpacked_size = 0u64;
+        for entry in archive.entries().context("failed to read tar entries")? {
+            let mut entry = entry.context("failed to read tar entry")?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            file_count = file_count.saturating_add(1);
+            unpacked_size = unpacked_size.saturating_add(entry.size());
+
+            let path = entry.path().context("invalid tar entry path")?.into_owned();
+            let Some(file_name) = top_level_file_name(&path) else {
+                continue;
+            };
+            let Some(kind) = MANIFEST_FILES
+                .into_iter()
+                .map(|(_, file)| file)
+                .find(|kind| *kind == file_name)
+            else {
+                continue;
+            };
+            if manifests.contains_key(kind) {
+                continue;
+            }
+            let mut raw = String::new();
+            (&mut entry)
+                .take(MAX_MANIFEST_BYTES)
+                .read_to_string(&mut raw)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            manifests.insert(kind, raw);
+        }
+
+        let kind = match expected {
+            Some(kind) => kind,
+            None => match manifests.keys().copied().collect::<Vec<_>>().as_slice() {
+                [] => bail!("no package.json, Cargo.toml, or PKG-INFO found at the tarball root"),
+                [kind] => *kind,
+                kinds => bail!(
+                    "tarball has manifests for several formats ({}); pass --registry to pick one",
+                    kinds.join(", ")
+                ),
+            },
+        };
+        let raw = manifests
+            .get(kind)
+            .ok_or_else(|| anyhow!("no {kind} found at the tarball root"))?;
+        let mut manifest = match kind {
+            "package.json" => parse_package_json(raw)?,
+            "Cargo.toml" => parse_cargo_toml(raw)?,
+            _ => parse_pkg_info(raw)?,
+        };
+        if kind != "package.json"
+            && let Some(raw) = manifests.get("package.json")
+        {
+            let root: serde_json::Value =
+                serde_json::from_str(raw).context("failed to parse package.json")?;
+            manifest.install_scripts = package_json_install_scripts(&root);
+        }
+        manifest.file_count = file_count;
+        manifest.unpacked_size = unpacked_size;
+        Ok(manifest)
+    }
+
+    fn read_file(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        let size = std::fs::metadata(path)
+            .with_context(|| format!("failed to read tarball {}", path.display()))?
+            .len();
+        if usize::try_from(size).map_or(true, |size| size > self.max_bytes) {
+            bail!(
+                "tarball {} exceeds the {}-byte size limit",
+                path.display(),
+                self.max_bytes
+            );
+        }
+        std::fs::read(path).with_context(|| format!("failed to read tarball {}", path.display()))
+    }
+
+    async fn download(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+        let client = build_http_client();
+        let response = send_with_retry(
+            || client.get(url),
+            "tarball download",
+            RetryPolicy::default(),
+            &self.request_limits,
+        )
+        .await?;
+        if !response.status().is_success() {
+            return Err(safe_pkgs_registry_http::map_status_error(
+                "tarball download",
+                response.status(),
+            )
+            .into());
+        }
+        Ok(
+            safe_pkgs_registry_http::read_bytes(response, "tarball download", self.max_bytes)
+                .await?,
+        )
+    }
+}
+
+/// Infers the registry from an unambiguous archive extension; `.tar.gz` is left open
+/// because both npm and PyPI use it.
+fn registry_from_extension(source: &str) -> Option<&'static str> {
+    let path = source.split(['?', '#']).next().unwrap_or(source);
+    let path = path.to_ascii_lowercase();
+    if path.ends_with(".tgz") {
+        Some("npm")
+    } else if path.ends_with(".crate") {
+        Some("cargo")
+    } else {
+        None
+    }
+}
+
+/// Returns the file name of `root/<file>` entries, the layout every supported format uses.
+fn top_level_file_name(path: &Path) -> Option<&str> {
+    let components = path
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect::<Vec<_>>();
+    match components.as_slice() {
+        [Component::Normal(_), Component::Normal(file)] => file.to_str(),
+        _ => None,
+    }
+}
+
+fn parse_package_json(raw: &str) -> anyhow::Result<TarballManifest> {
+    let root: serde_json::Value =
+        serde_json::from_str(raw).context("failed to parse package.json")?;
+    let name = json_string(&root, "name").ok_or_else(|| anyhow!("package.json has no name"))?;
+    let version =
+        json_string(&root, "version").ok_or_else(|| anyhow!("package.json has no version"))?;
+    let license = json_string(&root, "license").or_else(|| {
+        root.get("license")
+            .and_then(|license| json_string(license, "type"))
+    });
+
+    let install_scripts = package_json_install_scripts(&root);
+
+    let bin_targets = match root.get("bin") {
+        Some(serde_json::Value::String(path)) => vec![path.clone()],
+        Some(serde_json::Value::Object(entries)) => entries
+            .values()
+            .filter_map(|path| path.as_str().map(ToOwned::to_owned))
+            .collect(),
+        _ => Vec::new(),
+    }
+    .into_iter()
+    .map(|path| path.trim().to_string())
+    .filter(|path| !path.is_empty())
+    .collect();
+
+    Ok(TarballManifest {
+        registry: "npm",
+        ecosystem: RegistryEcosystem::Npm,
+        name,
+        version,
+        license,
+        install_scripts,
+        bin_targets,
+        file_count: 0,
+        unpacked_size: 0,
+    })
+}
+
+/// npm install hooks declared by a `package.json`, formatted as `<hook>: <command>`.
+fn package_json_install_scripts(root: &serde_json::Value) -> Vec<String> {
+    root.get("scripts")
+        .and_then(|scripts| scripts.as_object())
+        .map(|scripts| {
+            INSTALL_HOOKS
+                .iter()
+                .filter_map(|hook| {
+                    scripts
+                        .get(*hook)
+                        .and_then(|command| command.as_str())
+                        .map(|command| format!("{hook}: {command}"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_cargo_toml(raw: &str) -> anyhow::Result<TarballManifest> {
+    let root: toml::Value = toml::from_str(raw).context("failed to parse Cargo.toml")?;
+    let package = root
+        .get("package")
+        .ok_or_else(|| anyhow!("Cargo.toml has no [package] table"))?;
+    let field = |key: &str| {
+        package
+            .get(key)
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+    };
+
+    Ok(TarballManifest {
+        registry: "cargo",
+        ecosystem: RegistryEcosystem::CratesIo,
+        name: field("name").ok_or_else(|| anyhow!("Cargo.toml has no package name"))?,
+        version: field("version").ok_or_else(|| anyhow!("Cargo.toml has no package version"))?,
+        license: field("license").or_else(|| field("license-file")),
+        install_scripts: Vec::new(),
+        bin_targets: Vec::new(),
+        file_count: 0,
+        unpacked_size: 0,
+    })
+}
+
+/// Parses the RFC 822-style header block of a PyPI `PKG-INFO` file.
+fn parse_pkg_info(raw: &str) -> anyhow::Result<TarballManifest> {
+    let mut headers = BTreeMap::<String, String>::new();
+    for line in raw.lines() {
+        // The description body follows the first blank line.
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers
+                .entry(key.trim().to_ascii_lowercase())
+                .or_insert_with(|| value.trim().to_string());
+        }
+    }
+    let mut header = |key: &str| headers.remove(key).filter(|value| !value.is_empty());
+
+    Ok(TarballManifest {
+        registry: "pypi",
+        ecosystem: RegistryEcosystem::PyPI,
+        name: header("name").ok_or_else(|| anyhow!("PKG-INFO has no Name"))?,
+        version: header("version").ok_or_else(|| anyhow!("PKG-INFO has no Version"))?,
+        license: header("license-expression").or_else(|| header("license")),
+        install_scripts: Vec::new(),
+        bin_targets: Vec::new(),
+        file_count: 0,
+        unpacked_size: 0,
+    })
+}
+
+fn json_string(value: &serde_json::Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Registry client that serves one inspected tarball and makes no network calls.
+pub struct TarballRegistryClient {
+    ecosystem: RegistryEcosystem,
+    record: PackageRecord,
+}
+
+impl TarballRegistryClient {
+    pub fn new(manifest: &TarballManifest) -> Self {
+        Self {
+            ecosystem: manifest.ecosystem,
+            record: manifest.package_record(),
+        }
+    }
+}
+
+#[async_trait]
+impl RegistryClient for TarballRegistryClient {
+    fn ecosystem(&self) -> RegistryEcosystem {
+        self.ecosystem
+    }
+
+    async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
+        if package != self.record.name {
+            return Err(RegistryError::NotFound {
+                registry: "tarball",
+                package: package.to_string(),
+            });
+        }
+        Ok(self.record.clone())
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/tarball.rs"]
+mod tests;
@@ -0,0 +1,187 @@
+//! In-memory registry fixtures for exercising the full evaluation pipeline without network access.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use safe_pkgs_core::{
+    PackageAdvisory, PackageRecord, PackageVersion, RegistryClient, RegistryEcosystem,
+    RegistryError,
+};
+
+/// Registry client that serves canned package records, download counts, and advisories.
+///
+/// Unknown packages return `RegistryError::NotFound`, so tests can exercise missing-package
+/// handling without extra setup.
+#[derive(Debug, Clone)]
+pub struct MockRegistryClient {
+    ecosystem: RegistryEcosystem,
+    packages: HashMap<String, PackageRecord>,
+    weekly_downloads: HashMap<String, u64>,
+    popular_names: Vec<String>,
+    advisories: HashMap<(String, String), Vec<PackageAdvisory>>,
+    transport_failures: HashSet<String>,
+}
+
+impl MockRegistryClient {
+    /// Creates an empty client for `ecosystem`.
+    pub fn new(ecosystem: RegistryEcosystem) -> Self {
+        Self {
+            ecosystem,
+            packages: HashMap::new(),
+            weekly_downloads: HashMap::new(),
+            popular_names: Vec::new(),
+            advisories: HashMap::new(),
+            transport_failures: HashSet::new(),
+        }
+    }
+
+    /// Serves `record` for its package name.
+    pub fn with_package(mut self, record: PackageRecord) -> Self {
+        self.packages.insert(record.name.clone(), record);
+        self
+    }
+
+    /// Reports `downloads` weekly downloads for `package`.
+    pub fn with_weekly_downloads(mut self, package: &str, downloads: u64) -> Self {
+        self.weekly_downloads.insert(package.to_string(), downloads);
+        self
+    }
+
+    /// Serves `names` as the registry's popular package list.
+    pub fn with_popular_names(mut self, names: &[&str]) -> Self {
+        self.popular_names = names.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Reports `advisory_ids` for one package version.
+    pub fn with_advisories(mut self, package: &str, version: &str, advisory_ids: &[&str]) -> Self {
+        let advisories = advisory_ids
+            .iter()
+            .map(|id| PackageAdvisory {
+                id: (*id).to_string(),
+                aliases: Vec::new(),
+                fixed_versions: Vec::new(),
+                git_range_only: false,
+                cvss_score: None,
+                severity: Vec::new(),
+            })
+            .collect();
+        self.advisories
+            .insert((package.to_string(), version.to_string()), advisories);
+        self
+    }
+
+    /// Fails every fetch of `package` with `RegistryError::Transport`.
+    pub fn with_transport_failure(mut self, package: &str) -> Self {
+        self.transport_failures.insert(package.to_string());
+        self
+    }
+}
+
+#[async_trait]
+impl RegistryClient for MockRegistryClient {
+    fn ecosystem(&self) -> RegistryEcosystem {
+        self.ecosystem
+    }
+
+    async fn fetch_package(&self, package: &str) -> Result<PackageRecord, RegistryError> {
+        if self.transport_failures.contains(package) {
+            return Err(RegistryError::Transport {
+                message: format!("connection reset while fetching {package}"),
+            });
+        }
+        self.packages
+            .get(package)
+            .cloned()
+            .ok_or_else(|| RegistryError::NotFound {
+                registry: "mock",
+                package: package.to_string(),
+            })
+    }
+
+    async fn fetch_weekly_downloads(&self, package: &str) -> Result<Option<u64>, RegistryError> {
+        Ok(self.weekly_downloads.get(package).copied())
+    }
+
+    async fn fetch_popular_package_names(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<String>, RegistryError> {
+        Ok(self.popular_names.iter().take(limit).cloned().collect())
+    }
+
+    async fn fetch_advisories(
+        &self,
+        package: &str,
+        version: &str,
+    ) -> Result<Vec<PackageAdvisory>, RegistryError> {
+        Ok(self
+            .advisories
+            .get(&(package.to_string(), version.to_string()))
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+/// Builds a package record from `(version, published_days_ago)` pairs; the last pair is latest.
+pub fn package_record(name: &str, versions: &[(&str, i64)]) -> PackageRecord {
+    let latest = versions
+        .last()
+        .map(|(version, _)| (*version).to_string())
+        .unwrap_or_default();
+    let versions = versions
+        .iter()
+        .map(|(version, days_ago)| {
+            (
+                (*version).to_string(),
+                PackageVersion {
+                    version: (*version).to_string(),
+                    published: Some(Utc::now() - Duration::days(*days_ago)),
+                    deprecated: false,
+                    install_scripts: Vec::new(),
+                    unpacked_size: None,
+                    file_count: None,
+                    maintainer_emails: Vec::new(),
+                    bin_targets: Vec::new(),
+                    license: None,
+                    repository_url: None,
+                    published_estimated: false,
+                },
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    PackageRecord {
+        name: name.to_string(),
+        latest,
+        publishers: Vec::new(),
+        versions,
+    }
+}
+
+/// Temporary directory that is removed on drop, even when an assertion panics.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates an empty directory named `<prefix>-<nanos>` under the system temp dir.
+    pub fn new(prefix: &str) -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nanos}"));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        Self(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -1,17 +1,9 @@
 use super::*;
 use std::fs;
-use std::fs::OpenOptions;
-use std::path::PathBuf;
-use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-fn unique_temp_path(file_name: &str) -> PathBuf {
-    let nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("system time")
-        .as_nanos();
-    std::env::temp_dir().join(format!("safe-pkgs-{nanos}-{file_name}"))
-}
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::test_support::TempDir;
 
 #[test]
 fn package_decision_contains_expected_fields() {
@@ -29,6 +21,7 @@ fn package_decision_contains_expected_fields() {
         risk: Severity::Low,
         reasons: vec!["ok".to_string()],
         evidence: Vec::new(),
+        checks_run: vec!["existence".to_string()],
         metadata: None,
         cached: false,
     });
@@ -47,19 +40,87 @@ fn package_decision_contains_expected_fields() {
     assert_eq!(json["risk"], "low");
     assert_eq!(json["cached"], false);
     assert!(json["evidence"].is_array());
+    assert_eq!(json["checks_run"], serde_json::json!(["existence"]));
+    assert_eq!(json["findings"], serde_json::json!([]));
+}
+
+fn evidence(kind: EvidenceKind, id: &str, severity: Severity) -> Evidence {
+    Evidence {
+        kind,
+        id: id.to_string(),
+        code: id.to_string(),
+        severity,
+        message: id.to_string(),
+        facts: Default::default(),
+        remediation: None,
+    }
+}
+
+#[test]
+fn package_decision_captures_per_check_findings() {
+    let record = AuditRecord::package_decision(PackageDecision {
+        policy_snapshot_version: 2,
+        config_fingerprint: "cfg123",
+        policy_fingerprint: "pol123",
+        enabled_checks: vec!["typosquat".to_string(), "version_age".to_string()],
+        evaluation_time: "2026-01-01T00:00:00Z".to_string(),
+        context: "check_package",
+        package: "raect",
+        requested: Some("1.0.0"),
+        registry: "npm",
+        allow: false,
+        risk: Severity::High,
+        reasons: vec!["typosquat".to_string(), "too new".to_string()],
+        evidence: vec![
+            evidence(
+                EvidenceKind::Check,
+                "typosquat.close_to_popular_name",
+                Severity::High,
+            ),
+            evidence(EvidenceKind::Check, "version_age.too_new", Severity::Medium),
+            evidence(
+                EvidenceKind::CustomRule,
+                "custom_rule.low-downloads",
+                Severity::Medium,
+            ),
+            evidence(
+                EvidenceKind::Policy,
+                "risk.medium_pair_escalation",
+                Severity::High,
+            ),
+        ],
+        checks_run: vec!["typosquat".to_string(), "version_age".to_string()],
+        metadata: None,
+        cached: false,
+    });
+
+    let json = serde_json::to_value(record).expect("serialize record");
+    assert_eq!(
+        json["findings"],
+        serde_json::json!([
+            { "check_id": "typosquat", "severity": "high" },
+            { "check_id": "version_age", "severity": "medium" },
+            { "check_id": "custom_rule.low-downloads", "severity": "medium" }
+        ])
+    );
+    assert_eq!(
+        json["checks_run"],
+        serde_json::json!(["typosquat", "version_age"])
+    );
+    assert_eq!(json["reasons"].as_array().map(Vec::len), Some(2));
+    assert_eq!(json["evidence"].as_array().map(Vec::len), Some(4));
 }
 
 #[test]
 fn log_writes_one_json_line() {
-    let path = unique_temp_path("audit.log");
-    let file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-        .expect("create audit log file");
-    let logger = AuditLogger {
-        file: Mutex::new(file),
-    };
+    let temp_dir = TempDir::new("audit-log");
+    let path = temp_dir.path().join("audit.log");
+    let logger = AuditLogger::open(
+        path.clone(),
+        crate::config::DEFAULT_AUDIT_LOG_MAX_BYTES,
+        crate::config::DEFAULT_AUDIT_LOG_MAX_FILES,
+    )
+    .expect("create audit log file");
 
     logger
         .log(AuditRecord::package_decision(PackageDecision {
@@ -76,11 +137,14 @@ fn log_writes_one_json_line() {
             risk: Severity::High,
             reasons: vec!["reason".to_string()],
             evidence: Vec::new(),
+            checks_run: Vec::new(),
             metadata: Some(Metadata {
                 latest: Some("2.0.0".to_string()),
                 requested: Some("latest".to_string()),
+                resolved: Some("2.0.0".to_string()),
                 published: None,
                 weekly_downloads: Some(10),
+                fetch_ms: None,
             }),
             cached: true,
         }))
@@ -93,6 +157,99 @@ fn log_writes_one_json_line() {
     assert_eq!(parsed["package"], "demo");
     assert_eq!(parsed["cached"], true);
     assert_eq!(parsed["policy_fingerprint"], "pol123");
+}
+
+fn minimal_decision(package: &str) -> AuditRecord {
+    AuditRecord::package_decision(PackageDecision {
+        policy_snapshot_version: 1,
+        config_fingerprint: "cfg",
+        policy_fingerprint: "pol",
+        enabled_checks: Vec::new(),
+        evaluation_time: "2026-01-01T00:00:00Z".to_string(),
+        context: "check_package",
+        package,
+        requested: None,
+        registry: "npm",
+        allow: true,
+        risk: Severity::Low,
+        reasons: Vec::new(),
+        evidence: Vec::new(),
+        checks_run: Vec::new(),
+        metadata: None,
+        cached: false,
+    })
+}
+
+fn read_records(path: &Path) -> Vec<serde_json::Value> {
+    let raw = fs::read_to_string(path).expect("read audit file");
+    assert!(raw.len() as u64 <= 800);
+    raw.lines()
+        .map(|line| serde_json::from_str(line).expect("ndjson line"))
+        .collect()
+}
+
+#[test]
+fn log_rotates_when_size_limit_is_exceeded() {
+    let temp_dir = TempDir::new("audit-rotate");
+    let path = temp_dir.path().join("audit.log");
+    // Roughly two records fit before the limit is crossed. Two loggers on the same file
+    // stand in for separate processes: each holds its own handle on the lock file.
+    let loggers = [
+        Arc::new(AuditLogger::open(path.clone(), 800, 50).expect("open audit log")),
+        Arc::new(AuditLogger::open(path.clone(), 800, 50).expect("open audit log")),
+    ];
+
+    let writers = (0..4)
+        .map(|writer| {
+            let logger = Arc::clone(&loggers[writer % 2]);
+            std::thread::spawn(move || {
+                for index in 0..5 {
+                    logger
+                        .log(minimal_decision(&format!("pkg-{writer}-{index}")))
+                        .expect("write audit record");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for writer in writers {
+        writer.join().expect("writer thread");
+    }
+
+    assert!(rotated_path(&path, 1).exists(), "rotated file should exist");
+    let mut packages = std::iter::once(path.clone())
+        .chain((1..=50).map(|generation| rotated_path(&path, generation)))
+        .filter(|file| file.exists())
+        .flat_map(|file| read_records(&file))
+        .map(|record| {
+            assert_eq!(record["registry"], "npm");
+            record["package"].as_str().expect("package").to_string()
+        })
+        .collect::<Vec<_>>();
+    packages.sort();
+    packages.dedup();
+    assert_eq!(packages.len(), 20, "no record lost or duplicated");
+}
+
+#[test]
+fn rotation_keeps_at_most_max_files_generations() {
+    let temp_dir = TempDir::new("audit-generations");
+    let path = temp_dir.path().join("audit.log");
+    let logger = AuditLogger::open(path.clone(), 800, 2).expect("open audit log");
+
+    for index in 0..20 {
+        logger
+            .log(minimal_decision(&format!("pkg-{index}")))
+            .expect("write audit record");
+    }
 
-    let _ = fs::remove_file(path);
+    assert!(rotated_path(&path, 1).exists());
+    assert!(rotated_path(&path, 2).exists());
+    assert!(!rotated_path(&path, 3).exists());
+    let newest = read_records(&path);
+    let oldest = read_records(&rotated_path(&path, 2));
+    assert_eq!(newest.last().expect("record")["package"], "pkg-19");
+    assert_ne!(
+        oldest[0]["package"], "pkg-0",
+        "oldest generation was dropped"
+    );
 }
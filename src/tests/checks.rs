@@ -1,7 +1,7 @@
 use super::*;
 use crate::config::{
     CustomRuleCondition, CustomRuleConfig, CustomRuleField, CustomRuleMatchMode,
-    CustomRuleOperator, SafePkgsConfig,
+    CustomRuleOperator, EnforcementMode, ListPrecedence, SafePkgsConfig,
 };
 use async_trait::async_trait;
 use chrono::{Duration, Utc};
@@ -62,6 +62,13 @@ fn package_record(latest: &str, requested: &str, published_days_ago: i64) -> Pac
             published: Some(Utc::now() - Duration::days(published_days_ago)),
             deprecated: false,
             install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
         },
     );
     versions.insert(
@@ -71,6 +78,13 @@ fn package_record(latest: &str, requested: &str, published_days_ago: i64) -> Pac
             published: Some(Utc::now() - Duration::days(100)),
             deprecated: false,
             install_scripts: Vec::new(),
+            unpacked_size: None,
+            file_count: None,
+            maintainer_emails: Vec::new(),
+            bin_targets: Vec::new(),
+            license: None,
+            repository_url: None,
+            published_estimated: false,
         },
     );
 
@@ -121,6 +135,39 @@ async fn not_found_is_critical_and_denied() {
     assert!(report.reasons[0].contains("does not exist"));
 }
 
+#[tokio::test]
+async fn configured_missing_version_severity_replaces_critical() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("2.0.0", "1.0.0", 100)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.existence.missing_version = Severity::High;
+
+    let report = run_all_checks(
+        "demo",
+        Some("9.9.9"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert_eq!(report.risk, Severity::High);
+    assert!(!report.allow);
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "existence.missing_version" && item.severity == Severity::High)
+    );
+}
+
 #[tokio::test]
 async fn existence_remains_mandatory_when_disabled_in_config() {
     let supported_checks = all_supported_checks();
@@ -188,17 +235,21 @@ async fn very_new_version_is_high_risk() {
 }
 
 #[tokio::test]
-async fn typosquat_signal_is_high_risk() {
+async fn future_dated_version_is_clamped_and_flagged() {
     let supported_checks = all_supported_checks();
+    let mut package = package_record("1.0.0", "1.0.0", 0);
+    if let Some(version) = package.versions.get_mut("1.0.0") {
+        version.published = Some(Utc::now() + Duration::hours(1));
+    }
     let client = FakeRegistryClient {
-        result: Ok(package_record("1.0.0", "1.0.0", 30)),
-        weekly_downloads: Some(10),
-        popular_packages: vec!["react".to_string(), "lodash".to_string()],
+        result: Ok(package),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
         advisories: Vec::new(),
     };
 
     let report = run_all_checks(
-        "raect",
+        "demo",
         Some("1.0.0"),
         "npm",
         &supported_checks,
@@ -207,146 +258,152 @@ async fn typosquat_signal_is_high_risk() {
     )
     .await
     .expect("check report");
-    assert_eq!(report.risk, Severity::High);
-    assert!(report.reasons.iter().any(|reason| reason.contains("react")));
-}
-
-#[test]
-fn multiple_medium_findings_escalate_to_high() {
-    let report = report_from_findings(
-        vec![
-            StructuredFinding {
-                severity: Severity::Medium,
-                reason: "signal a".to_string(),
-                evidence: Evidence {
-                    kind: EvidenceKind::Check,
-                    id: "a".to_string(),
-                    severity: Severity::Medium,
-                    message: "signal a".to_string(),
-                    facts: std::collections::BTreeMap::new(),
-                },
-            },
-            StructuredFinding {
-                severity: Severity::Medium,
-                reason: "signal b".to_string(),
-                evidence: Evidence {
-                    kind: EvidenceKind::Check,
-                    id: "b".to_string(),
-                    severity: Severity::Medium,
-                    message: "signal b".to_string(),
-                    facts: std::collections::BTreeMap::new(),
-                },
-            },
-        ],
-        Metadata {
-            latest: None,
-            requested: None,
-            published: None,
-            weekly_downloads: None,
-        },
-        Severity::Medium,
-    );
-    assert_eq!(report.risk, Severity::High);
-    assert!(!report.allow);
+    let mut future_codes = report
+        .evidence
+        .iter()
+        .filter(|evidence| evidence.id.ends_with(".future_publish_time"))
+        .inspect(|evidence| assert_eq!(evidence.severity, Severity::Low))
+        .map(|evidence| evidence.code.as_str())
+        .collect::<Vec<_>>();
+    future_codes.sort_unstable();
+    assert_eq!(future_codes, vec!["AGE004", "POP002", "STALE005"]);
     assert!(
         report
-            .evidence
+            .reasons
             .iter()
-            .any(|item| item.id == "risk.medium_pair_escalation")
+            .any(|reason| reason.contains("published 0 day(s) ago"))
     );
 }
 
 #[tokio::test]
-async fn denylist_package_rule_denies_immediately() {
+async fn metadata_resolved_reports_the_version_checks_evaluated() {
     let supported_checks = all_supported_checks();
     let client = FakeRegistryClient {
-        result: Ok(package_record("1.0.0", "1.0.0", 30)),
-        weekly_downloads: Some(100),
+        result: Ok(package_record("2.0.0", "1.0.0", 400)),
+        weekly_downloads: Some(1_000_000),
         popular_packages: Vec::new(),
         advisories: Vec::new(),
     };
-    let mut config = default_config();
-    config.denylist.packages = vec!["demo".to_string()];
-
-    let report = run_all_checks(
-        "demo",
-        Some("1.0.0"),
-        "npm",
-        &supported_checks,
-        &client,
-        &config,
-    )
-    .await
-    .expect("check report");
 
-    assert_eq!(report.risk, Severity::Critical);
-    assert!(!report.allow);
-    assert!(
-        report
-            .reasons
-            .iter()
-            .any(|reason| reason.contains("denylist"))
-    );
-    assert!(
-        report
-            .evidence
-            .iter()
-            .any(|item| item.id == "denylist.package")
-    );
+    for (requested, expected_resolved) in [
+        (None, Some("2.0.0")),
+        (Some("latest"), Some("2.0.0")),
+        (Some("1.0.0"), Some("1.0.0")),
+        (Some("^1.0.0"), None),
+    ] {
+        let report = run_all_checks(
+            "demo",
+            requested,
+            "npm",
+            &supported_checks,
+            &client,
+            &default_config(),
+        )
+        .await
+        .expect("check report");
+        assert_eq!(report.metadata.requested.as_deref(), requested);
+        assert_eq!(report.metadata.resolved.as_deref(), expected_resolved);
+    }
 }
 
 #[tokio::test]
-async fn allowlist_package_rule_allows_immediately() {
+async fn require_exact_version_denies_ranges_and_allows_pins() {
     let supported_checks = all_supported_checks();
     let client = FakeRegistryClient {
-        result: Ok(package_record("1.0.0", "1.0.0", 1)),
-        weekly_downloads: Some(0),
+        result: Ok(package_record("1.2.3", "1.2.3", 400)),
+        weekly_downloads: Some(1_000_000),
         popular_packages: Vec::new(),
         advisories: Vec::new(),
     };
-    let mut config = default_config();
-    config.allowlist.packages = vec!["demo".to_string()];
+    let mut strict = default_config();
+    strict.require_exact_version = true;
+
+    for requested in [Some("^1.0.0"), Some("latest"), None] {
+        let report = run_all_checks(
+            "demo",
+            requested,
+            "npm",
+            &supported_checks,
+            &client,
+            &strict,
+        )
+        .await
+        .expect("check report");
+        assert!(!report.allow, "{requested:?} should be denied");
+        assert!(
+            report
+                .reasons
+                .contains(&"non-exact version requested; pin an exact version".to_string())
+        );
+        let finding = report
+            .evidence
+            .iter()
+            .find(|evidence| evidence.id == "version.non_exact_request")
+            .expect("non-exact evidence");
+        assert_eq!(finding.severity, Severity::Medium);
+    }
 
+    // The dist-tag resolves cleanly, so the medium finding alone sets risk and score, and
+    // still denies under a permissive `max_risk`.
+    let mut permissive = strict.clone();
+    permissive.max_risk = Severity::High;
     let report = run_all_checks(
         "demo",
-        Some("1.0.0"),
+        Some("latest"),
         "npm",
         &supported_checks,
         &client,
-        &config,
+        &permissive,
     )
     .await
     .expect("check report");
+    assert!(!report.allow);
+    assert_eq!(report.risk, Severity::Medium);
+    assert_eq!(report.score, risk_score([Severity::Medium]));
 
-    assert_eq!(report.risk, Severity::Low);
+    let report = run_all_checks(
+        "demo",
+        Some("1.2.3"),
+        "npm",
+        &supported_checks,
+        &client,
+        &strict,
+    )
+    .await
+    .expect("check report");
     assert!(report.allow);
-    assert!(
-        report
-            .reasons
-            .iter()
-            .any(|reason| reason.contains("allowlist"))
-    );
     assert!(
         report
             .evidence
             .iter()
-            .any(|item| item.id == "allowlist.package")
+            .all(|evidence| evidence.id != "version.non_exact_request")
     );
 }
 
 #[tokio::test]
-async fn denylist_publisher_rule_denies_immediately() {
+async fn every_evidence_item_has_a_code() {
     let supported_checks = all_supported_checks();
-    let mut record = package_record("1.0.0", "1.0.0", 30);
-    record.publishers = vec!["suspicious-user".to_string()];
     let client = FakeRegistryClient {
-        result: Ok(record),
-        weekly_downloads: Some(1_000_000),
+        result: Ok(package_record("1.0.1", "1.0.0", 1)),
+        weekly_downloads: Some(10),
         popular_packages: Vec::new(),
         advisories: Vec::new(),
     };
     let mut config = default_config();
-    config.denylist.publishers = vec!["suspicious-user".to_string()];
+    config.enforcement = EnforcementMode::WarnOnly;
+    config.custom_rules = vec![CustomRuleConfig {
+        id: "low-downloads".to_string(),
+        enabled: true,
+        registries: vec!["npm".to_string()],
+        match_mode: CustomRuleMatchMode::All,
+        severity: Severity::High,
+        reason: None,
+        conditions: vec![CustomRuleCondition {
+            field: CustomRuleField::WeeklyDownloads,
+            op: CustomRuleOperator::Lt,
+            value: Some(json!(20)),
+        }],
+    }];
 
     let report = run_all_checks(
         "demo",
@@ -359,37 +416,46 @@ async fn denylist_publisher_rule_denies_immediately() {
     .await
     .expect("check report");
 
-    assert_eq!(report.risk, Severity::Critical);
-    assert!(!report.allow);
-    assert!(
-        report
-            .reasons
-            .iter()
-            .any(|reason| reason.contains("publisher"))
-    );
-    assert!(
-        report
-            .evidence
-            .iter()
-            .any(|item| item.id == "denylist.publisher")
-    );
+    let kinds = report
+        .evidence
+        .iter()
+        .map(|evidence| evidence.kind)
+        .collect::<Vec<_>>();
+    for kind in [
+        EvidenceKind::Check,
+        EvidenceKind::Policy,
+        EvidenceKind::CustomRule,
+    ] {
+        assert!(kinds.contains(&kind), "missing {kind:?} evidence");
+    }
+    for evidence in &report.evidence {
+        assert!(!evidence.code.is_empty(), "{} has no code", evidence.id);
+    }
+    let policy = report
+        .evidence
+        .iter()
+        .find(|evidence| evidence.kind == EvidenceKind::Policy)
+        .expect("policy evidence");
+    assert_eq!(policy.code, policy.id);
 }
 
 #[tokio::test]
-async fn dependency_confusion_public_shadow_is_denied() {
+async fn require_exact_version_keeps_denylist_decision_for_ranges() {
     let supported_checks = all_supported_checks();
     let client = FakeRegistryClient {
-        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        result: Ok(package_record("1.2.3", "1.2.3", 400)),
         weekly_downloads: Some(1_000_000),
         popular_packages: Vec::new(),
         advisories: Vec::new(),
     };
     let mut config = default_config();
-    config.dependency_confusion.internal_packages = vec!["demo".to_string()];
+    config.require_exact_version = true;
+    config.list_precedence = ListPrecedence::AllowFirst;
+    config.denylist.packages = vec!["demo".to_string()];
 
     let report = run_all_checks(
         "demo",
-        Some("1.0.0"),
+        Some("^1.0.0"),
         "npm",
         &supported_checks,
         &client,
@@ -397,320 +463,1686 @@ async fn dependency_confusion_public_shadow_is_denied() {
     )
     .await
     .expect("check report");
-
-    assert_eq!(report.risk, Severity::Critical);
     assert!(!report.allow);
+    assert_eq!(report.risk, Severity::Critical);
     assert!(
         report
             .evidence
             .iter()
-            .any(|item| item.id == "dependency_confusion.public_shadow")
+            .any(|evidence| evidence.id == "denylist.package")
     );
 }
 
 #[tokio::test]
-async fn dependency_confusion_scope_match_is_denied() {
+async fn typosquat_signal_is_high_risk() {
     let supported_checks = all_supported_checks();
     let client = FakeRegistryClient {
         result: Ok(package_record("1.0.0", "1.0.0", 30)),
-        weekly_downloads: Some(1_000_000),
-        popular_packages: Vec::new(),
+        weekly_downloads: Some(10),
+        popular_packages: vec!["react".to_string(), "lodash".to_string()],
         advisories: Vec::new(),
     };
-    let mut config = default_config();
-    config.dependency_confusion.internal_scopes = vec!["@myorg".to_string()];
 
     let report = run_all_checks(
-        "@myorg/widget",
+        "raect",
         Some("1.0.0"),
         "npm",
         &supported_checks,
         &client,
-        &config,
+        &default_config(),
     )
     .await
     .expect("check report");
+    assert_eq!(report.risk, Severity::High);
+    assert!(report.reasons.iter().any(|reason| reason.contains("react")));
+}
 
-    assert_eq!(report.risk, Severity::Critical);
-    assert!(!report.allow);
-    assert!(
-        report
-            .evidence
-            .iter()
-            .any(|item| item.id == "dependency_confusion.public_shadow")
-    );
+fn has_unparseable_version_evidence(report: &CheckReport) -> bool {
+    report
+        .evidence
+        .iter()
+        .any(|evidence| evidence.id == "version.unparseable")
 }
 
 #[tokio::test]
-async fn dependency_confusion_ignores_non_internal_name() {
+async fn non_semver_version_reports_skipped_checks() {
     let supported_checks = all_supported_checks();
     let client = FakeRegistryClient {
-        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        result: Ok(package_record("1.0.0b3", "1.0.0b3", 30)),
         weekly_downloads: Some(1_000_000),
         popular_packages: Vec::new(),
         advisories: Vec::new(),
     };
-    let mut config = default_config();
-    config.dependency_confusion.internal_packages = vec!["internal-only".to_string()];
 
     let report = run_all_checks(
         "demo",
-        Some("1.0.0"),
+        Some("1.0.0b3"),
         "npm",
         &supported_checks,
         &client,
-        &config,
+        &default_config(),
     )
     .await
     .expect("check report");
-
+    assert!(has_unparseable_version_evidence(&report));
     assert!(
-        !report
-            .evidence
+        report
+            .reasons
             .iter()
-            .any(|item| item.id == "dependency_confusion.public_shadow")
+            .any(|reason| reason.contains("not valid semver"))
     );
+
+    let mut quiet = default_config();
+    quiet.warn_on_unparseable_version = false;
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0b3"),
+        "npm",
+        &supported_checks,
+        &client,
+        &quiet,
+    )
+    .await
+    .expect("check report");
+    assert!(!has_unparseable_version_evidence(&report));
 }
 
 #[tokio::test]
-async fn dependency_confusion_not_emitted_on_not_found() {
+async fn unsupported_version_scheme_fails_closed() {
     let supported_checks = all_supported_checks();
-    let client = FakeRegistryClient {
-        result: Err(RegistryError::NotFound {
-            registry: "npm",
-            package: "internal-pkg".to_string(),
-        }),
-        weekly_downloads: None,
-        popular_packages: Vec::new(),
-        advisories: Vec::new(),
-    };
-    let mut config = default_config();
-    config.dependency_confusion.internal_packages = vec!["internal-pkg".to_string()];
+    let client = crate::test_support::MockRegistryClient::new(RegistryEcosystem::Maven)
+        .with_package(crate::test_support::package_record(
+            "org.example:demo",
+            &[("1.0.0", 30)],
+        ))
+        .with_weekly_downloads("org.example:demo", 1_000_000);
 
     let report = run_all_checks(
-        "internal-pkg",
-        None,
+        "org.example:demo",
+        Some("1.0.0"),
         "npm",
         &supported_checks,
         &client,
-        &config,
+        &default_config(),
     )
     .await
     .expect("check report");
 
-    assert!(
-        !report
-            .evidence
-            .iter()
-            .any(|item| item.id == "dependency_confusion.public_shadow"),
-        "internal name absent from the public registry must not trigger dependency confusion"
-    );
+    assert!(!report.allow);
+    assert!(!has_unparseable_version_evidence(&report));
+    let evidence = report
+        .evidence
+        .iter()
+        .find(|evidence| evidence.id == "version.unsupported_scheme")
+        .expect("unsupported scheme evidence");
+    assert_eq!(evidence.severity, Severity::High);
+    assert_eq!(evidence.facts["ecosystem"], "Maven");
 }
 
 #[tokio::test]
-async fn registry_disabled_check_is_skipped() {
+async fn date_based_semver_version_is_not_reported_unparseable() {
     let supported_checks = all_supported_checks();
     let client = FakeRegistryClient {
-        result: Ok(package_record("1.0.0", "1.0.0", 30)),
-        weekly_downloads: Some(10),
-        popular_packages: vec!["react".to_string(), "lodash".to_string()],
+        result: Ok(package_record("2024.1.1", "2024.1.1", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
         advisories: Vec::new(),
     };
 
-    let mut config = default_config();
-    config.checks.registry.insert(
-        "npm".to_string(),
-        crate::config::RegistryChecksConfig {
-            disable: vec!["typosquat".to_string()],
-        },
-    );
-
     let report = run_all_checks(
-        "raect",
-        Some("1.0.0"),
+        "demo",
+        Some("2024.1.1"),
         "npm",
         &supported_checks,
         &client,
-        &config,
+        &default_config(),
     )
     .await
     .expect("check report");
+    assert!(!has_unparseable_version_evidence(&report));
+}
 
-    assert!(
-        !report.reasons.iter().any(|reason| reason.contains("react")),
-        "typosquat finding should be disabled for npm"
-    );
+fn has_blackout_evidence(report: &CheckReport) -> bool {
+    report
+        .evidence
+        .iter()
+        .any(|evidence| evidence.id == "blackout.published_during_freeze")
 }
 
 #[tokio::test]
-async fn unsupported_check_is_skipped_for_registry() {
+async fn version_published_after_blackout_is_high_risk() {
+    let supported_checks = all_supported_checks();
     let client = FakeRegistryClient {
-        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        result: Ok(package_record("1.0.1", "1.0.0", 30)),
         weekly_downloads: Some(1_000_000),
         popular_packages: Vec::new(),
         advisories: Vec::new(),
     };
-    let cargo_supported_without_install_scripts = [
-        "existence",
-        "version_age",
-        "staleness",
-        "popularity",
-        "typosquat",
-        "advisory",
-    ];
 
+    let mut before_freeze = default_config();
+    before_freeze.blackout.after = Some(Utc::now() - Duration::days(20));
     let report = run_all_checks(
         "demo",
         Some("1.0.0"),
-        "cargo",
-        &cargo_supported_without_install_scripts,
+        "npm",
+        &supported_checks,
         &client,
-        &default_config(),
+        &before_freeze,
     )
     .await
     .expect("check report");
+    assert!(!has_blackout_evidence(&report));
 
+    let mut during_freeze = default_config();
+    during_freeze.blackout.after = Some(Utc::now() - Duration::days(40));
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &during_freeze,
+    )
+    .await
+    .expect("check report");
+    assert_eq!(report.risk, Severity::High);
+    assert!(!report.allow);
+    assert!(has_blackout_evidence(&report));
     assert!(
-        !report
+        report
             .reasons
             .iter()
-            .any(|reason| reason.contains("install hook")),
-        "install_script finding should be skipped when registry does not support it"
+            .any(|reason| reason.contains("change freeze"))
     );
 }
 
-#[test]
-fn descriptor_registry_matches_runnable_checks() {
-    let descriptor_ids = check_descriptors()
-        .iter()
-        .map(|descriptor| descriptor.id)
-        .collect::<Vec<_>>();
-    let expected = all_supported_checks();
-    assert_eq!(descriptor_ids.as_slice(), expected.as_slice());
+#[tokio::test]
+async fn version_inside_quarantine_window_is_critical() {
+    let supported_checks = all_supported_checks();
+    let mut package = package_record("1.0.1", "1.0.1", 0);
+    if let Some(latest) = package.versions.get_mut("1.0.1") {
+        latest.published = Some(Utc::now() - Duration::hours(2));
+    }
+    let client = FakeRegistryClient {
+        result: Ok(package),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let has_quarantine_evidence = |report: &CheckReport| {
+        report
+            .evidence
+            .iter()
+            .any(|evidence| evidence.id == "quarantine.within_window")
+    };
+
+    let mut quarantined = default_config();
+    quarantined.quarantine_hours = Some(24);
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.1"),
+        "npm",
+        &supported_checks,
+        &client,
+        &quarantined,
+    )
+    .await
+    .expect("check report");
+    assert_eq!(report.risk, Severity::Critical);
+    assert!(!report.allow);
+    assert!(has_quarantine_evidence(&report));
+
+    let mut short_window = default_config();
+    short_window.quarantine_hours = Some(1);
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.1"),
+        "npm",
+        &supported_checks,
+        &client,
+        &short_window,
+    )
+    .await
+    .expect("check report");
+    assert!(!has_quarantine_evidence(&report));
 }
 
-#[test]
-fn runtime_requirements_derive_from_enabled_checks() {
+#[tokio::test]
+async fn registry_blackout_overrides_global_timestamp() {
     let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.1", "1.0.0", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+
     let mut config = default_config();
-    config.checks.disable = vec!["advisory".to_string(), "popularity".to_string()];
-    config.checks.registry.insert(
+    config.blackout.after = Some(Utc::now() - Duration::days(40));
+    config.blackout.registry.insert(
         "npm".to_string(),
-        crate::config::RegistryChecksConfig {
-            disable: vec!["typosquat".to_string()],
+        crate::config::RegistryBlackoutConfig {
+            after: Some(Utc::now() - Duration::days(20)),
         },
     );
 
-    let requirements = runtime_requirements_for_registry("npm", &supported_checks, &config);
-    assert!(!requirements.needs_weekly_downloads);
-    assert!(!requirements.needs_advisories);
+    let npm_report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("npm report");
+    assert!(!has_blackout_evidence(&npm_report));
+
+    let cargo_report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "cargo",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("cargo report");
+    assert!(has_blackout_evidence(&cargo_report));
 }
 
-#[test]
-fn enabled_check_ids_for_registry_are_sorted_and_normalized() {
-    let supported_checks = all_supported_checks();
-    let mut config = default_config();
-    config.checks.disable = vec!["Version-Age".to_string(), "typosquat".to_string()];
-    config.checks.registry.insert(
-        "npm".to_string(),
-        crate::config::RegistryChecksConfig {
-            disable: vec!["install-script".to_string()],
-        },
-    );
+struct PopularIndexDownClient {
+    record: PackageRecord,
+}
 
-    let enabled = enabled_check_ids_for_registry("npm", &supported_checks, &config);
-    let mut sorted = enabled.clone();
-    sorted.sort();
-    assert_eq!(enabled, sorted);
-    assert!(!enabled.iter().any(|id| id == "version_age"));
-    assert!(!enabled.iter().any(|id| id == "typosquat"));
-    assert!(!enabled.iter().any(|id| id == "install_script"));
+#[async_trait]
+impl RegistryClient for PopularIndexDownClient {
+    fn ecosystem(&self) -> RegistryEcosystem {
+        RegistryEcosystem::Npm
+    }
+
+    async fn fetch_package(&self, _package: &str) -> Result<PackageRecord, RegistryError> {
+        Ok(self.record.clone())
+    }
+
+    async fn fetch_weekly_downloads(&self, _package: &str) -> Result<Option<u64>, RegistryError> {
+        Ok(Some(10))
+    }
+
+    async fn fetch_popular_package_names(
+        &self,
+        _limit: usize,
+    ) -> Result<Vec<String>, RegistryError> {
+        Err(RegistryError::Transport {
+            message: "popular index unavailable".to_string(),
+        })
+    }
 }
 
 #[tokio::test]
-async fn custom_rule_match_emits_finding() {
+async fn popular_index_failure_does_not_fail_evaluation_by_default() {
     let supported_checks = all_supported_checks();
-    let client = FakeRegistryClient {
-        result: Ok(package_record("1.0.0", "1.0.0", 40)),
-        weekly_downloads: Some(10),
-        popular_packages: Vec::new(),
-        advisories: Vec::new(),
+    let client = PopularIndexDownClient {
+        record: package_record("1.0.1", "1.0.0", 1),
     };
-    let mut config = default_config();
-    config.checks.disable = vec![
-        "version_age".to_string(),
-        "staleness".to_string(),
-        "popularity".to_string(),
-        "install_script".to_string(),
-        "typosquat".to_string(),
-        "advisory".to_string(),
-    ];
-    config.custom_rules = vec![CustomRuleConfig {
-        id: "low-downloads".to_string(),
-        enabled: true,
-        registries: vec!["npm".to_string()],
-        match_mode: CustomRuleMatchMode::All,
-        severity: Severity::High,
-        reason: Some("weekly downloads below allowed floor".to_string()),
-        conditions: vec![CustomRuleCondition {
-            field: CustomRuleField::WeeklyDownloads,
-            op: CustomRuleOperator::Lt,
-            value: Some(json!(20)),
-        }],
-    }];
 
     let report = run_all_checks(
-        "demo",
+        "raect",
         Some("1.0.0"),
         "npm",
         &supported_checks,
         &client,
-        &config,
+        &default_config(),
     )
     .await
-    .expect("check report");
-
-    assert!(!report.allow);
-    assert_eq!(report.risk, Severity::High);
+    .expect("evaluation continues without popular index");
+    // Other checks still run: the 1-day-old version trips version_age.
     assert!(
         report
-            .reasons
+            .evidence
             .iter()
-            .any(|reason| reason.contains("custom rule 'low-downloads' matched")),
-        "custom rule finding should be included in reasons"
+            .any(|evidence| evidence.id == "version_age.too_new")
     );
     assert!(
         report
             .evidence
             .iter()
-            .any(|item| item.kind == EvidenceKind::CustomRule
-                && item.id == "custom_rule.low-downloads"),
-        "custom rule evidence should include rule id"
+            .all(|evidence| !evidence.id.starts_with("typosquat."))
     );
+
+    let mut fail_closed = default_config();
+    fail_closed.typosquat.fail_open = false;
+    let err = run_all_checks(
+        "raect",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &fail_closed,
+    )
+    .await
+    .expect_err("fail-closed typosquat propagates index errors");
+    assert!(matches!(err, RegistryError::Transport { .. }));
 }
 
 #[test]
-fn runtime_requirements_include_custom_rules() {
-    let supported_checks = all_supported_checks();
-    let mut config = default_config();
-    config.checks.disable = vec!["advisory".to_string(), "popularity".to_string()];
-    config.custom_rules = vec![CustomRuleConfig {
-        id: "needs-extra-data".to_string(),
-        enabled: true,
-        registries: vec!["npm".to_string()],
-        match_mode: CustomRuleMatchMode::Any,
-        severity: Severity::Medium,
-        reason: None,
-        conditions: vec![
-            CustomRuleCondition {
-                field: CustomRuleField::WeeklyDownloads,
-                op: CustomRuleOperator::Exists,
-                value: Some(json!(true)),
+fn multiple_medium_findings_escalate_to_high() {
+    let report = report_from_findings(
+        vec![
+            StructuredFinding {
+                severity: Severity::Medium,
+                reason: "signal a".to_string(),
+                evidence: Evidence {
+                    kind: EvidenceKind::Check,
+                    id: "a".to_string(),
+                    code: "a".to_string(),
+                    severity: Severity::Medium,
+                    message: "signal a".to_string(),
+                    facts: std::collections::BTreeMap::new(),
+                    remediation: None,
+                },
             },
-            CustomRuleCondition {
-                field: CustomRuleField::AdvisoryCount,
-                op: CustomRuleOperator::Gt,
-                value: Some(json!(0)),
+            StructuredFinding {
+                severity: Severity::Medium,
+                reason: "signal b".to_string(),
+                evidence: Evidence {
+                    kind: EvidenceKind::Check,
+                    id: "b".to_string(),
+                    code: "b".to_string(),
+                    severity: Severity::Medium,
+                    message: "signal b".to_string(),
+                    facts: std::collections::BTreeMap::new(),
+                    remediation: None,
+                },
+            },
+        ],
+        Metadata {
+            latest: None,
+            requested: None,
+            resolved: None,
+            published: None,
+            weekly_downloads: None,
+            fetch_ms: None,
+        },
+        Vec::new(),
+        Severity::Medium,
+        None,
+        ReasonLimits::default(),
+    );
+    assert_eq!(report.risk, Severity::High);
+    assert!(!report.allow);
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "risk.medium_pair_escalation")
+    );
+}
+
+fn finding_with_severity(severity: Severity) -> StructuredFinding {
+    StructuredFinding {
+        severity,
+        reason: format!("{severity:?} signal"),
+        evidence: Evidence {
+            kind: EvidenceKind::Check,
+            id: "signal".to_string(),
+            code: "signal".to_string(),
+            severity,
+            message: format!("{severity:?} signal"),
+            facts: std::collections::BTreeMap::new(),
+            remediation: None,
+        },
+    }
+}
+
+fn score_for(severities: &[Severity]) -> u8 {
+    report_from_findings(
+        severities
+            .iter()
+            .copied()
+            .map(finding_with_severity)
+            .collect(),
+        Metadata {
+            latest: None,
+            requested: None,
+            resolved: None,
+            published: None,
+            weekly_downloads: None,
+            fetch_ms: None,
+        },
+        Vec::new(),
+        Severity::Medium,
+        None,
+        ReasonLimits::default(),
+    )
+    .score
+}
+
+#[test]
+fn risk_score_grows_with_finding_count_and_severity() {
+    assert_eq!(score_for(&[]), 0);
+    assert_eq!(score_for(&[Severity::Info]), 0);
+    assert!(score_for(&[Severity::Low]) < score_for(&[Severity::Low, Severity::Low]));
+    assert!(score_for(&[Severity::Low]) < score_for(&[Severity::Medium]));
+    assert!(score_for(&[Severity::Medium]) < score_for(&[Severity::High]));
+    assert!(score_for(&[Severity::High]) < score_for(&[Severity::Critical]));
+    assert!(
+        score_for(&[Severity::Medium, Severity::Medium])
+            < score_for(&[Severity::Medium, Severity::Medium, Severity::Medium])
+    );
+    assert_eq!(score_for(&[Severity::Critical, Severity::Critical]), 100);
+}
+
+#[test]
+fn risk_score_is_independent_of_the_categorical_risk() {
+    // The medium pair escalates `risk` to high, but the score stays a plain weighted sum.
+    let pair = score_for(&[Severity::Medium, Severity::Medium]);
+    assert_eq!(pair, risk_score([Severity::Medium, Severity::Medium]));
+    assert!(pair < score_for(&[Severity::High]));
+}
+
+#[tokio::test]
+async fn denylist_package_rule_denies_immediately() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(100),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.denylist.packages = vec!["demo".to_string()];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert_eq!(report.risk, Severity::Critical);
+    assert!(!report.allow);
+    assert!(
+        report
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("denylist"))
+    );
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "denylist.package")
+    );
+}
+
+#[tokio::test]
+async fn allowlist_package_rule_allows_immediately() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 1)),
+        weekly_downloads: Some(0),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.allowlist.packages = vec!["demo".to_string()];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert_eq!(report.risk, Severity::Low);
+    assert!(report.allow);
+    assert!(
+        report
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("allowlist"))
+    );
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "allowlist.package")
+    );
+}
+
+fn config_matching_both_lists(precedence: ListPrecedence) -> SafePkgsConfig {
+    let mut config = default_config();
+    config.denylist.patterns = vec!["dem*".to_string()];
+    config.allowlist.packages = vec!["demo@1.0.0".to_string()];
+    config.list_precedence = precedence;
+    config
+}
+
+#[tokio::test]
+async fn deny_first_precedence_denies_package_on_both_lists() {
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(100),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let config = config_matching_both_lists(ListPrecedence::DenyFirst);
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &all_supported_checks(),
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(!report.allow);
+    assert_eq!(report.evidence[0].id, "denylist.pattern");
+}
+
+#[tokio::test]
+async fn allow_first_precedence_lets_allowlisted_version_override_denylist() {
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.1.0", 30)),
+        weekly_downloads: Some(100),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let config = config_matching_both_lists(ListPrecedence::AllowFirst);
+    let supported = all_supported_checks();
+
+    let report = run_all_checks("demo", Some("1.0.0"), "npm", &supported, &client, &config)
+        .await
+        .expect("check report");
+    assert!(report.allow);
+    assert_eq!(report.evidence[0].id, "allowlist.package");
+
+    // Versions the allowlist does not name still fall through to the denylist.
+    let report = run_all_checks("demo", Some("1.1.0"), "npm", &supported, &client, &config)
+        .await
+        .expect("check report");
+    assert!(!report.allow);
+    assert_eq!(report.evidence[0].id, "denylist.pattern");
+}
+
+#[tokio::test]
+async fn denylist_publisher_rule_denies_immediately() {
+    let supported_checks = all_supported_checks();
+    let mut record = package_record("1.0.0", "1.0.0", 30);
+    record.publishers = vec!["suspicious-user".to_string()];
+    let client = FakeRegistryClient {
+        result: Ok(record),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.denylist.publishers = vec!["suspicious-user".to_string()];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert_eq!(report.risk, Severity::Critical);
+    assert!(!report.allow);
+    assert!(
+        report
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("publisher"))
+    );
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "denylist.publisher")
+    );
+}
+
+#[tokio::test]
+async fn dependency_confusion_public_shadow_is_denied() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.dependency_confusion.internal_packages = vec!["demo".to_string()];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert_eq!(report.risk, Severity::Critical);
+    assert!(!report.allow);
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "dependency_confusion.public_shadow")
+    );
+}
+
+#[tokio::test]
+async fn dependency_confusion_scope_match_is_denied() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.dependency_confusion.internal_scopes = vec!["@myorg".to_string()];
+
+    let report = run_all_checks(
+        "@myorg/widget",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert_eq!(report.risk, Severity::Critical);
+    assert!(!report.allow);
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "dependency_confusion.public_shadow")
+    );
+}
+
+#[tokio::test]
+async fn dependency_confusion_prefix_match_is_denied() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.dependency_confusion.internal_prefixes = vec!["@acme/".to_string(), "acme-".to_string()];
+
+    for package_name in ["@acme/secret", "acme-billing"] {
+        let report = run_all_checks(
+            package_name,
+            Some("1.0.0"),
+            "npm",
+            &supported_checks,
+            &client,
+            &config,
+        )
+        .await
+        .expect("check report");
+
+        assert_eq!(report.risk, Severity::Critical, "{package_name}");
+        assert!(!report.allow);
+        assert!(
+            report
+                .evidence
+                .iter()
+                .any(|item| item.id == "dependency_confusion.public_shadow")
+        );
+    }
+}
+
+#[tokio::test]
+async fn dependency_confusion_prefix_not_emitted_on_not_found() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Err(RegistryError::NotFound {
+            registry: "npm",
+            package: "@acme/secret".to_string(),
+        }),
+        weekly_downloads: None,
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.dependency_confusion.internal_prefixes = vec!["@acme/".to_string()];
+
+    let report = run_all_checks(
+        "@acme/secret",
+        None,
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(
+        !report
+            .evidence
+            .iter()
+            .any(|item| item.id == "dependency_confusion.public_shadow")
+    );
+}
+
+#[tokio::test]
+async fn dependency_confusion_ignores_non_internal_name() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.dependency_confusion.internal_packages = vec!["internal-only".to_string()];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(
+        !report
+            .evidence
+            .iter()
+            .any(|item| item.id == "dependency_confusion.public_shadow")
+    );
+}
+
+#[tokio::test]
+async fn dependency_confusion_not_emitted_on_not_found() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Err(RegistryError::NotFound {
+            registry: "npm",
+            package: "internal-pkg".to_string(),
+        }),
+        weekly_downloads: None,
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.dependency_confusion.internal_packages = vec!["internal-pkg".to_string()];
+
+    let report = run_all_checks(
+        "internal-pkg",
+        None,
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(
+        !report
+            .evidence
+            .iter()
+            .any(|item| item.id == "dependency_confusion.public_shadow"),
+        "internal name absent from the public registry must not trigger dependency confusion"
+    );
+}
+
+#[tokio::test]
+async fn registry_disabled_check_is_skipped() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(10),
+        popular_packages: vec!["react".to_string(), "lodash".to_string()],
+        advisories: Vec::new(),
+    };
+
+    let mut config = default_config();
+    config.checks.registry.insert(
+        "npm".to_string(),
+        crate::config::RegistryChecksConfig {
+            disable: vec!["typosquat".to_string()],
+        },
+    );
+
+    let report = run_all_checks(
+        "raect",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(
+        !report.reasons.iter().any(|reason| reason.contains("react")),
+        "typosquat finding should be disabled for npm"
+    );
+}
+
+#[tokio::test]
+async fn globally_disabled_check_is_skipped() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(10),
+        popular_packages: vec!["react".to_string(), "lodash".to_string()],
+        advisories: Vec::new(),
+    };
+
+    let mut config = default_config();
+    config.checks.disable = vec!["typosquat".to_string()];
+
+    let report = run_all_checks(
+        "raect",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(
+        !report.reasons.iter().any(|reason| reason.contains("react")),
+        "typosquat finding should be disabled globally"
+    );
+    assert!(
+        !report
+            .evidence
+            .iter()
+            .any(|evidence| evidence.id.starts_with("typosquat."))
+    );
+    assert!(!report.checks_run.iter().any(|check| check == "typosquat"));
+}
+
+#[tokio::test]
+async fn unsupported_check_is_skipped_for_registry() {
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let cargo_supported_without_install_scripts = [
+        "existence",
+        "version_age",
+        "staleness",
+        "popularity",
+        "typosquat",
+        "advisory",
+    ];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "cargo",
+        &cargo_supported_without_install_scripts,
+        &client,
+        &default_config(),
+    )
+    .await
+    .expect("check report");
+
+    assert!(
+        !report
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("install hook")),
+        "install_script finding should be skipped when registry does not support it"
+    );
+}
+
+#[tokio::test]
+async fn trusted_publisher_skips_adoption_checks_but_not_advisories() {
+    let mut package = package_record("1.0.0", "1.0.0", 0);
+    package.publishers = vec!["Acme-Bot".to_string()];
+    let client = FakeRegistryClient {
+        result: Ok(package),
+        weekly_downloads: Some(3),
+        popular_packages: Vec::new(),
+        advisories: vec![PackageAdvisory {
+            id: "GHSA-demo".to_string(),
+            aliases: Vec::new(),
+            fixed_versions: Vec::new(),
+            git_range_only: false,
+            cvss_score: None,
+            severity: Vec::new(),
+        }],
+    };
+    let mut config = default_config();
+    config.allowlist.publishers = vec!["acme-bot".to_string()];
+    let supported = all_supported_checks();
+
+    let report = run_all_checks("demo", Some("1.0.0"), "npm", &supported, &client, &config)
+        .await
+        .expect("check report");
+
+    assert!(!report.allow);
+    let evidence_ids = report
+        .evidence
+        .iter()
+        .map(|item| item.id.as_str())
+        .collect::<Vec<_>>();
+    assert!(evidence_ids.contains(&"advisory.known_advisory"));
+    assert!(evidence_ids.contains(&"allowlist.trusted_publisher"));
+    assert!(
+        !evidence_ids
+            .iter()
+            .any(|id| id.starts_with("popularity.") || id.starts_with("version_age.")),
+        "adoption checks should be skipped: {evidence_ids:?}"
+    );
+}
+
+async fn pinned_major_report(fixed_versions: &[&str]) -> CheckReport {
+    let client = FakeRegistryClient {
+        result: Ok(package_record("4.0.0", "1.2.0", 400)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: vec![PackageAdvisory {
+            id: "GHSA-pinned".to_string(),
+            aliases: Vec::new(),
+            fixed_versions: fixed_versions.iter().map(ToString::to_string).collect(),
+            git_range_only: false,
+            cvss_score: None,
+            severity: Vec::new(),
+        }],
+    };
+    run_all_checks(
+        "demo",
+        Some("1.2.0"),
+        "npm",
+        &all_supported_checks(),
+        &client,
+        &default_config(),
+    )
+    .await
+    .expect("check report")
+}
+
+#[tokio::test]
+async fn advisory_fixed_only_in_later_major_flags_pinned_vulnerable_major() {
+    let report = pinned_major_report(&["4.0.0"]).await;
+
+    let evidence = report
+        .evidence
+        .iter()
+        .find(|item| item.id == "advisory.pinned_vulnerable_major")
+        .expect("pinned vulnerable major evidence");
+    assert_eq!(evidence.severity, Severity::High);
+    assert_eq!(evidence.facts["fixed_versions"], json!(["4.0.0"]));
+    assert!(!report.allow);
+}
+
+#[tokio::test]
+async fn advisory_fixed_within_pinned_major_is_not_escalated() {
+    let report = pinned_major_report(&["1.2.5", "4.0.0"]).await;
+
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "advisory.known_advisory")
+    );
+    assert!(
+        !report
+            .evidence
+            .iter()
+            .any(|item| item.id == "advisory.pinned_vulnerable_major")
+    );
+}
+
+async fn suppressed_advisory_report(check: &str, until_days_from_now: i64) -> CheckReport {
+    suppressed_advisory_report_with_max_risk(check, until_days_from_now, default_config().max_risk)
+        .await
+}
+
+async fn suppressed_advisory_report_with_max_risk(
+    check: &str,
+    until_days_from_now: i64,
+    max_risk: Severity,
+) -> CheckReport {
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: vec![PackageAdvisory {
+            id: "GHSA-snoozed".to_string(),
+            aliases: Vec::new(),
+            fixed_versions: Vec::new(),
+            git_range_only: false,
+            cvss_score: None,
+            severity: Vec::new(),
+        }],
+    };
+    let config = SafePkgsConfig {
+        max_risk,
+        suppressions: vec![crate::config::SuppressionConfig {
+            package: "demo".to_string(),
+            check: check.to_string(),
+            until: Utc::now() + Duration::days(until_days_from_now),
+            reason: "not reachable from our code".to_string(),
+        }],
+        ..default_config()
+    };
+    run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &all_supported_checks(),
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report")
+}
+
+#[tokio::test]
+async fn active_suppression_hides_finding_and_records_it() {
+    let report = suppressed_advisory_report("advisory", 7).await;
+
+    assert!(report.allow);
+    assert!(
+        !report
+            .evidence
+            .iter()
+            .any(|item| item.id == "advisory.known_advisory")
+    );
+    let suppression = report
+        .evidence
+        .iter()
+        .find(|item| item.id == "suppression.applied")
+        .expect("suppression evidence");
+    assert_eq!(
+        suppression.facts["suppressed_evidence_id"],
+        json!("advisory.known_advisory")
+    );
+}
+
+#[tokio::test]
+async fn active_suppression_does_not_count_toward_risk() {
+    let report = suppressed_advisory_report_with_max_risk("advisory", 7, Severity::Low).await;
+
+    assert!(report.allow);
+    assert_eq!(report.score, 0);
+    let suppression = report
+        .evidence
+        .iter()
+        .find(|item| item.id == "suppression.applied")
+        .expect("suppression evidence");
+    assert_eq!(suppression.severity, Severity::Info);
+}
+
+#[tokio::test]
+async fn expired_suppression_does_not_hide_finding() {
+    let report = suppressed_advisory_report("advisory", -1).await;
+
+    assert!(!report.allow);
+    let advisory = report
+        .evidence
+        .iter()
+        .find(|item| item.id == "advisory.known_advisory")
+        .expect("advisory evidence");
+    assert_eq!(advisory.code, "ADV001");
+    assert!(
+        !report
+            .evidence
+            .iter()
+            .any(|item| item.id == "suppression.applied")
+    );
+}
+
+#[tokio::test]
+async fn suppression_matches_finding_code() {
+    let report = suppressed_advisory_report("adv001", 7).await;
+
+    assert!(report.allow);
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "suppression.applied")
+    );
+}
+
+#[tokio::test]
+async fn fetch_latency_is_reported_only_when_enabled() {
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 30)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let supported = all_supported_checks();
+    let mut config = default_config();
+
+    let report = run_all_checks("demo", Some("1.0.0"), "npm", &supported, &client, &config)
+        .await
+        .expect("check report");
+    assert!(report.metadata.fetch_ms.is_none());
+
+    config.include_fetch_latency = true;
+    let report = run_all_checks("demo", Some("1.0.0"), "npm", &supported, &client, &config)
+        .await
+        .expect("check report");
+    assert!(report.metadata.fetch_ms.is_some());
+}
+
+#[tokio::test]
+async fn denylist_pattern_blocks_matching_names_before_fetch() {
+    let client = FakeRegistryClient {
+        result: Err(RegistryError::Transport {
+            message: "registry should not be called".to_string(),
+        }),
+        weekly_downloads: None,
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.denylist.patterns = vec!["*-PAYLOAD-test".to_string(), "mal?are-*".to_string()];
+    let supported = all_supported_checks();
+
+    for name in ["evil-payload-test", "malware-kit"] {
+        let report = run_all_checks(name, None, "npm", &supported, &client, &config)
+            .await
+            .expect("pattern deny should not touch the registry");
+        assert!(!report.allow);
+        assert_eq!(report.risk, Severity::Critical);
+        assert_eq!(report.evidence[0].id, "denylist.pattern");
+    }
+}
+
+#[test]
+fn denylist_pattern_leaves_non_matching_names_alone() {
+    let patterns = vec!["*-payload-test".to_string(), "mal?are-*".to_string()];
+    assert_eq!(matching_name_pattern(&patterns, "payload-parser"), None);
+    assert_eq!(matching_name_pattern(&patterns, "payload-test"), None);
+    assert_eq!(
+        matching_name_pattern(&patterns, "a-payload-test"),
+        Some("*-payload-test")
+    );
+}
+
+#[test]
+fn package_rules_match_registry_normalized_names() {
+    let rules = vec!["Flask".to_string(), "Zope.Interface@6.0".to_string()];
+
+    assert_eq!(
+        matching_package_rule(&rules, RegistryEcosystem::PyPI, "flask", None, None),
+        Some("Flask")
+    );
+    assert_eq!(
+        matching_package_rule(
+            &rules,
+            RegistryEcosystem::PyPI,
+            "zope-interface",
+            Some("6.0"),
+            None
+        ),
+        Some("Zope.Interface@6.0")
+    );
+    assert_eq!(
+        matching_package_rule(&rules, RegistryEcosystem::Npm, "FLASK", None, None),
+        Some("Flask")
+    );
+    assert_eq!(
+        matching_package_rule(&rules, RegistryEcosystem::CratesIo, "flask", None, None),
+        None
+    );
+}
+
+#[tokio::test]
+async fn denylist_matches_case_insensitively_on_npm() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 40)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.denylist.packages = vec!["Demo".to_string()];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(!report.allow);
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "denylist.package")
+    );
+}
+
+#[test]
+fn descriptor_registry_matches_runnable_checks() {
+    let descriptor_ids = check_descriptors()
+        .iter()
+        .map(|descriptor| descriptor.id)
+        .collect::<Vec<_>>();
+    let expected = all_supported_checks();
+    assert_eq!(descriptor_ids.as_slice(), expected.as_slice());
+}
+
+#[test]
+fn runtime_requirements_derive_from_enabled_checks() {
+    let supported_checks = all_supported_checks();
+    let mut config = default_config();
+    config.checks.disable = vec![
+        "advisory".to_string(),
+        "popularity".to_string(),
+        "bus_factor".to_string(),
+        "single_version".to_string(),
+    ];
+    config.checks.registry.insert(
+        "npm".to_string(),
+        crate::config::RegistryChecksConfig {
+            disable: vec!["typosquat".to_string()],
+        },
+    );
+
+    let requirements = runtime_requirements_for_registry("npm", &supported_checks, &config);
+    assert!(!requirements.needs_weekly_downloads);
+    assert!(!requirements.needs_advisories);
+}
+
+#[test]
+fn enabled_check_ids_for_registry_are_sorted_and_normalized() {
+    let supported_checks = all_supported_checks();
+    let mut config = default_config();
+    config.checks.disable = vec!["Version-Age".to_string(), "typosquat".to_string()];
+    config.checks.registry.insert(
+        "npm".to_string(),
+        crate::config::RegistryChecksConfig {
+            disable: vec!["install-script".to_string()],
+        },
+    );
+
+    let enabled = enabled_check_ids_for_registry("npm", &supported_checks, &config);
+    let mut sorted = enabled.clone();
+    sorted.sort();
+    assert_eq!(enabled, sorted);
+    assert!(!enabled.iter().any(|id| id == "version_age"));
+    assert!(!enabled.iter().any(|id| id == "typosquat"));
+    assert!(!enabled.iter().any(|id| id == "install_script"));
+}
+
+#[tokio::test]
+async fn custom_rule_match_emits_finding() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 40)),
+        weekly_downloads: Some(10),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.checks.disable = vec![
+        "version_age".to_string(),
+        "staleness".to_string(),
+        "popularity".to_string(),
+        "install_script".to_string(),
+        "typosquat".to_string(),
+        "advisory".to_string(),
+    ];
+    config.custom_rules = vec![CustomRuleConfig {
+        id: "low-downloads".to_string(),
+        enabled: true,
+        registries: vec!["npm".to_string()],
+        match_mode: CustomRuleMatchMode::All,
+        severity: Severity::High,
+        reason: Some("weekly downloads below allowed floor".to_string()),
+        conditions: vec![CustomRuleCondition {
+            field: CustomRuleField::WeeklyDownloads,
+            op: CustomRuleOperator::Lt,
+            value: Some(json!(20)),
+        }],
+    }];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(!report.allow);
+    assert_eq!(report.risk, Severity::High);
+    assert!(
+        report
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("custom rule 'low-downloads' matched")),
+        "custom rule finding should be included in reasons"
+    );
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.kind == EvidenceKind::CustomRule
+                && item.id == "custom_rule.low-downloads"),
+        "custom rule evidence should include rule id"
+    );
+}
+
+#[tokio::test]
+async fn custom_rule_denies_gpl_license() {
+    let supported_checks = all_supported_checks();
+    let mut record = package_record("1.0.0", "1.0.0", 40);
+    if let Some(version) = record.versions.get_mut("1.0.0") {
+        version.license = Some("GPL-3.0-only".to_string());
+    }
+    let client = FakeRegistryClient {
+        result: Ok(record),
+        weekly_downloads: Some(10_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.checks.disable = all_supported_checks()
+        .into_iter()
+        .map(ToString::to_string)
+        .collect();
+    config.custom_rules = vec![CustomRuleConfig {
+        id: "no-gpl".to_string(),
+        enabled: true,
+        registries: Vec::new(),
+        match_mode: CustomRuleMatchMode::All,
+        severity: Severity::High,
+        reason: Some("GPL-licensed packages are not allowed".to_string()),
+        conditions: vec![CustomRuleCondition {
+            field: CustomRuleField::License,
+            op: CustomRuleOperator::Contains,
+            value: Some(json!("GPL")),
+        }],
+    }];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(!report.allow);
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "custom_rule.no-gpl")
+    );
+}
+
+#[tokio::test]
+async fn custom_rule_flags_suspicious_repository_tld() {
+    let supported_checks = all_supported_checks();
+    let mut record = package_record("1.0.0", "1.0.0", 40);
+    if let Some(version) = record.versions.get_mut("1.0.0") {
+        version.repository_url = Some("https://git.example.ru/demo/demo".to_string());
+    }
+    let client = FakeRegistryClient {
+        result: Ok(record),
+        weekly_downloads: Some(10_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.checks.disable = all_supported_checks()
+        .into_iter()
+        .map(ToString::to_string)
+        .collect();
+    config.custom_rules = vec![CustomRuleConfig {
+        id: "ru-repository".to_string(),
+        enabled: true,
+        registries: Vec::new(),
+        match_mode: CustomRuleMatchMode::Any,
+        severity: Severity::Low,
+        reason: Some("repository hosted under .ru".to_string()),
+        conditions: vec![
+            CustomRuleCondition {
+                field: CustomRuleField::RepositoryUrl,
+                op: CustomRuleOperator::EndsWith,
+                value: Some(json!(".ru")),
+            },
+            CustomRuleCondition {
+                field: CustomRuleField::RepositoryUrl,
+                op: CustomRuleOperator::Contains,
+                value: Some(json!(".ru/")),
+            },
+        ],
+    }];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(report.allow, "low-severity flag stays under max_risk");
+    assert_eq!(report.risk, Severity::Low);
+    assert!(
+        report
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("custom rule 'ru-repository' matched"))
+    );
+}
+
+#[tokio::test]
+async fn custom_rule_flags_weekly_downloads_spike_over_stored_baseline() {
+    use crate::cache::SqliteCache;
+    use crate::registries::{DownloadHistoryClient, DownloadHistoryStore};
+    use crate::test_support::MockRegistryClient;
+    use std::sync::Arc;
+
+    let supported_checks = all_supported_checks();
+    let record = package_record("1.0.0", "1.0.0", 40);
+    let mut config = default_config();
+    config.checks.disable = all_supported_checks()
+        .into_iter()
+        .map(ToString::to_string)
+        .collect();
+    config.custom_rules = vec![CustomRuleConfig {
+        id: "download-spike".to_string(),
+        enabled: true,
+        registries: Vec::new(),
+        match_mode: CustomRuleMatchMode::All,
+        severity: Severity::High,
+        reason: Some("weekly downloads jumped sharply".to_string()),
+        conditions: vec![CustomRuleCondition {
+            field: CustomRuleField::WeeklyDownloadsChange,
+            op: CustomRuleOperator::Gt,
+            value: Some(json!(500)),
+        }],
+    }];
+    let cache = Arc::new(SqliteCache::in_memory(30).expect("in-memory cache"));
+    let store = DownloadHistoryStore::new(cache, std::time::Duration::from_secs(3600));
+
+    // The first evaluation has no baseline yet and records 1,000 weekly downloads.
+    let steady = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(record.clone())
+        .with_weekly_downloads("demo", 1_000);
+    let client = DownloadHistoryClient::new(&steady, Some(&store), "npm");
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+    assert!(report.allow);
+
+    // 8,000 downloads is a 700% jump over the stored baseline.
+    let spiked = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(record)
+        .with_weekly_downloads("demo", 8_000);
+    let client = DownloadHistoryClient::new(&spiked, Some(&store), "npm");
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+    assert!(!report.allow);
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "custom_rule.download-spike")
+    );
+}
+
+#[tokio::test]
+async fn info_findings_surface_as_reasons_but_never_deny() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 40)),
+        weekly_downloads: Some(10),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.checks.disable = all_supported_checks()
+        .into_iter()
+        .map(ToString::to_string)
+        .collect();
+    config.max_risk = Severity::Info;
+    config.custom_rules = vec![CustomRuleConfig {
+        id: "fyi-low-downloads".to_string(),
+        enabled: true,
+        registries: Vec::new(),
+        match_mode: CustomRuleMatchMode::All,
+        severity: Severity::Info,
+        reason: Some("weekly downloads are modest".to_string()),
+        conditions: vec![CustomRuleCondition {
+            field: CustomRuleField::WeeklyDownloads,
+            op: CustomRuleOperator::Lt,
+            value: Some(json!(20)),
+        }],
+    }];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(report.allow, "info findings must never deny");
+    assert_eq!(report.risk, Severity::Low);
+    assert!(
+        report
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("custom rule 'fyi-low-downloads' matched"))
+    );
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "custom_rule.fyi-low-downloads"
+                && item.severity == Severity::Info)
+    );
+}
+
+#[test]
+fn severity_info_orders_below_low_and_uses_lowercase_name() {
+    assert!(Severity::Info < Severity::Low);
+    assert_eq!(json!(Severity::Info), json!("info"));
+    let parsed: Severity = serde_json::from_value(json!("info")).expect("parse info");
+    assert_eq!(parsed, Severity::Info);
+}
+
+#[test]
+fn runtime_requirements_include_custom_rules() {
+    let supported_checks = all_supported_checks();
+    let mut config = default_config();
+    config.checks.disable = vec!["advisory".to_string(), "popularity".to_string()];
+    config.custom_rules = vec![CustomRuleConfig {
+        id: "needs-extra-data".to_string(),
+        enabled: true,
+        registries: vec!["npm".to_string()],
+        match_mode: CustomRuleMatchMode::Any,
+        severity: Severity::Medium,
+        reason: None,
+        conditions: vec![
+            CustomRuleCondition {
+                field: CustomRuleField::WeeklyDownloads,
+                op: CustomRuleOperator::Exists,
+                value: Some(json!(true)),
+            },
+            CustomRuleCondition {
+                field: CustomRuleField::AdvisoryCount,
+                op: CustomRuleOperator::Gt,
+                value: Some(json!(0)),
             },
         ],
     }];
@@ -719,3 +2151,449 @@ fn runtime_requirements_include_custom_rules() {
     assert!(requirements.needs_weekly_downloads);
     assert!(requirements.needs_advisories);
 }
+
+fn single_finding(severity: Severity) -> Vec<StructuredFinding> {
+    vec![StructuredFinding {
+        severity,
+        reason: "signal".to_string(),
+        evidence: Evidence {
+            kind: EvidenceKind::Check,
+            id: "demo.signal".to_string(),
+            code: "demo.signal".to_string(),
+            severity,
+            message: "signal".to_string(),
+            facts: std::collections::BTreeMap::new(),
+            remediation: None,
+        },
+    }]
+}
+
+fn empty_metadata() -> Metadata {
+    Metadata {
+        latest: None,
+        requested: None,
+        resolved: None,
+        published: None,
+        weekly_downloads: None,
+        fetch_ms: None,
+    }
+}
+
+#[test]
+fn deny_floor_allows_findings_below_floor() {
+    for severity in [Severity::Low, Severity::Medium] {
+        let report = report_from_findings(
+            single_finding(severity),
+            empty_metadata(),
+            Vec::new(),
+            Severity::Low,
+            Some(Severity::High),
+            ReasonLimits::default(),
+        );
+        assert_eq!(report.risk, severity);
+        assert!(
+            report.allow,
+            "{severity:?} should be allowed below the floor"
+        );
+        let expected_floor_evidence = severity > Severity::Low;
+        assert_eq!(
+            report
+                .evidence
+                .iter()
+                .any(|item| item.id == "risk.below_deny_floor"),
+            expected_floor_evidence
+        );
+    }
+}
+
+#[test]
+fn deny_floor_still_denies_at_or_above_floor() {
+    for severity in [Severity::High, Severity::Critical] {
+        let report = report_from_findings(
+            single_finding(severity),
+            empty_metadata(),
+            Vec::new(),
+            Severity::Low,
+            Some(Severity::High),
+            ReasonLimits::default(),
+        );
+        assert!(!report.allow, "{severity:?} should deny");
+    }
+
+    let report = report_from_findings(
+        single_finding(Severity::Critical),
+        empty_metadata(),
+        Vec::new(),
+        Severity::Low,
+        Some(Severity::Critical),
+        ReasonLimits::default(),
+    );
+    assert!(!report.allow, "critical always denies");
+}
+
+#[test]
+fn max_reasons_keeps_most_severe_and_summarizes_the_rest() {
+    let findings = [
+        Severity::Low,
+        Severity::Critical,
+        Severity::Medium,
+        Severity::High,
+        Severity::Low,
+    ]
+    .into_iter()
+    .map(finding_with_severity)
+    .collect();
+    let report = report_from_findings(
+        findings,
+        empty_metadata(),
+        Vec::new(),
+        Severity::Low,
+        None,
+        ReasonLimits {
+            max_reasons: Some(2),
+            max_reason_length: None,
+        },
+    );
+
+    assert_eq!(
+        report.reasons,
+        vec![
+            "Critical signal".to_string(),
+            "High signal".to_string(),
+            "… and 3 more findings".to_string(),
+        ]
+    );
+    assert_eq!(report.evidence.len(), 5, "evidence is never capped");
+}
+
+#[test]
+fn max_reason_length_truncates_long_reasons() {
+    let mut finding = finding_with_severity(Severity::High);
+    finding.reason = "demo@1.0.0 has a very long explanation".to_string();
+    let report = report_from_findings(
+        vec![finding, finding_with_severity(Severity::Low)],
+        empty_metadata(),
+        Vec::new(),
+        Severity::Low,
+        None,
+        ReasonLimits {
+            max_reasons: None,
+            max_reason_length: Some(12),
+        },
+    );
+
+    assert_eq!(
+        report.reasons,
+        vec!["demo@1.0.0…".to_string(), "Low signal".to_string()]
+    );
+    assert!(report.reasons[0].chars().count() <= 12);
+}
+
+fn advisory_client() -> FakeRegistryClient {
+    FakeRegistryClient {
+        result: Ok(package_record("1.0.0", "1.0.0", 100)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: vec![PackageAdvisory {
+            id: "GHSA-warn".to_string(),
+            aliases: Vec::new(),
+            fixed_versions: Vec::new(),
+            git_range_only: false,
+            cvss_score: None,
+            severity: Vec::new(),
+        }],
+    }
+}
+
+#[tokio::test]
+async fn warn_only_enforcement_allows_high_risk_with_reasons() {
+    let config = SafePkgsConfig {
+        enforcement: EnforcementMode::WarnOnly,
+        ..default_config()
+    };
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &all_supported_checks(),
+        &advisory_client(),
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(report.allow);
+    assert_eq!(report.risk, Severity::High);
+    assert!(
+        report
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("GHSA-warn"))
+    );
+    assert!(
+        report
+            .evidence
+            .iter()
+            .any(|item| item.id == "enforcement.warn_only")
+    );
+}
+
+#[tokio::test]
+async fn warn_only_enforcement_still_denies_denylisted_package() {
+    let mut config = SafePkgsConfig {
+        enforcement: EnforcementMode::WarnOnly,
+        ..default_config()
+    };
+    config.denylist.packages = vec!["demo".to_string()];
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &all_supported_checks(),
+        &advisory_client(),
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(!report.allow);
+    assert_eq!(report.risk, Severity::Critical);
+    assert!(
+        !report
+            .evidence
+            .iter()
+            .any(|item| item.id == "enforcement.warn_only")
+    );
+}
+
+#[tokio::test]
+async fn off_enforcement_skips_checks() {
+    let config = SafePkgsConfig {
+        enforcement: EnforcementMode::Off,
+        ..default_config()
+    };
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &all_supported_checks(),
+        &advisory_client(),
+        &config,
+    )
+    .await
+    .expect("check report");
+
+    assert!(report.allow);
+    assert_eq!(report.risk, Severity::Low);
+    assert!(report.checks_run.is_empty());
+    assert_eq!(report.evidence[0].id, "enforcement.off");
+}
+
+#[tokio::test]
+async fn advisory_evidence_carries_upgrade_remediation() {
+    let supported_checks = all_supported_checks();
+    let client = FakeRegistryClient {
+        result: Ok(package_record("2.0.0", "1.0.0", 400)),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: vec![PackageAdvisory {
+            id: "GHSA-demo".to_string(),
+            aliases: Vec::new(),
+            fixed_versions: vec!["1.4.2".to_string()],
+            git_range_only: false,
+            cvss_score: None,
+            severity: Vec::new(),
+        }],
+    };
+
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client,
+        &default_config(),
+    )
+    .await
+    .expect("check report");
+    let evidence = report
+        .evidence
+        .iter()
+        .find(|item| item.id == "advisory.known_advisory")
+        .expect("advisory evidence");
+    assert_eq!(
+        evidence.remediation.as_deref(),
+        Some("upgrade to demo@1.4.2 or later")
+    );
+}
+
+#[test]
+fn upgrade_from_current_rates_major_minor_and_patch_deltas() {
+    let upgrade_from_current_evidence = |package, current, proposed, scheme| {
+        upgrade_from_current_finding(package, current, proposed, scheme)
+            .map(|finding| finding.evidence)
+    };
+    let major = upgrade_from_current_evidence("lodash", "4.17.21", "5.0.0", VersionScheme::SemVer)
+        .expect("major upgrade");
+    assert_eq!(major.id, "version.major_upgrade");
+    assert_eq!(major.severity, Severity::Medium);
+    assert_eq!(
+        major.message,
+        "lodash: major upgrade from current 4.17.21 to proposed 5.0.0"
+    );
+    assert_eq!(major.facts["current_version"], json!("4.17.21"));
+    assert_eq!(major.facts["proposed_version"], json!("5.0.0"));
+
+    let minor = upgrade_from_current_evidence("lodash", "4.17.21", "4.18.0", VersionScheme::SemVer)
+        .expect("minor upgrade");
+    assert_eq!(minor.id, "version.minor_upgrade");
+    assert_eq!(minor.severity, Severity::Low);
+
+    for (current, proposed) in [
+        ("4.17.20", "4.17.21"),
+        ("4.17.21", "4.17.21"),
+        ("5.0.0", "4.17.21"),
+        ("not-a-version", "5.0.0"),
+    ] {
+        assert!(
+            upgrade_from_current_evidence("lodash", current, proposed, VersionScheme::SemVer)
+                .is_none(),
+            "{current} -> {proposed}"
+        );
+    }
+
+    let pep440 = upgrade_from_current_evidence("django", "4.2", "5.0rc1", VersionScheme::Pep440)
+        .expect("pep440 major upgrade");
+    assert_eq!(pep440.severity, Severity::Medium);
+
+    let zero = upgrade_from_current_evidence("zod", "0.3.1", "0.4.0", VersionScheme::SemVer)
+        .expect("0.x minor upgrade");
+    assert_eq!(zero.id, "version.major_upgrade");
+}
+
+#[tokio::test]
+async fn require_publish_dates_flags_records_without_any_timestamps() {
+    let supported_checks = all_supported_checks();
+    let mut undated = package_record("1.1.0", "1.0.0", 30);
+    for version in undated.versions.values_mut() {
+        version.published = None;
+    }
+    let client = |record: PackageRecord| FakeRegistryClient {
+        result: Ok(record),
+        weekly_downloads: Some(1_000_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let has_gap_evidence = |report: &CheckReport| {
+        report
+            .evidence
+            .iter()
+            .any(|evidence| evidence.id == "version.no_publish_dates")
+    };
+
+    let mut config = default_config();
+    config.require_publish_dates = true;
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client(undated.clone()),
+        &config,
+    )
+    .await
+    .expect("check report");
+    assert!(has_gap_evidence(&report), "{report:?}");
+    assert!(
+        report.reasons.iter().any(|reason| {
+            reason == "demo: no publish dates available; age-based checks skipped"
+        })
+    );
+    let evidence = report
+        .evidence
+        .iter()
+        .find(|evidence| evidence.id == "version.no_publish_dates")
+        .expect("gap evidence");
+    assert_eq!(evidence.severity, Severity::Low);
+    assert_eq!(evidence.facts["version_count"], json!(2));
+
+    // Off by default, and a single dated version is enough to measure age.
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client(undated),
+        &default_config(),
+    )
+    .await
+    .expect("check report");
+    assert!(!has_gap_evidence(&report));
+
+    let mut partly_dated = package_record("1.1.0", "1.0.0", 30);
+    if let Some(version) = partly_dated.versions.get_mut("1.1.0") {
+        version.published = None;
+    }
+    let report = run_all_checks(
+        "demo",
+        Some("1.0.0"),
+        "npm",
+        &supported_checks,
+        &client(partly_dated),
+        &config,
+    )
+    .await
+    .expect("check report");
+    assert!(!has_gap_evidence(&report));
+}
+
+#[tokio::test]
+async fn patch_exemption_needs_the_installed_version_as_baseline() {
+    let client = FakeRegistryClient {
+        result: Ok(package_record("1.2.3", "1.2.4", 1)),
+        weekly_downloads: Some(100_000),
+        popular_packages: Vec::new(),
+        advisories: Vec::new(),
+    };
+    let mut config = default_config();
+    config.version_age.exempt_patch_upgrades = true;
+
+    let evidence_ids = |report: &CheckReport| {
+        report
+            .evidence
+            .iter()
+            .map(|item| item.id.clone())
+            .collect::<Vec<_>>()
+    };
+    // An aged 1.2.3 in the registry alone does not vouch for 1.2.4.
+    let unknown = run_all_checks(
+        "demo",
+        Some("1.2.4"),
+        "npm",
+        &all_supported_checks(),
+        &client,
+        &config,
+    )
+    .await
+    .expect("check report");
+    assert!(evidence_ids(&unknown).contains(&"version_age.too_new".to_string()));
+
+    let installed = run_all_checks_at_time(
+        "demo",
+        Some("1.2.4"),
+        "npm",
+        &all_supported_checks(),
+        &client,
+        &config,
+        Some("1.2.3"),
+        Utc::now(),
+    )
+    .await
+    .expect("check report");
+    let ids = evidence_ids(&installed);
+    assert!(ids.contains(&"version_age.patch_upgrade_exempt".to_string()));
+    assert!(!ids.contains(&"version_age.too_new".to_string()));
+}
@@ -17,6 +17,21 @@ fn missing_config_uses_defaults() {
     assert_eq!(config.min_version_age_days, DEFAULT_MIN_VERSION_AGE_DAYS);
     assert_eq!(config.min_weekly_downloads, DEFAULT_MIN_WEEKLY_DOWNLOADS);
     assert_eq!(config.max_risk, DEFAULT_MAX_RISK);
+    assert!(config.warn_on_unparseable_version);
+    assert!(!config.require_exact_version);
+    assert!(!config.require_publish_dates);
+    assert!(!config.include_fetch_latency);
+    assert_eq!(config.never_deny_below, None);
+    assert_eq!(config.enforcement, EnforcementMode::Enforce);
+    assert_eq!(config.list_precedence, ListPrecedence::DenyFirst);
+    assert_eq!(config.quarantine_hours, None);
+    assert_eq!(config.max_reasons, None);
+    assert_eq!(config.max_reason_length, None);
+    assert_eq!(
+        config.max_in_flight_requests,
+        DEFAULT_MAX_IN_FLIGHT_REQUESTS
+    );
+    assert_eq!(config.max_response_bytes, DEFAULT_MAX_RESPONSE_BYTES);
     assert_eq!(
         config.staleness.warn_major_versions_behind,
         DEFAULT_WARN_MAJOR_VERSIONS_BEHIND
@@ -29,6 +44,15 @@ fn missing_config_uses_defaults() {
     assert!(config.checks.disable.is_empty());
     assert!(config.checks.registry.is_empty());
     assert_eq!(config.cache.ttl_minutes, DEFAULT_CACHE_TTL_MINUTES);
+    assert_eq!(
+        config.cache.popular_names_ttl_minutes,
+        DEFAULT_POPULAR_NAMES_TTL_MINUTES
+    );
+    assert_eq!(
+        config.cache.downloads_ttl_minutes,
+        DEFAULT_DOWNLOADS_TTL_MINUTES
+    );
+    assert_eq!(config.audit_log.max_log_bytes, DEFAULT_AUDIT_LOG_MAX_BYTES);
     assert_eq!(
         config.lockfile.eval_concurrency,
         DEFAULT_LOCKFILE_EVAL_CONCURRENCY
@@ -37,7 +61,43 @@ fn missing_config_uses_defaults() {
         config.lockfile.inter_batch_delay_ms,
         DEFAULT_INTER_BATCH_DELAY_MS
     );
+    assert_eq!(
+        config.lockfile.advisory_concurrency,
+        DEFAULT_LOCKFILE_ADVISORY_CONCURRENCY
+    );
+    assert_eq!(config.lockfile.on_transport_error, TransportErrorMode::Deny);
+    assert!(!config.lockfile.report_unsupported_sources);
+    assert_eq!(
+        config.existence.not_found_retries,
+        DEFAULT_EXISTENCE_NOT_FOUND_RETRIES
+    );
+    assert_eq!(
+        config.existence.not_found_retry_delay_ms,
+        DEFAULT_EXISTENCE_NOT_FOUND_RETRY_DELAY_MS
+    );
+    assert_eq!(config.existence.missing_package, Severity::Critical);
+    assert_eq!(config.existence.missing_version, Severity::Critical);
+    assert!(config.typosquat.fail_open);
+    assert!(config.typosquat.popular_names_file.is_none());
+    assert_eq!(
+        config.bus_factor.high_download_threshold,
+        DEFAULT_BUS_FACTOR_HIGH_DOWNLOAD_THRESHOLD
+    );
+    assert_eq!(
+        config.release_churn.max_releases_per_week,
+        DEFAULT_RELEASE_CHURN_MAX_RELEASES_PER_WEEK
+    );
+    assert_eq!(
+        config.stale_stable.window_days,
+        DEFAULT_STALE_STABLE_WINDOW_DAYS
+    );
+    assert_eq!(config.advisory.min_cvss, None);
+    assert!(!config.version_age.exempt_patch_upgrades);
+    assert!(config.install_script.detect_persistence);
+    assert!(!config.install_script.deny_all);
+    assert!(config.install_script.allowed_commands.is_empty());
     assert!(config.custom_rules.is_empty());
+    assert!(config.suppressions.is_empty());
 }
 
 #[test]
@@ -47,13 +107,28 @@ fn parses_config_values_and_lists() {
 min_version_age_days = 14
 min_weekly_downloads = 250
 max_risk = "high"
+never_deny_below = "critical"
+enforcement = "warn_only"
+list_precedence = "allow_first"
+quarantine_hours = 24
+warn_on_unparseable_version = false
+require_exact_version = true
+require_publish_dates = true
+include_fetch_latency = true
+max_reasons = 5
+max_reason_length = 200
+max_in_flight_requests = 8
+max_response_bytes = 1048576
+npmrc = ".npmrc"
 
 [allowlist]
 packages = ["internal-lib", "internal-lib@1.2.3"]
+publishers = ["trusted-org"]
 
 [denylist]
 packages = ["bad-lib", "danger-lib@0.1.0"]
 publishers = ["suspicious-user"]
+patterns = ["*-test-malware"]
 
 [staleness]
 warn_major_versions_behind = 4
@@ -69,11 +144,56 @@ disable = ["install_script"]
 
 [cache]
 ttl_minutes = 45
+popular_names_ttl_minutes = 0
+downloads_ttl_minutes = 5
+
+[audit_log]
+max_log_bytes = 1048576
+max_files = 3
 
 [lockfile]
 eval_concurrency = 7
 inter_batch_delay_ms = 75
 
+[existence]
+not_found_retries = 3
+not_found_retry_delay_ms = 200
+missing_version = "high"
+
+[typosquat]
+fail_open = false
+popular_names_file = "/srv/safe-pkgs/popular.txt"
+ignore = ["reactx", "@acme/*"]
+
+[typosquat.popular_index_urls]
+NPM = "https://example.com/top-npm.json"
+
+[bus_factor]
+high_download_threshold = 250000
+
+[version_age]
+exempt_patch_upgrades = true
+
+[install_script]
+detect_persistence = false
+deny_all = true
+allowed_commands = ["node-gyp rebuild"]
+
+[release_churn]
+max_releases_per_week = 25
+
+[stale_stable]
+window_days = 90
+
+[advisory]
+min_cvss = 7.0
+
+[blackout]
+after = "2026-03-01T00:00:00Z"
+
+[blackout.registry.NPM]
+after = "2026-03-05T12:00:00Z"
+
 [[custom_rules]]
 id = "block-new-packages"
 severity = "high"
@@ -84,6 +204,12 @@ conditions = [
   { field = "version_age_days", op = "lt", value = 3 },
   { field = "weekly_downloads", op = "lt", value = 100 }
 ]
+
+[[suppressions]]
+package = "left-pad@1.3.0"
+check = "advisory"
+until = "2026-12-31T00:00:00Z"
+reason = "patched in our fork"
 "#;
     fs::write(&path, raw).expect("write config");
 
@@ -93,15 +219,30 @@ conditions = [
     assert_eq!(config.min_version_age_days, 14);
     assert_eq!(config.min_weekly_downloads, 250);
     assert_eq!(config.max_risk, Severity::High);
+    assert_eq!(config.never_deny_below, Some(Severity::Critical));
+    assert_eq!(config.enforcement, EnforcementMode::WarnOnly);
+    assert_eq!(config.list_precedence, ListPrecedence::AllowFirst);
+    assert_eq!(config.quarantine_hours, Some(24));
+    assert!(!config.warn_on_unparseable_version);
+    assert!(config.require_exact_version);
+    assert!(config.require_publish_dates);
+    assert_eq!(config.npmrc, Some(std::path::PathBuf::from(".npmrc")));
+    assert_eq!(config.max_reasons, Some(5));
+    assert_eq!(config.max_reason_length, Some(200));
+    assert!(config.include_fetch_latency);
+    assert_eq!(config.max_in_flight_requests, 8);
+    assert_eq!(config.max_response_bytes, 1_048_576);
     assert_eq!(
         config.allowlist.packages,
         vec!["internal-lib", "internal-lib@1.2.3"]
     );
+    assert_eq!(config.allowlist.publishers, vec!["trusted-org"]);
     assert_eq!(
         config.denylist.packages,
         vec!["bad-lib", "danger-lib@0.1.0"]
     );
     assert_eq!(config.denylist.publishers, vec!["suspicious-user"]);
+    assert_eq!(config.denylist.patterns, vec!["*-test-malware"]);
     assert_eq!(config.staleness.warn_major_versions_behind, 4);
     assert_eq!(config.staleness.warn_minor_versions_behind, 8);
     assert_eq!(config.staleness.warn_age_days, 500);
@@ -117,11 +258,64 @@ conditions = [
         vec!["install_script"]
     );
     assert_eq!(config.cache.ttl_minutes, 45);
+    assert_eq!(config.cache.popular_names_ttl_minutes, 0);
+    assert_eq!(config.cache.downloads_ttl_minutes, 5);
+    assert_eq!(config.audit_log.max_log_bytes, 1_048_576);
+    assert_eq!(config.audit_log.max_files, 3);
     assert_eq!(config.lockfile.eval_concurrency, 7);
     assert_eq!(config.lockfile.inter_batch_delay_ms, 75);
+    assert_eq!(config.existence.not_found_retries, 3);
+    assert_eq!(config.existence.not_found_retry_delay_ms, 200);
+    assert_eq!(config.existence.missing_package, Severity::Critical);
+    assert_eq!(config.existence.missing_version, Severity::High);
+    assert!(!config.typosquat.fail_open);
+    assert_eq!(
+        config.typosquat.popular_names_file,
+        Some(PathBuf::from("/srv/safe-pkgs/popular.txt"))
+    );
+    assert_eq!(config.typosquat.ignore, vec!["reactx", "@acme/*"]);
+    assert_eq!(
+        config
+            .typosquat
+            .popular_index_urls
+            .get("npm")
+            .map(String::as_str),
+        Some("https://example.com/top-npm.json")
+    );
+    assert_eq!(config.bus_factor.high_download_threshold, 250_000);
+    assert!(!config.install_script.detect_persistence);
+    assert!(config.install_script.deny_all);
+    assert_eq!(
+        config.install_script.allowed_commands,
+        vec!["node-gyp rebuild".to_string()]
+    );
+    assert_eq!(config.release_churn.max_releases_per_week, 25);
+    assert_eq!(config.stale_stable.window_days, 90);
+    assert_eq!(config.advisory.min_cvss, Some(7.0));
+    assert!(config.version_age.exempt_patch_upgrades);
+    assert_eq!(
+        config
+            .blackout
+            .after_for_registry("cargo")
+            .map(|ts| ts.to_rfc3339()),
+        Some("2026-03-01T00:00:00+00:00".to_string())
+    );
+    assert_eq!(
+        config
+            .blackout
+            .after_for_registry("npm")
+            .map(|ts| ts.to_rfc3339()),
+        Some("2026-03-05T12:00:00+00:00".to_string())
+    );
     assert_eq!(config.custom_rules.len(), 1);
     assert_eq!(config.custom_rules[0].id, "block-new-packages");
     assert_eq!(config.custom_rules[0].conditions.len(), 2);
+    assert_eq!(config.suppressions.len(), 1);
+    assert_eq!(config.suppressions[0].package, "left-pad@1.3.0");
+    assert_eq!(
+        config.suppressions[0].until.to_rfc3339(),
+        "2026-12-31T00:00:00+00:00"
+    );
 }
 
 #[test]
@@ -201,9 +395,12 @@ conditions = [
     )
     .expect("write project config");
 
-    let config =
-        SafePkgsConfig::load_with_paths(Some(global_path.clone()), Some(project_path.clone()))
-            .expect("merged config");
+    let config = SafePkgsConfig::load_with_paths(
+        Some(global_path.clone()),
+        Some(project_path.clone()),
+        None,
+    )
+    .expect("merged config");
 
     let _ = fs::remove_file(global_path);
     let _ = fs::remove_file(project_path);
@@ -301,6 +498,27 @@ conditions = [
     );
 }
 
+#[test]
+fn out_of_range_min_cvss_is_rejected() {
+    let path = unique_temp_path("invalid-min-cvss.toml");
+    fs::write(&path, "[advisory]\nmin_cvss = 12.5\n").expect("write config");
+
+    let err = SafePkgsConfig::load_from_path(&path).expect_err("min_cvss above 10 should fail");
+    let _ = fs::remove_file(path);
+    assert!(format!("{err:#}").contains("advisory.min_cvss must be between 0.0 and 10.0"));
+}
+
+#[test]
+fn out_of_range_min_coverage_ratio_is_rejected() {
+    let path = unique_temp_path("invalid-min-coverage.toml");
+    fs::write(&path, "[lockfile]\nmin_coverage_ratio = 1.5\n").expect("write config");
+
+    let err =
+        SafePkgsConfig::load_from_path(&path).expect_err("min_coverage_ratio above 1 should fail");
+    let _ = fs::remove_file(path);
+    assert!(format!("{err:#}").contains("lockfile.min_coverage_ratio must be between 0.0 and 1.0"));
+}
+
 #[test]
 fn float_numeric_custom_rule_value_is_rejected() {
     let path = unique_temp_path("float-custom-rule.toml");
@@ -322,6 +540,24 @@ conditions = [
     );
 }
 
+#[test]
+fn weekly_downloads_change_requires_numeric_value() {
+    let path = unique_temp_path("downloads-change-custom-rule.toml");
+    let raw = r#"
+[[custom_rules]]
+id = "download-spike"
+severity = "high"
+conditions = [
+  { field = "weekly_downloads_change", op = "gt", value = "500" }
+]
+"#;
+    fs::write(&path, raw).expect("write config");
+
+    let err = SafePkgsConfig::load_from_path(&path).expect_err("string threshold should fail");
+    let _ = fs::remove_file(path);
+    assert!(format!("{err:#}").contains("requires numeric value"));
+}
+
 #[test]
 fn lockfile_config_parses_with_custom_values() {
     let path = unique_temp_path("lockfile-config.toml");
@@ -329,6 +565,10 @@ fn lockfile_config_parses_with_custom_values() {
 [lockfile]
 eval_concurrency = 10
 inter_batch_delay_ms = 200
+advisory_concurrency = 2
+on_transport_error = "warn"
+min_coverage_ratio = 0.9
+report_unsupported_sources = true
 "#;
     fs::write(&path, raw).expect("write config");
 
@@ -337,6 +577,10 @@ inter_batch_delay_ms = 200
 
     assert_eq!(config.lockfile.eval_concurrency, 10);
     assert_eq!(config.lockfile.inter_batch_delay_ms, 200);
+    assert_eq!(config.lockfile.advisory_concurrency, 2);
+    assert_eq!(config.lockfile.on_transport_error, TransportErrorMode::Warn);
+    assert!(config.lockfile.report_unsupported_sources);
+    assert_eq!(config.lockfile.min_coverage_ratio, Some(0.9));
 }
 
 #[test]
@@ -424,9 +668,12 @@ eval_concurrency = 3
     )
     .expect("write project config");
 
-    let config =
-        SafePkgsConfig::load_with_paths(Some(global_path.clone()), Some(project_path.clone()))
-            .expect("merged config");
+    let config = SafePkgsConfig::load_with_paths(
+        Some(global_path.clone()),
+        Some(project_path.clone()),
+        None,
+    )
+    .expect("merged config");
 
     let _ = fs::remove_file(global_path);
     let _ = fs::remove_file(project_path);
@@ -458,6 +705,7 @@ async fn remote_source_overlay_is_applied() {
         }),
         None,
         None,
+        None,
     )
     .await
     .expect("remote config");
@@ -490,6 +738,7 @@ async fn project_overlay_overrides_remote_source() {
         }),
         None,
         Some(project_path.clone()),
+        None,
     )
     .await
     .expect("remote + project config");
@@ -535,6 +784,7 @@ async fn remote_source_does_not_send_bearer_token_over_http() {
         }),
         None,
         None,
+        None,
     )
     .await
     .expect("remote config over http drops the bearer token");
@@ -573,6 +823,7 @@ async fn remote_source_errors_on_non_success_status() {
         }),
         None,
         None,
+        None,
     )
     .await;
 
@@ -604,9 +855,12 @@ inter_batch_delay_ms = 0
     )
     .expect("write project config");
 
-    let config =
-        SafePkgsConfig::load_with_paths(Some(global_path.clone()), Some(project_path.clone()))
-            .expect("merged config");
+    let config = SafePkgsConfig::load_with_paths(
+        Some(global_path.clone()),
+        Some(project_path.clone()),
+        None,
+    )
+    .expect("merged config");
 
     let _ = fs::remove_file(global_path);
     let _ = fs::remove_file(project_path);
@@ -615,3 +869,279 @@ inter_batch_delay_ms = 0
     assert_eq!(config.lockfile.eval_concurrency, 2);
     assert_eq!(config.lockfile.inter_batch_delay_ms, 0);
 }
+
+#[test]
+fn selected_profile_overrides_base_config() {
+    let path = unique_temp_path("profiles.toml");
+    fs::write(
+        &path,
+        r#"
+max_risk = "medium"
+min_weekly_downloads = 50
+
+[denylist]
+packages = ["base-blocked"]
+
+[profiles.strict]
+max_risk = "low"
+
+[profiles.strict.denylist]
+packages = ["strict-blocked"]
+
+[profiles.relaxed]
+max_risk = "high"
+"#,
+    )
+    .expect("write config");
+
+    let base = SafePkgsConfig::load_with_paths(Some(path.clone()), None, None).expect("base");
+    let strict =
+        SafePkgsConfig::load_with_paths(Some(path.clone()), None, Some("strict")).expect("strict");
+    let missing = SafePkgsConfig::load_with_paths(Some(path.clone()), None, Some("staging"));
+    let _ = fs::remove_file(path);
+
+    assert_eq!(base.max_risk, Severity::Medium);
+    assert_eq!(base.denylist.packages, vec!["base-blocked".to_string()]);
+
+    assert_eq!(strict.max_risk, Severity::Low);
+    assert_eq!(strict.min_weekly_downloads, 50);
+    assert_eq!(
+        strict.denylist.packages,
+        vec!["base-blocked".to_string(), "strict-blocked".to_string()]
+    );
+
+    let err = missing.expect_err("undefined profile should fail");
+    assert!(err.to_string().contains("staging"), "{err:#}");
+}
+
+#[test]
+fn profiles_apply_after_every_base_layer() {
+    let global_path = unique_temp_path("global-profiles.toml");
+    let project_path = unique_temp_path("project-profiles.toml");
+    fs::write(&global_path, "[profiles.strict]\nmax_risk = \"low\"\n").expect("write global");
+    fs::write(
+        &project_path,
+        "max_risk = \"high\"\n\n[profiles.strict]\nmin_weekly_downloads = 10\n",
+    )
+    .expect("write project");
+
+    let config = SafePkgsConfig::load_with_paths(
+        Some(global_path.clone()),
+        Some(project_path.clone()),
+        Some("strict"),
+    )
+    .expect("merged config");
+    let _ = fs::remove_file(global_path);
+    let _ = fs::remove_file(project_path);
+
+    // The global profile section overrides the project base layer too.
+    assert_eq!(config.max_risk, Severity::Low);
+    assert_eq!(config.min_weekly_downloads, 10);
+}
+
+#[test]
+fn nested_profiles_are_rejected() {
+    let path = unique_temp_path("nested-profiles.toml");
+    fs::write(
+        &path,
+        "[profiles.strict.profiles.inner]\nmax_risk = \"low\"\n",
+    )
+    .expect("write config");
+
+    let result = SafePkgsConfig::load_with_paths(Some(path.clone()), None, Some("strict"));
+    let _ = fs::remove_file(path);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn yaml_config_loads_identically_to_equivalent_toml() {
+    let toml_path = unique_temp_path("equivalent.toml");
+    let yaml_path = unique_temp_path("equivalent.yaml");
+    fs::write(
+        &toml_path,
+        r#"
+min_version_age_days = 3
+max_risk = "low"
+require_exact_version = true
+
+[cache]
+ttl_minutes = 45
+
+[lockfile]
+eval_concurrency = 7
+on_transport_error = "warn"
+
+[blackout.registry.npm]
+after = "2026-02-20T00:00:00Z"
+
+[[custom_rules]]
+id = "young-and-quiet"
+severity = "high"
+reason = "package is too new and has low adoption"
+registries = ["npm"]
+match = "all"
+conditions = [
+  { field = "version_age_days", op = "lt", value = 7 },
+  { field = "weekly_downloads", op = "lt", value = 100 }
+]
+
+[install_script]
+allowed_commands = ["node-gyp rebuild"]
+
+[checks.registry.npm]
+disable = ["install_script"]
+
+[denylist]
+packages = ["event-stream@3.3.6"]
+
+[profiles.strict]
+max_risk = "info"
+"#,
+    )
+    .expect("write toml config");
+    fs::write(
+        &yaml_path,
+        r#"
+# Same settings as the TOML file.
+min_version_age_days: 3
+max_risk: low
+require_exact_version: true
+cache:
+  ttl_minutes: 45
+lockfile:
+  eval_concurrency: 7
+  on_transport_error: warn
+blackout:
+  registry:
+    npm:
+      after: "2026-02-20T00:00:00Z"
+custom_rules:
+  - id: young-and-quiet
+    severity: high
+    reason: package is too new and has low adoption
+    registries: [npm]
+    match: all
+    conditions:
+      - { field: version_age_days, op: lt, value: 7 }
+      - { field: weekly_downloads, op: lt, value: 100 }
+install_script:
+  allowed_commands: ["node-gyp rebuild"]
+checks:
+  registry:
+    npm:
+      disable: [install_script]
+denylist:
+  packages: ["event-stream@3.3.6"]
+profiles:
+  strict:
+    max_risk: info
+"#,
+    )
+    .expect("write yaml config");
+
+    let from_toml = SafePkgsConfig::load_file(&toml_path, Some("strict")).expect("toml config");
+    let from_yaml = SafePkgsConfig::load_file(&yaml_path, Some("strict")).expect("yaml config");
+    assert_eq!(
+        serde_json::to_value(&from_yaml).expect("yaml json"),
+        serde_json::to_value(&from_toml).expect("toml json")
+    );
+    assert_eq!(from_yaml.max_risk, Severity::Info);
+    assert_eq!(from_yaml.lockfile.eval_concurrency, 7);
+    assert_eq!(from_yaml.custom_rules.len(), 1);
+
+    let _ = fs::remove_file(toml_path);
+    let _ = fs::remove_file(yaml_path);
+}
+
+#[test]
+fn config_discovery_prefers_toml_then_yaml_then_yml() {
+    let dir = unique_temp_path("config-discovery");
+    fs::create_dir_all(&dir).expect("create temp dir");
+    assert_eq!(
+        discover_config_file(&dir, ".safe-pkgs"),
+        dir.join(".safe-pkgs.toml")
+    );
+
+    fs::write(dir.join(".safe-pkgs.yml"), "").expect("write yml");
+    assert_eq!(
+        discover_config_file(&dir, ".safe-pkgs"),
+        dir.join(".safe-pkgs.yml")
+    );
+    // An empty YAML document is an empty config, as with TOML.
+    let config = SafePkgsConfig::load_from_path(&dir.join(".safe-pkgs.yml")).expect("empty yml");
+    assert_eq!(config.max_risk, DEFAULT_MAX_RISK);
+
+    fs::write(dir.join(".safe-pkgs.yaml"), "max_risk: high\n").expect("write yaml");
+    assert_eq!(
+        discover_config_file(&dir, ".safe-pkgs"),
+        dir.join(".safe-pkgs.yaml")
+    );
+
+    fs::write(dir.join(".safe-pkgs.toml"), "").expect("write toml");
+    assert_eq!(
+        discover_config_file(&dir, ".safe-pkgs"),
+        dir.join(".safe-pkgs.toml")
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn trusted_only_keys_are_rejected_from_project_and_remote_layers() {
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let global_path = unique_temp_path("hook-global.toml");
+    fs::write(
+        &global_path,
+        "decision_hook = \"/usr/local/bin/hook\"\ndecision_hook_fail_open = true\n",
+    )
+    .expect("write global config");
+    let project_path = unique_temp_path("hook-project.toml");
+    fs::write(
+        &project_path,
+        "decision_hook = \"./scripts/allow-all.sh\"\n",
+    )
+    .expect("write project config");
+
+    let trusted = SafePkgsConfig::load_with_paths(Some(global_path.clone()), None, None)
+        .expect("global config may set the hook");
+    assert_eq!(
+        trusted.decision_hook.as_deref(),
+        Some(Path::new("/usr/local/bin/hook"))
+    );
+    assert!(trusted.decision_hook_fail_open);
+
+    let project = SafePkgsConfig::load_with_paths(None, Some(project_path.clone()), None)
+        .expect_err("project config must not set the hook");
+    assert!(format!("{project:#}").contains("decision_hook may only be set"));
+    fs::write(&project_path, "npmrc = \".npmrc\"\n").expect("write project config");
+    let project = SafePkgsConfig::load_with_paths(None, Some(project_path.clone()), None)
+        .expect_err("project config must not set npmrc");
+    assert!(format!("{project:#}").contains("npmrc may only be set"));
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "[profiles.ci]\ndecision_hook_fail_open = true\n",
+            "text/plain",
+        ))
+        .mount(&mock_server)
+        .await;
+    let remote = SafePkgsConfig::load_with_sources(
+        Some(RemoteConfigSource {
+            url: mock_server.uri(),
+            token: None,
+        }),
+        None,
+        None,
+        Some("ci"),
+    )
+    .await
+    .expect_err("remote config must not set fail-open");
+    assert!(format!("{remote:#}").contains("decision_hook_fail_open may only be set"));
+
+    let _ = fs::remove_file(global_path);
+    let _ = fs::remove_file(project_path);
+}
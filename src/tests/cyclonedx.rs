@@ -0,0 +1,183 @@
+use super::*;
+use crate::types::{DecisionFingerprints, EvidenceKind};
+use safe_pkgs_core::DependencyScope;
+use std::collections::BTreeMap;
+
+fn package(
+    name: &str,
+    version: Option<&str>,
+    allow: bool,
+    risk: Severity,
+) -> LockfilePackageResult {
+    LockfilePackageResult {
+        name: name.to_string(),
+        requested: version.map(str::to_string),
+        scope: DependencyScope::Prod,
+        allow,
+        risk,
+        score: 0,
+        reasons: Vec::new(),
+        evidence: Vec::new(),
+        dependency_ancestry: None,
+        reevaluated: None,
+        registry: None,
+    }
+}
+
+fn response(packages: Vec<LockfilePackageResult>) -> LockfileResponse {
+    LockfileResponse {
+        allow: packages.iter().all(|package| package.allow),
+        risk: packages
+            .iter()
+            .map(|package| package.risk)
+            .max()
+            .unwrap_or(Severity::Low),
+        total: packages.len(),
+        denied: packages.iter().filter(|package| !package.allow).count(),
+        skipped: 0,
+        unreported_sources: 0,
+        evaluated: 0,
+        coverage_shortfall: None,
+        packages,
+        fingerprints: DecisionFingerprints {
+            config: "cfg".to_string(),
+            policy: "pol".to_string(),
+        },
+    }
+}
+
+fn property_values<'a>(component: &'a Value, name: &str) -> Vec<&'a str> {
+    component["properties"]
+        .as_array()
+        .expect("properties array")
+        .iter()
+        .filter(|property| property["name"] == name)
+        .filter_map(|property| property["value"].as_str())
+        .collect()
+}
+
+#[test]
+fn emits_required_cyclonedx_fields() {
+    let bom = to_cyclonedx(
+        &response(vec![package(
+            "lodash",
+            Some("4.17.21"),
+            true,
+            Severity::Low,
+        )]),
+        Some("npm"),
+    );
+
+    assert_eq!(bom["bomFormat"], "CycloneDX");
+    assert_eq!(bom["specVersion"], SPEC_VERSION);
+    assert_eq!(bom["version"], 1);
+    let components = bom["components"].as_array().expect("components");
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0]["type"], "library");
+    assert_eq!(components[0]["name"], "lodash");
+    assert_eq!(components[0]["version"], "4.17.21");
+    assert_eq!(components[0]["purl"], "pkg:npm/lodash@4.17.21");
+    assert_eq!(components[0]["bom-ref"], components[0]["purl"]);
+    assert_eq!(property_values(&components[0], "safe-pkgs:allow"), ["true"]);
+    assert!(bom.get("vulnerabilities").is_none());
+}
+
+#[test]
+fn findings_become_properties_and_vulnerabilities() {
+    let mut flagged = package("@scope/demo", Some("1.0.0"), false, Severity::High);
+    flagged.reasons = vec!["demo@1.0.0 has known advisories: GHSA-xxxx".to_string()];
+    flagged.evidence = vec![Evidence {
+        kind: EvidenceKind::Check,
+        id: ADVISORY_EVIDENCE_ID.to_string(),
+        code: ADVISORY_EVIDENCE_ID.to_string(),
+        severity: Severity::High,
+        message: flagged.reasons[0].clone(),
+        facts: BTreeMap::from([(
+            "advisory_ids".to_string(),
+            json!(["GHSA-xxxx", "CVE-2024-0001"]),
+        )]),
+        remediation: None,
+    }];
+
+    let bom = to_cyclonedx(&response(vec![flagged]), Some("npm"));
+    let component = &bom["components"][0];
+    assert_eq!(component["group"], "@scope");
+    assert_eq!(component["name"], "demo");
+    assert_eq!(component["purl"], "pkg:npm/%40scope/demo@1.0.0");
+    assert_eq!(property_values(component, "safe-pkgs:risk"), ["high"]);
+    assert_eq!(property_values(component, "safe-pkgs:reason").len(), 1);
+
+    let vulnerabilities = bom["vulnerabilities"].as_array().expect("vulnerabilities");
+    assert_eq!(vulnerabilities.len(), 2);
+    assert_eq!(vulnerabilities[0]["id"], "GHSA-xxxx");
+    assert_eq!(vulnerabilities[0]["ratings"][0]["severity"], "high");
+    assert_eq!(
+        vulnerabilities[0]["affects"][0]["ref"],
+        component["bom-ref"]
+    );
+}
+
+#[test]
+fn per_package_registry_wins_and_unknown_registry_has_no_purl() {
+    let mut crate_package = package("serde", Some("1.0.0"), true, Severity::Low);
+    crate_package.registry = Some("cargo".to_string());
+    let python_package = package("Typing_Extensions", None, true, Severity::Low);
+    let foreign = package("guava", Some("33.0"), false, Severity::High);
+
+    let bom = to_cyclonedx(
+        &response(vec![crate_package.clone(), python_package]),
+        Some("pypi"),
+    );
+    assert_eq!(bom["components"][0]["purl"], "pkg:cargo/serde@1.0.0");
+    assert_eq!(bom["components"][1]["purl"], "pkg:pypi/typing-extensions");
+
+    let bom = to_cyclonedx(&response(vec![crate_package, foreign]), None);
+    assert_eq!(bom["components"][0]["purl"], "pkg:cargo/serde@1.0.0");
+    assert!(bom["components"][1].get("purl").is_none());
+    assert_eq!(bom["components"][1]["bom-ref"], "component-1");
+}
+
+#[test]
+fn packages_sharing_a_purl_collapse_into_one_component() {
+    let advisory = Evidence {
+        kind: EvidenceKind::Check,
+        id: ADVISORY_EVIDENCE_ID.to_string(),
+        code: ADVISORY_EVIDENCE_ID.to_string(),
+        severity: Severity::High,
+        message: "demo@1.0.0 has known advisories: GHSA-xxxx".to_string(),
+        facts: BTreeMap::from([("advisory_ids".to_string(), json!(["GHSA-xxxx"]))]),
+        remediation: None,
+    };
+    let mut first = package("demo", Some("1.0.0"), true, Severity::Low);
+    first.reasons = vec!["first path".to_string()];
+    first.evidence = vec![advisory.clone()];
+    let mut second = package("demo", Some("1.0.0"), false, Severity::High);
+    second.reasons = vec!["second path".to_string()];
+    second.evidence = vec![advisory];
+    let other = package("other", None, true, Severity::Low);
+
+    let bom = to_cyclonedx(&response(vec![first, second, other]), Some("npm"));
+    let components = bom["components"].as_array().expect("components");
+    assert_eq!(components.len(), 2);
+    let refs: HashSet<_> = components
+        .iter()
+        .map(|component| component["bom-ref"].as_str().expect("bom-ref"))
+        .collect();
+    assert_eq!(refs.len(), 2);
+    assert_eq!(
+        property_values(&components[0], "safe-pkgs:allow"),
+        ["false"]
+    );
+    assert_eq!(property_values(&components[0], "safe-pkgs:risk"), ["high"]);
+    assert_eq!(
+        property_values(&components[0], "safe-pkgs:reason"),
+        ["first path", "second path"]
+    );
+    assert_eq!(
+        bom["vulnerabilities"]
+            .as_array()
+            .expect("vulnerabilities")
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,130 @@
+use super::*;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn write_hook_script(name: &str, body: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("safe-pkgs-{nanos}-{name}.sh"));
+    std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).expect("write hook script");
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        .expect("make hook script executable");
+    path
+}
+
+fn allowed_response() -> ToolResponse {
+    serde_json::from_value(json!({
+        "allow": true,
+        "risk": "low",
+        "reasons": [],
+        "metadata": { "requested": "1.0.0" },
+        "fingerprints": { "config": "c", "policy": "p" },
+    }))
+    .expect("tool response")
+}
+
+fn config_with_hook(hook: PathBuf) -> SafePkgsConfig {
+    SafePkgsConfig {
+        decision_hook: Some(hook),
+        ..SafePkgsConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn hook_can_flip_allow_to_deny() {
+    let hook = write_hook_script(
+        "deny-hook",
+        r#"input=$(cat)
+case "$input" in *'"allow":true'*) ;; *) exit 1 ;; esac
+echo '{"allow": false, "risk": "high", "reason": "internal risk API denied demo"}'"#,
+    );
+
+    let response = apply(&config_with_hook(hook.clone()), allowed_response()).await;
+    assert!(!response.allow);
+    assert_eq!(response.risk, Severity::High);
+    assert!(
+        response
+            .reasons
+            .contains(&"internal risk API denied demo".to_string())
+    );
+    let evidence = response
+        .evidence
+        .iter()
+        .find(|item| item.id == OVERRIDE_EVIDENCE_ID)
+        .expect("override evidence");
+    assert_eq!(evidence.facts["original_allow"], json!(true));
+
+    let _ = std::fs::remove_file(hook);
+}
+
+#[tokio::test]
+async fn failing_hook_denies_unless_fail_open() {
+    let hook = write_hook_script("broken-hook", "echo 'not json'");
+
+    let mut config = config_with_hook(hook.clone());
+    let denied = apply(&config, allowed_response()).await;
+    assert!(!denied.allow);
+    assert_eq!(denied.risk, Severity::Critical);
+    assert!(
+        denied
+            .evidence
+            .iter()
+            .any(|item| item.id == FAILED_EVIDENCE_ID)
+    );
+
+    config.decision_hook_fail_open = true;
+    let kept = apply(&config, allowed_response()).await;
+    assert!(kept.allow);
+    assert_eq!(kept.risk, Severity::Low);
+    assert!(
+        kept.evidence
+            .iter()
+            .any(|item| item.id == FAILED_EVIDENCE_ID)
+    );
+
+    let _ = std::fs::remove_file(hook);
+}
+
+#[tokio::test]
+async fn slow_hook_times_out_and_fails_closed() {
+    let hook = write_hook_script("slow-hook", "sleep 5\necho '{\"allow\": true}'");
+
+    let mut config = config_with_hook(hook.clone());
+    config.decision_hook_timeout_ms = 100;
+    let response = apply(&config, allowed_response()).await;
+    assert!(!response.allow);
+    assert!(
+        response
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("timed out after 100 ms"))
+    );
+
+    let _ = std::fs::remove_file(hook);
+}
+
+#[tokio::test]
+async fn hook_cannot_allow_a_critical_report() {
+    let hook = write_hook_script(
+        "allow-hook",
+        r#"echo '{"allow": true, "risk": "low", "reason": "hook says fine"}'"#,
+    );
+
+    let mut critical = allowed_response();
+    critical.allow = false;
+    critical.risk = Severity::Critical;
+    let response = apply(&config_with_hook(hook.clone()), critical).await;
+    assert!(!response.allow);
+    assert_eq!(response.risk, Severity::Critical);
+    assert!(
+        !response
+            .evidence
+            .iter()
+            .any(|item| item.id == OVERRIDE_EVIDENCE_ID)
+    );
+
+    let _ = std::fs::remove_file(hook);
+}
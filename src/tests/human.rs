@@ -0,0 +1,139 @@
+use super::*;
+use crate::types::{DecisionFingerprints, LockfilePackageResult, Metadata, ToolResponse};
+use safe_pkgs_core::DependencyScope;
+
+fn fingerprints() -> DecisionFingerprints {
+    DecisionFingerprints {
+        config: "cfg".to_string(),
+        policy: "pol".to_string(),
+    }
+}
+
+fn check_result(
+    package: &str,
+    resolved: Option<&str>,
+    allow: bool,
+    risk: Severity,
+    reasons: &[&str],
+) -> PackageCheckResult {
+    PackageCheckResult {
+        package: package.to_string(),
+        requested: None,
+        registry: "npm".to_string(),
+        response: ToolResponse {
+            allow,
+            risk,
+            score: 0,
+            reasons: reasons.iter().map(|reason| reason.to_string()).collect(),
+            evidence: Vec::new(),
+            metadata: Metadata {
+                latest: None,
+                requested: None,
+                resolved: resolved.map(str::to_string),
+                published: None,
+                weekly_downloads: None,
+                fetch_ms: None,
+            },
+            fingerprints: fingerprints(),
+        },
+    }
+}
+
+#[test]
+fn package_results_list_name_risk_and_every_reason() {
+    let results = vec![
+        check_result("lodash", Some("4.17.21"), true, Severity::Low, &[]),
+        check_result(
+            "evil-pkg",
+            Some("0.0.1"),
+            false,
+            Severity::Critical,
+            &[
+                "evil-pkg@0.0.1 has a suspicious install hook",
+                "evil-pkg@0.0.1 was published 2 hours ago",
+            ],
+        ),
+    ];
+
+    let rendered = render_package_results(&results, false);
+
+    assert!(rendered.contains("ALLOW  low       npm:lodash@4.17.21"));
+    assert!(rendered.contains("DENY   critical  npm:evil-pkg@0.0.1"));
+    assert!(rendered.contains("    - evil-pkg@0.0.1 has a suspicious install hook"));
+    assert!(rendered.contains("    - evil-pkg@0.0.1 was published 2 hours ago"));
+    assert!(rendered.ends_with("2 packages checked: 1 allowed, 1 denied"));
+    assert!(!rendered.contains('\x1b'));
+}
+
+#[test]
+fn color_output_wraps_decision_and_risk_in_ansi_codes() {
+    let results = vec![check_result(
+        "left-pad",
+        None,
+        false,
+        Severity::High,
+        &["left-pad is unmaintained"],
+    )];
+
+    let rendered = render_package_results(&results, true);
+
+    assert!(rendered.contains("\x1b[1;31mDENY \x1b[0m"));
+    assert!(rendered.contains("\x1b[31mhigh    \x1b[0m"));
+    assert!(rendered.contains("left-pad is unmaintained"));
+}
+
+#[test]
+fn control_characters_in_fields_are_escaped() {
+    let results = vec![check_result(
+        "evil\x1b[2Jpkg",
+        Some("1.0.0"),
+        false,
+        Severity::High,
+        &["description says \x1b[31mtrusted\x1b[0m\nALLOW  low  npm:fake"],
+    )];
+
+    let rendered = render_package_results(&results, false);
+
+    assert!(!rendered.contains('\x1b'));
+    assert!(rendered.contains("npm:evil\\u{1b}[2Jpkg@1.0.0"));
+    assert!(
+        rendered
+            .contains("    - description says \\u{1b}[31mtrusted\\u{1b}[0m\\nALLOW  low  npm:fake")
+    );
+    assert_eq!(rendered.lines().count(), 4);
+}
+
+#[test]
+fn lockfile_report_summarizes_totals_and_packages() {
+    let report = LockfileResponse {
+        allow: false,
+        risk: Severity::High,
+        total: 2,
+        denied: 1,
+        skipped: 0,
+        unreported_sources: 0,
+        evaluated: 2,
+        coverage_shortfall: None,
+        packages: vec![LockfilePackageResult {
+            name: "request".to_string(),
+            requested: Some("2.88.2".to_string()),
+            scope: DependencyScope::Prod,
+            allow: false,
+            risk: Severity::High,
+            score: 0,
+            reasons: vec!["request@2.88.2 is deprecated".to_string()],
+            evidence: Vec::new(),
+            dependency_ancestry: None,
+            reevaluated: None,
+            registry: None,
+        }],
+        fingerprints: fingerprints(),
+    };
+
+    let rendered = render_lockfile_report(&report, false);
+
+    assert!(rendered.starts_with("DENY  risk high\n"));
+    assert!(rendered.contains("2 packages: 1 allowed, 1 denied, 2 evaluated"));
+    assert!(rendered.contains("DENY   high      request@2.88.2"));
+    assert!(rendered.contains("    - request@2.88.2 is deprecated"));
+}
@@ -0,0 +1,98 @@
+use super::*;
+use safe_pkgs_core::{DependencyScope, UnsupportedSource};
+
+fn spec(name: &str, version: &str) -> DependencySpec {
+    DependencySpec {
+        name: name.to_string(),
+        version: Some(version.to_string()),
+        dependency_paths: vec![vec![name.to_string()]],
+        scope: DependencyScope::Prod,
+        unsupported_source: None,
+        hashes: Vec::new(),
+    }
+}
+
+fn key(specs: &[DependencySpec]) -> String {
+    response_cache_key("cfg", "pol", "npm", "abc", specs).expect("cache key")
+}
+
+#[test]
+fn response_cache_key_is_stable_for_equal_dependency_sets() {
+    let specs = vec![spec("lodash", "4.17.21"), spec("react", "18.2.0")];
+    assert_eq!(key(&specs), key(&specs.clone()));
+    assert!(key(&specs).starts_with("lockfile_response:v2:cfg:pol:npm:abc:"));
+}
+
+#[test]
+fn response_cache_key_covers_every_dependency_field() {
+    let base = spec("lodash", "4.17.21");
+    let variants = [
+        DependencySpec {
+            version: Some("4.17.20".to_string()),
+            ..base.clone()
+        },
+        DependencySpec {
+            dependency_paths: vec![vec!["app".to_string(), "lodash".to_string()]],
+            ..base.clone()
+        },
+        DependencySpec {
+            scope: DependencyScope::Dev,
+            ..base.clone()
+        },
+        DependencySpec {
+            unsupported_source: Some(UnsupportedSource::Git),
+            ..base.clone()
+        },
+        DependencySpec {
+            hashes: vec!["sha256:abc".to_string()],
+            ..base.clone()
+        },
+    ];
+
+    let base_key = key(std::slice::from_ref(&base));
+    for variant in variants {
+        assert_ne!(key(&[variant]), base_key);
+    }
+}
+
+#[test]
+fn reusable_response_expires_by_original_evaluation_time() {
+    let evaluated_at = chrono::Utc::now();
+    let dependency = spec("lodash", "4.17.21");
+    let snapshot = LockfileSnapshot {
+        content_hash: "abc".to_string(),
+        entries: vec![LockfileSnapshotEntry {
+            name: dependency.name.clone(),
+            requested: dependency.version.clone(),
+            response: serde_json::from_value(serde_json::json!({
+                "allow": true,
+                "risk": "low",
+                "reasons": [],
+                "metadata": {},
+                "fingerprints": { "config": "cfg", "policy": "pol" },
+            }))
+            .expect("tool response"),
+            evaluated_at,
+        }],
+    };
+    let index = snapshot.into_index();
+    let max_age = Duration::minutes(30);
+
+    let (_, reused_at) = reusable_response(
+        &index,
+        &dependency,
+        evaluated_at + Duration::minutes(29),
+        max_age,
+    )
+    .expect("fresh entry is reused");
+    assert_eq!(reused_at, evaluated_at);
+    assert!(
+        reusable_response(
+            &index,
+            &dependency,
+            evaluated_at + Duration::minutes(30),
+            max_age
+        )
+        .is_none()
+    );
+}
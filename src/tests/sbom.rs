@@ -0,0 +1,101 @@
+use super::*;
+
+const MIXED_SBOM: &str = r#"{
+  "bomFormat": "CycloneDX",
+  "specVersion": "1.5",
+  "components": [
+    { "type": "library", "name": "lodash", "version": "4.17.21", "purl": "pkg:npm/lodash@4.17.21" },
+    { "type": "library", "name": "core", "version": "7.24.0", "purl": "pkg:npm/%40babel/core@7.24.0" },
+    { "type": "library", "name": "serde", "version": "1.0.200", "purl": "pkg:cargo/serde@1.0.200" },
+    {
+      "type": "library",
+      "name": "requests",
+      "version": "2.32.0",
+      "purl": "pkg:pypi/requests@2.32.0?extension=whl#src",
+      "scope": "optional",
+      "components": [
+        { "type": "library", "name": "idna", "purl": "pkg:pypi/idna@3.7" }
+      ]
+    },
+    { "type": "library", "name": "guava", "version": "33.0", "purl": "pkg:maven/com.google.guava/guava@33.0" },
+    { "type": "file", "name": "README.md" }
+  ]
+}"#;
+
+fn supported(component: &SbomComponent) -> (&str, &str, Option<&str>) {
+    match component {
+        SbomComponent::Supported { registry, spec } => (
+            registry.as_str(),
+            spec.name.as_str(),
+            spec.version.as_deref(),
+        ),
+        other => panic!("expected supported component, got {other:?}"),
+    }
+}
+
+#[test]
+fn parses_mixed_ecosystem_cyclonedx_document() {
+    let components = CycloneDxParser.parse_str(MIXED_SBOM).expect("parse sbom");
+    assert_eq!(components.len(), 7);
+
+    assert_eq!(
+        supported(&components[0]),
+        ("npm", "lodash", Some("4.17.21"))
+    );
+    assert_eq!(
+        supported(&components[1]),
+        ("npm", "@babel/core", Some("7.24.0"))
+    );
+    assert_eq!(
+        supported(&components[2]),
+        ("cargo", "serde", Some("1.0.200"))
+    );
+    assert_eq!(
+        supported(&components[3]),
+        ("pypi", "requests", Some("2.32.0"))
+    );
+    assert_eq!(supported(&components[4]), ("pypi", "idna", Some("3.7")));
+
+    let SbomComponent::Supported { spec, .. } = &components[3] else {
+        panic!("requests should be supported");
+    };
+    assert_eq!(spec.scope, DependencyScope::Optional);
+
+    assert!(matches!(
+        &components[5],
+        SbomComponent::Unsupported { name, reason, .. }
+            if name == "guava" && reason.contains("maven")
+    ));
+    assert!(matches!(
+        &components[6],
+        SbomComponent::Unsupported { name, reason, .. }
+            if name == "README.md" && reason.contains("no purl")
+    ));
+}
+
+#[test]
+fn purl_parsing_handles_unencoded_scope_and_missing_version() {
+    let purl = PackageUrl::parse("pkg:npm/@types/node@20.1.0").expect("purl");
+    assert_eq!(purl.namespace.as_deref(), Some("@types"));
+    assert_eq!(purl.name, "node");
+    assert_eq!(purl.version.as_deref(), Some("20.1.0"));
+
+    let purl = PackageUrl::parse("pkg:pypi/zope.interface").expect("purl");
+    assert_eq!(purl.name, "zope.interface");
+    assert_eq!(purl.version, None);
+
+    let purl = PackageUrl::parse("pkg:npm/name%2Bplus@1.0.0%2Bbuild").expect("purl");
+    assert_eq!(purl.name, "name+plus");
+    assert_eq!(purl.version.as_deref(), Some("1.0.0+build"));
+
+    assert!(PackageUrl::parse("npm/lodash@1.0.0").is_none());
+    assert!(PackageUrl::parse("pkg:npm/bad%zzname").is_none());
+}
+
+#[test]
+fn rejects_documents_that_are_not_cyclonedx() {
+    let err = CycloneDxParser
+        .parse_str(r#"{ "spdxVersion": "SPDX-2.3", "packages": [] }"#)
+        .expect_err("not cyclonedx");
+    assert!(err.contains("CycloneDX"));
+}
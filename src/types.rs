@@ -10,7 +10,7 @@ use serde_json::Value as JsonValue;
 /// These types are defined and primarily documented in the `safe_pkgs_core` crate;
 /// they are re-exported here so CLI commands and MCP tools can depend only on this
 /// crate while still using the same canonical representations.
-pub use safe_pkgs_core::{Metadata, Severity};
+pub use safe_pkgs_core::{DependencyScope, Metadata, Severity};
 
 /// Deterministic fingerprints for correlating decision outputs with audit records.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +59,10 @@ pub struct Evidence {
     pub kind: EvidenceKind,
     /// Stable machine-readable identifier (e.g., check id or policy code).
     pub id: String,
+    /// Short stable finding code: the check's code (e.g., `AGE001`) for check findings, and
+    /// `id` for policy, custom-rule, and runtime evidence. Never empty.
+    #[serde(default)]
+    pub code: String,
     /// Severity associated with this evidence.
     pub severity: Severity,
     /// Human-readable summary for this evidence item.
@@ -66,6 +70,9 @@ pub struct Evidence {
     /// Optional structured fields for deterministic downstream handling.
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub facts: BTreeMap<String, JsonValue>,
+    /// Actionable next step for resolving the finding; set for check findings only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
 }
 
 /// Decision result returned by package checks.
@@ -75,6 +82,10 @@ pub struct ToolResponse {
     pub allow: bool,
     /// Aggregated risk level from all enabled checks.
     pub risk: Severity,
+    /// Numeric 0-100 risk score weighted by finding severity and count; informational only,
+    /// `risk` drives the decision.
+    #[serde(default)]
+    pub score: u8,
     /// Human-readable findings that explain the decision.
     pub reasons: Vec<String>,
     /// Machine-readable evidence from checks and policy evaluation.
@@ -86,6 +97,85 @@ pub struct ToolResponse {
     pub fingerprints: DecisionFingerprints,
 }
 
+/// Decision for a package tarball, as emitted by `safe-pkgs check-tarball`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TarballCheckResult {
+    /// Tarball path or URL that was inspected.
+    pub source: String,
+    /// Package name from the tarball manifest.
+    pub package: String,
+    /// Package version from the tarball manifest.
+    pub version: String,
+    /// Registry inferred from the manifest format.
+    pub registry: String,
+    /// License declared by the manifest, when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Decision payload from the metadata-only checks.
+    #[serde(flatten)]
+    pub response: ToolResponse,
+}
+
+/// Decision for one standalone package spec, as emitted by `safe-pkgs check`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageCheckResult {
+    /// Package name as resolved from the spec.
+    pub package: String,
+    /// Requested version from the spec when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested: Option<String>,
+    /// Registry the package was checked against.
+    pub registry: String,
+    /// Decision payload, identical to a `check_package` response.
+    #[serde(flatten)]
+    pub response: ToolResponse,
+}
+
+/// Aggregate response for an explicit list of package specs (`check_packages`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageListResponse {
+    /// Whether every package was allowed.
+    pub allow: bool,
+    /// Highest risk observed across the packages.
+    pub risk: Severity,
+    /// Number of packages checked.
+    pub total: usize,
+    /// Number of packages denied.
+    pub denied: usize,
+    /// Per-package decisions, in request order.
+    pub packages: Vec<PackageCheckResult>,
+}
+
+/// Result of resolving the safest version of a package within a range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafestVersionResponse {
+    /// Whether any candidate version in the range passed policy.
+    pub allow: bool,
+    /// Newest candidate version that passed policy, if any.
+    #[serde(default)]
+    pub recommended_version: Option<String>,
+    /// Full decision for the recommended version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision: Option<ToolResponse>,
+    /// Newer candidates that were evaluated and denied, newest first.
+    #[serde(default)]
+    pub rejected: Vec<RejectedVersion>,
+    /// Explanation when no candidate passed or the candidate limit was reached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Candidate version denied during safest-version resolution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedVersion {
+    /// Candidate version that was evaluated.
+    pub version: String,
+    /// Aggregated risk level for this candidate.
+    pub risk: Severity,
+    /// Findings that caused the candidate to be denied.
+    pub reasons: Vec<String>,
+}
+
 /// Per-package result in a lockfile audit.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockfilePackageResult {
@@ -93,10 +183,16 @@ pub struct LockfilePackageResult {
     pub name: String,
     /// Requested version string from the lockfile when present.
     pub requested: Option<String>,
+    /// Dependency scope declared by the lockfile or manifest.
+    #[serde(default)]
+    pub scope: DependencyScope,
     /// Whether this package passed policy checks.
     pub allow: bool,
     /// Risk level for this specific package.
     pub risk: Severity,
+    /// Numeric 0-100 risk score for this package.
+    #[serde(default)]
+    pub score: u8,
     /// Findings for this package only.
     pub reasons: Vec<String>,
     /// Machine-readable evidence for this package decision.
@@ -105,6 +201,13 @@ pub struct LockfilePackageResult {
     /// Structured transitive ancestry representation for this package.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dependency_ancestry: Option<DependencyAncestry>,
+    /// Incremental audits only: whether this package was evaluated in this run
+    /// (`false` means the decision was reused from the previous audit).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reevaluated: Option<bool>,
+    /// SBOM audits only: registry the component was routed to by its purl.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
 }
 
 /// One ancestry chain entry for a package.
@@ -134,6 +237,78 @@ pub struct SimulationReport {
     pub audit: LockfileResponse,
 }
 
+/// Decisions that flip between two configs for the same dependency file (`safe-pkgs policy-diff`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDiffReport {
+    /// Packages audited under config A.
+    pub total: usize,
+    /// Packages whose `allow` or `risk` differs between the configs, in lockfile order,
+    /// followed by packages only one config's audit reported.
+    pub changed: Vec<PolicyDiffEntry>,
+    /// Aggregate decision under config A.
+    pub a: PolicyDiffDecision,
+    /// Aggregate decision under config B.
+    pub b: PolicyDiffDecision,
+}
+
+/// One package whose decision differs between two configs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDiffEntry {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested: Option<String>,
+    pub change: PolicyDiffChange,
+    /// Decision under config A; absent when only config B reported the package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub a: Option<PolicyDiffDecision>,
+    /// Decision under config B; absent when only config A reported the package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub b: Option<PolicyDiffDecision>,
+}
+
+/// How a package differs between the two audits of a policy diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyDiffChange {
+    /// Both configs report the package with different decisions.
+    Changed,
+    /// Only config B reports the package (for example, A ignores dev dependencies).
+    Added,
+    /// Only config A reports the package.
+    Removed,
+}
+
+/// The `allow`/`risk` pair compared by a policy diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyDiffDecision {
+    pub allow: bool,
+    pub risk: Severity,
+}
+
+/// Summary of a cache warm-up run (`safe-pkgs warm`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheWarmReport {
+    /// Registry whose decision cache was populated.
+    pub registry: String,
+    /// Dependencies parsed from the input file.
+    pub total: usize,
+    /// Dependencies whose decisions are now cached.
+    pub warmed: usize,
+    /// Dependencies whose evaluation failed; they are not cached and are retried on the next run.
+    pub failed: usize,
+}
+
+/// Result of the network-free readiness probe (`safe-pkgs ready`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    /// True when the config loaded and the cache database accepted a write and read.
+    pub ready: bool,
+    /// Fingerprint of the loaded config.
+    pub config_fingerprint: String,
+    /// Registry keys available for package checks.
+    pub registries: Vec<String>,
+}
+
 /// Aggregate response returned by lockfile audits.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockfileResponse {
@@ -145,8 +320,27 @@ pub struct LockfileResponse {
     pub total: usize,
     /// Number of packages denied by policy or errors.
     pub denied: usize,
+    /// Packages left out of the report after transport failures
+    /// (`lockfile.on_transport_error = "skip"`).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub skipped: usize,
+    /// Non-registry (git, path, or URL) dependencies left out of `packages` because
+    /// `lockfile.report_unsupported_sources` is off. They still count toward coverage.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub unreported_sources: usize,
+    /// Packages fully evaluated: checked without a failure, a non-registry source, or an
+    /// unparseable version.
+    #[serde(default)]
+    pub evaluated: usize,
+    /// Why the audit was denied for falling below `lockfile.min_coverage_ratio`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage_shortfall: Option<String>,
     /// Per-package outcomes.
     pub packages: Vec<LockfilePackageResult>,
     /// Fingerprints for correlation with audit log records.
     pub fingerprints: DecisionFingerprints,
 }
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
